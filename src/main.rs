@@ -15,28 +15,59 @@ use std::process::ExitCode;
 
 /// Run with arguments passed via the CLI.
 fn main() -> ExitCode {
-    let raw_args = env::args();
+    let raw_args: Vec<String> = env::args().collect();
     let raw_vars = env::vars();
 
-    let vars = cli::parse_vars(raw_vars);
-    let args = cli::parse_args(raw_args, vars).unwrap_or_else(|err| err.exit());
-
-    match cli::run(&args) {
+    let result = match cli::personality_of(&raw_args) {
+        cli::Personality::Rm => {
+            let vars = cli::parse_vars(raw_vars);
+            let args = cli::parse_args(raw_args, vars).unwrap_or_else(|err| err.exit());
+            cli::run(&args)
+        },
+        cli::Personality::Rmdir => {
+            let args = cli::parse_rmdir_args(raw_args).unwrap_or_else(|err| err.exit());
+            cli::run_rmdir(&args).map_err(cli::Failure::from)
+        },
+        cli::Personality::Unlink => {
+            let args = cli::parse_unlink_args(raw_args).unwrap_or_else(|err| err.exit());
+            cli::run_unlink(&args).map_err(cli::Failure::from)
+        },
+    };
+
+    match result {
         Ok(()) => ExitCode::SUCCESS,
-        Err(()) => ExitCode::FAILURE,
+        Err(cli::Failure::Error) => ExitCode::FAILURE,
+        Err(cli::Failure::Interrupted(code)) => ExitCode::from(code),
     }
 }
 
 /// Programmatic interface for the CLI.
 mod cli {
-    use super::{lang, logging, rm, transform, walk};
+    #[cfg(feature = "pick")]
+    use super::pick;
+    use super::{fs, lang, logging, progress, rm, transform, walk};
 
-    use std::ffi::OsString;
+    use std::cell::RefCell;
+    use std::collections::{BTreeMap, HashMap, HashSet};
+    use std::env;
+    use std::ffi::{OsStr, OsString};
+    use std::fs::{File, canonicalize, read_dir, read_to_string, remove_file};
+    use std::io::{self, BufReader, IsTerminal as _, Write as _};
+    use std::iter;
+    use std::path::{Path, PathBuf};
+    use std::process;
+    use std::rc::Rc;
+    use std::slice;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-    use clap::Parser;
     use clap::error::Error;
-    use log::{error, info, trace};
+    use clap::{Parser, ValueEnum};
+    use log::{error, info, trace, warn};
     use owo_colors::OwoColorize as _;
+    use regex::Regex;
 
     #[cfg(test)]
     use proptest_derive::Arbitrary;
@@ -54,35 +85,325 @@ mod cli {
     ///
     /// If you use rm to remove a file, it might be possible to recover some of its contents, given
     /// sufficient expertise and/or time. For greater assurance that the contents are truly
-    /// unrecoverable, consider using shred(1).
+    /// unrecoverable, consider using --shred.
     #[derive(Parser)]
     #[command(name = "rm", version = None)]
     #[command(about = "Remove (unlink) the PATH(s)", long_about)]
     #[allow(clippy::struct_excessive_bools, reason = "CLI options are not a state machine")]
     pub struct Args {
+        /// Also remove the other hard links in DIR to the files that are removed.
+        ///
+        /// DIR is searched recursively, without following symbolic links, before anything is
+        /// removed. Only available on Unix.
+        #[cfg(unix)]
+        #[arg(short = None, long, value_name = "DIR", value_parser = parse_dir)]
+        all_links: Option<PathBuf>,
+
+        /// Remove the entries listed in the plan FILE, as written by --plan-out.
+        ///
+        /// Entries that changed since the plan was made are refused. The root directory and entries
+        /// protected by --protect-recent, --protect-open or --confirm-* are refused as without a
+        /// plan. Must be used without PATHs.
+        #[arg(
+            short = None,
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["interactive", "parents", "paths", "plan_out"]
+        )]
+        apply_plan: Option<PathBuf>,
+
+        /// Read the answers for --interactive and --confirm-* from FILE instead of the terminal.
+        ///
+        /// Use '-' to read the answers from stdin. Requires --interactive, --confirm-count,
+        /// --confirm-home or --confirm-size, where the thresholds may also come from the
+        /// environment.
+        #[arg(short = None, long, value_name = "FILE")]
+        answers_from: Option<PathBuf>,
+
         /// Ignore nonexistent files and directories.
         #[arg(short = 'b', long)]
         blind: bool,
 
+        /// Warn about removing files held open by running processes.
+        ///
+        /// Removing such files frees no space until they are closed. Only available on Linux.
+        #[cfg(target_os = "linux")]
+        #[arg(short = None, long)]
+        check_open: bool,
+
+        /// Ask to type the name of directories containing more than N entries before removing them.
+        ///
+        /// Defaults to the value of the `RUST_RM_CONFIRM_COUNT` environment variable, if set. Has
+        /// no effect without --force or --interactive.
+        #[arg(short = None, long, value_name = "N")]
+        confirm_count: Option<usize>,
+
+        /// Ask to type the name of your home directory and the directories in it before removing
+        /// them.
+        ///
+        /// Has no effect without --force or --interactive.
+        #[arg(short = None, long)]
+        confirm_home: bool,
+
+        /// Ask to type the name of directories containing more than SIZE before removing them.
+        ///
+        /// SIZE is a number of bytes, optionally followed by one of the units K, M, G or T for
+        /// multiples of 1024. Defaults to the value of the `RUST_RM_CONFIRM_SIZE` environment
+        /// variable, if set. Has no effect without --force or --interactive.
+        #[arg(short = None, long, value_name = "SIZE", value_parser = parse_size)]
+        confirm_size: Option<u64>,
+
+        /// Remove the contents of directories, but not the directories themselves.
+        ///
+        /// Implies --recursive for the contents.
+        #[arg(short = None, long)]
+        contents: bool,
+
+        /// Rename PATHs to hidden tombstones and remove those in the background.
+        ///
+        /// Every PATH is renamed within its directory, so the PATH is free immediately. A
+        /// background process then removes the tombstones and writes its output to a log file in
+        /// the temporary directory. Use --gc to remove tombstones left behind if it is stopped.
+        /// Cannot be used when `RUST_RM_MAX_COUNT` or `RUST_RM_PROTECT_RECENT` is set.
+        #[arg(
+            short = None,
+            long,
+            requires = "recursive",
+            conflicts_with_all = [
+                "contents",
+                "edit",
+                "empty_dirs",
+                "empty_files",
+                "gradual",
+                "keep_newest",
+                "keep_oldest",
+                "larger_than",
+                "max_count",
+                "newer_than",
+                "older_than",
+                "one_file_system",
+                "parents",
+                "protect_recent",
+                "shred",
+                "smaller_than",
+                "throttle_count",
+                "throttle_size",
+                "types",
+            ],
+        )]
+        #[cfg_attr(feature = "pick", arg(conflicts_with = "pick"))]
+        #[cfg_attr(feature = "trash", arg(conflicts_with = "trash"))]
+        #[cfg_attr(unix, arg(conflicts_with_all = ["all_links", "group", "user"]))]
+        #[cfg_attr(
+            target_os = "linux",
+            arg(conflicts_with_all = ["check_open", "protect_open", "throttle_idle"])
+        )]
+        detach: bool,
+
         /// Remove empty directories.
         #[arg(short = 'd', long, group = "dirs")]
         dir: bool,
 
+        /// Review the entries to remove in an editor before removing them.
+        ///
+        /// The entries that would be removed are listed in a file opened with $VISUAL or $EDITOR.
+        /// Only the entries still listed when the editor exits are removed, directories are kept
+        /// if anything in them is kept.
+        #[arg(short = None, long, group = "method")]
+        edit: bool,
+
+        /// Only remove directories that are empty or contain nothing but empty directories.
+        ///
+        /// Everything else is skipped. Requires --recursive.
+        #[arg(short = None, long, requires = "recursive", conflicts_with = "dir")]
+        empty_dirs: bool,
+
+        /// Only remove regular files that are empty.
+        ///
+        /// Directories are only removed if all of their contents are.
+        #[arg(short = None, long)]
+        empty_files: bool,
+
         /// Remove without prompt.
         #[arg(short = 'f', long, group = "method")]
         force: bool,
 
+        /// Remove entries modified recently despite --protect-recent.
+        #[arg(short = None, long)]
+        force_recent: bool,
+
+        /// Remove the tombstones left behind by --detach in the directories PATHs.
+        ///
+        /// Searches the current directory if no PATHs are given. Implies --recursive.
+        #[arg(short = None, long, conflicts_with_all = ["apply_plan", "detach"])]
+        gc: bool,
+
+        /// Truncate regular files larger than CHUNK one CHUNK at a time before removing them.
+        ///
+        /// Avoids freeing a lot of space at once, which can stall other I/O on some file systems.
+        /// CHUNK defaults to 1G, see --larger-than for its format. Files with other hard links are
+        /// not truncated. Use --gradual-pause to pause after every truncation.
+        #[arg(
+            short = None,
+            long,
+            value_name = "CHUNK",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "1G",
+            value_parser = parse_chunk,
+            conflicts_with = "shred",
+        )]
+        #[cfg_attr(feature = "trash", arg(conflicts_with = "trash"))]
+        gradual: Option<u64>,
+
+        /// Pause for DURATION after every truncation by --gradual.
+        ///
+        /// DURATION is for example '100ms' or '1s'.
+        #[arg(short = None, long, value_name = "DURATION", value_parser = parse_pause, requires = "gradual")]
+        gradual_pause: Option<Duration>,
+
+        /// Only remove entries owned by GROUP, given as a name or ID.
+        #[cfg(unix)]
+        #[arg(short = None, long, value_name = "GROUP", value_parser = parse_group)]
+        group: Option<u32>,
+
         /// Prompt to remove.
         ///
-        /// Answer "Y" or "yes" to remove an entry or "n" or "no" to keep it. Any other input will
-        /// be ignored and the entry skipped.
+        /// Answer "y" or "yes" to remove an entry, or just press Enter as removing is the default.
+        /// Answer "n" or "no" to keep it, "a" or "all" to remove it and all remaining entries, "d"
+        /// or "dir" to keep it and the rest of its directory, or "q" or "quit" to keep it and all
+        /// remaining entries. Answer "?" or "help" to list the answers. Any other input will be
+        /// ignored and the entry skipped.
         #[arg(short = 'i', long, group = "method")]
         interactive: bool,
 
+        /// Prompt once before removing more than three PATHs or removing recursively.
+        ///
+        /// Only available in BSD mode.
+        #[cfg(feature = "bsd-mode")]
+        #[arg(short = 'I', hide = true)]
+        interactive_once: bool,
+
+        /// Group PATHs by REGEX for --keep-newest and --keep-oldest.
+        ///
+        /// PATHs are grouped by the first capture group of REGEX, or by the whole match if it has
+        /// none. PATHs that don't match REGEX form a single group.
+        #[arg(short = None, long, value_name = "REGEX", value_parser = parse_regex, requires = "retention")]
+        keep_group: Option<Regex>,
+
+        /// Keep the N most recently modified PATHs and remove the rest.
+        ///
+        /// The retained PATHs are skipped. Use --keep-group to keep N PATHs per group.
+        #[arg(short = None, long, value_name = "N", group = "retention")]
+        keep_newest: Option<usize>,
+
+        /// Keep the N least recently modified PATHs and remove the rest.
+        ///
+        /// The retained PATHs are skipped. Use --keep-group to keep N PATHs per group.
+        #[arg(short = None, long, value_name = "N", group = "retention")]
+        keep_oldest: Option<usize>,
+
+        /// Only remove files larger than SIZE.
+        ///
+        /// SIZE is a number of bytes, optionally followed by one of the units K, M, G or T for
+        /// multiples of 1024. Directories are only removed if all of their contents are.
+        #[arg(short = None, long, value_name = "SIZE", value_parser = parse_size)]
+        larger_than: Option<u64>,
+
+        /// Refuse to remove anything if more than N entries would be removed.
+        ///
+        /// Everything is walked before anything is removed, so with --interactive all prompts are
+        /// shown first. Defaults to the value of the `RUST_RM_MAX_COUNT` environment variable, if
+        /// set. Ancestors removed because of --parents are not counted.
+        #[arg(short = None, long, value_name = "N")]
+        max_count: Option<usize>,
+
+        /// Only remove entries newer than AGE.
+        ///
+        /// See --older-than for the format of AGE.
+        #[arg(short = None, long, value_name = "AGE", value_parser = parse_age)]
+        newer_than: Option<Age>,
+
+        /// Don't show the size, number of entries and modification time when prompting.
+        ///
+        /// Useful on slow (network) file systems. Only has an effect when used with --interactive.
+        #[arg(short = None, long)]
+        no_details: bool,
+
         /// Do not treat the file system root specially.
         #[arg(short = None, long)]
         no_preserve_root: bool,
 
+        /// Only remove entries older than AGE.
+        ///
+        /// AGE is either a duration, for example '30d' or '1d12h' using the units s, m, h, d and w,
+        /// or a date in UTC, for example '2024-01-31' or '2024-01-31T12:00'. Directories are only
+        /// removed if all of their contents are, empty directories only if they match themselves.
+        #[arg(short = None, long, value_name = "AGE", value_parser = parse_age)]
+        older_than: Option<Age>,
+
+        /// Skip directories on a different file system than the PATH.
+        ///
+        /// Only has an effect when used with --recursive.
+        #[arg(short = 'x', long)]
+        one_file_system: bool,
+
+        /// Overwrite regular files before removing them, like --shred=3.
+        ///
+        /// Only available in BSD mode.
+        #[cfg(feature = "bsd-mode")]
+        #[arg(short = 'P', hide = true)]
+        overwrite: bool,
+
+        /// Remove ancestors of the PATH(s) that are empty after removal.
+        ///
+        /// Stops at the first ancestor that is not empty, the current directory, or the file
+        /// system root.
+        #[arg(short = 'p', long)]
+        parents: bool,
+
+        /// Pick the entries to remove from a checklist before removing them.
+        ///
+        /// The PATH(s) are listed, or their direct contents with --recursive. Only the picked
+        /// entries are removed, directories are kept if anything in them is kept.
+        #[cfg(feature = "pick")]
+        #[arg(short = None, long, group = "method")]
+        pick: bool,
+
+        /// Write a plan of what would be removed to FILE.
+        ///
+        /// The plan records the identity and metadata of each entry, use --apply-plan to remove
+        /// exactly those entries later.
+        #[arg(short = None, long, value_name = "FILE")]
+        plan_out: Option<PathBuf>,
+
+        /// Show the progress of the removal on stderr.
+        ///
+        /// Shows the number of entries processed, errors, throughput and the current directory.
+        /// Only shown if stderr is a terminal.
+        #[arg(short = None, long)]
+        progress: bool,
+
+        /// Count the entries to remove first, to show an estimate of the time left.
+        #[arg(short = None, long, requires = "progress")]
+        progress_eta: bool,
+
+        /// Refuse to remove files held open by running processes.
+        ///
+        /// Only available on Linux.
+        #[cfg(target_os = "linux")]
+        #[arg(short = None, long)]
+        protect_open: bool,
+
+        /// Refuse to remove entries modified within DURATION.
+        ///
+        /// DURATION is for example '10m' or '1h30m', using the units s, m, h, d and w. Defaults to
+        /// the value of the `RUST_RM_PROTECT_RECENT` environment variable, if set. Directories are
+        /// only removed if none of their contents are refused. Use --force-recent to remove them
+        /// anyway.
+        #[arg(short = None, long, value_name = "DURATION", value_parser = parse_window)]
+        protect_recent: Option<Duration>,
+
         /// Don't output to stdout.
         ///
         /// Only has an effect when used with --force.
@@ -93,11 +414,75 @@ mod cli {
         #[arg(short = 'r', long, group = "dirs")]
         recursive: bool,
 
+        /// Overwrite regular files PASSES times before removing them.
+        ///
+        /// All passes but the last write random data, the last pass writes zeros. PASSES defaults
+        /// to 3. Files are also truncated and renamed before they are removed. Files with other
+        /// hard links are refused. This cannot guarantee that the contents are unrecoverable on
+        /// copy-on-write or journaling file systems, nor on flash storage.
+        #[arg(
+            short = None,
+            long,
+            value_name = "PASSES",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "3",
+            value_parser = clap::value_parser!(u8).range(1..),
+        )]
+        #[cfg_attr(feature = "trash", arg(conflicts_with = "trash"))]
+        shred: Option<u8>,
+
+        /// Only remove files smaller than SIZE.
+        ///
+        /// See --larger-than for the format of SIZE.
+        #[arg(short = None, long, value_name = "SIZE", value_parser = parse_size)]
+        smaller_than: Option<u64>,
+
+        /// Remove at most N entries per second.
+        #[arg(short = None, long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+        throttle_count: Option<u64>,
+
+        /// Set the I/O scheduling class to idle while removing.
+        ///
+        /// Only available on Linux.
+        #[cfg(target_os = "linux")]
+        #[arg(short = None, long)]
+        throttle_idle: bool,
+
+        /// Free at most SIZE bytes per second.
+        ///
+        /// See --larger-than for the format of SIZE. Only the sizes of files are counted.
+        #[arg(short = None, long, value_name = "SIZE", value_parser = parse_chunk)]
+        throttle_size: Option<u64>,
+
+        /// The timestamp used by --older-than and --newer-than.
+        #[arg(short = None, long, value_name = "TIME", value_enum, default_value_t = TimeArg::Mtime)]
+        time: TimeArg,
+
         /// Move to the trash bin instead of removing.
         #[cfg(feature = "trash")]
         #[arg(short = 't', long)]
         trash: bool,
 
+        /// Only remove entries of the given TYPE(s).
+        ///
+        /// Multiple types can be separated by commas. Directories are only removed if all of
+        /// their contents are.
+        #[arg(short = None, long = "type", value_name = "TYPE", value_enum, value_delimiter = ',')]
+        types: Vec<TypeArg>,
+
+        /// Attempt to undelete the PATH(s).
+        ///
+        /// Only available in BSD mode, where it is not supported.
+        #[cfg(feature = "bsd-mode")]
+        #[arg(short = 'W', hide = true)]
+        undelete: bool,
+
+        /// Only remove entries owned by USER, given as a name or ID.
+        #[cfg(unix)]
+        #[arg(short = None, long, value_name = "USER", value_parser = parse_user)]
+        user: Option<u32>,
+
         /// Explain what is being done.
         #[arg(short = 'v', long, group = "verbosity")]
         verbose: bool,
@@ -119,1875 +504,12491 @@ mod cli {
         }
     }
 
-    /// The `Result` type for parsing CLI arguments.
-    type ParseResult = Result<Args, Error>;
-
-    /// Parse arguments for the CLI.
-    ///
-    /// # Errors
-    ///
-    /// If the given arguments couldn't be parsed.
-    pub fn parse_args<T>(args: T, vars: Vars) -> ParseResult
-    where
-        T: IntoIterator<Item = String>,
-    {
-        let mut args = Args::try_parse_from(args)?;
-
-        #[cfg(feature = "gnu-mode")]
-        if vars.gnu_mode {
-            args = parse_args_gnu_mode(args)?;
-        }
+    /// Enum representing the age of entries as given on the command line.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub enum Age {
+        /// An age relative to the present, e.g. 7 days ago.
+        Ago(Duration),
+        /// An absolute point in time.
+        At(SystemTime),
+    }
 
-        if vars.debug {
-            args.verbose = true;
+    impl Age {
+        /// Get the point in time represented by this [`Age`] as seen from `now`.
+        fn cutoff(self, now: SystemTime) -> SystemTime {
+            match self {
+                Self::Ago(duration) => now.checked_sub(duration).unwrap_or(UNIX_EPOCH),
+                Self::At(time) => time,
+            }
         }
-
-        Ok(args)
     }
 
-    /// Tests for the [`parse_args`] function.
+    /// Tests for the [`Age`] enum.
     #[cfg(test)]
-    mod test_parse_args {
-        use super::test_helpers::{TestArgs, TestArgsAndIndex, parse_args};
+    mod test_age {
+        use crate::test_helpers::unix_time;
 
-        use super::Vars;
+        use super::Age;
 
-        use std::ffi::OsString;
+        use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
         use proptest::prelude::*;
         use proptest_attr_macro::proptest;
 
         #[proptest]
-        fn paths(args: TestArgs, vars: Vars) {
-            let args = args.inner();
-
-            let options = args.iter().take_while(|arg| **arg != "--");
-            let operands = args.iter().skip_while(|arg| **arg != "--").skip(1);
-            let expected: Vec<OsString> = options
-                .filter(|arg| !arg.starts_with('-'))
-                .chain(operands)
-                .map(OsString::from)
-                .collect();
+        fn cutoff_ago(secs: u32) {
+            let now = unix_time(u64::from(u32::MAX));
+            let age = Age::Ago(Duration::from_secs(u64::from(secs)));
+            prop_assert_eq!(age.cutoff(now), unix_time(u64::from(u32::MAX.saturating_sub(secs))));
+        }
 
-            match parse_args(args, vars) {
-                Ok(args) => prop_assert_eq!(args.paths, expected),
-                Err(()) => prop_assume!(false),
-            }
+        #[test]
+        fn cutoff_ago_overflow() {
+            let age = Age::Ago(Duration::MAX);
+            assert_eq!(age.cutoff(unix_time(10)), UNIX_EPOCH);
         }
 
         #[proptest]
-        fn blind_long_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!vars.gnu_mode());
-
-            match parse_args(args.insert("--blind"), vars) {
-                Ok(args) => prop_assert!(args.blind),
-                Err(()) => prop_assume!(false),
-            }
+        fn cutoff_at(secs: u32) {
+            let time = unix_time(u64::from(secs));
+            prop_assert_eq!(Age::At(time).cutoff(SystemTime::now()), time);
         }
+    }
 
-        #[proptest]
-        fn blind_short_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!vars.gnu_mode());
+    /// Parse an [`Age`] from a command line value, either a duration or a date.
+    ///
+    /// # Errors
+    ///
+    /// If the value is neither a valid duration nor a valid date.
+    fn parse_age(value: &str) -> Result<Age, String> {
+        parse_duration(value).map(Age::Ago).or_else(|| parse_date(value).map(Age::At)).ok_or_else(
+            || "expected a duration such as '30d' or a date such as '2024-01-31'".to_owned(),
+        )
+    }
 
-            match parse_args(args.insert("-b"), vars) {
-                Ok(args) => prop_assert!(args.blind),
-                Err(()) => prop_assume!(false),
-            }
-        }
+    /// Tests for the [`parse_age`] function.
+    #[cfg(test)]
+    mod test_parse_age {
+        use crate::test_helpers::unix_time;
 
-        #[proptest]
-        fn not_blind(args: TestArgs, vars: Vars) {
-            prop_assume!(!args.contains("--blind"));
-            prop_assume!(!args.contains("-b"));
-            prop_assume!(!vars.gnu_mode());
+        use super::{Age, parse_age};
 
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(!args.blind),
-                Err(()) => prop_assume!(false),
-            }
-        }
+        use std::time::Duration;
 
-        #[proptest]
-        fn dir_long_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
+        #[test]
+        fn duration() {
+            assert_eq!(parse_age("2h"), Ok(Age::Ago(Duration::from_secs(7200))));
+        }
 
-            match parse_args(args.insert("--dir"), vars) {
-                Ok(args) => prop_assert!(args.dir),
-                Err(()) => prop_assume!(false),
-            }
+        #[test]
+        fn date() {
+            assert_eq!(parse_age("1970-01-02"), Ok(Age::At(unix_time(86400))));
         }
 
-        #[proptest]
-        fn dir_short_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
+        #[test]
+        fn invalid() {
+            assert!(parse_age("yesterday").is_err());
+        }
+    }
 
-            match parse_args(args.insert("-d"), vars) {
-                Ok(args) => prop_assert!(args.dir),
-                Err(()) => prop_assume!(false),
+    /// Parse a duration consisting of one or more numbers each followed by a unit, one of 's', 'm',
+    /// 'h', 'd' or 'w'. For example "30d" or "1d12h".
+    fn parse_duration(value: &str) -> Option<Duration> {
+        let mut total: u64 = 0;
+        let mut number: Option<u64> = None;
+        for char in value.chars() {
+            if let Some(digit) = char.to_digit(10) {
+                number = number.unwrap_or(0).checked_mul(10)?.checked_add(u64::from(digit));
+                number?;
+            } else {
+                let unit: u64 = match char {
+                    's' => 1,
+                    'm' => 60,
+                    'h' => 60 * 60,
+                    'd' => 24 * 60 * 60,
+                    'w' => 7 * 24 * 60 * 60,
+                    _ => return None,
+                };
+                total = total.checked_add(number.take()?.checked_mul(unit)?)?;
             }
         }
 
-        #[proptest]
-        fn not_dir(args: TestArgs, vars: Vars) {
-            prop_assume!(!args.contains("--dir"));
-            prop_assume!(!args.contains("-d"));
-
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(!args.dir),
-                Err(()) => prop_assume!(false),
-            }
+        match (value.is_empty(), number) {
+            (false, None) => Some(Duration::from_secs(total)),
+            _ => None,
         }
+    }
 
-        #[proptest]
-        fn force_long_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!vars.gnu_mode());
+    /// Tests for the [`parse_duration`] function.
+    #[cfg(test)]
+    mod test_parse_duration {
+        use super::parse_duration;
 
-            match parse_args(args.insert("--force"), vars) {
-                Ok(args) => prop_assert!(args.force),
-                Err(()) => prop_assume!(false),
-            }
-        }
+        use std::time::Duration;
 
-        #[proptest]
-        fn force_short_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!vars.gnu_mode());
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
 
-            match parse_args(args.insert("-f"), vars) {
-                Ok(args) => prop_assert!(args.force),
-                Err(()) => prop_assume!(false),
-            }
+        #[proptest]
+        fn seconds(n: u32) {
+            let out = parse_duration(&format!("{n}s"));
+            prop_assert_eq!(out, Some(Duration::from_secs(u64::from(n))));
         }
 
-        #[proptest]
-        fn not_force(args: TestArgs, vars: Vars) {
-            prop_assume!(!args.contains("--force"));
-            prop_assume!(!args.contains("-f"));
-            prop_assume!(!vars.gnu_mode());
+        #[test]
+        fn units() {
+            assert_eq!(parse_duration("0s"), Some(Duration::ZERO));
+            assert_eq!(parse_duration("1m"), Some(Duration::from_secs(60)));
+            assert_eq!(parse_duration("1h"), Some(Duration::from_secs(3600)));
+            assert_eq!(parse_duration("1d"), Some(Duration::from_secs(86400)));
+            assert_eq!(parse_duration("1w"), Some(Duration::from_secs(604_800)));
+        }
 
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(!args.force),
-                Err(()) => prop_assume!(false),
-            }
+        #[test]
+        fn combined() {
+            assert_eq!(parse_duration("1d12h"), Some(Duration::from_secs(129_600)));
+            assert_eq!(parse_duration("1h1h"), Some(Duration::from_secs(7200)));
         }
 
-        #[proptest]
-        fn interactive_long_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
+        #[test]
+        fn overflow() {
+            assert_eq!(
+                parse_duration("18446744073709551615s"),
+                Some(Duration::from_secs(u64::MAX))
+            );
+            assert_eq!(parse_duration("18446744073709551616s"), None);
+            assert_eq!(parse_duration("18446744073709551615m"), None);
+        }
 
-            match parse_args(args.insert("--interactive"), vars) {
-                Ok(args) => prop_assert!(args.interactive),
-                Err(()) => prop_assume!(false),
-            }
+        #[test]
+        fn invalid() {
+            assert_eq!(parse_duration(""), None);
+            assert_eq!(parse_duration("1"), None);
+            assert_eq!(parse_duration("d"), None);
+            assert_eq!(parse_duration("1d2"), None);
+            assert_eq!(parse_duration("1y"), None);
+            assert_eq!(parse_duration("-1d"), None);
+            assert_eq!(parse_duration("1 d"), None);
         }
+    }
 
-        #[proptest]
-        fn interactive_short_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
+    /// Parse a window of time from a command line value, see [`parse_duration`].
+    ///
+    /// # Errors
+    ///
+    /// If the value is not a valid duration.
+    fn parse_window(value: &str) -> Result<Duration, String> {
+        parse_duration(value)
+            .ok_or_else(|| "expected a duration such as '10m' or '1h30m'".to_owned())
+    }
 
-            match parse_args(args.insert("-i"), vars) {
-                Ok(args) => prop_assert!(args.interactive),
-                Err(()) => prop_assume!(false),
-            }
-        }
+    /// Tests for the [`parse_window`] function.
+    #[cfg(test)]
+    mod test_parse_window {
+        use super::parse_window;
 
-        #[proptest]
-        fn not_interactive(args: TestArgs, vars: Vars) {
-            prop_assume!(!args.contains("--interactive"));
-            prop_assume!(!args.contains("-i"));
+        use std::time::Duration;
 
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(!args.interactive),
-                Err(()) => prop_assume!(false),
-            }
+        #[test]
+        fn duration() {
+            assert_eq!(parse_window("10m"), Ok(Duration::from_secs(600)));
         }
 
-        #[proptest]
-        fn no_preserve_root_long_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
+        #[test]
+        fn invalid() {
+            assert!(parse_window("2024-01-31").is_err());
+        }
+    }
 
-            match parse_args(args.insert("--no-preserve-root"), vars) {
-                Ok(args) => prop_assert!(args.no_preserve_root),
-                Err(()) => prop_assume!(false),
-            }
+    /// Parse a pause from a command line value, either a number of milliseconds followed by "ms" or
+    /// a duration (see [`parse_duration`]).
+    ///
+    /// # Errors
+    ///
+    /// If the value is not a valid number of milliseconds or duration.
+    fn parse_pause(value: &str) -> Result<Duration, String> {
+        match value.strip_suffix("ms") {
+            Some(millis)
+                if !millis.is_empty() && millis.chars().all(|char| char.is_ascii_digit()) =>
+            {
+                millis.parse().map(Duration::from_millis).map_err(|err| err.to_string())
+            },
+            _ => parse_duration(value)
+                .ok_or_else(|| "expected a duration such as '100ms' or '1s'".to_owned()),
         }
+    }
 
-        #[proptest]
-        fn not_no_preserve_root(args: TestArgs, vars: Vars) {
-            prop_assume!(!args.contains("--no-preserve-root"));
+    /// Tests for the [`parse_pause`] function.
+    #[cfg(test)]
+    mod test_parse_pause {
+        use super::parse_pause;
 
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(!args.no_preserve_root),
-                Err(()) => prop_assume!(false),
-            }
+        use std::time::Duration;
+
+        #[test]
+        fn millis() {
+            assert_eq!(parse_pause("100ms"), Ok(Duration::from_millis(100)));
         }
 
-        #[proptest]
-        fn quiet_long_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!vars.gnu_mode());
+        #[test]
+        fn duration() {
+            assert_eq!(parse_pause("2s"), Ok(Duration::from_secs(2)));
+        }
 
-            match parse_args(args.insert("--quiet"), vars) {
-                Ok(args) => prop_assert!(args.quiet),
-                Err(()) => prop_assume!(false),
-            }
+        #[test]
+        fn invalid() {
+            assert!(parse_pause("ms").is_err());
+            assert!(parse_pause("1s100ms").is_err());
+            assert!(parse_pause("-1ms").is_err());
+            assert!(parse_pause("fast").is_err());
         }
+    }
 
-        #[proptest]
-        fn quiet_short_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!vars.gnu_mode());
+    /// Parse a date in UTC of the form "YYYY-MM-DD", optionally followed by a 'T' or space and a
+    /// time of the form "HH:MM" or "HH:MM:SS". Dates before 1970 are not supported.
+    fn parse_date(value: &str) -> Option<SystemTime> {
+        let (date, time) = match value.split_once(['T', ' ']) {
+            Some((date, time)) => (date, Some(time)),
+            None => (value, None),
+        };
 
-            match parse_args(args.insert("-q"), vars) {
-                Ok(args) => prop_assert!(args.quiet),
-                Err(()) => prop_assume!(false),
-            }
-        }
+        let (year, month, day) = match date.split('-').collect::<Vec<_>>().as_slice() {
+            [year, month, day] => {
+                (parse_digits(year, 4)?, parse_digits(month, 2)?, parse_digits(day, 2)?)
+            },
+            _ => return None,
+        };
+        let (hours, minutes, seconds) = match time.map(|time| time.split(':').collect::<Vec<_>>()) {
+            None => (0, 0, 0),
+            Some(time) => match time.as_slice() {
+                [hours, minutes] => (parse_digits(hours, 2)?, parse_digits(minutes, 2)?, 0),
+                [hours, minutes, seconds] => {
+                    (parse_digits(hours, 2)?, parse_digits(minutes, 2)?, parse_digits(seconds, 2)?)
+                },
+                _ => return None,
+            },
+        };
 
-        #[proptest]
-        fn not_quiet(args: TestArgs, vars: Vars) {
-            prop_assume!(!args.contains("--quiet"));
-            prop_assume!(!args.contains("-q"));
-            prop_assume!(!vars.gnu_mode());
+        if year < 1970
+            || !(1..=12).contains(&month)
+            || !(1..=days_in_month(year, month)).contains(&day)
+            || hours >= 24
+            || minutes >= 60
+            || seconds >= 60
+        {
+            return None;
+        }
+
+        let days = (1970..year)
+            .map(|year| if is_leap_year(year) { 366 } else { 365 })
+            .chain((1..month).map(|month| days_in_month(year, month)))
+            .sum::<u64>()
+            .checked_add(day)?
+            .checked_sub(1)?;
+        let secs = days
+            .checked_mul(24)?
+            .checked_add(hours)?
+            .checked_mul(60)?
+            .checked_add(minutes)?
+            .checked_mul(60)?
+            .checked_add(seconds)?;
+
+        UNIX_EPOCH.checked_add(Duration::from_secs(secs))
+    }
 
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(!args.quiet),
-                Err(()) => prop_assume!(false),
-            }
-        }
+    /// Tests for the [`parse_date`] function.
+    #[cfg(test)]
+    mod test_parse_date {
+        use crate::test_helpers::unix_time;
 
-        #[proptest]
-        fn recursive_long_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
+        use super::parse_date;
 
-            match parse_args(args.insert("--recursive"), vars) {
-                Ok(args) => prop_assert!(args.recursive),
-                Err(()) => prop_assume!(false),
-            }
-        }
+        use std::time::UNIX_EPOCH;
 
-        #[proptest]
-        fn recursive_short_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
+        #[test]
+        fn epoch() {
+            assert_eq!(parse_date("1970-01-01"), Some(UNIX_EPOCH));
+        }
 
-            match parse_args(args.insert("-r"), vars) {
-                Ok(args) => prop_assert!(args.recursive),
-                Err(()) => prop_assume!(false),
-            }
+        #[test]
+        fn date() {
+            assert_eq!(parse_date("2000-01-01"), Some(unix_time(946_684_800)));
+            assert_eq!(parse_date("2024-03-01"), Some(unix_time(1_709_251_200)));
         }
 
-        #[proptest]
-        fn not_recursive(args: TestArgs, vars: Vars) {
-            prop_assume!(!args.contains("--recursive"));
-            prop_assume!(!args.contains("-r"));
+        #[test]
+        fn date_and_time() {
+            let expected = Some(unix_time(946_729_815));
+            assert_eq!(parse_date("2000-01-01T12:30:15"), expected);
+            assert_eq!(parse_date("2000-01-01 12:30:15"), expected);
 
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(!args.recursive),
-                Err(()) => prop_assume!(false),
-            }
+            let expected = Some(unix_time(946_729_800));
+            assert_eq!(parse_date("2000-01-01T12:30"), expected);
         }
 
-        #[proptest]
-        #[cfg(feature = "trash")]
-        fn trash_long_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!vars.gnu_mode());
+        #[test]
+        fn leap_day() {
+            assert_eq!(parse_date("2000-02-29"), Some(unix_time(951_782_400)));
+            assert_eq!(parse_date("2024-02-29"), Some(unix_time(1_709_164_800)));
+            assert_eq!(parse_date("1900-02-29"), None);
+            assert_eq!(parse_date("2023-02-29"), None);
+            assert_eq!(parse_date("2100-02-29"), None);
+        }
 
-            match parse_args(args.insert("--trash"), vars) {
-                Ok(args) => prop_assert!(args.trash),
-                Err(()) => prop_assume!(false),
-            }
+        #[test]
+        fn invalid() {
+            assert_eq!(parse_date(""), None);
+            assert_eq!(parse_date("1969-12-31"), None);
+            assert_eq!(parse_date("2000-1-01"), None);
+            assert_eq!(parse_date("2000-00-01"), None);
+            assert_eq!(parse_date("2000-13-01"), None);
+            assert_eq!(parse_date("2000-04-31"), None);
+            assert_eq!(parse_date("2000-01-00"), None);
+            assert_eq!(parse_date("2000-01-01T"), None);
+            assert_eq!(parse_date("2000-01-01T24:00"), None);
+            assert_eq!(parse_date("2000-01-01T12:60"), None);
+            assert_eq!(parse_date("2000-01-01T12:00:60"), None);
+            assert_eq!(parse_date("2000-01-01T12:00:00:00"), None);
+            assert_eq!(parse_date("2000-01-01-01"), None);
+            assert_eq!(parse_date("+200-01-01"), None);
         }
+    }
 
-        #[proptest]
-        #[cfg(feature = "trash")]
-        fn trash_short_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!vars.gnu_mode());
+    /// Parse a number consisting of exactly `len` ASCII digits.
+    fn parse_digits(value: &str, len: usize) -> Option<u64> {
+        if value.len() == len && value.bytes().all(|byte| byte.is_ascii_digit()) {
+            value.parse().ok()
+        } else {
+            None
+        }
+    }
 
-            match parse_args(args.insert("-t"), vars) {
-                Ok(args) => prop_assert!(args.trash),
-                Err(()) => prop_assume!(false),
-            }
+    /// Get the number of days in the given `month` (1 through 12) of the given `year`.
+    fn days_in_month(year: u64, month: u64) -> u64 {
+        match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if is_leap_year(year) => 29,
+            2 => 28,
+            _ => 0,
         }
+    }
 
-        #[proptest]
-        #[cfg(feature = "trash")]
-        fn not_trash(args: TestArgs, vars: Vars) {
-            prop_assume!(!args.contains("--trash"));
-            prop_assume!(!args.contains("-t"));
-            prop_assume!(!vars.gnu_mode());
+    /// Returns `true` if the given `year` is a leap year in the Gregorian calendar.
+    fn is_leap_year(year: u64) -> bool {
+        year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+    }
 
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(!args.trash),
-                Err(()) => prop_assume!(false),
+    /// Enum representing the timestamps that can be selected on the command line.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+    #[cfg_attr(test, derive(Arbitrary))]
+    pub enum TimeArg {
+        /// The time of last access.
+        Atime,
+        /// The time of last status change, or creation on non-Unix systems.
+        Ctime,
+        /// The time of last modification.
+        Mtime,
+    }
+
+    impl From<TimeArg> for fs::Timestamp {
+        fn from(time: TimeArg) -> Self {
+            match time {
+                TimeArg::Atime => Self::Accessed,
+                TimeArg::Ctime => Self::Changed,
+                TimeArg::Mtime => Self::Modified,
             }
         }
+    }
 
-        #[proptest]
-        fn verbose_long_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!vars.debug);
+    /// Parse the path to an existing directory.
+    ///
+    /// # Errors
+    ///
+    /// If the value is not the path to a directory.
+    #[cfg(unix)]
+    fn parse_dir(value: &str) -> Result<PathBuf, String> {
+        let path = PathBuf::from(value);
+        if path.is_dir() { Ok(path) } else { Err("expected a directory".to_owned()) }
+    }
 
-            match parse_args(args.insert("--verbose"), vars) {
-                Ok(args) => prop_assert!(args.verbose),
-                Err(()) => prop_assume!(false),
-            }
-        }
+    /// Tests for the [`parse_dir`] function.
+    #[cfg(test)]
+    #[cfg(unix)]
+    mod test_parse_dir {
+        use crate::test_helpers::{TestResult, with_test_dir};
 
-        #[proptest]
-        fn verbose_short_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!vars.debug);
+        use super::parse_dir;
 
-            match parse_args(args.insert("-v"), vars) {
-                Ok(args) => prop_assert!(args.verbose),
-                Err(()) => prop_assume!(false),
-            }
-        }
+        use assert_fs::prelude::*;
 
-        #[proptest]
-        fn not_verbose(args: TestArgs, vars: Vars) {
-            prop_assume!(!args.contains("--verbose"));
-            prop_assume!(!args.contains("-v"));
-            prop_assume!(!vars.debug);
+        #[test]
+        fn dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
 
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(!args.verbose),
-                Err(()) => prop_assume!(false),
-            }
+                let value = dir.to_string_lossy();
+                assert_eq!(parse_dir(&value), Ok(dir.to_path_buf()));
+
+                Ok(())
+            })
         }
 
-        #[proptest]
-        fn debug_not_verbose(args: TestArgs, vars: Vars) {
-            prop_assume!(!args.contains("--verbose"));
-            prop_assume!(!args.contains("-v"));
-            prop_assume!(vars.debug);
+        #[test]
+        fn not_a_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
 
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(args.verbose),
-                Err(()) => prop_assume!(false),
-            }
+                assert!(parse_dir(&file.to_string_lossy()).is_err());
+                assert!(parse_dir(&test_dir.child("missing").to_string_lossy()).is_err());
+
+                Ok(())
+            })
         }
+    }
 
-        #[proptest]
-        fn debug_and_verbose(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(vars.debug);
+    /// Parse a size in bytes, optionally followed by one of the units 'K', 'M', 'G' or 'T'.
+    ///
+    /// # Errors
+    ///
+    /// If the value is not a valid size.
+    fn parse_size(value: &str) -> Result<u64, String> {
+        let (number, unit) = match value.find(|char: char| !char.is_ascii_digit()) {
+            Some(index) => value.split_at(index),
+            None => (value, ""),
+        };
+        let multiplier: u64 = match unit {
+            "" => 1,
+            "K" => 1 << 10,
+            "M" => 1 << 20,
+            "G" => 1 << 30,
+            "T" => 1 << 40,
+            _ => return Err("expected a size such as '512' or '10M'".to_owned()),
+        };
 
-            match parse_args(args.insert("--verbose"), vars) {
-                Ok(args) => prop_assert!(args.verbose),
-                Err(()) => prop_assume!(false),
-            }
-        }
+        number
+            .parse::<u64>()
+            .ok()
+            .and_then(|number| number.checked_mul(multiplier))
+            .ok_or_else(|| "expected a size such as '512' or '10M'".to_owned())
+    }
+
+    /// Tests for the [`parse_size`] function.
+    #[cfg(test)]
+    mod test_parse_size {
+        use super::parse_size;
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
 
         #[proptest]
-        fn disallow_dir_with_recursive(vars: Vars) {
-            let out = test_combination_errors(("dir", 'd'), ("recursive", 'r'), vars);
-            prop_assert!(out.is_err());
+        fn bytes(n: u64) {
+            prop_assert_eq!(parse_size(&n.to_string()), Ok(n));
         }
 
-        #[proptest]
-        fn disallow_force_with_interactive(vars: Vars) {
-            let out = test_combination_errors(("force", 'f'), ("interactive", 'i'), vars);
-            prop_assert!(out.is_err());
+        #[test]
+        fn units() {
+            assert_eq!(parse_size("0K"), Ok(0));
+            assert_eq!(parse_size("1K"), Ok(1024));
+            assert_eq!(parse_size("1M"), Ok(1_048_576));
+            assert_eq!(parse_size("1G"), Ok(1_073_741_824));
+            assert_eq!(parse_size("1T"), Ok(1_099_511_627_776));
         }
 
-        #[proptest]
-        fn disallow_quiet_with_verbose(vars: Vars) {
-            let out = test_combination_errors(("quiet", 'q'), ("verbose", 'v'), vars);
-            prop_assert!(out.is_err());
+        #[test]
+        fn overflow() {
+            assert_eq!(parse_size("16777215T"), Ok(18_446_742_974_197_923_840));
+            assert!(parse_size("16777216T").is_err());
+            assert!(parse_size("18446744073709551616").is_err());
         }
 
-        /// Type representing the long and short names of a flag.
-        type FlagPair<'a> = (&'a str, char);
+        #[test]
+        fn invalid() {
+            assert!(parse_size("").is_err());
+            assert!(parse_size("K").is_err());
+            assert!(parse_size("1k").is_err());
+            assert!(parse_size("1KB").is_err());
+            assert!(parse_size("-1").is_err());
+            assert!(parse_size("1.5M").is_err());
+        }
+    }
 
-        /// Test that parsing arguments with both `flag1` and `flag2` present always errors.
-        ///
-        /// # Example
-        ///
-        /// ```no_run
-        /// use cli::Vars;
-        ///
-        /// let vars = Vars { debug: false, gnu_mode: false };
-        /// test_combination_errors(("long-a", 'a'), ("long-b", 'b'), vars)?;
-        /// ```
-        fn test_combination_errors(flag1: FlagPair, flag2: FlagPair, vars: Vars) -> Result<(), ()> {
-            let cases = [
-                vec![format!("--{}", flag1.0), format!("--{}", flag2.0)],
-                vec![format!("--{}", flag1.0), format!("-{}", flag2.1)],
-                vec![format!("-{}", flag1.1), format!("--{}", flag2.0)],
-                vec![format!("-{}", flag1.1), format!("-{}", flag2.1)],
-                vec![format!("--{}", flag2.0), format!("--{}", flag1.0)],
-                vec![format!("--{}", flag2.0), format!("-{}", flag1.1)],
-                vec![format!("-{}", flag2.1), format!("--{}", flag1.0)],
-                vec![format!("-{}", flag2.1), format!("-{}", flag1.1)],
-                vec![format!("-{}{}", flag1.1, flag2.1)],
-                vec![format!("-{}{}", flag2.1, flag1.1)],
-            ];
-
-            for args in cases {
-                let out = parse_args(args.clone(), vars);
-                if out.is_err() {
-                    return Err(());
-                }
-            }
-
-            Ok(())
-        }
-    }
-
-    /// Parse arguments for the CLI with GNU mode enabled, modifying the given `args` in place.
+    /// Parse a chunk size, a size (see [`parse_size`]) that is not zero.
     ///
     /// # Errors
     ///
-    /// If an unsupported flags is used, but only if the `force` option isn't set.
-    #[cfg(feature = "gnu-mode")]
-    fn parse_args_gnu_mode(mut args: Args) -> ParseResult {
-        use clap::error::ErrorKind;
-
-        macro_rules! check_use_of_invalid_flag {
-            ($flag:ident) => {
-                if args.$flag {
-                    return Err(Error::raw(
-                        ErrorKind::UnknownArgument,
-                        format!("option --{} not supported in GNU mode\n", stringify!($flag)),
-                    ));
-                }
-            };
+    /// If the value is not a valid size or zero.
+    fn parse_chunk(value: &str) -> Result<u64, String> {
+        match parse_size(value)? {
+            0 => Err("expected a size larger than zero".to_owned()),
+            size => Ok(size),
         }
+    }
 
-        if !args.force {
-            check_use_of_invalid_flag!(blind);
-            check_use_of_invalid_flag!(quiet);
-            #[cfg(feature = "trash")]
-            check_use_of_invalid_flag!(trash);
-        }
+    /// Tests for the [`parse_chunk`] function.
+    #[cfg(test)]
+    mod test_parse_chunk {
+        use super::parse_chunk;
 
-        args.blind = args.force; // rm(1) behaves blindly with --force
-        args.force = !args.interactive; // rm(1) removes unless --interactive
-        args.quiet = true; // rm(1) is always --quiet
-        #[cfg(feature = "trash")]
-        {
-            args.trash = false; // rm(1) does not support --trash
+        #[test]
+        fn size() {
+            assert_eq!(parse_chunk("1G"), Ok(1 << 30));
         }
 
-        Ok(args)
+        #[test]
+        fn zero() {
+            assert!(parse_chunk("0").is_err());
+            assert!(parse_chunk("0M").is_err());
+        }
     }
 
-    /// Tests for the [`parse_args_gnu_mode`] function.
-    #[cfg(test)]
-    #[cfg(feature = "gnu-mode")]
-    mod test_parse_args_gnu_mode {
-        use super::test_helpers::{TestArgs, TestArgsAndIndex};
+    /// Enum representing the types of entries that can be selected on the command line.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+    #[cfg_attr(test, derive(Arbitrary))]
+    pub enum TypeArg {
+        /// Regular file.
+        #[value(name = "f")]
+        File,
+        /// Directory.
+        #[value(name = "d")]
+        Dir,
+        /// Symbolic link.
+        #[value(name = "l")]
+        Symlink,
+        /// Named pipe.
+        #[value(name = "p")]
+        Fifo,
+        /// Socket.
+        #[value(name = "s")]
+        Socket,
+    }
 
-        use super::Vars;
+    impl From<TypeArg> for fs::EntryKind {
+        fn from(kind: TypeArg) -> Self {
+            match kind {
+                TypeArg::Dir => Self::Dir,
+                TypeArg::Fifo => Self::Fifo,
+                TypeArg::File => Self::File,
+                TypeArg::Socket => Self::Socket,
+                TypeArg::Symlink => Self::Symlink,
+            }
+        }
+    }
 
-        use proptest::prelude::*;
-        use proptest_attr_macro::proptest;
+    /// Parse a user given by name or ID into a user ID.
+    ///
+    /// # Errors
+    ///
+    /// If the value is not an ID and no user with that name exists.
+    #[cfg(unix)]
+    fn parse_user(value: &str) -> Result<u32, String> {
+        value
+            .parse()
+            .ok()
+            .or_else(|| id_by_name(value, libc::getpwnam_r, |passwd| passwd.pw_uid))
+            .ok_or_else(|| "no such user".to_owned())
+    }
 
-        #[proptest]
-        fn blind_when_force_long_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
+    /// Parse a group given by name or ID into a group ID.
+    ///
+    /// # Errors
+    ///
+    /// If the value is not an ID and no group with that name exists.
+    #[cfg(unix)]
+    fn parse_group(value: &str) -> Result<u32, String> {
+        value
+            .parse()
+            .ok()
+            .or_else(|| id_by_name(value, libc::getgrnam_r, |group| group.gr_gid))
+            .ok_or_else(|| "no such group".to_owned())
+    }
 
-            match parse_args(args.insert("--force"), vars) {
-                Ok(args) => prop_assert!(args.blind),
-                Err(()) => prop_assume!(false),
+    /// The signature of the reentrant functions to look up a database entry by name, such as
+    /// `getpwnam_r(3)` and `getgrnam_r(3)`.
+    #[cfg(unix)]
+    type LookupByName<T> = unsafe extern "C" fn(
+        *const libc::c_char,
+        *mut T,
+        *mut libc::c_char,
+        libc::size_t,
+        *mut *mut T,
+    ) -> libc::c_int;
+
+    /// The largest buffer used for the strings of a database entry, see [`id_by_name`].
+    #[cfg(unix)]
+    const MAX_LOOKUP_BUFFER: usize = 1 << 20;
+
+    /// Look up the ID for the given `name` using the given `lookup` function, so that all sources
+    /// configured for the system (e.g. LDAP through NSS) are used, and get it from the entry using
+    /// `id_of`.
+    #[cfg(unix)]
+    fn id_by_name<T>(name: &str, lookup: LookupByName<T>, id_of: fn(&T) -> u32) -> Option<u32> {
+        use std::ffi::CString;
+        use std::mem::MaybeUninit;
+        use std::ptr;
+
+        let name = CString::new(name).ok()?;
+        let mut len = 1024;
+        while len <= MAX_LOOKUP_BUFFER {
+            let mut buf: Vec<libc::c_char> = vec![0; len];
+            let mut entry = MaybeUninit::<T>::uninit();
+            let mut result = ptr::null_mut();
+
+            // SAFETY: all pointers are valid for the duration of the call and `buf.len()` is the
+            // size of the buffer pointed to.
+            let code = unsafe {
+                lookup(
+                    name.as_ptr(),
+                    entry.as_mut_ptr(),
+                    buf.as_mut_ptr(),
+                    buf.len(),
+                    &raw mut result,
+                )
+            };
+            if code == libc::ERANGE {
+                len = len.saturating_mul(2);
+                continue;
             }
+            if code != 0 || result.is_null() {
+                return None;
+            }
+
+            // SAFETY: the lookup succeeded and found an entry, so `entry` has been initialized.
+            return Some(id_of(unsafe { entry.assume_init_ref() }));
         }
 
-        #[proptest]
-        fn blind_when_force_short_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
+        None
+    }
 
-            match parse_args(args.insert("-f"), vars) {
-                Ok(args) => prop_assert!(args.blind),
-                Err(()) => prop_assume!(false),
-            }
-        }
+    /// Tests for the [`id_by_name`] function.
+    #[cfg(all(test, unix))]
+    mod test_id_by_name {
+        use super::id_by_name;
 
-        #[proptest]
-        fn not_blind_when_not_force(args: TestArgs, vars: Vars) {
-            prop_assume!(!args.contains("--force"));
-            prop_assume!(!args.contains("-f"));
+        #[test]
+        fn user_found() {
+            let out = id_by_name("root", libc::getpwnam_r, |passwd| passwd.pw_uid);
+            assert_eq!(out, Some(0));
+        }
 
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(!args.blind),
-                Err(()) => prop_assume!(false),
-            }
+        #[test]
+        #[cfg(target_os = "linux")]
+        fn group_found() {
+            let out = id_by_name("root", libc::getgrnam_r, |group| group.gr_gid);
+            assert_eq!(out, Some(0));
         }
 
-        #[proptest]
-        fn dir_when_dir_long_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
+        #[test]
+        fn not_found() {
+            let name = "rust-rm-missing";
+            assert_eq!(id_by_name(name, libc::getpwnam_r, |passwd| passwd.pw_uid), None);
+            assert_eq!(id_by_name(name, libc::getgrnam_r, |group| group.gr_gid), None);
+        }
 
-            match parse_args(args.insert("--dir"), vars) {
-                Ok(args) => prop_assert!(args.dir),
-                Err(()) => prop_assume!(false),
-            }
+        #[test]
+        fn invalid() {
+            assert_eq!(id_by_name("ro\0ot", libc::getpwnam_r, |passwd| passwd.pw_uid), None);
         }
+    }
 
-        #[proptest]
-        fn dir_when_dir_short_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
+    /// Parse a regular expression.
+    ///
+    /// # Errors
+    ///
+    /// If the value is not a valid regular expression.
+    fn parse_regex(value: &str) -> Result<Regex, String> {
+        Regex::new(value).map_err(|err| err.to_string())
+    }
 
-            match parse_args(args.insert("-d"), vars) {
-                Ok(args) => prop_assert!(args.dir),
-                Err(()) => prop_assume!(false),
-            }
-        }
+    /// Enum representing which operands to retain.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    enum Retention {
+        /// Retain the given number of most recently modified operands.
+        Newest(usize),
+        /// Retain the given number of least recently modified operands.
+        Oldest(usize),
+    }
 
-        #[proptest]
-        fn not_dir_when_not_dir(args: TestArgs, vars: Vars) {
-            prop_assume!(!args.contains("--dir"));
-            prop_assume!(!args.contains("-d"));
+    impl Retention {
+        /// Get the [`Retention`] configured by the [`Args`], if any.
+        fn of(args: &Args) -> Option<Self> {
+            args.keep_newest.map(Self::Newest).or_else(|| args.keep_oldest.map(Self::Oldest))
+        }
+    }
 
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(!args.dir),
-                Err(()) => prop_assume!(false),
-            }
+    /// Get the [`transform::Thresholds`] configured by the [`Args`], if any.
+    ///
+    /// The home directory is only included if it can be determined.
+    fn thresholds_of(args: &Args) -> Option<transform::Thresholds> {
+        let home = if args.confirm_home {
+            env::home_dir().and_then(|home| canonicalize(home).ok())
+        } else {
+            None
+        };
+        if args.confirm_count.is_none() && args.confirm_size.is_none() && home.is_none() {
+            return None;
         }
 
-        #[proptest]
-        fn force_when_not_interactive(args: TestArgs, vars: Vars) {
-            prop_assume!(!args.contains("--interactive"));
-            prop_assume!(!args.contains("-i"));
+        Some(transform::Thresholds { count: args.confirm_count, size: args.confirm_size, home })
+    }
 
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(args.force),
-                Err(()) => prop_assume!(false),
-            }
-        }
+    /// Select the operands in `paths` to retain, per group of operands if `group_by` is provided.
+    /// Operands that can't be opened are never retained.
+    fn retained_operands(
+        paths: &[OsString],
+        retention: Retention,
+        group_by: Option<&Regex>,
+    ) -> Vec<PathBuf> {
+        let mut groups: BTreeMap<Option<String>, Vec<(SystemTime, PathBuf)>> = BTreeMap::new();
+        for path in paths {
+            let Some(modified) = fs::open(path)
+                .ok()
+                .and_then(|entry| fs::timestamp_of(&entry, fs::Timestamp::Modified))
+            else {
+                continue;
+            };
 
-        #[proptest]
-        fn not_force_when_interactive_long_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
+            let name = path.to_string_lossy();
+            let group = group_by.and_then(|regex| regex.captures(&name)).and_then(|captures| {
+                captures.get(1).or_else(|| captures.get(0)).map(|m| m.as_str().to_owned())
+            });
+            groups.entry(group).or_default().push((modified, PathBuf::from(path)));
+        }
+
+        groups
+            .into_values()
+            .flat_map(|mut group| {
+                let count = match retention {
+                    Retention::Newest(count) => {
+                        group.sort_by(|a, b| b.cmp(a));
+                        count
+                    },
+                    Retention::Oldest(count) => {
+                        group.sort();
+                        count
+                    },
+                };
+                group.into_iter().take(count).map(|(_, path)| path)
+            })
+            .collect()
+    }
 
-            match parse_args(args.insert("--interactive"), vars) {
-                Ok(args) => prop_assert!(!args.force),
-                Err(()) => prop_assume!(false),
-            }
-        }
+    /// Tests for the [`retained_operands`] function.
+    #[cfg(test)]
+    mod test_retained_operands {
+        use crate::test_helpers::{TestResult, unix_time, with_test_dir};
 
-        #[proptest]
-        fn not_force_when_interactive_short_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
+        use super::{Retention, retained_operands};
 
-            match parse_args(args.insert("-i"), vars) {
-                Ok(args) => prop_assert!(!args.force),
-                Err(()) => prop_assume!(false),
-            }
-        }
+        use std::ffi::OsString;
+        use std::fs::File;
+        use std::io;
+        use std::path::PathBuf;
 
-        #[proptest]
-        fn always_quiet(args: TestArgs, vars: Vars) {
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(args.quiet),
-                Err(()) => prop_assume!(false),
-            }
+        use assert_fs::TempDir;
+        use assert_fs::prelude::*;
+        use regex::Regex;
+
+        /// Create files with the given `names` in the `test_dir` with increasing modification
+        /// times, in the order given, and return their paths.
+        fn files(test_dir: &TempDir, names: &[&str]) -> io::Result<Vec<OsString>> {
+            names
+                .iter()
+                .zip(1..)
+                .map(|(name, secs)| {
+                    let path = test_dir.child(name).to_path_buf();
+                    File::create(&path)?.set_modified(unix_time(secs))?;
+                    Ok(path.into_os_string())
+                })
+                .collect()
         }
 
-        #[proptest]
-        #[cfg(feature = "trash")]
-        fn never_trash(args: TestArgs, vars: Vars) {
-            match parse_args(args.inner(), vars) {
-                Ok(args) => prop_assert!(!args.trash),
-                Err(()) => prop_assume!(false),
-            }
-        }
+        #[test]
+        fn newest() -> TestResult {
+            with_test_dir(|test_dir| {
+                let paths = files(test_dir, &["a", "b", "c"])?;
 
-        #[proptest]
-        fn disallow_blind_full_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!args.contains("--force"));
-            prop_assume!(!args.contains("-f"));
+                let out = retained_operands(&paths, Retention::Newest(2), None);
+                assert_eq!(
+                    out,
+                    vec![test_dir.child("c").to_path_buf(), test_dir.child("b").to_path_buf()]
+                );
 
-            prop_assert!(parse_args(args.insert("--blind"), vars).is_err());
+                Ok(())
+            })
         }
 
-        #[proptest]
-        fn disallow_blind_short_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!args.contains("--force"));
-            prop_assume!(!args.contains("-f"));
+        #[test]
+        fn oldest() -> TestResult {
+            with_test_dir(|test_dir| {
+                let paths = files(test_dir, &["a", "b", "c"])?;
 
-            prop_assert!(parse_args(args.insert("-b"), vars).is_err());
+                let out = retained_operands(&paths, Retention::Oldest(1), None);
+                assert_eq!(out, vec![test_dir.child("a").to_path_buf()]);
+
+                Ok(())
+            })
         }
 
-        #[proptest]
-        fn disallow_quiet_full_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!args.contains("--force"));
-            prop_assume!(!args.contains("-f"));
+        #[test]
+        fn more_than_available() -> TestResult {
+            with_test_dir(|test_dir| {
+                let paths = files(test_dir, &["a"])?;
 
-            prop_assert!(parse_args(args.insert("--quiet"), vars).is_err());
-        }
+                let out = retained_operands(&paths, Retention::Newest(5), None);
+                assert_eq!(out, vec![test_dir.child("a").to_path_buf()]);
 
-        #[proptest]
-        fn disallow_quiet_short_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!args.contains("--force"));
-            prop_assume!(!args.contains("-f"));
+                let out = retained_operands(&paths, Retention::Newest(0), None);
+                assert_eq!(out, Vec::<PathBuf>::new());
 
-            prop_assert!(parse_args(args.insert("-q"), vars).is_err());
+                Ok(())
+            })
         }
 
-        #[proptest]
-        #[cfg(feature = "trash")]
-        fn disallow_trash_full_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!args.contains("--force"));
-            prop_assume!(!args.contains("-f"));
+        #[test]
+        fn missing() -> TestResult {
+            with_test_dir(|test_dir| {
+                let paths = vec![test_dir.child("missing").to_path_buf().into_os_string()];
 
-            prop_assert!(parse_args(args.insert("--trash"), vars).is_err());
+                let out = retained_operands(&paths, Retention::Newest(1), None);
+                assert_eq!(out, Vec::<PathBuf>::new());
+
+                Ok(())
+            })
         }
 
-        #[proptest]
-        #[cfg(feature = "trash")]
-        fn disallow_trash_short_name(args: TestArgsAndIndex, vars: Vars) {
-            prop_assume!(!args.has_arg_before_index("--"));
-            prop_assume!(!args.contains("--force"));
-            prop_assume!(!args.contains("-f"));
+        #[test]
+        fn grouped() -> TestResult {
+            with_test_dir(|test_dir| {
+                let paths = files(test_dir, &["api-1", "web-1", "api-2", "web-2", "other"])?;
+                let regex = Regex::new("(api|web)-")?;
 
-            prop_assert!(parse_args(args.insert("-t"), vars).is_err());
+                let out = retained_operands(&paths, Retention::Newest(1), Some(&regex));
+                assert_eq!(
+                    out,
+                    vec![
+                        test_dir.child("other").to_path_buf(),
+                        test_dir.child("api-2").to_path_buf(),
+                        test_dir.child("web-2").to_path_buf(),
+                    ]
+                );
+
+                Ok(())
+            })
         }
 
-        /// Convenience wrapper to parse arguments using [`super::parse_args`]. Always sets
-        /// `vars.gnu_mode` to `true`.
-        ///
-        /// See also [`super::test_helpers::parse_args`].
-        fn parse_args(args: Vec<String>, vars: Vars) -> super::test_helpers::ParseResult {
-            super::test_helpers::parse_args(args, Vars { gnu_mode: true, ..vars })
+        #[test]
+        fn grouped_by_whole_match() -> TestResult {
+            with_test_dir(|test_dir| {
+                let paths = files(test_dir, &["api-1", "web-1", "api-2"])?;
+                let regex = Regex::new("api|web")?;
+
+                let out = retained_operands(&paths, Retention::Oldest(1), Some(&regex));
+                assert_eq!(
+                    out,
+                    vec![
+                        test_dir.child("api-1").to_path_buf(),
+                        test_dir.child("web-1").to_path_buf()
+                    ]
+                );
+
+                Ok(())
+            })
         }
     }
 
-    /// A standard environment variable name to enable verbose mode.
-    const DEBUG_MODE: &str = "DEBUG";
+    /// The `Result` type for parsing CLI arguments.
+    type ParseResult = Result<Args, Error>;
 
-    /// The environment variable name to enable compatibility mode with the GNU version of `rm(1)`.
-    #[cfg(feature = "gnu-mode")]
-    const GNU_MODE: &str = "RUST_RM_GNU_MODE";
+    /// Parse arguments for the CLI.
+    ///
+    /// # Errors
+    ///
+    /// If the given arguments couldn't be parsed.
+    pub fn parse_args<T>(args: T, vars: Vars) -> ParseResult
+    where
+        T: IntoIterator<Item = String>,
+    {
+        let mut args = Args::try_parse_from(args)?;
 
-    /// Struct representing parsed environment configuration values.
-    #[cfg_attr(test, derive(Arbitrary, Clone, Copy, Debug))]
-    pub struct Vars {
-        /// The environment configuration value for debug mode.
-        debug: bool,
+        #[cfg(all(feature = "bsd-mode", feature = "gnu-mode"))]
+        if vars.bsd_mode && vars.gnu_mode {
+            use clap::error::ErrorKind;
+
+            return Err(Error::raw(
+                ErrorKind::ArgumentConflict,
+                "BSD mode and GNU mode cannot be used together\n",
+            ));
+        }
+
+        #[cfg(feature = "bsd-mode")]
+        if vars.bsd_mode {
+            args = parse_args_bsd_mode(args)?;
+        } else {
+            check_no_bsd_mode_flags(&args)?;
+        }
 
-        /// The environment configuration value for GNU mode.
         #[cfg(feature = "gnu-mode")]
-        gnu_mode: bool,
-    }
+        if vars.gnu_mode {
+            args = parse_args_gnu_mode(args)?;
+        }
 
-    /// Parse environment variables for the CLI.
-    pub fn parse_vars<T>(vars: T) -> Vars
-    where
-        T: IntoIterator<Item = (String, String)>,
-    {
-        let vars: Vec<String> = vars.into_iter().map(|(name, _)| name).collect();
-        Vars {
-            debug: vars.contains(&DEBUG_MODE.to_owned()),
-            #[cfg(feature = "gnu-mode")]
-            gnu_mode: vars.contains(&GNU_MODE.to_owned()),
+        if vars.debug {
+            args.verbose = true;
+        }
+
+        if args.confirm_count.is_none() {
+            args.confirm_count = vars.confirm_count;
+        }
+        if args.confirm_size.is_none() {
+            args.confirm_size = vars.confirm_size;
+        }
+        if args.max_count.is_none() {
+            args.max_count = vars.max_count;
+        }
+        if args.protect_recent.is_none() {
+            args.protect_recent = vars.protect_recent;
         }
+
+        if args.detach {
+            check_detach_vars(&vars)?;
+        }
+        if args.answers_from.is_some() {
+            check_answers_used(&args)?;
+        }
+
+        Ok(args)
     }
 
-    /// Tests for the [`parse_vars`] function.
+    /// Tests for the [`parse_args`] function.
     #[cfg(test)]
-    mod test_parse_vars {
-        use super::test_helpers::{TestVars, TestVarsAndIndex};
+    mod test_parse_args {
+        use super::test_helpers::{TestArgs, TestArgsAndIndex, parse_args};
 
-        use super::parse_vars;
+        use super::{Age, TimeArg, TypeArg, Vars};
 
+        use std::ffi::OsString;
+        use std::path::PathBuf;
+        use std::time::{Duration, UNIX_EPOCH};
+
+        use clap::ValueEnum as _;
         use proptest::prelude::*;
         use proptest_attr_macro::proptest;
 
         #[proptest]
-        #[cfg(feature = "gnu-mode")]
-        fn gnu_mode_not_set(vars: TestVars) {
-            prop_assume!(!vars.contains_key(super::GNU_MODE));
+        fn paths(args: TestArgs, vars: Vars) {
+            let args = args.inner();
 
-            let out = parse_vars(vars.inner());
-            prop_assert!(!out.gnu_mode);
+            let options = args.iter().take_while(|arg| **arg != "--");
+            let operands = args.iter().skip_while(|arg| **arg != "--").skip(1);
+            let expected: Vec<OsString> = options
+                .filter(|arg| !arg.starts_with('-'))
+                .chain(operands)
+                .map(OsString::from)
+                .collect();
+
+            match parse_args(args, vars) {
+                Ok(args) => prop_assert_eq!(args.paths, expected),
+                Err(()) => prop_assume!(false),
+            }
         }
 
         #[proptest]
-        #[cfg(feature = "gnu-mode")]
-        fn gnu_mode_set(vars: TestVarsAndIndex, val: String) {
-            let out = parse_vars(vars.insert((super::GNU_MODE, &val)));
-            prop_assert!(out.gnu_mode);
+        fn blind_long_name(args: TestArgsAndIndex, vars: Vars) {
+            let vars = vars.without_bsd_mode();
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!vars.gnu_mode());
+
+            match parse_args(args.insert("--blind"), vars) {
+                Ok(args) => prop_assert!(args.blind),
+                Err(()) => prop_assume!(false),
+            }
         }
 
         #[proptest]
-        fn debug_not_set(vars: TestVars) {
-            prop_assume!(!vars.contains_key(super::DEBUG_MODE));
+        fn blind_short_name(args: TestArgsAndIndex, vars: Vars) {
+            let vars = vars.without_bsd_mode();
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!vars.gnu_mode());
 
-            let out = parse_vars(vars.inner());
-            prop_assert!(!out.debug);
+            match parse_args(args.insert("-b"), vars) {
+                Ok(args) => prop_assert!(args.blind),
+                Err(()) => prop_assume!(false),
+            }
         }
 
         #[proptest]
-        fn debug_set(vars: TestVarsAndIndex, val: String) {
-            let out = parse_vars(vars.insert((super::DEBUG_MODE, &val)));
-            prop_assert!(out.debug);
-        }
-    }
+        fn not_blind(args: TestArgs, vars: Vars) {
+            let vars = vars.without_bsd_mode();
+            prop_assume!(!args.contains("--blind"));
+            prop_assume!(!args.contains("-b"));
+            prop_assume!(!vars.gnu_mode());
 
-    /// Run the CLI with the given (parsed) arguments.
-    ///
-    /// See also [`parse_args`].
-    ///
-    /// # Errors
-    ///
-    /// If there is a CLI runtime error.
-    pub fn run(args: &Args) -> Result<(), ()> {
-        let dry_run = !args.force && !args.interactive;
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.blind),
+                Err(()) => prop_assume!(false),
+            }
+        }
 
-        logging::configure(&if args.quiet && !dry_run {
-            logging::Verbosity::Quiet
-        } else if args.verbose {
-            logging::Verbosity::Verbose
-        } else {
-            logging::Verbosity::Normal
-        });
+        #[proptest]
+        fn contents(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
 
-        let transformers: [transform::Transformer; 5] = [
-            transform::disallow_current_and_parent_dir,
-            if args.no_preserve_root { transform::identity } else { transform::disallow_root },
-            if args.blind { transform::skip_not_found } else { transform::tip_not_found },
-            match (args.dir, args.recursive) {
-                (false, false) => transform::disallow_all_dirs,
-                (true, false) => transform::disallow_filled_dirs,
-                (_, true) => transform::identity,
-            },
-            if args.interactive { transform::interactive } else { transform::identity },
-        ];
+            match parse_args(args.insert("--contents"), vars) {
+                Ok(args) => prop_assert!(args.contents),
+                Err(()) => prop_assume!(false),
+            }
+        }
 
-        #[cfg(feature = "trash")]
-        let walk = if args.recursive && !args.trash {
-            walk::recurse(transformers)
-        } else {
-            walk::given(transformers)
-        };
-        #[cfg(not(feature = "trash"))]
-        let walk =
-            if args.recursive { walk::recurse(transformers) } else { walk::given(transformers) };
+        #[proptest]
+        fn not_contents(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--contents"));
 
-        #[cfg(feature = "trash")]
-        let remove = match (dry_run, args.trash) {
-            (false, false) => rm::remove,
-            (false, true) => rm::dispose,
-            (true, false) => rm::show_remove,
-            (true, true) => rm::show_dispose,
-        };
-        #[cfg(not(feature = "trash"))]
-        let remove = if dry_run { rm::show_remove } else { rm::remove };
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.contents),
+                Err(()) => prop_assume!(false),
+            }
+        }
 
-        trace!("start processing");
-        let (removed, errored): (usize, usize) = args
-            .paths
-            .iter()
-            .flat_map(|path| walk(path))
-            .map(|result| match result {
-                Ok(entry) => remove(entry),
-                Err(err) => Err(err),
-            })
-            .inspect(|result| match result {
-                Ok(msg) => info!("{msg}"),
-                Err(err) => error!("{err}"),
-            })
-            .fold((0, 0), |(oks, errs), result| match result {
-                Ok(_) => (oks.saturating_add(1), errs),
-                Err(_) => (oks, errs.saturating_add(1)),
-            });
+        #[proptest]
+        fn dir_long_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
 
-        info!(
-            "{}{removed} {}{}, {} occurred",
-            if removed > 0 || errored > 0 || args.verbose { "\n" } else { "" },
-            if dry_run { "would be removed" } else { "removed" },
-            if dry_run && removed > 0 {
-                format!(" {}", "(use '--force' to remove)".italic())
-            } else {
-                String::new()
-            },
-            lang::pluralize("error", errored),
-        );
+            match parse_args(args.insert("--dir"), vars) {
+                Ok(args) => prop_assert!(args.dir),
+                Err(()) => prop_assume!(false),
+            }
+        }
 
-        if errored > 0 { Err(()) } else { Ok(()) }
-    }
+        #[proptest]
+        fn dir_short_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
 
-    /// Helpers for writing unit tests in or using this module.
-    #[cfg(test)]
-    mod test_helpers {
-        use super::Vars;
+            match parse_args(args.insert("-d"), vars) {
+                Ok(args) => prop_assert!(args.dir),
+                Err(()) => prop_assume!(false),
+            }
+        }
 
-        use proptest::prelude::*;
+        #[proptest]
+        fn not_dir(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--dir"));
+            prop_assume!(!args.contains("-d"));
 
-        /// Utility functionality for working with [`Vars`] in tests.
-        impl Vars {
-            /// Check if [`Vars::gnu_mode`] is set to true.
-            #[cfg(feature = "gnu-mode")]
-            pub fn gnu_mode(self) -> bool {
-                self.gnu_mode
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.dir),
+                Err(()) => prop_assume!(false),
             }
+        }
 
-            /// Always returns `false` (because the "gnu-mode" feature is off).
-            #[cfg(not(feature = "gnu-mode"))]
-            pub fn gnu_mode(self) -> bool {
-                false
+        #[proptest]
+        fn empty_dirs(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            let mut args = args.insert("--empty-dirs");
+            args.insert(0, "--recursive".to_owned());
+            match parse_args(args, vars) {
+                Ok(args) => prop_assert!(args.empty_dirs),
+                Err(()) => prop_assume!(false),
             }
         }
 
-        /// The `Result` type for parsing args for tests.
-        pub type ParseResult = Result<super::Args, ()>;
+        #[proptest]
+        fn empty_dirs_without_recursive(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!args.contains("--recursive"));
+            prop_assume!(!args.contains("-r"));
 
-        /// Convenience wrapper to parse arguments using [`super::parse_args`] for testing purposes.
-        ///
-        /// # Errors
-        ///
-        /// If the given arguments couldn't be parsed.
-        ///
-        /// # Example
-        ///
-        /// ```no_run
-        /// use cli::Vars;
-        ///
-        /// let args = vec!["--foo", "bar"];
-        /// let vars = Vars { debug: false, gnu_mode: false };
-        /// let out = parse_args(args, vars);
-        /// assert!(out.is_err());
-        /// ```
-        pub fn parse_args(mut args: Vec<String>, vars: Vars) -> ParseResult {
-            args.insert(0, "rm".to_owned());
-            match super::parse_args(args, vars) {
-                Ok(args) => Ok(args),
-                Err(_) => Err(()),
+            prop_assert!(parse_args(args.insert("--empty-dirs"), vars).is_err());
+        }
+
+        #[proptest]
+        fn not_empty_dirs(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--empty-dirs"));
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.empty_dirs),
+                Err(()) => prop_assume!(false),
             }
         }
 
-        /// Struct wrapping a [`String`] that implements [`Arbitrary`] to generate a valid argument
-        /// for the CLI.
-        #[derive(Clone, Debug)]
-        struct TestArg(String);
+        #[proptest]
+        fn empty_files(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
 
-        impl TestArg {
-            /// Returns the contained value, consuming the `self` value.
-            fn inner(self) -> String {
-                self.0
+            match parse_args(args.insert("--empty-files"), vars) {
+                Ok(args) => prop_assert!(args.empty_files),
+                Err(()) => prop_assume!(false),
             }
         }
 
-        impl Arbitrary for TestArg {
-            type Parameters = ();
-            type Strategy = BoxedStrategy<Self>;
+        #[proptest]
+        fn not_empty_files(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--empty-files"));
 
-            fn arbitrary_with((): ()) -> Self::Strategy {
-                const KNOWN_FLAG_PATTERN: &str = "\
-                    --blind|-b|\
-                    --dir|-d|\
-                    --force|-f|\
-                    --interactive|-i|\
-                    --no_preserver_root|\
-                    --one_file_system|\
-                    --quiet|-q|\
-                    --recursive|-r|\
-                    --trash|-t|\
-                    --verbose|-v|\
-                    --\
-                ";
-                const NON_FLAG_PATTERN: &str = "[^-].*";
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.empty_files),
+                Err(()) => prop_assume!(false),
+            }
+        }
 
-                let strategies = vec![(1, KNOWN_FLAG_PATTERN), (10, NON_FLAG_PATTERN)];
+        #[proptest]
+        #[cfg(unix)]
+        fn group(args: TestArgsAndIndex, vars: Vars, gid: u32) {
+            prop_assume!(!args.has_arg_before_index("--"));
 
-                prop::strategy::Union::new_weighted(strategies).prop_map(Self).boxed()
+            match parse_args(args.insert(&format!("--group={gid}")), vars) {
+                Ok(args) => prop_assert_eq!(args.group, Some(gid)),
+                Err(()) => prop_assume!(false),
             }
         }
 
-        /// Struct wrapping a list of [`String`]s that implements [`Arbitrary`] to generate valid
-        /// lists of arguments for the CLI.
-        ///
-        /// See also [`TestArg`].
-        #[derive(Clone, Debug)]
-        pub struct TestArgs(Vec<String>);
+        #[proptest]
+        #[cfg(unix)]
+        fn user(args: TestArgsAndIndex, vars: Vars, uid: u32) {
+            prop_assume!(!args.has_arg_before_index("--"));
 
-        impl TestArgs {
-            /// Returns `true` if the contained list contains the given value.
-            pub fn contains(&self, arg: &str) -> bool {
-                self.0.contains(&arg.to_owned())
+            match parse_args(args.insert(&format!("--user={uid}")), vars) {
+                Ok(args) => prop_assert_eq!(args.user, Some(uid)),
+                Err(()) => prop_assume!(false),
             }
+        }
 
-            /// Returns the contained value, consuming the `self` value.
-            pub fn inner(self) -> Vec<String> {
-                self.0
+        #[proptest]
+        #[cfg(unix)]
+        fn no_user_or_group(args: TestArgs, vars: Vars) {
+            match parse_args(args.inner(), vars) {
+                Ok(args) => {
+                    prop_assert_eq!(args.user, None);
+                    prop_assert_eq!(args.group, None);
+                },
+                Err(()) => prop_assume!(false),
             }
         }
 
-        impl Arbitrary for TestArgs {
-            type Parameters = ();
-            type Strategy = BoxedStrategy<Self>;
+        #[proptest]
+        fn keep_newest(args: TestArgsAndIndex, vars: Vars, count: usize) {
+            prop_assume!(!args.has_arg_before_index("--"));
 
-            fn arbitrary_with((): ()) -> Self::Strategy {
-                let size_range = 1..=16;
-                prop::collection::vec(TestArg::arbitrary(), size_range)
-                    .prop_map(|v| Self(v.into_iter().map(TestArg::inner).collect()))
-                    .boxed()
+            match parse_args(args.insert(&format!("--keep-newest={count}")), vars) {
+                Ok(args) => prop_assert_eq!(args.keep_newest, Some(count)),
+                Err(()) => prop_assume!(false),
             }
         }
 
-        /// Struct wrapping a list of [`String`]s that implements [`Arbitrary`] to generate valid
-        /// lists of arguments for the CLI. To use the contained value one more [`String`] has to be
-        /// inserted.
-        ///
-        /// See also [`TestArgs`].
-        #[derive(Clone, Debug)]
-        pub struct TestArgsAndIndex(Vec<String>, usize);
+        #[proptest]
+        fn keep_oldest(args: TestArgsAndIndex, vars: Vars, count: usize) {
+            prop_assume!(!args.has_arg_before_index("--"));
 
-        impl TestArgsAndIndex {
-            /// Returns the contained value with the given value at the associated index, consuming
-            /// the `self` value.
-            pub fn insert(self, arg: &str) -> Vec<String> {
-                let Self(mut args, index) = self;
-                args.insert(index, arg.to_owned());
-                args
+            match parse_args(args.insert(&format!("--keep-oldest={count}")), vars) {
+                Ok(args) => prop_assert_eq!(args.keep_oldest, Some(count)),
+                Err(()) => prop_assume!(false),
             }
+        }
 
-            /// Returns `true` if the given value occurs in the list of arguments, and `false`
-            /// otherwise.
-            pub fn contains(&self, val: &str) -> bool {
-                self.0.iter().any(|arg| arg == val)
+        #[proptest]
+        fn no_retention(args: TestArgs, vars: Vars) {
+            match parse_args(args.inner(), vars) {
+                Ok(args) => {
+                    prop_assert_eq!(args.keep_newest, None);
+                    prop_assert_eq!(args.keep_oldest, None);
+                    prop_assert!(args.keep_group.is_none());
+                },
+                Err(()) => prop_assume!(false),
             }
+        }
 
-            /// Returns `true` if the given value occurs in the list of arguments before the
-            /// associated index, and `false` otherwise.
-            pub fn has_arg_before_index(&self, val: &str) -> bool {
-                self.0.iter().take(self.1).any(|arg| arg == val)
+        #[proptest]
+        fn keep_group(vars: Vars) {
+            let args = vec!["--keep-newest=1".to_owned(), "--keep-group=^(.+)-".to_owned()];
+
+            match parse_args(args, vars) {
+                Ok(args) => {
+                    let regex = args.keep_group.map(|regex| regex.to_string());
+                    prop_assert_eq!(regex, Some("^(.+)-".to_owned()));
+                },
+                Err(()) => prop_assume!(false),
             }
         }
 
-        impl Arbitrary for TestArgsAndIndex {
-            type Parameters = ();
-            type Strategy = BoxedStrategy<Self>;
+        #[proptest]
+        fn keep_group_without_retention(vars: Vars) {
+            let args = vec!["--keep-group=^(.+)-".to_owned()];
+            prop_assert!(parse_args(args, vars).is_err());
+        }
 
-            fn arbitrary_with((): ()) -> Self::Strategy {
-                let size_range = 1..=16;
-                prop::collection::vec(TestArg::arbitrary(), size_range)
-                    .prop_flat_map(|vec| (0..vec.len(), Just(vec)))
-                    .prop_map(|(i, vec)| Self(vec.into_iter().map(TestArg::inner).collect(), i))
-                    .boxed()
+        #[proptest]
+        fn keep_group_invalid(vars: Vars) {
+            let args = vec!["--keep-newest=1".to_owned(), "--keep-group=(".to_owned()];
+            prop_assert!(parse_args(args, vars).is_err());
+        }
+
+        #[proptest]
+        fn keep_newest_and_oldest(vars: Vars) {
+            let args = vec!["--keep-newest=1".to_owned(), "--keep-oldest=1".to_owned()];
+            prop_assert!(parse_args(args, vars).is_err());
+        }
+
+        #[proptest]
+        fn larger_than(args: TestArgsAndIndex, vars: Vars, size: u32) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert(&format!("--larger-than={size}K")), vars) {
+                Ok(args) => prop_assert_eq!(args.larger_than, Some(u64::from(size) << 10)),
+                Err(()) => prop_assume!(false),
             }
         }
 
-        /// Struct wrapping a [`String`]-based (key, value) pair that implements [`Arbitrary`] to
-        /// generate a valid environment variable for the CLI.
-        #[derive(Clone, Debug)]
-        struct TestVar((String, String));
+        #[proptest]
+        fn max_count(args: TestArgsAndIndex, vars: Vars, count: usize) {
+            prop_assume!(!args.has_arg_before_index("--"));
 
-        impl TestVar {
-            /// Returns the contained value, consuming the `self` value.
-            fn inner(self) -> (String, String) {
-                self.0
+            match parse_args(args.insert(&format!("--max-count={count}")), vars) {
+                Ok(args) => prop_assert_eq!(args.max_count, Some(count)),
+                Err(()) => prop_assume!(false),
             }
         }
 
-        impl Arbitrary for TestVar {
-            type Parameters = ();
-            type Strategy = BoxedStrategy<Self>;
+        #[proptest]
+        fn confirm_count(args: TestArgsAndIndex, vars: Vars, count: usize) {
+            prop_assume!(!args.has_arg_before_index("--"));
 
-            fn arbitrary_with((): ()) -> Self::Strategy {
-                const KNOWN_VAR_PATTERN: &str = "RUST_RM_GNU_MODE|DEBUG";
-                const GENERAL_VAR_PATTERN: &str = "[a-zA-Z_]+";
+            match parse_args(args.insert(&format!("--confirm-count={count}")), vars) {
+                Ok(args) => prop_assert_eq!(args.confirm_count, Some(count)),
+                Err(()) => prop_assume!(false),
+            }
+        }
 
-                let strategies = vec![(1, KNOWN_VAR_PATTERN), (10, GENERAL_VAR_PATTERN)];
+        #[proptest]
+        fn confirm_count_from_vars(args: TestArgs, vars: Vars, count: usize) {
+            prop_assume!(!args.contains("--confirm-count"));
 
-                (prop::strategy::Union::new_weighted(strategies), String::arbitrary())
-                    .prop_map(|(key, val)| TestVar((key, val)))
-                    .boxed()
+            match parse_args(args.inner(), Vars { confirm_count: Some(count), ..vars }) {
+                Ok(args) => prop_assert_eq!(args.confirm_count, Some(count)),
+                Err(()) => prop_assume!(false),
             }
         }
 
-        /// Struct wrapping a list of [`String`]-based (key, value) pairs that implements
-        /// [`Arbitrary`] to generate valid environment variables for the CLI.
-        ///
-        /// See also [`TestVar`].
-        #[derive(Clone, Debug)]
-        pub struct TestVars(Vec<(String, String)>);
+        #[proptest]
+        fn confirm_home(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--confirm-home"), vars) {
+                Ok(args) => prop_assert!(args.confirm_home),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn confirm_size(args: TestArgsAndIndex, vars: Vars, size: u64) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert(&format!("--confirm-size={size}")), vars) {
+                Ok(args) => prop_assert_eq!(args.confirm_size, Some(size)),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn confirm_size_overrides_vars(args: TestArgsAndIndex, vars: Vars, size: u64) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            let vars = Vars { confirm_size: Some(size.wrapping_add(1)), ..vars };
+            match parse_args(args.insert(&format!("--confirm-size={size}")), vars) {
+                Ok(args) => prop_assert_eq!(args.confirm_size, Some(size)),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn protect_recent(args: TestArgsAndIndex, vars: Vars, secs: u32) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert(&format!("--protect-recent={secs}s")), vars) {
+                Ok(args) => {
+                    prop_assert_eq!(args.protect_recent, Some(Duration::from_secs(secs.into())));
+                },
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn protect_recent_from_vars(args: TestArgs, vars: Vars, secs: u32) {
+            let window = Some(Duration::from_secs(secs.into()));
+            match parse_args(args.inner(), Vars { protect_recent: window, ..vars }) {
+                Ok(args) => prop_assert_eq!(args.protect_recent, window),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        #[cfg(unix)]
+        fn all_links(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--all-links=."), vars) {
+                Ok(args) => prop_assert_eq!(args.all_links, Some(PathBuf::from("."))),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        #[cfg(target_os = "linux")]
+        fn check_open(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--check-open"), vars) {
+                Ok(args) => prop_assert!(args.check_open),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        #[cfg(target_os = "linux")]
+        fn protect_open(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--protect-open"), vars) {
+                Ok(args) => prop_assert!(args.protect_open),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn gradual(args: TestArgsAndIndex, vars: Vars, chunk: u32) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(chunk > 0);
+
+            match parse_args(args.insert(&format!("--gradual={chunk}")), vars) {
+                Ok(args) => prop_assert_eq!(args.gradual, Some(u64::from(chunk))),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn gradual_default_chunk(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--gradual"), vars) {
+                Ok(args) => prop_assert_eq!(args.gradual, Some(1 << 30)),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn gradual_pause(args: TestArgsAndIndex, vars: Vars, millis: u32) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            let args = [vec![format!("--gradual-pause={millis}ms")], args.insert("--gradual")];
+            match parse_args(args.concat(), vars) {
+                Ok(args) => {
+                    prop_assert_eq!(args.gradual_pause, Some(Duration::from_millis(millis.into())));
+                },
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn gradual_pause_without_gradual(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!args.contains("--trash") && !args.contains("-t"));
+
+            prop_assert!(parse_args(args.insert("--gradual-pause=1s"), vars).is_err());
+        }
+
+        #[proptest]
+        fn gradual_with_shred(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            let args = [vec!["--shred".to_owned()], args.insert("--gradual")].concat();
+            prop_assert!(parse_args(args, vars).is_err());
+        }
+
+        #[proptest]
+        fn throttle_count(args: TestArgsAndIndex, vars: Vars, count: u64) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(count > 0);
+
+            match parse_args(args.insert(&format!("--throttle-count={count}")), vars) {
+                Ok(args) => prop_assert_eq!(args.throttle_count, Some(count)),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn throttle_count_zero(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            prop_assert!(parse_args(args.insert("--throttle-count=0"), vars).is_err());
+        }
+
+        #[proptest]
+        fn throttle_size(args: TestArgsAndIndex, vars: Vars, size: u32) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(size > 0);
+
+            match parse_args(args.insert(&format!("--throttle-size={size}K")), vars) {
+                Ok(args) => prop_assert_eq!(args.throttle_size, Some(u64::from(size) << 10)),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        #[cfg(target_os = "linux")]
+        fn throttle_idle(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--throttle-idle"), vars) {
+                Ok(args) => prop_assert!(args.throttle_idle),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn progress(args: TestArgsAndIndex, vars: Vars) {
+            let vars = vars.without_bsd_mode();
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!vars.gnu_mode());
+
+            match parse_args(args.insert("--progress"), vars) {
+                Ok(args) => prop_assert!(args.progress),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn progress_eta_without_progress(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            prop_assert!(parse_args(args.insert("--progress-eta"), vars).is_err());
+        }
+
+        #[proptest]
+        fn shred(args: TestArgsAndIndex, vars: Vars, passes: u8) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(passes > 0);
+
+            match parse_args(args.insert(&format!("--shred={passes}")), vars) {
+                Ok(args) => prop_assert_eq!(args.shred, Some(passes)),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn shred_default_passes(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--shred"), vars) {
+                Ok(args) => prop_assert_eq!(args.shred, Some(3)),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn shred_zero_passes(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            prop_assert!(parse_args(args.insert("--shred=0"), vars).is_err());
+        }
+
+        #[proptest]
+        #[cfg(feature = "trash")]
+        fn shred_with_trash(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            let args = [vec!["--trash".to_owned()], args.insert("--shred")].concat();
+            prop_assert!(parse_args(args, vars).is_err());
+        }
+
+        #[proptest]
+        fn force_recent(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--force-recent"), vars) {
+                Ok(args) => prop_assert!(args.force_recent),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn max_count_from_vars(args: TestArgs, vars: Vars, count: usize) {
+            prop_assume!(!args.contains("--max-count"));
+
+            match parse_args(args.inner(), Vars { max_count: Some(count), ..vars }) {
+                Ok(args) => prop_assert_eq!(args.max_count, Some(count)),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn max_count_overrides_vars(args: TestArgsAndIndex, vars: Vars, count: usize) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            let vars = Vars { max_count: Some(count.wrapping_add(1)), ..vars };
+            match parse_args(args.insert(&format!("--max-count={count}")), vars) {
+                Ok(args) => prop_assert_eq!(args.max_count, Some(count)),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn smaller_than(args: TestArgsAndIndex, vars: Vars, size: u64) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert(&format!("--smaller-than={size}")), vars) {
+                Ok(args) => prop_assert_eq!(args.smaller_than, Some(size)),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn no_size(args: TestArgs, vars: Vars) {
+            match parse_args(args.inner(), vars) {
+                Ok(args) => {
+                    prop_assert_eq!(args.larger_than, None);
+                    prop_assert_eq!(args.smaller_than, None);
+                },
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn types(args: TestArgsAndIndex, vars: Vars, types: Vec<TypeArg>) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!types.is_empty());
+
+            let value = types
+                .iter()
+                .filter_map(TypeArg::to_possible_value)
+                .map(|value| value.get_name().to_owned())
+                .collect::<Vec<_>>()
+                .join(",");
+            match parse_args(args.insert(&format!("--type={value}")), vars) {
+                Ok(args) => prop_assert_eq!(args.types, types),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn no_types(args: TestArgs, vars: Vars) {
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(args.types.is_empty()),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn newer_than(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--newer-than=1h"), vars) {
+                Ok(args) => {
+                    prop_assert_eq!(args.newer_than, Some(Age::Ago(Duration::from_secs(3600))));
+                },
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_newer_than(args: TestArgs, vars: Vars) {
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert_eq!(args.newer_than, None),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn older_than(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--older-than=1970-01-01"), vars) {
+                Ok(args) => prop_assert_eq!(args.older_than, Some(Age::At(UNIX_EPOCH))),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_older_than(args: TestArgs, vars: Vars) {
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert_eq!(args.older_than, None),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn time(args: TestArgsAndIndex, vars: Vars, time: TimeArg) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            let value = time.to_possible_value().map(|value| value.get_name().to_owned());
+            prop_assume!(value.is_some());
+            match parse_args(args.insert(&format!("--time={}", value.unwrap_or_default())), vars) {
+                Ok(args) => prop_assert_eq!(args.time, time),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn default_time(args: TestArgs, vars: Vars) {
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert_eq!(args.time, TimeArg::Mtime),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn force_long_name(args: TestArgsAndIndex, vars: Vars) {
+            let vars = vars.without_bsd_mode();
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!vars.gnu_mode());
+
+            match parse_args(args.insert("--force"), vars) {
+                Ok(args) => prop_assert!(args.force),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn force_short_name(args: TestArgsAndIndex, vars: Vars) {
+            let vars = vars.without_bsd_mode();
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!vars.gnu_mode());
+
+            match parse_args(args.insert("-f"), vars) {
+                Ok(args) => prop_assert!(args.force),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_force(args: TestArgs, vars: Vars) {
+            let vars = vars.without_bsd_mode();
+            prop_assume!(!args.contains("--force"));
+            prop_assume!(!args.contains("-f"));
+            prop_assume!(!vars.gnu_mode());
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.force),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn edit(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--edit"), vars) {
+                Ok(args) => prop_assert!(args.edit),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_edit(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--edit"));
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.edit),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn interactive_long_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--interactive"), vars) {
+                Ok(args) => prop_assert!(args.interactive),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn interactive_short_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("-i"), vars) {
+                Ok(args) => prop_assert!(args.interactive),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_interactive(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--interactive"));
+            prop_assume!(!args.contains("-i"));
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.interactive),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn no_details_long_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--no-details"), vars) {
+                Ok(args) => prop_assert!(args.no_details),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_no_details(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--no-details"));
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.no_details),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn no_preserve_root_long_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--no-preserve-root"), vars) {
+                Ok(args) => prop_assert!(args.no_preserve_root),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_no_preserve_root(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--no-preserve-root"));
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.no_preserve_root),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn one_file_system_long_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--one-file-system"), vars) {
+                Ok(args) => prop_assert!(args.one_file_system),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn one_file_system_short_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("-x"), vars) {
+                Ok(args) => prop_assert!(args.one_file_system),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_one_file_system(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--one-file-system"));
+            prop_assume!(!args.contains("-x"));
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.one_file_system),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn parents_long_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--parents"), vars) {
+                Ok(args) => prop_assert!(args.parents),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn parents_short_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("-p"), vars) {
+                Ok(args) => prop_assert!(args.parents),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_parents(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--parents"));
+            prop_assume!(!args.contains("-p"));
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.parents),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn quiet_long_name(args: TestArgsAndIndex, vars: Vars) {
+            let vars = vars.without_bsd_mode();
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!vars.gnu_mode());
+
+            match parse_args(args.insert("--quiet"), vars) {
+                Ok(args) => prop_assert!(args.quiet),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn quiet_short_name(args: TestArgsAndIndex, vars: Vars) {
+            let vars = vars.without_bsd_mode();
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!vars.gnu_mode());
+
+            match parse_args(args.insert("-q"), vars) {
+                Ok(args) => prop_assert!(args.quiet),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_quiet(args: TestArgs, vars: Vars) {
+            let vars = vars.without_bsd_mode();
+            prop_assume!(!args.contains("--quiet"));
+            prop_assume!(!args.contains("-q"));
+            prop_assume!(!vars.gnu_mode());
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.quiet),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn recursive_long_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--recursive"), vars) {
+                Ok(args) => prop_assert!(args.recursive),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn recursive_short_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("-r"), vars) {
+                Ok(args) => prop_assert!(args.recursive),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_recursive(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--recursive"));
+            prop_assume!(!args.contains("-r"));
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.recursive),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        #[cfg(feature = "trash")]
+        fn trash_long_name(args: TestArgsAndIndex, vars: Vars) {
+            let vars = vars.without_bsd_mode();
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!vars.gnu_mode());
+
+            match parse_args(args.insert("--trash"), vars) {
+                Ok(args) => prop_assert!(args.trash),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        #[cfg(feature = "trash")]
+        fn trash_short_name(args: TestArgsAndIndex, vars: Vars) {
+            let vars = vars.without_bsd_mode();
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!vars.gnu_mode());
+
+            match parse_args(args.insert("-t"), vars) {
+                Ok(args) => prop_assert!(args.trash),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        #[cfg(feature = "trash")]
+        fn not_trash(args: TestArgs, vars: Vars) {
+            let vars = vars.without_bsd_mode();
+            prop_assume!(!args.contains("--trash"));
+            prop_assume!(!args.contains("-t"));
+            prop_assume!(!vars.gnu_mode());
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.trash),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn verbose_long_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!vars.debug);
+
+            match parse_args(args.insert("--verbose"), vars) {
+                Ok(args) => prop_assert!(args.verbose),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn verbose_short_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!vars.debug);
+
+            match parse_args(args.insert("-v"), vars) {
+                Ok(args) => prop_assert!(args.verbose),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_verbose(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--verbose"));
+            prop_assume!(!args.contains("-v"));
+            prop_assume!(!vars.debug);
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.verbose),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn debug_not_verbose(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--verbose"));
+            prop_assume!(!args.contains("-v"));
+            prop_assume!(vars.debug);
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(args.verbose),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn debug_and_verbose(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(vars.debug);
+
+            match parse_args(args.insert("--verbose"), vars) {
+                Ok(args) => prop_assert!(args.verbose),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        #[cfg(all(feature = "bsd-mode", feature = "gnu-mode"))]
+        fn disallow_bsd_mode_with_gnu_mode(args: TestArgs, vars: Vars) {
+            let vars = Vars { bsd_mode: true, gnu_mode: true, ..vars };
+            prop_assert!(parse_args(args.inner(), vars).is_err());
+        }
+
+        #[proptest]
+        fn disallow_dir_with_recursive(vars: Vars) {
+            let out = test_combination_errors(("dir", 'd'), ("recursive", 'r'), vars);
+            prop_assert!(out.is_err());
+        }
+
+        #[proptest]
+        fn disallow_force_with_interactive(vars: Vars) {
+            let out = test_combination_errors(("force", 'f'), ("interactive", 'i'), vars);
+            prop_assert!(out.is_err());
+        }
+
+        #[proptest]
+        fn disallow_quiet_with_verbose(vars: Vars) {
+            let out = test_combination_errors(("quiet", 'q'), ("verbose", 'v'), vars);
+            prop_assert!(out.is_err());
+        }
+
+        /// Type representing the long and short names of a flag.
+        type FlagPair<'a> = (&'a str, char);
+
+        /// Test that parsing arguments with both `flag1` and `flag2` present always errors.
+        ///
+        /// # Example
+        ///
+        /// ```no_run
+        /// use cli::Vars;
+        ///
+        /// let vars = Vars { debug: false, gnu_mode: false };
+        /// test_combination_errors(("long-a", 'a'), ("long-b", 'b'), vars)?;
+        /// ```
+        fn test_combination_errors(flag1: FlagPair, flag2: FlagPair, vars: Vars) -> Result<(), ()> {
+            let cases = [
+                vec![format!("--{}", flag1.0), format!("--{}", flag2.0)],
+                vec![format!("--{}", flag1.0), format!("-{}", flag2.1)],
+                vec![format!("-{}", flag1.1), format!("--{}", flag2.0)],
+                vec![format!("-{}", flag1.1), format!("-{}", flag2.1)],
+                vec![format!("--{}", flag2.0), format!("--{}", flag1.0)],
+                vec![format!("--{}", flag2.0), format!("-{}", flag1.1)],
+                vec![format!("-{}", flag2.1), format!("--{}", flag1.0)],
+                vec![format!("-{}", flag2.1), format!("-{}", flag1.1)],
+                vec![format!("-{}{}", flag1.1, flag2.1)],
+                vec![format!("-{}{}", flag2.1, flag1.1)],
+            ];
+
+            for args in cases {
+                let out = parse_args(args.clone(), vars);
+                if out.is_err() {
+                    return Err(());
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Check that no environment configuration values are set that --detach cannot honor, as
+    /// the contents of the PATHs are only walked by the background process.
+    ///
+    /// # Errors
+    ///
+    /// If `RUST_RM_MAX_COUNT` or `RUST_RM_PROTECT_RECENT` is set.
+    fn check_detach_vars(vars: &Vars) -> Result<(), Error> {
+        use clap::error::ErrorKind;
+
+        let var = if vars.max_count.is_some() {
+            MAX_COUNT
+        } else if vars.protect_recent.is_some() {
+            PROTECT_RECENT
+        } else {
+            return Ok(());
+        };
+
+        Err(Error::raw(
+            ErrorKind::ArgumentConflict,
+            format!("option --detach cannot be used with {var} set\n"),
+        ))
+    }
+
+    /// Tests for the [`check_detach_vars`] function.
+    #[cfg(test)]
+    mod test_check_detach_vars {
+        use super::{check_detach_vars, parse_vars};
+
+        #[test]
+        fn none_set() {
+            let vars = parse_vars(vec![("RUST_RM_CONFIRM_COUNT".to_owned(), "10".to_owned())]);
+            assert!(check_detach_vars(&vars).is_ok());
+        }
+
+        #[test]
+        fn max_count_set() {
+            let vars = parse_vars(vec![(super::MAX_COUNT.to_owned(), "10".to_owned())]);
+            assert!(check_detach_vars(&vars).is_err());
+        }
+
+        #[test]
+        fn protect_recent_set() {
+            let vars = parse_vars(vec![(super::PROTECT_RECENT.to_owned(), "10m".to_owned())]);
+            assert!(check_detach_vars(&vars).is_err());
+        }
+    }
+
+    /// Check that the answers of --answers-from are used, i.e. that there is something to prompt
+    /// for, including thresholds for --confirm-* that come from the environment.
+    ///
+    /// # Errors
+    ///
+    /// If neither --interactive, -I (in BSD mode), nor any --confirm-* option is in effect.
+    fn check_answers_used(args: &Args) -> Result<(), Error> {
+        use clap::error::ErrorKind;
+
+        if args.interactive
+            || args.confirm_home
+            || args.confirm_count.is_some()
+            || args.confirm_size.is_some()
+        {
+            return Ok(());
+        }
+        #[cfg(feature = "bsd-mode")]
+        if args.interactive_once {
+            return Ok(());
+        }
+
+        Err(Error::raw(
+            ErrorKind::MissingRequiredArgument,
+            "option --answers-from requires --interactive, --confirm-count, --confirm-home or --confirm-size\n",
+        ))
+    }
+
+    /// Tests for the [`check_answers_used`] function.
+    #[cfg(test)]
+    mod test_check_answers_used {
+        use super::{parse_args, parse_vars};
+
+        /// Parse the given `args`, preceded by `--answers-from -`, with the given `vars`.
+        fn parse_with_answers(args: &[&str], vars: &[(&str, &str)]) -> super::ParseResult {
+            let args = ["rm", "--answers-from", "-"].iter().chain(args).map(|&arg| arg.to_owned());
+            let vars =
+                vars.iter().map(|&(key, val)| (key.to_owned(), val.to_owned())).collect::<Vec<_>>();
+            parse_args(args, parse_vars(vars))
+        }
+
+        #[test]
+        fn unused() {
+            assert!(parse_with_answers(&["file"], &[]).is_err());
+        }
+
+        #[test]
+        fn interactive() {
+            assert!(parse_with_answers(&["--interactive", "file"], &[]).is_ok());
+        }
+
+        #[test]
+        fn confirm_count() {
+            assert!(parse_with_answers(&["--confirm-count", "10", "file"], &[]).is_ok());
+        }
+
+        #[test]
+        fn confirm_home() {
+            assert!(parse_with_answers(&["--confirm-home", "file"], &[]).is_ok());
+        }
+
+        #[test]
+        fn confirm_size() {
+            assert!(parse_with_answers(&["--confirm-size", "10M", "file"], &[]).is_ok());
+        }
+
+        #[test]
+        fn confirm_count_from_vars() {
+            let vars = [(super::CONFIRM_COUNT, "10")];
+            assert!(parse_with_answers(&["file"], &vars).is_ok());
+        }
+
+        #[test]
+        fn confirm_size_from_vars() {
+            let vars = [(super::CONFIRM_SIZE, "10M")];
+            assert!(parse_with_answers(&["file"], &vars).is_ok());
+        }
+    }
+
+    /// Modify the given `args` in place to behave like rm(1), as shared by GNU mode and BSD mode.
+    /// The `mode` is the name of the mode used in error messages.
+    ///
+    /// # Errors
+    ///
+    /// If an unsupported flags is used, but only if the `force` option isn't set. Or, if the
+    /// detach or gc flag is used.
+    #[cfg(any(feature = "bsd-mode", feature = "gnu-mode"))]
+    fn parse_args_rm_mode(mut args: Args, mode: &str) -> ParseResult {
+        use clap::error::ErrorKind;
+
+        macro_rules! check_use_of_invalid_flag {
+            ($flag:ident) => {
+                if args.$flag {
+                    return Err(Error::raw(
+                        ErrorKind::UnknownArgument,
+                        format!("option --{} not supported in {mode} mode\n", stringify!($flag)),
+                    ));
+                }
+            };
+        }
+
+        check_use_of_invalid_flag!(detach); // would remove in the foreground instead
+        check_use_of_invalid_flag!(gc); // would remove the PATH(s) instead of their tombstones
+        if !args.force {
+            check_use_of_invalid_flag!(blind);
+            check_use_of_invalid_flag!(quiet);
+            #[cfg(feature = "trash")]
+            check_use_of_invalid_flag!(trash);
+        }
+
+        args.blind = args.force; // rm(1) behaves blindly with --force
+        args.force = !args.interactive; // rm(1) removes unless --interactive
+        args.quiet = true; // rm(1) is always --quiet
+        if args.interactive && args.answers_from.is_none() {
+            args.answers_from = Some(PathBuf::from("-")); // rm(1) reads answers from stdin
+        }
+        args.progress = false; // rm(1) does not show progress
+        #[cfg(feature = "trash")]
+        {
+            args.trash = false; // rm(1) does not support --trash
+        }
+
+        Ok(args)
+    }
+
+    /// Parse arguments for the CLI with GNU mode enabled, modifying the given `args` in place.
+    ///
+    /// # Errors
+    ///
+    /// If an unsupported flags is used, but only if the `force` option isn't set.
+    #[cfg(feature = "gnu-mode")]
+    fn parse_args_gnu_mode(args: Args) -> ParseResult {
+        parse_args_rm_mode(args, "GNU")
+    }
+
+    /// Tests for the [`parse_args_gnu_mode`] function.
+    #[cfg(test)]
+    #[cfg(feature = "gnu-mode")]
+    mod test_parse_args_gnu_mode {
+        use super::test_helpers::{TestArgs, TestArgsAndIndex};
+
+        use super::Vars;
+
+        use std::path::PathBuf;
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn answers_from_stdin_when_interactive(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("-i"), vars) {
+                Ok(args) => prop_assert_eq!(args.answers_from, Some(PathBuf::from("-"))),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn blind_when_force_long_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--force"), vars) {
+                Ok(args) => prop_assert!(args.blind),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn blind_when_force_short_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("-f"), vars) {
+                Ok(args) => prop_assert!(args.blind),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_blind_when_not_force(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--force"));
+            prop_assume!(!args.contains("-f"));
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.blind),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn dir_when_dir_long_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--dir"), vars) {
+                Ok(args) => prop_assert!(args.dir),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn dir_when_dir_short_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("-d"), vars) {
+                Ok(args) => prop_assert!(args.dir),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_dir_when_not_dir(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--dir"));
+            prop_assume!(!args.contains("-d"));
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.dir),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn force_when_not_interactive(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("--interactive"));
+            prop_assume!(!args.contains("-i"));
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(args.force),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_force_when_interactive_long_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--interactive"), vars) {
+                Ok(args) => prop_assert!(!args.force),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_force_when_interactive_short_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("-i"), vars) {
+                Ok(args) => prop_assert!(!args.force),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn always_quiet(args: TestArgs, vars: Vars) {
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(args.quiet),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn never_progress(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("--progress"), vars) {
+                Ok(args) => prop_assert!(!args.progress),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        #[cfg(feature = "trash")]
+        fn never_trash(args: TestArgs, vars: Vars) {
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.trash),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn disallow_blind_full_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!args.contains("--force"));
+            prop_assume!(!args.contains("-f"));
+
+            prop_assert!(parse_args(args.insert("--blind"), vars).is_err());
+        }
+
+        #[proptest]
+        fn disallow_blind_short_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!args.contains("--force"));
+            prop_assume!(!args.contains("-f"));
+
+            prop_assert!(parse_args(args.insert("-b"), vars).is_err());
+        }
+
+        #[proptest]
+        fn disallow_quiet_full_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!args.contains("--force"));
+            prop_assume!(!args.contains("-f"));
+
+            prop_assert!(parse_args(args.insert("--quiet"), vars).is_err());
+        }
+
+        #[proptest]
+        fn disallow_quiet_short_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!args.contains("--force"));
+            prop_assume!(!args.contains("-f"));
+
+            prop_assert!(parse_args(args.insert("-q"), vars).is_err());
+        }
+
+        #[proptest]
+        #[cfg(feature = "trash")]
+        fn disallow_trash_full_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!args.contains("--force"));
+            prop_assume!(!args.contains("-f"));
+
+            prop_assert!(parse_args(args.insert("--trash"), vars).is_err());
+        }
+
+        #[proptest]
+        #[cfg(feature = "trash")]
+        fn disallow_trash_short_name(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+            prop_assume!(!args.contains("--force"));
+            prop_assume!(!args.contains("-f"));
+
+            prop_assert!(parse_args(args.insert("-t"), vars).is_err());
+        }
+
+        #[proptest]
+        fn disallow_gc(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            prop_assert!(parse_args(args.insert("--gc"), vars).is_err());
+        }
+
+        /// Convenience wrapper to parse arguments using [`super::parse_args`]. Always sets
+        /// `vars.gnu_mode` to `true`.
+        ///
+        /// See also [`super::test_helpers::parse_args`].
+        fn parse_args(args: Vec<String>, vars: Vars) -> super::test_helpers::ParseResult {
+            super::test_helpers::parse_args(
+                args,
+                Vars {
+                    #[cfg(feature = "bsd-mode")]
+                    bsd_mode: false,
+                    gnu_mode: true,
+                    ..vars
+                },
+            )
+        }
+    }
+
+    /// Parse arguments for the CLI with BSD mode enabled, modifying the given `args` in place.
+    ///
+    /// # Errors
+    ///
+    /// If an unsupported flags is used, but only if the `force` option isn't set. Or, if the
+    /// undelete flag is used.
+    #[cfg(feature = "bsd-mode")]
+    fn parse_args_bsd_mode(mut args: Args) -> ParseResult {
+        use clap::error::ErrorKind;
+
+        if args.undelete {
+            return Err(Error::raw(
+                ErrorKind::UnknownArgument,
+                "option -W not supported in BSD mode\n",
+            ));
+        }
+
+        if args.interactive_once && args.answers_from.is_none() {
+            args.answers_from = Some(PathBuf::from("-")); // rm(1) reads answers from stdin
+        }
+
+        parse_args_rm_mode(args, "BSD")
+    }
+
+    /// Tests for the [`parse_args_bsd_mode`] function.
+    #[cfg(test)]
+    #[cfg(feature = "bsd-mode")]
+    mod test_parse_args_bsd_mode {
+        use super::test_helpers::{TestArgs, TestArgsAndIndex};
+
+        use super::Vars;
+
+        use std::path::PathBuf;
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn interactive_once(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("-I"), vars) {
+                Ok(args) => prop_assert!(args.interactive_once),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn answers_from_stdin_when_interactive_once(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("-I"), vars) {
+                Ok(args) => prop_assert_eq!(args.answers_from, Some(PathBuf::from("-"))),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_interactive_once(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("-I"));
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.interactive_once),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn one_file_system(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("-x"), vars) {
+                Ok(args) => prop_assert!(args.one_file_system),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn overwrite(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            match parse_args(args.insert("-P"), vars) {
+                Ok(args) => prop_assert!(args.overwrite),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn not_overwrite(args: TestArgs, vars: Vars) {
+            prop_assume!(!args.contains("-P"));
+
+            match parse_args(args.inner(), vars) {
+                Ok(args) => prop_assert!(!args.overwrite),
+                Err(()) => prop_assume!(false),
+            }
+        }
+
+        #[proptest]
+        fn disallow_undelete(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            prop_assert!(parse_args(args.insert("-W"), vars).is_err());
+        }
+
+        /// Convenience wrapper to parse arguments using [`super::parse_args`]. Always sets
+        /// `vars.bsd_mode` to `true`.
+        ///
+        /// See also [`super::test_helpers::parse_args`].
+        fn parse_args(args: Vec<String>, vars: Vars) -> super::test_helpers::ParseResult {
+            super::test_helpers::parse_args(
+                args,
+                Vars {
+                    bsd_mode: true,
+                    #[cfg(feature = "gnu-mode")]
+                    gnu_mode: false,
+                    ..vars
+                },
+            )
+        }
+    }
+
+    /// Check that none of the flags exclusive to BSD mode are used.
+    ///
+    /// # Errors
+    ///
+    /// If a flag exclusive to BSD mode is used.
+    #[cfg(feature = "bsd-mode")]
+    fn check_no_bsd_mode_flags(args: &Args) -> Result<(), Error> {
+        use clap::error::ErrorKind;
+
+        let flags = [('I', args.interactive_once), ('P', args.overwrite), ('W', args.undelete)];
+
+        match flags.iter().find(|(_, used)| *used) {
+            Some((flag, _)) => Err(Error::raw(
+                ErrorKind::UnknownArgument,
+                format!("option -{flag} only supported in BSD mode\n"),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Tests for the [`check_no_bsd_mode_flags`] function.
+    #[cfg(test)]
+    #[cfg(feature = "bsd-mode")]
+    mod test_check_no_bsd_mode_flags {
+        use super::test_helpers::TestArgsAndIndex;
+
+        use super::Vars;
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn disallow_interactive_once(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            prop_assert!(parse_args(args.insert("-I"), vars).is_err());
+        }
+
+        #[proptest]
+        fn disallow_overwrite(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            prop_assert!(parse_args(args.insert("-P"), vars).is_err());
+        }
+
+        #[proptest]
+        fn disallow_undelete(args: TestArgsAndIndex, vars: Vars) {
+            prop_assume!(!args.has_arg_before_index("--"));
+
+            prop_assert!(parse_args(args.insert("-W"), vars).is_err());
+        }
+
+        /// Convenience wrapper to parse arguments using [`super::parse_args`]. Always sets
+        /// `vars.bsd_mode` to `false`.
+        ///
+        /// See also [`super::test_helpers::parse_args`].
+        fn parse_args(args: Vec<String>, vars: Vars) -> super::test_helpers::ParseResult {
+            super::test_helpers::parse_args(args, Vars { bsd_mode: false, ..vars })
+        }
+    }
+
+    /// The environment variable name to enable compatibility mode with the BSD version of `rm(1)`.
+    #[cfg(feature = "bsd-mode")]
+    const BSD_MODE: &str = "RUST_RM_BSD_MODE";
+
+    /// The environment variable name to set the default value for `--confirm-count`.
+    const CONFIRM_COUNT: &str = "RUST_RM_CONFIRM_COUNT";
+
+    /// The environment variable name to set the default value for `--confirm-size`.
+    const CONFIRM_SIZE: &str = "RUST_RM_CONFIRM_SIZE";
+
+    /// A standard environment variable name to enable verbose mode.
+    const DEBUG_MODE: &str = "DEBUG";
+
+    /// The environment variable name to enable compatibility mode with the GNU version of `rm(1)`.
+    #[cfg(feature = "gnu-mode")]
+    const GNU_MODE: &str = "RUST_RM_GNU_MODE";
+
+    /// The environment variable name to set the default value for `--max-count`.
+    const MAX_COUNT: &str = "RUST_RM_MAX_COUNT";
+
+    /// The environment variable name to set the default value for `--protect-recent`.
+    const PROTECT_RECENT: &str = "RUST_RM_PROTECT_RECENT";
+
+    /// Struct representing parsed environment configuration values.
+    #[cfg_attr(test, derive(Arbitrary, Clone, Copy, Debug))]
+    pub struct Vars {
+        /// The environment configuration value for BSD mode.
+        #[cfg(feature = "bsd-mode")]
+        bsd_mode: bool,
+
+        /// The environment configuration value for the number of entries above which to confirm
+        /// by name.
+        confirm_count: Option<usize>,
+
+        /// The environment configuration value for the size above which to confirm by name.
+        confirm_size: Option<u64>,
+
+        /// The environment configuration value for debug mode.
+        debug: bool,
+
+        /// The environment configuration value for GNU mode.
+        #[cfg(feature = "gnu-mode")]
+        gnu_mode: bool,
+
+        /// The environment configuration value for the maximum number of entries to remove.
+        max_count: Option<usize>,
+
+        /// The environment configuration value for how recently modified entries are protected.
+        protect_recent: Option<Duration>,
+    }
+
+    /// Parse environment variables for the CLI.
+    ///
+    /// Values that cannot be parsed are ignored.
+    pub fn parse_vars<T>(vars: T) -> Vars
+    where
+        T: IntoIterator<Item = (String, String)>,
+    {
+        let vars: Vec<(String, String)> = vars.into_iter().collect();
+        let is_set = |name: &str| vars.iter().any(|(key, _)| key == name);
+        Vars {
+            #[cfg(feature = "bsd-mode")]
+            bsd_mode: is_set(BSD_MODE),
+            confirm_count: vars
+                .iter()
+                .find(|(key, _)| key == CONFIRM_COUNT)
+                .and_then(|(_, val)| val.parse().ok()),
+            confirm_size: vars
+                .iter()
+                .find(|(key, _)| key == CONFIRM_SIZE)
+                .and_then(|(_, val)| parse_size(val).ok()),
+            debug: is_set(DEBUG_MODE),
+            #[cfg(feature = "gnu-mode")]
+            gnu_mode: is_set(GNU_MODE),
+            max_count: vars
+                .iter()
+                .find(|(key, _)| key == MAX_COUNT)
+                .and_then(|(_, val)| val.parse().ok()),
+            protect_recent: vars
+                .iter()
+                .find(|(key, _)| key == PROTECT_RECENT)
+                .and_then(|(_, val)| parse_duration(val)),
+        }
+    }
+
+    /// Tests for the [`parse_vars`] function.
+    #[cfg(test)]
+    mod test_parse_vars {
+        use super::test_helpers::{TestVars, TestVarsAndIndex};
+
+        use super::parse_vars;
+
+        use std::time::Duration;
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        #[cfg(feature = "bsd-mode")]
+        fn bsd_mode_not_set(vars: TestVars) {
+            prop_assume!(!vars.contains_key(super::BSD_MODE));
+
+            let out = parse_vars(vars.inner());
+            prop_assert!(!out.bsd_mode);
+        }
+
+        #[proptest]
+        #[cfg(feature = "bsd-mode")]
+        fn bsd_mode_set(vars: TestVarsAndIndex, val: String) {
+            let out = parse_vars(vars.insert((super::BSD_MODE, &val)));
+            prop_assert!(out.bsd_mode);
+        }
+
+        #[proptest]
+        #[cfg(feature = "gnu-mode")]
+        fn gnu_mode_not_set(vars: TestVars) {
+            prop_assume!(!vars.contains_key(super::GNU_MODE));
+
+            let out = parse_vars(vars.inner());
+            prop_assert!(!out.gnu_mode);
+        }
+
+        #[proptest]
+        #[cfg(feature = "gnu-mode")]
+        fn gnu_mode_set(vars: TestVarsAndIndex, val: String) {
+            let out = parse_vars(vars.insert((super::GNU_MODE, &val)));
+            prop_assert!(out.gnu_mode);
+        }
+
+        #[proptest]
+        fn debug_not_set(vars: TestVars) {
+            prop_assume!(!vars.contains_key(super::DEBUG_MODE));
+
+            let out = parse_vars(vars.inner());
+            prop_assert!(!out.debug);
+        }
+
+        #[proptest]
+        fn debug_set(vars: TestVarsAndIndex, val: String) {
+            let out = parse_vars(vars.insert((super::DEBUG_MODE, &val)));
+            prop_assert!(out.debug);
+        }
+
+        #[proptest]
+        fn confirm_count_set(vars: TestVars, val: usize) {
+            prop_assume!(!vars.contains_key(super::CONFIRM_COUNT));
+
+            let mut vars = vars.inner();
+            vars.push((super::CONFIRM_COUNT.to_owned(), val.to_string()));
+
+            let out = parse_vars(vars);
+            prop_assert_eq!(out.confirm_count, Some(val));
+        }
+
+        #[proptest]
+        fn confirm_count_invalid(vars: TestVars) {
+            prop_assume!(!vars.contains_key(super::CONFIRM_COUNT));
+
+            let mut vars = vars.inner();
+            vars.push((super::CONFIRM_COUNT.to_owned(), "many".to_owned()));
+
+            let out = parse_vars(vars);
+            prop_assert_eq!(out.confirm_count, None);
+        }
+
+        #[proptest]
+        fn confirm_size_set(vars: TestVars, val: u32) {
+            prop_assume!(!vars.contains_key(super::CONFIRM_SIZE));
+
+            let mut vars = vars.inner();
+            vars.push((super::CONFIRM_SIZE.to_owned(), format!("{val}K")));
+
+            let out = parse_vars(vars);
+            prop_assert_eq!(out.confirm_size, Some(u64::from(val) << 10));
+        }
+
+        #[proptest]
+        fn confirm_size_invalid(vars: TestVars) {
+            prop_assume!(!vars.contains_key(super::CONFIRM_SIZE));
+
+            let mut vars = vars.inner();
+            vars.push((super::CONFIRM_SIZE.to_owned(), "large".to_owned()));
+
+            let out = parse_vars(vars);
+            prop_assert_eq!(out.confirm_size, None);
+        }
+
+        #[proptest]
+        fn max_count_not_set(vars: TestVars) {
+            prop_assume!(!vars.contains_key(super::MAX_COUNT));
+
+            let out = parse_vars(vars.inner());
+            prop_assert_eq!(out.max_count, None);
+        }
+
+        #[proptest]
+        fn max_count_set(vars: TestVars, val: usize) {
+            prop_assume!(!vars.contains_key(super::MAX_COUNT));
+
+            let mut vars = vars.inner();
+            vars.push((super::MAX_COUNT.to_owned(), val.to_string()));
+
+            let out = parse_vars(vars);
+            prop_assert_eq!(out.max_count, Some(val));
+        }
+
+        #[proptest]
+        fn max_count_invalid(vars: TestVars) {
+            prop_assume!(!vars.contains_key(super::MAX_COUNT));
+
+            let mut vars = vars.inner();
+            vars.push((super::MAX_COUNT.to_owned(), "many".to_owned()));
+
+            let out = parse_vars(vars);
+            prop_assert_eq!(out.max_count, None);
+        }
+
+        #[proptest]
+        fn protect_recent_set(vars: TestVars, minutes: u32) {
+            prop_assume!(!vars.contains_key(super::PROTECT_RECENT));
+
+            let mut vars = vars.inner();
+            vars.push((super::PROTECT_RECENT.to_owned(), format!("{minutes}m")));
+
+            let out = parse_vars(vars);
+            prop_assert_eq!(
+                out.protect_recent,
+                Some(Duration::from_secs(60).saturating_mul(minutes))
+            );
+        }
+
+        #[proptest]
+        fn protect_recent_invalid(vars: TestVars) {
+            prop_assume!(!vars.contains_key(super::PROTECT_RECENT));
+
+            let mut vars = vars.inner();
+            vars.push((super::PROTECT_RECENT.to_owned(), "recently".to_owned()));
+
+            let out = parse_vars(vars);
+            prop_assert_eq!(out.protect_recent, None);
+        }
+    }
+
+    /// Run the CLI with the given (parsed) arguments.
+    ///
+    /// See also [`parse_args`].
+    ///
+    /// # Errors
+    ///
+    /// If there is a CLI runtime error or the removal is interrupted.
+    pub fn run(args: &Args) -> Result<(), Failure> {
+        #[cfg(feature = "pick")]
+        let pick = args.pick;
+        #[cfg(not(feature = "pick"))]
+        let pick = false;
+
+        let dry_run = is_dry_run(args);
+
+        logging::configure(&if args.quiet && !dry_run {
+            logging::Verbosity::Quiet
+        } else if args.verbose {
+            logging::Verbosity::Verbose
+        } else {
+            logging::Verbosity::Normal
+        });
+
+        let interrupted = Arc::new(AtomicBool::new(false));
+        let exit_code = on_interrupt(&interrupted);
+        #[cfg(feature = "bsd-mode")]
+        if args.interactive_once && !dry_run && !confirm_once(args, &interrupted) {
+            return Err(Failure::Error);
+        }
+
+        let session = if args.interactive {
+            new_session(args).map_err(|err| error!("{err}"))?
+        } else if thresholds_of(args).is_some() && !dry_run {
+            new_confirm_session(args)?
+        } else {
+            transform::Session::default()
+        };
+        let session = Rc::new(session.interruptible(Arc::clone(&interrupted)));
+        let (paths, walk) = match &args.apply_plan {
+            Some(file) => applied_plan(args, &session, file)?,
+            None if args.gc => (tombstones_in(&args.paths)?, new_walker(args, &session)),
+            None => (args.paths.clone(), new_walker(args, &session)),
+        };
+
+        let walk = if args.edit || pick || args.max_count.is_some() || args.plan_out.is_some() {
+            let plan = walk::plan(&paths, &interruptible(walk, Arc::clone(&interrupted)));
+            plan_ahead(args, plan, &interrupted)?
+        } else {
+            walk
+        };
+
+        let removed = walk::Removed::default();
+        let walk = if args.parents {
+            walk::with_ancestors(
+                walk,
+                Rc::new([
+                    Box::new(transform::skip_protected_dirs),
+                    transform::skip_filled_dirs_after(Rc::clone(&removed)),
+                    if args.interactive {
+                        transform::interactive(Rc::clone(&session), !args.no_details)
+                    } else {
+                        Box::new(transform::identity)
+                    },
+                ]),
+                Rc::clone(&removed),
+            )
+        } else {
+            walk
+        };
+
+        let progress = new_progress(args, &paths);
+        let walk = match &progress {
+            Some(progress) => progress::track_walker(walk, Rc::clone(progress)),
+            None => walk,
+        };
+        let tombstones = rm::Tombstones::default();
+        let remove = new_remover(args, &tombstones, &interrupted);
+        let remove = match &progress {
+            Some(progress) => progress::track_remover(remove, Rc::clone(progress)),
+            None => remove,
+        };
+        let remove = if args.parents && dry_run { record_removed(remove, removed) } else { remove };
+
+        #[cfg(target_os = "linux")]
+        if args.throttle_idle && !dry_run {
+            set_idle_io_priority().unwrap_or_else(|err| warn!("{err}"));
+        }
+
+        let walk = interruptible(walk, Arc::clone(&interrupted));
+        let (removed, errored) = remove_all(&paths, &walk, &remove);
+        if let Some(progress) = progress {
+            progress.clear();
+        }
+        let tombstones = tombstones.take();
+        let log = remove_in_background(&tombstones);
+
+        let summary = Summary {
+            removed: removed.saturating_sub(tombstones.len()),
+            detached: tombstones.len(),
+            errored: errored.saturating_add(usize::from(log.is_err())),
+            log: log.ok().flatten(),
+            interrupted: interrupted.load(Ordering::Relaxed),
+        };
+        summary.report(args);
+
+        if summary.interrupted {
+            Err(Failure::Interrupted(
+                u8::try_from(exit_code.load(Ordering::Relaxed)).unwrap_or(u8::MAX),
+            ))
+        } else if summary.errored > 0 {
+            Err(Failure::Error)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Struct representing the outcome of a run, see [`run`].
+    struct Summary {
+        /// The number of entries removed, or that would be removed in a dry run.
+        removed: usize,
+
+        /// The number of entries detached by --detach, to be removed in the background.
+        detached: usize,
+
+        /// The log file of the removal in the background, if it was started.
+        log: Option<PathBuf>,
+
+        /// The number of errors that occurred.
+        errored: usize,
+
+        /// Whether the run was interrupted.
+        interrupted: bool,
+    }
+
+    impl Summary {
+        /// Output the [`Summary`] of a run with the given (parsed) arguments.
+        fn report(&self, args: &Args) {
+            let dry_run = is_dry_run(args);
+            info!(
+                "{}{} {}{}{}, {} occurred{}",
+                if self.removed > 0 || self.detached > 0 || self.errored > 0 || args.verbose {
+                    "\n"
+                } else {
+                    ""
+                },
+                self.removed,
+                if dry_run { "would be removed" } else { "removed" },
+                if dry_run && self.removed > 0 {
+                    format!(" {}", "(use '--force' to remove)".italic())
+                } else {
+                    String::new()
+                },
+                self.detached_note(),
+                lang::pluralize("error", self.errored),
+                if self.interrupted { ", interrupted" } else { "" },
+            );
+        }
+
+        /// Get the part of the summary about detached entries, naming the log file of their
+        /// removal if any.
+        fn detached_note(&self) -> String {
+            match (self.detached, &self.log) {
+                (0, _) => String::new(),
+                (detached, Some(log)) => format!(
+                    ", {detached} detached (removing in the background, see {})",
+                    log.display().bold(),
+                ),
+                (detached, None) => format!(", {detached} detached"),
+            }
+        }
+    }
+
+    /// Tests for the [`Summary`] struct.
+    #[cfg(test)]
+    mod test_summary {
+        use super::Summary;
+
+        use std::path::PathBuf;
+
+        use owo_colors::OwoColorize as _;
+
+        #[test]
+        fn nothing_detached() {
+            let summary = summary(0, None);
+            assert_eq!(summary.detached_note(), "");
+        }
+
+        #[test]
+        fn detached_with_log() {
+            let log = PathBuf::from("rust-rm-abc.log");
+            let summary = summary(2, Some(log.clone()));
+            assert_eq!(
+                summary.detached_note(),
+                format!(", 2 detached (removing in the background, see {})", log.display().bold()),
+            );
+        }
+
+        #[test]
+        fn detached_without_log() {
+            let summary = summary(2, None);
+            assert_eq!(summary.detached_note(), ", 2 detached");
+        }
+
+        /// Create a [`Summary`] with the given number of `detached` entries and `log`.
+        fn summary(detached: usize, log: Option<PathBuf>) -> Summary {
+            Summary { removed: 0, detached, log, errored: 0, interrupted: false }
+        }
+    }
+
+    /// Create the [`rm::Remover`] to use for the given (parsed) arguments, throttled if requested.
+    /// Entries detached by --detach are recorded in `tombstones`, throttling stops waiting once
+    /// `interrupted` is set.
+    fn new_remover(
+        args: &Args,
+        tombstones: &rm::Tombstones,
+        interrupted: &Arc<AtomicBool>,
+    ) -> rm::Remover {
+        let dry_run = is_dry_run(args);
+        if args.detach && !dry_run {
+            return rm::detach(Rc::clone(tombstones));
+        }
+
+        #[cfg(feature = "trash")]
+        let remove: rm::Remover = match (dry_run, args.trash) {
+            (false, false) => Box::new(rm::remove),
+            (false, true) => Box::new(rm::dispose),
+            (true, false) => Box::new(rm::show_remove),
+            (true, true) => Box::new(rm::show_dispose),
+        };
+        #[cfg(not(feature = "trash"))]
+        let remove: rm::Remover = Box::new(if dry_run { rm::show_remove } else { rm::remove });
+        let remove = match args.gradual {
+            Some(chunk) if !dry_run => rm::remove_gradually(rm::Gradual {
+                chunk,
+                pause: args.gradual_pause.unwrap_or_default(),
+            }),
+            _ => remove,
+        };
+        let remove = match args.shred {
+            Some(passes) if !dry_run => {
+                warn!("{WARN_SHRED}");
+                rm::shred(passes)
+            },
+            #[cfg(feature = "bsd-mode")]
+            None if args.overwrite && !dry_run => rm::shred(OVERWRITE_PASSES),
+            _ => remove,
+        };
+
+        if dry_run || (args.throttle_count.is_none() && args.throttle_size.is_none()) {
+            return remove;
+        }
+
+        let throttle = Throttle::new(SystemClock, args.throttle_count, args.throttle_size);
+        throttled(remove, throttle.interruptible(Arc::clone(interrupted)))
+    }
+
+    /// Create the [`progress::Progress`] to show for the given (parsed) arguments, if any, which
+    /// counts the entries at the given `paths` first if an estimate of the time left is wanted.
+    fn new_progress(args: &Args, paths: &[OsString]) -> Option<Rc<progress::Progress>> {
+        if !args.progress || !io::stderr().is_terminal() {
+            return None;
+        }
+
+        let total = args
+            .progress_eta
+            .then(|| count_entries(paths, args.recursive || args.contents || args.gc));
+        let prompts = args.interactive || thresholds_of(args).is_some();
+        Some(Rc::new(progress::Progress::new(Box::new(io::stderr()), total, prompts)))
+    }
+
+    /// Count the entries at the given `paths`, including the contents of directories if
+    /// `recursive`. Entries that can't be read are not counted.
+    fn count_entries(paths: &[OsString], recursive: bool) -> usize {
+        paths
+            .iter()
+            .filter_map(|path| fs::open(path).ok())
+            .map(|entry| {
+                let contents = if recursive && entry.is_dir() {
+                    fs::contents_of(&entry, usize::MAX).entries
+                } else {
+                    0
+                };
+                contents.saturating_add(1)
+            })
+            .fold(0, usize::saturating_add)
+    }
+
+    /// Tests for the [`count_entries`] function.
+    #[cfg(test)]
+    mod test_count_entries {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::count_entries;
+
+        use std::ffi::OsString;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn recursive() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("nested").child("file1").touch()?;
+                dir.child("file2").touch()?;
+                let file = test_dir.child("file3");
+                file.touch()?;
+
+                let paths = [OsString::from(dir.path()), OsString::from(file.path())];
+                assert_eq!(count_entries(&paths, true), 5);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn not_recursive() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file").touch()?;
+
+                assert_eq!(count_entries(&[OsString::from(dir.path())], false), 1);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn missing() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("missing");
+
+                assert_eq!(count_entries(&[OsString::from(file.path())], true), 0);
+
+                Ok(())
+            })
+        }
+    }
+
+    /// The base of the exit code used when the removal is interrupted, following the shell
+    /// convention of exiting with 128 plus the number of the signal.
+    #[cfg(unix)]
+    const EXIT_SIGNALED: i32 = 128;
+
+    /// The ways in which running the CLI can fail.
+    #[derive(Debug, Eq, PartialEq)]
+    pub enum Failure {
+        /// One or more errors occurred.
+        Error,
+        /// The removal was stopped by a signal, with the exit code for it.
+        Interrupted(u8),
+    }
+
+    impl From<()> for Failure {
+        fn from((): ()) -> Self {
+            Self::Error
+        }
+    }
+
+    /// Handle SIGINT and SIGTERM by setting the `interrupted` flag, letting the removal stop at the
+    /// next entry, and return the exit code for the signal received. Signals interrupt waiting for
+    /// the user to answer a prompt. If either signal is received again the process exits
+    /// immediately.
+    #[cfg(unix)]
+    fn on_interrupt(interrupted: &Arc<AtomicBool>) -> Arc<AtomicUsize> {
+        use signal_hook::consts::{SIGINT, SIGTERM};
+        use signal_hook::flag;
+
+        let exit_code = Arc::new(AtomicUsize::new(0));
+        for signal in [SIGINT, SIGTERM] {
+            let code = EXIT_SIGNALED.saturating_add(signal);
+            let registered =
+                flag::register_conditional_shutdown(signal, code, Arc::clone(interrupted))
+                    .and_then(|_| flag::register(signal, Arc::clone(interrupted)))
+                    .and_then(|_| {
+                        let code = usize::try_from(code).unwrap_or_default();
+                        flag::register_usize(signal, Arc::clone(&exit_code), code)
+                    })
+                    .and_then(|_| interrupt_syscalls(signal));
+            if let Err(err) = registered {
+                trace!("cannot handle signal {signal}: {err}");
+            }
+        }
+
+        exit_code
+    }
+
+    /// Handle interrupts by setting the `interrupted` flag and return the exit code for it. Signals
+    /// are not supported on this platform so the flag is never set.
+    #[cfg(not(unix))]
+    fn on_interrupt(_interrupted: &Arc<AtomicBool>) -> Arc<AtomicUsize> {
+        Arc::default()
+    }
+
+    /// Let the given signal interrupt system calls, such as waiting for input, instead of
+    /// restarting them after it has been handled.
+    ///
+    /// # Errors
+    ///
+    /// If the action for the signal cannot be obtained or changed.
+    #[cfg(unix)]
+    fn interrupt_syscalls(signal: i32) -> io::Result<()> {
+        use std::mem::MaybeUninit;
+        use std::ptr;
+
+        let mut action = MaybeUninit::<libc::sigaction>::zeroed();
+        // SAFETY: `action` is valid for writes and a null pointer leaves the action unchanged.
+        if unsafe { libc::sigaction(signal, ptr::null(), action.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // SAFETY: `action` was initialized by the successful call above.
+        let mut action = unsafe { action.assume_init() };
+        action.sa_flags &= !libc::SA_RESTART;
+        // SAFETY: `action` is the current action for the signal with only its flags changed, and
+        // a null pointer means the old action is not needed.
+        if unsafe { libc::sigaction(signal, &raw const action, ptr::null_mut()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    /// Wrap the given [`walk::Walker`] so that it stops walking once `interrupted` is set.
+    fn interruptible(walker: walk::Walker, interrupted: Arc<AtomicBool>) -> walk::Walker {
+        Box::new(move |path| {
+            let interrupted = Arc::clone(&interrupted);
+            let mut results = walker(path);
+            Box::new(iter::from_fn(move || {
+                if interrupted.load(Ordering::Relaxed) { None } else { results.next() }
+            }))
+        })
+    }
+
+    /// Tests for the [`interruptible`] function.
+    #[cfg(test)]
+    mod test_interruptible {
+        use crate::fs;
+        use crate::walk;
+
+        use super::interruptible;
+
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        #[test]
+        fn not_interrupted() {
+            let interrupted = Arc::new(AtomicBool::new(false));
+
+            let walker = interruptible(walker(), interrupted);
+            assert_eq!(walker(&"dir").count(), 3);
+        }
+
+        #[test]
+        fn interrupted_before() {
+            let interrupted = Arc::new(AtomicBool::new(true));
+
+            let walker = interruptible(walker(), interrupted);
+            assert_eq!(walker(&"dir").count(), 0);
+        }
+
+        #[test]
+        fn interrupted_during() {
+            let interrupted = Arc::new(AtomicBool::new(false));
+
+            let walker = interruptible(walker(), Arc::clone(&interrupted));
+            let mut results = walker(&"dir");
+            assert!(results.next().is_some());
+            interrupted.store(true, Ordering::Relaxed);
+            assert!(results.next().is_none());
+        }
+
+        /// A [`walk::Walker`] that always yields three files.
+        fn walker() -> walk::Walker {
+            Box::new(|_| {
+                Box::new(
+                    ["dir/a", "dir/b", "dir/c"]
+                        .into_iter()
+                        .map(|path| Ok::<_, fs::Error>(fs::test_helpers::new_file(path))),
+                )
+            })
+        }
+    }
+
+    /// Find the tombstones left behind by [`rm::detach`] directly in the given directories, or in
+    /// the current directory if none are given.
+    ///
+    /// # Errors
+    ///
+    /// If any of the directories cannot be read.
+    fn tombstones_in(dirs: &[OsString]) -> Result<Vec<OsString>, ()> {
+        let current_dir = [OsString::from(".")];
+        let dirs = if dirs.is_empty() { current_dir.as_slice() } else { dirs };
+
+        let mut tombstones = Vec::new();
+        for dir in dirs {
+            let dir = Path::new(dir);
+            let entries = read_dir(dir)
+                .map_err(|err| error!("Cannot read {}: {err}", dir.display().bold()))?;
+            tombstones.extend(
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| rm::is_tombstone(path))
+                    .map(PathBuf::into_os_string),
+            );
+        }
+
+        tombstones.sort();
+        Ok(tombstones)
+    }
+
+    /// Tests for the [`tombstones_in`] function.
+    #[cfg(test)]
+    mod test_tombstones_in {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::tombstones_in;
+
+        use std::ffi::OsString;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn found() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let tombstone1 = dir.child(".rust-rm-tombstone-abc");
+                tombstone1.child("file").touch()?;
+                let tombstone2 = dir.child(".rust-rm-tombstone-def");
+                tombstone2.touch()?;
+                dir.child("file").touch()?;
+                dir.child("nested").child(".rust-rm-tombstone-ghi").touch()?;
+
+                let out = tombstones_in(&[OsString::from(dir.path())]);
+                assert_eq!(
+                    out,
+                    Ok(vec![OsString::from(tombstone1.path()), OsString::from(tombstone2.path()),])
+                );
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn none() -> TestResult {
+            with_test_dir(|test_dir| {
+                test_dir.child("file").touch()?;
+
+                let out = tombstones_in(&[OsString::from(test_dir.path())]);
+                assert_eq!(out, Ok(vec![]));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn missing() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("missing");
+
+                let out = tombstones_in(&[OsString::from(dir.path())]);
+                assert_eq!(out, Err(()));
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Remove the given tombstones, left behind by [`rm::detach`], in a background process that
+    /// writes its output to a log file in the temporary directory. Returns the path of the log
+    /// file, if there was anything to remove.
+    ///
+    /// # Errors
+    ///
+    /// If the background process cannot be started, after reporting the problem.
+    fn remove_in_background(tombstones: &[PathBuf]) -> Result<Option<PathBuf>, ()> {
+        if tombstones.is_empty() {
+            return Ok(None);
+        }
+
+        spawn_removal(tombstones).map(Some).map_err(|err| {
+            error!("Cannot remove in the background: {err} (use '--gc' to remove the tombstones)");
+        })
+    }
+
+    /// Start a background process to recursively remove the given `paths`, writing its output to a
+    /// new log file in the temporary directory. Returns the path of the log file.
+    ///
+    /// # Errors
+    ///
+    /// If the log file cannot be created or the process cannot be started.
+    fn spawn_removal(paths: &[PathBuf]) -> io::Result<PathBuf> {
+        let (log, output) = create_temp_file("rust-rm-", ".log")?;
+
+        let mut command = process::Command::new(env::current_exe()?);
+        command
+            .args(["--force", "--recursive", "--"])
+            .args(paths)
+            .env_remove(CONFIRM_COUNT) // confirmed by name before detaching
+            .env_remove(CONFIRM_SIZE) // confirmed by name before detaching
+            .env_remove(MAX_COUNT) // refused before detaching
+            .env_remove(PROTECT_RECENT) // refused before detaching
+            .stdin(process::Stdio::null())
+            .stdout(output.try_clone()?)
+            .stderr(output);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt as _;
+
+            command.process_group(0); // keep running when the foreground job is interrupted
+        }
+
+        #[cfg(feature = "bsd-mode")]
+        command.env_remove(BSD_MODE);
+        #[cfg(feature = "gnu-mode")]
+        command.env_remove(GNU_MODE);
+
+        command.spawn()?;
+        Ok(log)
+    }
+
+    /// The number of times -P overwrites regular files in BSD mode.
+    #[cfg(feature = "bsd-mode")]
+    const OVERWRITE_PASSES: u8 = 3;
+
+    /// The caveat to output when shredding files.
+    const WARN_SHRED: &str = "Shredding cannot guarantee the contents are unrecoverable on \
+        copy-on-write or journaling file systems (such as Btrfs, ZFS or ext4), nor on flash storage";
+
+    /// Returns `true` if nothing would actually be removed for the given (parsed) arguments.
+    fn is_dry_run(args: &Args) -> bool {
+        #[cfg(feature = "pick")]
+        let pick = args.pick;
+        #[cfg(not(feature = "pick"))]
+        let pick = false;
+
+        !args.force && !args.interactive && !args.edit && !pick
+    }
+
+    /// Create the [`walk::Transformers`] to use for the given (parsed) arguments, using the given
+    /// [`transform::Session`] when interactive or confirming by name.
+    fn new_transformers(args: &Args, session: &Rc<transform::Session>) -> walk::Transformers {
+        let mut transformers: Vec<transform::Transformer> = vec![
+            Box::new(transform::disallow_current_and_parent_dir),
+            Box::new(if args.no_preserve_root {
+                transform::identity
+            } else {
+                transform::disallow_root
+            }),
+            Box::new(if args.blind { transform::skip_not_found } else { transform::tip_not_found }),
+            match (args.dir, args.recursive || args.contents || args.gc, args.empty_dirs) {
+                (false, false, _) => Box::new(transform::disallow_all_dirs),
+                (true, false, _) => Box::new(transform::disallow_filled_dirs),
+                (_, true, false) => Box::new(transform::identity),
+                (_, true, true) => transform::skip_all_but_empty_trees(),
+            },
+        ];
+
+        if let Some(retention) = Retention::of(args) {
+            let retained = retained_operands(&args.paths, retention, args.keep_group.as_ref());
+            transformers.push(transform::skip_retained(retained));
+        }
+        if !args.types.is_empty() {
+            let kinds = args.types.iter().copied().map(fs::EntryKind::from).collect();
+            transformers.push(transform::skip_unless_type(kinds));
+        }
+        if let Some(size) = args.larger_than {
+            transformers.push(transform::skip_unless_larger_than(size));
+        }
+        if let Some(size) = args.smaller_than {
+            transformers.push(transform::skip_unless_smaller_than(size));
+        }
+        if args.empty_files {
+            transformers.push(transform::skip_unless_empty_file());
+        }
+        #[cfg(unix)]
+        if let Some(uid) = args.user {
+            transformers.push(transform::skip_unless_user(uid));
+        }
+        #[cfg(unix)]
+        if let Some(gid) = args.group {
+            transformers.push(transform::skip_unless_group(gid));
+        }
+
+        let now = SystemTime::now();
+        if let Some(age) = args.older_than {
+            transformers.push(transform::skip_unless_older_than(args.time.into(), age.cutoff(now)));
+        }
+        if let Some(age) = args.newer_than {
+            transformers.push(transform::skip_unless_newer_than(args.time.into(), age.cutoff(now)));
+        }
+        transformers.extend(new_protections(args, now));
+
+        if args.interactive {
+            transformers.push(transform::interactive(Rc::clone(session), !args.no_details));
+        }
+        if let Some(thresholds) = thresholds_of(args)
+            && !is_dry_run(args)
+        {
+            transformers.push(transform::confirm_by_name(Rc::clone(session), thresholds));
+        }
+
+        Rc::from(transformers)
+    }
+
+    /// Create the [`transform::Transformer`]s protecting entries from removal for the given (parsed)
+    /// arguments, measuring the age of entries from `now`.
+    fn new_protections(args: &Args, now: SystemTime) -> Vec<transform::Transformer> {
+        let mut transformers = vec![];
+        if let Some(window) = args.protect_recent
+            && !args.force_recent
+        {
+            let cutoff = now.checked_sub(window).unwrap_or(UNIX_EPOCH);
+            transformers.push(transform::refuse_recently_modified(cutoff, now));
+        }
+        #[cfg(target_os = "linux")]
+        if args.protect_open {
+            transformers.push(transform::refuse_held_open(fs::OpenFiles::scan()));
+        } else if args.check_open {
+            transformers.push(transform::warn_held_open(fs::OpenFiles::scan()));
+        }
+
+        transformers
+    }
+
+    /// Create the [`walk::Transformers`] to use when applying a plan for the given (parsed)
+    /// arguments, using the given [`transform::Session`] when confirming by name. These are the
+    /// safety checks of [`new_transformers`], as the plan may have been edited or made elsewhere.
+    fn new_plan_transformers(args: &Args, session: &Rc<transform::Session>) -> walk::Transformers {
+        let mut transformers: Vec<transform::Transformer> = vec![
+            Box::new(transform::disallow_current_and_parent_dir),
+            Box::new(if args.no_preserve_root {
+                transform::identity
+            } else {
+                transform::disallow_root
+            }),
+        ];
+        transformers.extend(new_protections(args, SystemTime::now()));
+        if let Some(thresholds) = thresholds_of(args)
+            && !is_dry_run(args)
+        {
+            transformers.push(transform::confirm_by_name(Rc::clone(session), thresholds));
+        }
+
+        Rc::from(transformers)
+    }
+
+    /// Create the [`walk::Walker`] to use for the given (parsed) arguments, see also
+    /// [`new_transformers`].
+    fn new_walker(args: &Args, session: &Rc<transform::Session>) -> walk::Walker {
+        let transformers = new_transformers(args, session);
+
+        #[cfg(feature = "trash")]
+        let recursive = (args.recursive || args.contents || args.gc) && !args.trash && !args.detach;
+        #[cfg(not(feature = "trash"))]
+        let recursive = (args.recursive || args.contents || args.gc) && !args.detach;
+
+        let walk = match (recursive, args.one_file_system) {
+            (false, _) => walk::given(Rc::clone(&transformers)),
+            (true, false) => walk::recurse(Rc::clone(&transformers)),
+            (true, true) => walk::recurse_within_file_system(Rc::clone(&transformers)),
+        };
+
+        let walk = match (args.contents, args.one_file_system) {
+            (false, _) => walk,
+            (true, false) => walk::contents(walk, Rc::clone(&transformers)),
+            (true, true) => walk::contents_within_file_system(walk, Rc::clone(&transformers)),
+        };
+
+        #[cfg(unix)]
+        if let Some(dir) = &args.all_links {
+            return walk::with_links(walk, fs::hard_links_in(dir), transformers);
+        }
+
+        walk
+    }
+
+    /// Remove everything found by walking the given `paths` with the given [`rm::Remover`],
+    /// outputting the result for every entry. Returns the number of removed entries and the number
+    /// of errors, in that order.
+    fn remove_all(paths: &[OsString], walk: &walk::Walker, remove: &rm::Remover) -> (usize, usize) {
+        trace!("start processing");
+        paths
+            .iter()
+            .flat_map(|path| walk(path))
+            .map(|result| match result {
+                Ok(entry) => remove(entry),
+                Err(err) => Err(err),
+            })
+            .inspect(|result| match result {
+                Ok(msg) => info!("{msg}"),
+                Err(err) => error!("{err}"),
+            })
+            .fold((0, 0), |(oks, errs), result| match result {
+                Ok(_) => (oks.saturating_add(1), errs),
+                Err(_) => (oks, errs.saturating_add(1)),
+            })
+    }
+
+    /// Wrap the given [`rm::Remover`] to record the paths of the entries it removes in `removed`.
+    /// Used in dry runs to find out what would be removed with --parents.
+    fn record_removed(remove: rm::Remover, removed: walk::Removed) -> rm::Remover {
+        Box::new(move |entry| {
+            let path = entry.path();
+            let result = remove(entry);
+            if result.is_ok() {
+                removed.borrow_mut().insert(path);
+            }
+
+            result
+        })
+    }
+
+    /// Tests for the [`record_removed`] function.
+    #[cfg(test)]
+    mod test_record_removed {
+        use super::{fs, record_removed, rm, walk};
+
+        use std::path::PathBuf;
+        use std::rc::Rc;
+
+        #[test]
+        fn records_removed() {
+            let removed = walk::Removed::default();
+            let remove = record_removed(Box::new(rm::show_remove), Rc::clone(&removed));
+
+            let out = remove(fs::test_helpers::new_file("file"));
+            assert!(out.is_ok());
+            assert!(removed.borrow().contains(&PathBuf::from("file")));
+        }
+
+        #[test]
+        fn not_recording_errors() {
+            let removed = walk::Removed::default();
+            let remove: rm::Remover =
+                Box::new(|entry: fs::Entry| Err(entry.into_err(fs::ErrorKind::PermissionDenied)));
+            let remove = record_removed(remove, Rc::clone(&removed));
+
+            let out = remove(fs::test_helpers::new_file("file"));
+            assert!(out.is_err());
+            assert!(removed.borrow().is_empty());
+        }
+    }
+
+    /// Wrap the given [`rm::Remover`] so that it waits for the given [`Throttle`] before every
+    /// removal.
+    fn throttled<C: Clock + 'static>(remove: rm::Remover, throttle: Throttle<C>) -> rm::Remover {
+        let throttle = RefCell::new(throttle);
+        Box::new(move |entry| {
+            let size = if entry.is_dir() { 0 } else { fs::size_of(&entry).unwrap_or(0) };
+
+            let mut throttle = throttle.borrow_mut();
+            throttle.wait();
+            let result = remove(entry);
+            if result.is_ok() {
+                throttle.record(size);
+            }
+
+            result
+        })
+    }
+
+    /// Tests for the [`throttled`] function.
+    #[cfg(test)]
+    mod test_throttled {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::test_helpers::FakeClock;
+        use super::{Throttle, fs, rm, throttled};
+
+        use std::rc::Rc;
+        use std::time::Duration;
+
+        use assert_fs::prelude::*;
+        use predicates::prelude::*;
+
+        #[test]
+        fn count() -> TestResult {
+            with_test_dir(|test_dir| {
+                let clock = Rc::new(FakeClock::default());
+                let remove = throttled(
+                    Box::new(rm::remove),
+                    Throttle::new(Rc::clone(&clock), Some(2), None),
+                );
+
+                for name in ["file1", "file2", "file3"] {
+                    let file = test_dir.child(name);
+                    file.touch()?;
+                    assert!(remove(fs::test_helpers::new_file(file.path())).is_ok());
+                    file.assert(predicate::path::missing());
+                }
+                assert_eq!(clock.slept(), Duration::from_secs(1));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn size() -> TestResult {
+            with_test_dir(|test_dir| {
+                let clock = Rc::new(FakeClock::default());
+                let remove = throttled(
+                    Box::new(rm::remove),
+                    Throttle::new(Rc::clone(&clock), None, Some(1024)),
+                );
+
+                for name in ["file1", "file2", "file3"] {
+                    let file = test_dir.child(name);
+                    file.write_str(&"x".repeat(2048))?;
+                    assert!(remove(fs::test_helpers::new_file(file.path())).is_ok());
+                }
+                assert_eq!(clock.slept(), Duration::from_secs(4));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn errors_not_counted() -> TestResult {
+            with_test_dir(|test_dir| {
+                let clock = Rc::new(FakeClock::default());
+                let remove = throttled(
+                    Box::new(rm::remove),
+                    Throttle::new(Rc::clone(&clock), Some(1), None),
+                );
+
+                for name in ["missing1", "missing2", "missing3"] {
+                    let file = test_dir.child(name);
+                    assert!(remove(fs::test_helpers::new_file(file.path())).is_err());
+                }
+                assert_eq!(clock.slept(), Duration::ZERO);
+
+                Ok(())
+            })
+        }
+    }
+
+    /// A source of the current time that can also be slept on, see [`Throttle`].
+    trait Clock {
+        /// Get the current point in time.
+        fn now(&self) -> Instant;
+
+        /// Sleep for the given [`Duration`].
+        fn sleep(&self, duration: Duration);
+    }
+
+    /// The [`Clock`] of the system.
+    struct SystemClock;
+
+    impl Clock for SystemClock {
+        fn now(&self) -> Instant {
+            Instant::now()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            thread::sleep(duration);
+        }
+    }
+
+    impl<C: Clock> Clock for Rc<C> {
+        fn now(&self) -> Instant {
+            C::now(self)
+        }
+
+        fn sleep(&self, duration: Duration) {
+            C::sleep(self, duration);
+        }
+    }
+
+    /// The longest time the budget of a [`Throttle`] may go unused before it is reset, which bounds
+    /// the number of removals that can follow each other without waiting.
+    const THROTTLE_BURST: Duration = Duration::from_secs(1);
+
+    /// The longest time a [`Throttle`] sleeps before checking if it was interrupted.
+    const THROTTLE_SLICE: Duration = Duration::from_millis(100);
+
+    /// A limit on the rate of removals, by the number of removals and/or the number of bytes freed
+    /// per second.
+    struct Throttle<C> {
+        /// The [`Clock`] to measure time with.
+        clock: C,
+        /// The maximum number of removals per second, if any.
+        count: Option<u64>,
+        /// The maximum number of bytes freed per second, if any.
+        size: Option<u64>,
+        /// The point in time of the first removal since the budget was last reset, if any.
+        start: Option<Instant>,
+        /// The number of removals since the start.
+        removed: u64,
+        /// The number of bytes freed since the start.
+        freed: u64,
+        /// Whether the removal was interrupted, which ends waiting.
+        interrupted: Arc<AtomicBool>,
+    }
+
+    impl<C: Clock> Throttle<C> {
+        /// Create a new [`Throttle`] allowing at most `count` removals and `size` bytes freed per
+        /// second, measured using the given [`Clock`].
+        fn new(clock: C, count: Option<u64>, size: Option<u64>) -> Self {
+            Self {
+                clock,
+                count,
+                size,
+                start: None,
+                removed: 0,
+                freed: 0,
+                interrupted: Arc::default(),
+            }
+        }
+
+        /// Make the [`Throttle`] stop waiting once `interrupted` is set.
+        #[must_use]
+        fn interruptible(self, interrupted: Arc<AtomicBool>) -> Self {
+            Self { interrupted, ..self }
+        }
+
+        /// Wait until the next removal is allowed, or until interrupted, in slices of at most
+        /// [`THROTTLE_SLICE`]. If the budget went unused for longer than [`THROTTLE_BURST`], it is
+        /// reset instead.
+        fn wait(&mut self) {
+            let now = self.clock.now();
+            let start = *self.start.get_or_insert(now);
+            let due = start
+                .checked_add(Ord::max(
+                    self.count.map_or(Duration::ZERO, |rate| time_for(self.removed, rate)),
+                    self.size.map_or(Duration::ZERO, |rate| time_for(self.freed, rate)),
+                ))
+                .unwrap_or(now);
+
+            if now.saturating_duration_since(due) > THROTTLE_BURST {
+                trace!("reset throttle");
+                self.start = Some(now);
+                self.removed = 0;
+                self.freed = 0;
+                return;
+            }
+
+            let mut wait = due.saturating_duration_since(now);
+            if !wait.is_zero() {
+                trace!("throttle for {}ms", wait.as_millis());
+            }
+            while !wait.is_zero() && !self.interrupted.load(Ordering::Relaxed) {
+                self.clock.sleep(Ord::min(wait, THROTTLE_SLICE));
+                wait = due.saturating_duration_since(self.clock.now());
+            }
+        }
+
+        /// Record a removal that freed the given number of bytes.
+        fn record(&mut self, size: u64) {
+            self.removed = self.removed.saturating_add(1);
+            self.freed = self.freed.saturating_add(size);
+        }
+    }
+
+    /// Tests for the [`Throttle`] struct.
+    #[cfg(test)]
+    mod test_throttle {
+        use super::test_helpers::FakeClock;
+        use super::{Clock, THROTTLE_SLICE, Throttle};
+
+        use std::rc::Rc;
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::time::{Duration, Instant};
+
+        /// A [`Clock`] that is interrupted the first time it is slept on.
+        #[derive(Default)]
+        struct InterruptingClock {
+            /// The [`FakeClock`] to measure time with.
+            inner: FakeClock,
+            /// Whether the clock was slept on.
+            interrupted: Arc<AtomicBool>,
+        }
+
+        impl Clock for InterruptingClock {
+            fn now(&self) -> Instant {
+                self.inner.now()
+            }
+
+            fn sleep(&self, duration: Duration) {
+                self.inner.sleep(duration);
+                self.interrupted.store(true, Ordering::Relaxed);
+            }
+        }
+
+        #[test]
+        fn first_removal_not_throttled() {
+            let clock = Rc::new(FakeClock::default());
+            let mut throttle = Throttle::new(Rc::clone(&clock), Some(1), Some(1));
+
+            throttle.wait();
+            assert_eq!(clock.slept(), Duration::ZERO);
+        }
+
+        #[test]
+        fn count() {
+            let clock = Rc::new(FakeClock::default());
+            let mut throttle = Throttle::new(Rc::clone(&clock), Some(4), None);
+
+            for _ in 0..5 {
+                throttle.wait();
+                throttle.record(1 << 30);
+            }
+            assert_eq!(clock.slept(), Duration::from_secs(1));
+        }
+
+        #[test]
+        fn size() {
+            let clock = Rc::new(FakeClock::default());
+            let mut throttle = Throttle::new(Rc::clone(&clock), None, Some(100));
+
+            for size in [50, 150, 0] {
+                throttle.wait();
+                throttle.record(size);
+            }
+            assert_eq!(clock.slept(), Duration::from_secs(2));
+        }
+
+        #[test]
+        fn count_and_size() {
+            let clock = Rc::new(FakeClock::default());
+            let mut throttle = Throttle::new(Rc::clone(&clock), Some(1), Some(100));
+
+            throttle.wait();
+            throttle.record(300);
+            throttle.wait();
+            assert_eq!(clock.slept(), Duration::from_secs(3));
+
+            throttle.record(0);
+            throttle.wait();
+            assert_eq!(clock.slept(), Duration::from_secs(3));
+        }
+
+        #[test]
+        fn time_passed() {
+            let clock = Rc::new(FakeClock::default());
+            let mut throttle = Throttle::new(Rc::clone(&clock), Some(1), None);
+
+            throttle.wait();
+            throttle.record(0);
+            clock.advance(Duration::from_millis(600));
+            throttle.wait();
+            assert_eq!(clock.slept(), Duration::from_millis(400));
+        }
+
+        #[test]
+        fn burst_bounded() {
+            let clock = Rc::new(FakeClock::default());
+            let mut throttle = Throttle::new(Rc::clone(&clock), Some(1), Some(100));
+
+            throttle.wait();
+            throttle.record(0);
+            clock.advance(Duration::from_secs(10));
+            for _ in 0..3 {
+                throttle.wait();
+                throttle.record(100);
+            }
+            assert_eq!(clock.slept(), Duration::from_secs(2));
+        }
+
+        #[test]
+        fn burst_within_bound() {
+            let clock = Rc::new(FakeClock::default());
+            let mut throttle = Throttle::new(Rc::clone(&clock), Some(1), None);
+
+            throttle.wait();
+            throttle.record(0);
+            clock.advance(Duration::from_millis(1900));
+            for _ in 0..3 {
+                throttle.wait();
+                throttle.record(0);
+            }
+            assert_eq!(clock.slept(), Duration::from_millis(1100));
+        }
+
+        #[test]
+        fn interrupted_before() {
+            let clock = Rc::new(FakeClock::default());
+            let interrupted = Arc::new(AtomicBool::new(true));
+            let mut throttle = Throttle::new(Rc::clone(&clock), Some(1), None)
+                .interruptible(Arc::clone(&interrupted));
+
+            throttle.wait();
+            throttle.record(0);
+            throttle.wait();
+            assert_eq!(clock.slept(), Duration::ZERO);
+        }
+
+        #[test]
+        fn interrupted_during() {
+            let clock = Rc::new(InterruptingClock::default());
+            let mut throttle = Throttle::new(Rc::clone(&clock), None, Some(1))
+                .interruptible(Arc::clone(&clock.interrupted));
+
+            throttle.wait();
+            throttle.record(10);
+            throttle.wait();
+            assert_eq!(clock.inner.slept(), THROTTLE_SLICE);
+        }
+
+        #[test]
+        fn unlimited() {
+            let clock = Rc::new(FakeClock::default());
+            let mut throttle = Throttle::new(Rc::clone(&clock), None, None);
+
+            for _ in 0..5 {
+                throttle.wait();
+                throttle.record(1 << 30);
+            }
+            assert_eq!(clock.slept(), Duration::ZERO);
+        }
+    }
+
+    /// Get the time it takes to do the given `amount` of something at the given `rate` per second.
+    fn time_for(amount: u64, rate: u64) -> Duration {
+        let nanos = u128::from(amount)
+            .saturating_mul(1_000_000_000)
+            .checked_div(u128::from(rate))
+            .unwrap_or_default();
+
+        Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+    }
+
+    /// Tests for the [`time_for`] function.
+    #[cfg(test)]
+    mod test_time_for {
+        use super::time_for;
+
+        use std::time::Duration;
+
+        #[test]
+        fn whole_seconds() {
+            assert_eq!(time_for(10, 5), Duration::from_secs(2));
+        }
+
+        #[test]
+        fn fractions() {
+            assert_eq!(time_for(1, 4), Duration::from_millis(250));
+            assert_eq!(time_for(1, 3), Duration::from_nanos(333_333_333));
+        }
+
+        #[test]
+        fn large() {
+            assert_eq!(time_for(u64::MAX, u64::MAX), Duration::from_secs(1));
+            assert_eq!(time_for(u64::MAX, 1), Duration::from_nanos(u64::MAX));
+        }
+
+        #[test]
+        fn zero_rate() {
+            assert_eq!(time_for(1, 0), Duration::ZERO);
+        }
+    }
+
+    /// The `which` argument of `ioprio_set(2)` for setting the I/O priority of a process.
+    #[cfg(target_os = "linux")]
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+
+    /// The I/O scheduling class of processes that only use the disk when nothing else does.
+    #[cfg(target_os = "linux")]
+    const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+
+    /// The number of bits the I/O scheduling class is shifted by in an I/O priority.
+    #[cfg(target_os = "linux")]
+    const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+
+    /// Set the I/O scheduling class of this process to idle, using `ioprio_set(2)`, so that removing
+    /// only uses the disk when nothing else does.
+    ///
+    /// # Errors
+    ///
+    /// If the I/O priority cannot be set.
+    #[cfg(target_os = "linux")]
+    fn set_idle_io_priority() -> Result<(), String> {
+        trace!("set the I/O scheduling class to idle");
+        let priority = IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT;
+        // SAFETY: ioprio_set(2) takes only integer arguments, where a pid of 0 is this process.
+        let result =
+            unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, priority) };
+
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(format!("Cannot set the I/O priority to idle: {}", io::Error::last_os_error()))
+        }
+    }
+
+    /// The first line of a plan file, identifying the format of the file.
+    const PLAN_HEADER: &str = "# rust-rm plan v1";
+
+    /// Create the [`transform::Session`] to prompt the user through for the given (parsed)
+    /// arguments, using the terminal unless `--answers-from` is used.
+    ///
+    /// # Errors
+    ///
+    /// If there is no terminal or the answers cannot be read.
+    fn new_session(args: &Args) -> Result<transform::Session, String> {
+        match &args.answers_from {
+            Some(file) if file.as_os_str() == "-" => Ok(transform::Session::new(
+                Box::new(io::stdin().lock()),
+                Box::new(anstream::stderr()),
+            )),
+            Some(file) => File::open(file)
+                .map(|answers| {
+                    transform::Session::new(
+                        Box::new(BufReader::new(answers)),
+                        Box::new(anstream::stderr()),
+                    )
+                })
+                .map_err(|err| format!("Cannot read answers {}: {err}", file.display().bold())),
+            None => transform::Session::terminal().map_err(|err| {
+                format!(
+                    "Cannot prompt: no terminal available, {err} {}",
+                    "(use '--answers-from' to read answers from a file)".italic(),
+                )
+            }),
+        }
+    }
+
+    /// The tip for directories that must be confirmed by name when there is no terminal to do so.
+    const TIP_NO_TERMINAL: &str =
+        "no terminal to type its name in; use '--answers-from' to read answers from a file";
+
+    /// Create the [`transform::Session`] to confirm directories by name through for the given
+    /// (parsed) arguments, see [`new_session`]. If there is no terminal, directories that must be
+    /// confirmed are refused.
+    ///
+    /// # Errors
+    ///
+    /// If the answers cannot be read, after reporting the problem.
+    fn new_confirm_session(args: &Args) -> Result<transform::Session, ()> {
+        match new_session(args) {
+            Ok(session) => Ok(session),
+            Err(_) if args.answers_from.is_none() => {
+                Ok(transform::Session::unavailable(TIP_NO_TERMINAL))
+            },
+            Err(err) => {
+                error!("{err}");
+                Err(())
+            },
+        }
+    }
+
+    /// Return a [`walk::Walker`] that replays the given `plan`, made ahead of time, after checking
+    /// `--max-count`, letting the user make changes for `--edit`, and writing the plan for
+    /// `--plan-out`. If `interrupted` was set while planning, nothing is planned.
+    ///
+    /// # Errors
+    ///
+    /// If more than `--max-count` entries would be removed, the user's changes cannot be obtained,
+    /// or the plan cannot be written, after reporting the problem.
+    fn plan_ahead(
+        args: &Args,
+        plan: walk::Plan,
+        interrupted: &AtomicBool,
+    ) -> Result<walk::Walker, ()> {
+        if interrupted.load(Ordering::Relaxed) {
+            return Ok(walk::planned(vec![]));
+        }
+
+        let count = plan.iter().flat_map(|(_, results)| results).filter(|r| r.is_ok()).count();
+        if let Some(max_count) = args.max_count
+            && count > max_count
+        {
+            error!(
+                "Refused to remove anything: {count} would be removed, more than the maximum of {max_count} {}",
+                "(use '--max-count' to raise the limit)".italic(),
+            );
+            return Err(());
+        }
+
+        let plan = if args.edit {
+            edit_plan(plan).map_err(|err| error!("Cannot edit the entries to remove: {err}"))?
+        } else {
+            plan
+        };
+
+        #[cfg(feature = "pick")]
+        let plan = if args.pick {
+            pick_plan(plan).map_err(|err| error!("Cannot pick the entries to remove: {err}"))?
+        } else {
+            plan
+        };
+
+        if let Some(file) = &args.plan_out {
+            format_plan(&plan)
+                .and_then(|contents| {
+                    File::create(file)
+                        .and_then(|mut out| out.write_all(contents.as_bytes()))
+                        .map_err(|err| err.to_string())
+                })
+                .map_err(|err| error!("Cannot write plan {}: {err}", file.display().bold()))?;
+        }
+
+        Ok(walk::planned(plan))
+    }
+
+    /// The environment variables that configure the editor for `--edit`, in order of precedence.
+    const EDITOR_VARS: [&str; 2] = ["VISUAL", "EDITOR"];
+
+    /// The editor to use for `--edit` if none is configured.
+    #[cfg(unix)]
+    const DEFAULT_EDITOR: &str = "vi";
+
+    /// The editor to use for `--edit` if none is configured.
+    #[cfg(not(unix))]
+    const DEFAULT_EDITOR: &str = "notepad";
+
+    /// The explanation at the top of the list of entries for `--edit`.
+    const EDIT_HEADER: &str = "\
+        # The entries below would be removed, remove the line of an entry to keep it.\n\
+        # Directories are kept if anything in them is kept. Lines starting with '#' are ignored.\n";
+
+    /// Let the user edit the entries in the given [`walk::Plan`] in their editor, and return the
+    /// [`walk::Plan`] without the entries they kept.
+    ///
+    /// # Errors
+    ///
+    /// If the list of entries cannot be written or read, or the editor fails.
+    fn edit_plan(plan: walk::Plan) -> Result<walk::Plan, String> {
+        let contents = format_edit_list(&plan)?;
+
+        let file = create_temp_file("rust-rm-edit-", ".txt")
+            .and_then(|(file, mut out)| out.write_all(contents.as_bytes()).map(|()| file))
+            .map_err(|err| err.to_string())?;
+        let edited = run_editor(&file)
+            .and_then(|()| read_to_string(&file).map_err(|err| err.to_string()))
+            .and_then(|contents| parse_edit_list(&contents));
+        if let Err(err) = remove_file(&file) {
+            trace!("could not remove {}: {err}", file.display());
+        }
+
+        Ok(without_kept(plan, &edited?))
+    }
+
+    /// Create a new file in the temporary directory named `prefix`, a random part and `suffix`,
+    /// which is only accessible by the current user on Unix. Returns its path and the file.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be created.
+    fn create_temp_file(prefix: &str, suffix: &str) -> io::Result<(PathBuf, File)> {
+        use std::fs::OpenOptions;
+
+        let path = env::temp_dir().join(format!("{prefix}{}{suffix}", rm::random_name(12)?));
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt as _;
+
+            options.mode(0o600);
+        }
+
+        let file = options.open(&path)?;
+        Ok((path, file))
+    }
+
+    /// Tests for the [`create_temp_file`] function.
+    #[cfg(test)]
+    mod test_create_temp_file {
+        use crate::test_helpers::TestResult;
+
+        use super::create_temp_file;
+
+        use std::ffi::OsStr;
+        use std::fs::remove_file;
+        use std::path::Path;
+
+        #[test]
+        fn random_names() -> TestResult {
+            let (first, _) = create_temp_file("rust-rm-test-", ".txt")?;
+            let (second, _) = create_temp_file("rust-rm-test-", ".txt")?;
+            remove_file(&first)?;
+            remove_file(&second)?;
+
+            assert_ne!(first, second);
+            for path in [first, second] {
+                let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+                assert!(name.starts_with("rust-rm-test-"));
+                assert_eq!(Path::new(&name).extension(), Some(OsStr::new("txt")));
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn private() -> TestResult {
+            use std::os::unix::fs::PermissionsExt as _;
+
+            let (path, file) = create_temp_file("rust-rm-test-", ".txt")?;
+            let mode = file.metadata()?.permissions().mode();
+            remove_file(&path)?;
+
+            assert_eq!(mode & 0o777, 0o600);
+
+            Ok(())
+        }
+    }
+
+    /// Let the user pick the entries in the given [`walk::Plan`] to remove, and return the
+    /// [`walk::Plan`] without the entries they did not pick.
+    ///
+    /// # Errors
+    ///
+    /// If the entries cannot be shown to the user.
+    #[cfg(feature = "pick")]
+    fn pick_plan(plan: walk::Plan) -> Result<walk::Plan, String> {
+        let unpicked: HashSet<PathBuf> = pick::unpicked(&plan)?.into_iter().collect();
+        let listed: Vec<PathBuf> = plan
+            .iter()
+            .flat_map(|(_, results)| results)
+            .flatten()
+            .map(fs::Entry::path)
+            .filter(|path| !path.ancestors().any(|ancestor| unpicked.contains(ancestor)))
+            .collect();
+
+        Ok(without_kept(plan, &listed))
+    }
+
+    /// Open the given `file` in the editor configured by the user.
+    ///
+    /// # Errors
+    ///
+    /// If the editor cannot be run or exits unsuccessfully.
+    fn run_editor(file: &Path) -> Result<(), String> {
+        let editor = EDITOR_VARS
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|editor| !editor.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_EDITOR.to_owned());
+
+        let mut words = editor.split_whitespace();
+        let program = words.next().unwrap_or(DEFAULT_EDITOR);
+        trace!("edit {} using {editor}", file.display());
+        let status = process::Command::new(program)
+            .args(words)
+            .arg(file)
+            .status()
+            .map_err(|err| format!("cannot run {program}: {err}"))?;
+
+        if status.success() { Ok(()) } else { Err(format!("{program} failed ({status})")) }
+    }
+
+    /// Format the entries in the given [`walk::Plan`] as a list for the user to edit.
+    ///
+    /// # Errors
+    ///
+    /// If the path of an entry is not valid Unicode.
+    fn format_edit_list(plan: &walk::Plan) -> Result<String, String> {
+        let lines = plan
+            .iter()
+            .flat_map(|(_, results)| results)
+            .flatten()
+            .map(|entry| {
+                let path = entry.path();
+                let path = path.to_str().ok_or_else(|| format!("{entry} is not valid Unicode"))?;
+                Ok(format!("{}\n", escape(path)))
+            })
+            .collect::<Result<Vec<String>, String>>()?;
+
+        Ok(format!("{EDIT_HEADER}{}", lines.concat()))
+    }
+
+    /// Tests for the [`format_edit_list`] function.
+    #[cfg(test)]
+    mod test_format_edit_list {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{EDIT_HEADER, format_edit_list, fs, parse_edit_list};
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn empty() {
+            assert_eq!(format_edit_list(&vec![]), Ok(EDIT_HEADER.to_owned()));
+        }
+
+        #[test]
+        fn entries() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let file = dir.child("file");
+                file.touch()?;
+                let missing = test_dir.child("missing");
+
+                let plan = vec![
+                    (dir.to_path_buf(), vec![fs::open(&file), fs::open(&dir)]),
+                    (missing.to_path_buf(), vec![fs::open(&missing)]),
+                ];
+
+                let out = format_edit_list(&plan)?;
+                assert!(out.starts_with(EDIT_HEADER));
+                assert_eq!(parse_edit_list(&out)?, vec![file.to_path_buf(), dir.to_path_buf()]);
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Parse the paths listed in an edited list of entries, see [`format_edit_list`].
+    ///
+    /// # Errors
+    ///
+    /// If a path in the list is not escaped correctly.
+    fn parse_edit_list(contents: &str) -> Result<Vec<PathBuf>, String> {
+        contents
+            .lines()
+            .zip(1_usize..)
+            .filter(|(line, _)| !line.trim().is_empty() && !line.starts_with('#'))
+            .map(|(line, number)| {
+                unescape(line)
+                    .map(PathBuf::from)
+                    .ok_or_else(|| format!("invalid entry on line {number}"))
+            })
+            .collect()
+    }
+
+    /// Tests for the [`parse_edit_list`] function.
+    #[cfg(test)]
+    mod test_parse_edit_list {
+        use super::parse_edit_list;
+
+        use std::path::PathBuf;
+
+        #[test]
+        fn entries() {
+            let contents = "# comment\nfile\n\n  \nodd\\nname\n";
+            assert_eq!(
+                parse_edit_list(contents),
+                Ok(vec![PathBuf::from("file"), PathBuf::from("odd\nname")])
+            );
+        }
+
+        #[test]
+        fn invalid_entry() {
+            let contents = "# comment\nfile\nfile\\t\n";
+            assert_eq!(parse_edit_list(contents), Err("invalid entry on line 3".to_owned()));
+        }
+    }
+
+    /// Remove the entries from the given [`walk::Plan`] that are not `listed`, as well as the
+    /// directories containing them.
+    fn without_kept(mut plan: walk::Plan, listed: &[PathBuf]) -> walk::Plan {
+        let listed: HashSet<&PathBuf> = listed.iter().collect();
+        let mut kept = HashSet::new();
+        for path in plan.iter().flat_map(|(_, results)| results).flatten().map(fs::Entry::path) {
+            if !listed.contains(&path) {
+                kept.extend(path.ancestors().map(Path::to_owned));
+            }
+        }
+
+        for (_, results) in &mut plan {
+            results.retain(|result| match result {
+                Ok(entry) => {
+                    let path = entry.path();
+                    let keep = kept.contains(&path);
+                    if keep {
+                        trace!("skipped {}: Kept", path.display());
+                    }
+                    !keep
+                },
+                Err(_) => true,
+            });
+        }
+
+        plan
+    }
+
+    /// Tests for the [`without_kept`] function.
+    #[cfg(test)]
+    mod test_without_kept {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{fs, without_kept};
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn all_listed() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let file = dir.child("file");
+                file.touch()?;
+
+                let plan = vec![(dir.to_path_buf(), vec![fs::open(&file), fs::open(&dir)])];
+                let listed = [file.to_path_buf(), dir.to_path_buf()];
+
+                let out = without_kept(plan, &listed);
+                assert_eq!(out, vec![(dir.to_path_buf(), vec![fs::open(&file), fs::open(&dir)])]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn child_kept() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let file1 = dir.child("file1");
+                file1.touch()?;
+                let file2 = dir.child("file2");
+                file2.touch()?;
+
+                let plan = vec![(
+                    dir.to_path_buf(),
+                    vec![fs::open(&file1), fs::open(&file2), fs::open(&dir)],
+                )];
+                let listed = [file1.to_path_buf(), dir.to_path_buf()];
+
+                let out = without_kept(plan, &listed);
+                assert_eq!(out, vec![(dir.to_path_buf(), vec![fs::open(&file1)])]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn errors_retained() -> TestResult {
+            with_test_dir(|test_dir| {
+                let missing = test_dir.child("missing");
+
+                let plan = vec![(missing.to_path_buf(), vec![fs::open(&missing)])];
+
+                let out = without_kept(plan, &[]);
+                assert_eq!(out, vec![(missing.to_path_buf(), vec![fs::open(&missing)])]);
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Read the plan in the given `file` and return the planned paths together with a
+    /// [`walk::Walker`] that visits the planned entries that did not change since, and pass the
+    /// checks of [`new_plan_transformers`] for the given (parsed) arguments. Planned entries are
+    /// walked from their top-most planned ancestor, so directories are checked before their
+    /// contents.
+    ///
+    /// All entries are checked for changes before anything is removed.
+    ///
+    /// # Errors
+    ///
+    /// If the plan cannot be read, after reporting the problem.
+    fn applied_plan(
+        args: &Args,
+        session: &Rc<transform::Session>,
+        file: &Path,
+    ) -> Result<(Vec<OsString>, walk::Walker), ()> {
+        let entries = read_to_string(file)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| parse_plan(&contents))
+            .map_err(|err| error!("Cannot read plan {}: {err}", file.display().bold()))?;
+
+        let planned: HashSet<PathBuf> = entries.iter().map(|(_, path)| path.clone()).collect();
+        let mut plan: walk::Plan = vec![];
+        let mut tops: HashMap<PathBuf, usize> = HashMap::new();
+        for (snapshot, path) in entries {
+            let result = fs::open_unchanged(&path, &snapshot);
+            let top = path.ancestors().filter(|dir| planned.contains(*dir)).last();
+            let top = top.unwrap_or(&path).to_path_buf();
+            let index = *tops.entry(top.clone()).or_insert_with(|| {
+                plan.push((top, vec![]));
+                plan.len().saturating_sub(1)
+            });
+            if let Some((_, results)) = plan.get_mut(index) {
+                results.push(result);
+            }
+        }
+
+        let paths = plan.iter().map(|(path, _)| path.clone().into_os_string()).collect();
+        let walk = walk::recurse(new_plan_transformers(args, session));
+        Ok((paths, walk::replay(walk, plan)))
+    }
+
+    /// Tests for the [`applied_plan`] function.
+    #[cfg(test)]
+    mod test_applied_plan {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{PLAN_HEADER, applied_plan, fs, parse_args, parse_vars, transform};
+
+        use std::path::Path;
+        use std::rc::Rc;
+
+        use assert_fs::prelude::*;
+
+        /// Apply a plan for the given `path`, made just now, with the given extra `args`.
+        fn apply(test_dir: &assert_fs::TempDir, path: &Path, args: &[&str]) -> Vec<fs::Result> {
+            let entry = fs::open(path).expect("the planned path should exist");
+            let snapshot = fs::snapshot_of(&entry).expect("the planned path should be inspectable");
+            let plan = test_dir.child("plan");
+            plan.write_str(&format!("{PLAN_HEADER}\n{snapshot}\t{}\n", path.display()))
+                .expect("the plan should be writable");
+
+            let plan = plan.path().display().to_string();
+            let args =
+                ["rm", "--force", "--apply-plan", &plan].into_iter().chain(args.iter().copied());
+            let args = parse_args(args.map(str::to_owned), parse_vars(vec![]))
+                .expect("the arguments should be valid");
+            let session = Rc::new(transform::Session::default());
+            let (paths, walk) =
+                applied_plan(&args, &session, plan.as_ref()).expect("the plan should be readable");
+
+            paths.iter().flat_map(|path| walk(path)).collect()
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn root() -> TestResult {
+            with_test_dir(|test_dir| {
+                let out = apply(test_dir, Path::new("/"), &[]);
+                let [item] = out.as_slice() else { panic!("expected one item, got {out:?}") };
+                assert!(item.as_ref().is_err_and(|err| err.kind() == fs::ErrorKind::Refused));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn protect_recent() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let out = apply(test_dir, file.path(), &["--protect-recent", "1h"]);
+                let [item] = out.as_slice() else { panic!("expected one item, got {out:?}") };
+                assert!(item.as_ref().is_err_and(|err| err.kind() == fs::ErrorKind::Refused));
+
+                let out = apply(test_dir, file.path(), &[]);
+                assert_eq!(out, vec![fs::open(file.path())]);
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Format the entries in the given [`walk::Plan`] as the contents of a plan file.
+    ///
+    /// Every entry is put on its own line, as its [`fs::Snapshot`] followed by a tab and its path,
+    /// see [`escape`].
+    ///
+    /// # Errors
+    ///
+    /// If an entry cannot be inspected or its path is not valid Unicode.
+    fn format_plan(plan: &walk::Plan) -> Result<String, String> {
+        let lines = plan
+            .iter()
+            .flat_map(|(_, results)| results)
+            .flatten()
+            .map(|entry| {
+                let snapshot =
+                    fs::snapshot_of(entry).ok_or_else(|| format!("cannot inspect {entry}"))?;
+                let path = entry.path();
+                let path = path.to_str().ok_or_else(|| format!("{entry} is not valid Unicode"))?;
+                Ok(format!("{snapshot}\t{}\n", escape(path)))
+            })
+            .collect::<Result<Vec<String>, String>>()?;
+
+        Ok(format!("{PLAN_HEADER}\n{}", lines.concat()))
+    }
+
+    /// Tests for the [`format_plan`] function.
+    #[cfg(test)]
+    mod test_format_plan {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{PLAN_HEADER, format_plan, fs, parse_plan};
+
+        use std::path::PathBuf;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn empty() {
+            assert_eq!(format_plan(&vec![]), Ok(format!("{PLAN_HEADER}\n")));
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn entries() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let odd_file = test_dir.child("odd\\file\nname");
+                odd_file.touch()?;
+                let missing = test_dir.child("missing");
+
+                let plan = vec![
+                    (file.to_path_buf(), vec![fs::open(&file)]),
+                    (PathBuf::from("odd"), vec![fs::open(&odd_file), fs::open(&missing)]),
+                ];
+
+                let out = format_plan(&plan)?;
+                assert_eq!(out.lines().count(), 3);
+
+                let entries = parse_plan(&out)?;
+                let paths: Vec<PathBuf> = entries.into_iter().map(|(_, path)| path).collect();
+                assert_eq!(paths, vec![file.to_path_buf(), odd_file.to_path_buf()]);
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Parse the contents of a plan file into the [`fs::Snapshot`] and path of every entry in it.
+    ///
+    /// See also [`format_plan`].
+    ///
+    /// # Errors
+    ///
+    /// If the contents are not a valid plan.
+    fn parse_plan(contents: &str) -> Result<Vec<(fs::Snapshot, PathBuf)>, String> {
+        let mut lines = contents.lines();
+        if lines.next() != Some(PLAN_HEADER) {
+            return Err("not a plan file".to_owned());
+        }
+
+        lines
+            .zip(2_usize..)
+            .map(|(line, number)| {
+                line.match_indices('\t')
+                    .nth(4)
+                    .map(|(index, _)| line.split_at(index))
+                    .and_then(|(snapshot, path)| {
+                        let path = unescape(path.strip_prefix('\t')?)?;
+                        Some((fs::Snapshot::parse(snapshot)?, PathBuf::from(path)))
+                    })
+                    .ok_or_else(|| format!("invalid entry on line {number}"))
+            })
+            .collect()
+    }
+
+    /// Tests for the [`parse_plan`] function.
+    #[cfg(test)]
+    mod test_parse_plan {
+        use super::{PLAN_HEADER, fs, parse_plan};
+
+        use std::path::PathBuf;
+
+        #[test]
+        fn empty() {
+            assert_eq!(parse_plan(&format!("{PLAN_HEADER}\n")), Ok(vec![]));
+        }
+
+        #[test]
+        fn entries() {
+            let snapshot = "file\t1\t2\t3\t4.000000005";
+            let contents =
+                format!("{PLAN_HEADER}\n{snapshot}\tfile\n{snapshot}\tdir\\\\with\\ntab\t\n");
+
+            let expected = fs::Snapshot::parse(snapshot).expect("should be valid");
+            assert_eq!(
+                parse_plan(&contents),
+                Ok(vec![
+                    (expected.clone(), PathBuf::from("file")),
+                    (expected, PathBuf::from("dir\\with\ntab\t")),
+                ])
+            );
+        }
+
+        #[test]
+        fn missing_header() {
+            assert_eq!(
+                parse_plan("file\t1\t2\t3\t4.000000005\tfile\n"),
+                Err("not a plan file".to_owned())
+            );
+        }
+
+        #[test]
+        fn invalid_entry() {
+            let contents = format!("{PLAN_HEADER}\nfile\t1\t2\t3\t4.000000005\tfile\nfile\t1\t2\n");
+            assert_eq!(parse_plan(&contents), Err("invalid entry on line 3".to_owned()));
+        }
+
+        #[test]
+        fn invalid_escape() {
+            let contents = format!("{PLAN_HEADER}\nfile\t1\t2\t3\t4.000000005\tfile\\t\n");
+            assert_eq!(parse_plan(&contents), Err("invalid entry on line 2".to_owned()));
+        }
+    }
+
+    /// Escape backslashes and line breaks in the given path so it fits on a single line.
+    fn escape(path: &str) -> String {
+        path.replace('\\', "\\\\").replace('\n', "\\n").replace('\r', "\\r")
+    }
+
+    /// Tests for the [`escape`] function.
+    #[cfg(test)]
+    mod test_escape {
+        use super::escape;
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn single_line(path: String) {
+            prop_assert_eq!(escape(&path).lines().count(), usize::from(!path.is_empty()));
+        }
+
+        #[test]
+        fn special_characters() {
+            assert_eq!(escape("a\\b\nc\rd"), "a\\\\b\\nc\\rd");
+        }
+    }
+
+    /// Undo the escaping of a path from a plan file, see [`escape`].
+    fn unescape(path: &str) -> Option<String> {
+        let mut out = String::with_capacity(path.len());
+        let mut chars = path.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next()? {
+                    '\\' => out.push('\\'),
+                    'n' => out.push('\n'),
+                    'r' => out.push('\r'),
+                    _ => return None,
+                },
+                c => out.push(c),
+            }
+        }
+
+        Some(out)
+    }
+
+    /// Tests for the [`unescape`] function.
+    #[cfg(test)]
+    mod test_unescape {
+        use super::{escape, unescape};
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn escaped(path: String) {
+            prop_assert_eq!(unescape(&escape(&path)), Some(path));
+        }
+
+        #[test]
+        fn invalid() {
+            assert_eq!(unescape("\\"), None);
+            assert_eq!(unescape("\\t"), None);
+        }
+    }
+
+    /// Enum representing the command line tools this program can behave as.
+    #[cfg_attr(test, derive(Debug, Eq, PartialEq))]
+    pub enum Personality {
+        /// Behave as `rm(1)`, the default.
+        Rm,
+
+        /// Behave as `rmdir(1)`.
+        Rmdir,
+
+        /// Behave as `unlink(1)`.
+        Unlink,
+    }
+
+    /// Determine the [`Personality`] to use based on the name the program was invoked with, which
+    /// should be the first of the given `args`.
+    pub fn personality_of(args: &[String]) -> Personality {
+        let name = args.first().and_then(|program| Path::new(program).file_stem());
+        match name.and_then(OsStr::to_str) {
+            Some("rmdir") => Personality::Rmdir,
+            Some("unlink") => Personality::Unlink,
+            _ => Personality::Rm,
+        }
+    }
+
+    /// Tests for the [`personality_of`] function.
+    #[cfg(test)]
+    mod test_personality_of {
+        use super::{Personality, personality_of};
+
+        use std::path::MAIN_SEPARATOR_STR;
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[test]
+        fn no_args() {
+            assert_eq!(personality_of(&[]), Personality::Rm);
+        }
+
+        #[proptest]
+        fn rm(dir: String, args: Vec<String>) {
+            let program = ["rm", "rust-rm", "rm.exe", "unlinked", "rmdirs"];
+            for program in program {
+                let args = with_program(&dir, program, args.clone());
+                prop_assert_eq!(personality_of(&args), Personality::Rm);
+            }
+        }
+
+        #[proptest]
+        fn rmdir(dir: String, args: Vec<String>) {
+            for program in ["rmdir", "rmdir.exe"] {
+                let args = with_program(&dir, program, args.clone());
+                prop_assert_eq!(personality_of(&args), Personality::Rmdir);
+            }
+        }
+
+        #[proptest]
+        fn unlink(dir: String, args: Vec<String>) {
+            for program in ["unlink", "unlink.exe"] {
+                let args = with_program(&dir, program, args.clone());
+                prop_assert_eq!(personality_of(&args), Personality::Unlink);
+            }
+        }
+
+        /// Prefix the given `args` with the path to a program in the given directory.
+        fn with_program(dir: &str, program: &str, mut args: Vec<String>) -> Vec<String> {
+            let dir = dir.replace(MAIN_SEPARATOR_STR, "");
+            if dir.is_empty() {
+                args.insert(0, program.to_owned());
+            } else {
+                args.insert(0, format!("{dir}{MAIN_SEPARATOR_STR}{program}"));
+            }
+
+            args
+        }
+    }
+
+    /// Remove the DIRECTORY(ies), if they are empty.
+    ///
+    /// Removes by default, use the option --dry-run to only show what would be removed.
+    #[derive(Parser)]
+    #[allow(clippy::struct_excessive_bools, reason = "CLI options are not a state machine")]
+    #[command(name = "rmdir", version = None)]
+    #[command(about = "Remove the DIRECTORY(ies), if they are empty", long_about)]
+    pub struct RmdirArgs {
+        /// Only show what would be removed.
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+
+        /// Ignore each failure to remove a directory because it is not empty.
+        #[arg(short = None, long)]
+        ignore_fail_on_non_empty: bool,
+
+        /// Remove DIRECTORY and its ancestors.
+        ///
+        /// For example 'rmdir -p a/b' is similar to 'rmdir a/b a'.
+        #[arg(short = 'p', long)]
+        parents: bool,
+
+        /// Output a diagnostic for every directory processed.
+        #[arg(short = 'v', long)]
+        verbose: bool,
+
+        /// The directories to remove.
+        #[arg(required = true)]
+        dirs: Vec<OsString>,
+    }
+
+    /// Parse arguments for the CLI when behaving as `rmdir(1)`.
+    ///
+    /// # Errors
+    ///
+    /// If the given arguments couldn't be parsed.
+    pub fn parse_rmdir_args<T>(args: T) -> Result<RmdirArgs, Error>
+    where
+        T: IntoIterator<Item = String>,
+    {
+        RmdirArgs::try_parse_from(args)
+    }
+
+    /// Tests for the [`parse_rmdir_args`] function.
+    #[cfg(test)]
+    mod test_parse_rmdir_args {
+        use super::{RmdirArgs, parse_rmdir_args};
+
+        use clap::CommandFactory as _;
+
+        #[test]
+        fn clap_verification() {
+            RmdirArgs::command().debug_assert();
+        }
+
+        #[test]
+        fn dirs() {
+            let out = parse_rmdir_args(args(&["a", "b"])).expect("should parse");
+            assert_eq!(out.dirs, vec!["a", "b"]);
+        }
+
+        #[test]
+        fn no_dirs() {
+            assert!(parse_rmdir_args(args(&[])).is_err());
+            assert!(parse_rmdir_args(args(&["--parents"])).is_err());
+        }
+
+        #[test]
+        fn dry_run() {
+            for flag in ["-n", "--dry-run"] {
+                let out = parse_rmdir_args(args(&[flag, "a"])).expect("should parse");
+                assert!(out.dry_run);
+            }
+
+            let out = parse_rmdir_args(args(&["a"])).expect("should parse");
+            assert!(!out.dry_run);
+        }
+
+        #[test]
+        fn ignore_fail_on_non_empty() {
+            let out =
+                parse_rmdir_args(args(&["--ignore-fail-on-non-empty", "a"])).expect("should parse");
+            assert!(out.ignore_fail_on_non_empty);
+
+            let out = parse_rmdir_args(args(&["a"])).expect("should parse");
+            assert!(!out.ignore_fail_on_non_empty);
+        }
+
+        #[test]
+        fn parents() {
+            for flag in ["-p", "--parents"] {
+                let out = parse_rmdir_args(args(&[flag, "a"])).expect("should parse");
+                assert!(out.parents);
+            }
+
+            let out = parse_rmdir_args(args(&["a"])).expect("should parse");
+            assert!(!out.parents);
+        }
+
+        #[test]
+        fn verbose() {
+            for flag in ["-v", "--verbose"] {
+                let out = parse_rmdir_args(args(&[flag, "a"])).expect("should parse");
+                assert!(out.verbose);
+            }
+
+            let out = parse_rmdir_args(args(&["a"])).expect("should parse");
+            assert!(!out.verbose);
+        }
+
+        #[test]
+        fn rm_flags() {
+            for flag in ["--force", "--recursive", "--trash", "--blind"] {
+                assert!(parse_rmdir_args(args(&[flag, "a"])).is_err());
+            }
+        }
+
+        /// Create a list of arguments as `rmdir(1)` would receive them.
+        fn args(args: &[&str]) -> Vec<String> {
+            ["rmdir"].iter().chain(args).map(|arg| (*arg).to_owned()).collect()
+        }
+    }
+
+    /// Run the CLI as `rmdir(1)` with the given (parsed) arguments.
+    ///
+    /// See also [`parse_rmdir_args`].
+    ///
+    /// # Errors
+    ///
+    /// If there is a CLI runtime error.
+    pub fn run_rmdir(args: &RmdirArgs) -> Result<(), ()> {
+        configure_personality_logging(args.dry_run || args.verbose);
+
+        let removed = walk::Removed::default();
+        let transformers: walk::Transformers = Rc::new([
+            Box::new(transform::disallow_current_and_parent_dir),
+            Box::new(transform::disallow_root),
+            Box::new(transform::disallow_non_dirs),
+            if args.ignore_fail_on_non_empty {
+                transform::skip_filled_dirs_after(Rc::clone(&removed))
+            } else {
+                transform::disallow_filled_dirs_after(Rc::clone(&removed))
+            },
+            Box::new(transform::strip_tips),
+        ]);
+
+        let walk = if args.parents {
+            walk::with_ancestors(
+                walk::given(Rc::clone(&transformers)),
+                transformers,
+                Rc::clone(&removed),
+            )
+        } else {
+            walk::given(transformers)
+        };
+
+        let remove: rm::Remover = if args.dry_run {
+            record_removed(Box::new(rm::show_remove), removed)
+        } else {
+            Box::new(rm::remove)
+        };
+
+        let (_, errored) = remove_all(&args.dirs, &walk, &remove);
+        if errored > 0 { Err(()) } else { Ok(()) }
+    }
+
+    /// Call the unlink function to remove the specified FILE.
+    ///
+    /// Removes by default, use the option --dry-run to only show what would be removed.
+    #[derive(Parser)]
+    #[command(name = "unlink", version = None)]
+    #[command(about = "Call the unlink function to remove the specified FILE", long_about)]
+    pub struct UnlinkArgs {
+        /// Only show what would be removed.
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+
+        /// The file to remove.
+        file: OsString,
+    }
+
+    /// Parse arguments for the CLI when behaving as `unlink(1)`.
+    ///
+    /// # Errors
+    ///
+    /// If the given arguments couldn't be parsed.
+    pub fn parse_unlink_args<T>(args: T) -> Result<UnlinkArgs, Error>
+    where
+        T: IntoIterator<Item = String>,
+    {
+        UnlinkArgs::try_parse_from(args)
+    }
+
+    /// Tests for the [`parse_unlink_args`] function.
+    #[cfg(test)]
+    mod test_parse_unlink_args {
+        use super::{UnlinkArgs, parse_unlink_args};
+
+        use clap::CommandFactory as _;
+
+        #[test]
+        fn clap_verification() {
+            UnlinkArgs::command().debug_assert();
+        }
+
+        #[test]
+        fn one_file() {
+            let out = parse_unlink_args(args(&["a"])).expect("should parse");
+            assert_eq!(out.file, "a");
+        }
+
+        #[test]
+        fn no_file() {
+            assert!(parse_unlink_args(args(&[])).is_err());
+            assert!(parse_unlink_args(args(&["--dry-run"])).is_err());
+        }
+
+        #[test]
+        fn multiple_files() {
+            assert!(parse_unlink_args(args(&["a", "b"])).is_err());
+            assert!(parse_unlink_args(args(&["a", "b", "c"])).is_err());
+        }
+
+        #[test]
+        fn dry_run() {
+            for flag in ["-n", "--dry-run"] {
+                let out = parse_unlink_args(args(&[flag, "a"])).expect("should parse");
+                assert!(out.dry_run);
+            }
+
+            let out = parse_unlink_args(args(&["a"])).expect("should parse");
+            assert!(!out.dry_run);
+        }
+
+        #[test]
+        fn rm_flags() {
+            for flag in ["--force", "--recursive", "--dir", "--verbose"] {
+                assert!(parse_unlink_args(args(&[flag, "a"])).is_err());
+            }
+        }
+
+        /// Create a list of arguments as `unlink(1)` would receive them.
+        fn args(args: &[&str]) -> Vec<String> {
+            ["unlink"].iter().chain(args).map(|arg| (*arg).to_owned()).collect()
+        }
+    }
+
+    /// Run the CLI as `unlink(1)` with the given (parsed) arguments.
+    ///
+    /// See also [`parse_unlink_args`].
+    ///
+    /// # Errors
+    ///
+    /// If there is a CLI runtime error.
+    pub fn run_unlink(args: &UnlinkArgs) -> Result<(), ()> {
+        configure_personality_logging(args.dry_run);
+
+        let walk = walk::given(Rc::new([
+            Box::new(transform::disallow_current_and_parent_dir),
+            Box::new(transform::disallow_root),
+            Box::new(transform::disallow_all_dirs),
+            Box::new(transform::strip_tips),
+        ]));
+        let remove: rm::Remover = Box::new(if args.dry_run { rm::show_remove } else { rm::remove });
+
+        let (_, errored) = remove_all(slice::from_ref(&args.file), &walk, &remove);
+        if errored > 0 { Err(()) } else { Ok(()) }
+    }
+
+    /// Configure logging for a [`Personality`] other than `rm(1)`. These only output errors, unless
+    /// `verbose` (which includes dry runs).
+    fn configure_personality_logging(verbose: bool) {
+        logging::configure(&if verbose {
+            logging::Verbosity::Normal
+        } else {
+            logging::Verbosity::Quiet
+        });
+    }
+
+    /// Ask the user to confirm the removal of all paths at once if more than three paths are given
+    /// or if a directory is being removed recursively, see [`new_session`]. Returns `true` if the
+    /// user confirmed or if no confirmation is needed, `false` otherwise. Stops waiting for an
+    /// answer once `interrupted` is set.
+    #[cfg(feature = "bsd-mode")]
+    fn confirm_once(args: &Args, interrupted: &Arc<AtomicBool>) -> bool {
+        let recursive = args.recursive && args.paths.iter().any(|path| Path::new(path).is_dir());
+        if args.paths.len() <= 3 && !recursive {
+            return true;
+        }
+
+        let session = match new_session(args) {
+            Ok(session) => session.interruptible(Arc::clone(interrupted)),
+            Err(err) => {
+                error!("{err}");
+                return false;
+            },
+        };
+        transform::confirm(
+            &session,
+            &format!(
+                "Remove {}{}? [y/N] ",
+                lang::pluralize("argument", args.paths.len()),
+                if recursive { " recursively" } else { "" },
+            ),
+        )
+    }
+
+    /// Helpers for writing unit tests in or using this module.
+    #[cfg(test)]
+    mod test_helpers {
+        use super::{Clock, Vars};
+
+        use std::cell::Cell;
+        use std::time::{Duration, Instant};
+
+        use proptest::prelude::*;
+
+        /// A [`Clock`] for testing purposes, that only moves forward when it is slept on or when it
+        /// is explicitly advanced.
+        pub struct FakeClock {
+            /// The current point in time.
+            now: Cell<Instant>,
+            /// The total time slept.
+            slept: Cell<Duration>,
+        }
+
+        impl FakeClock {
+            /// Move the clock forward by the given [`Duration`] without sleeping.
+            pub fn advance(&self, duration: Duration) {
+                self.now.set(self.now.get().checked_add(duration).unwrap_or(self.now.get()));
+            }
+
+            /// Get the total time slept on this clock.
+            pub fn slept(&self) -> Duration {
+                self.slept.get()
+            }
+        }
+
+        impl Default for FakeClock {
+            fn default() -> Self {
+                Self { now: Cell::new(Instant::now()), slept: Cell::default() }
+            }
+        }
+
+        impl Clock for FakeClock {
+            fn now(&self) -> Instant {
+                self.now.get()
+            }
+
+            fn sleep(&self, duration: Duration) {
+                self.advance(duration);
+                self.slept.set(self.slept.get().saturating_add(duration));
+            }
+        }
+
+        /// Utility functionality for working with [`Vars`] in tests.
+        impl Vars {
+            /// Returns a copy of these [`Vars`] with [`Vars::bsd_mode`] set to false.
+            #[cfg(feature = "bsd-mode")]
+            pub fn without_bsd_mode(self) -> Self {
+                Self { bsd_mode: false, ..self }
+            }
+
+            /// Returns these [`Vars`] unchanged (because the "bsd-mode" feature is off).
+            #[cfg(not(feature = "bsd-mode"))]
+            pub fn without_bsd_mode(self) -> Self {
+                self
+            }
+
+            /// Check if [`Vars::gnu_mode`] is set to true.
+            #[cfg(feature = "gnu-mode")]
+            pub fn gnu_mode(self) -> bool {
+                self.gnu_mode
+            }
+
+            /// Always returns `false` (because the "gnu-mode" feature is off).
+            #[cfg(not(feature = "gnu-mode"))]
+            pub fn gnu_mode(self) -> bool {
+                false
+            }
+        }
+
+        /// The `Result` type for parsing args for tests.
+        pub type ParseResult = Result<super::Args, ()>;
+
+        /// Convenience wrapper to parse arguments using [`super::parse_args`] for testing purposes.
+        ///
+        /// # Errors
+        ///
+        /// If the given arguments couldn't be parsed.
+        ///
+        /// # Example
+        ///
+        /// ```no_run
+        /// use cli::Vars;
+        ///
+        /// let args = vec!["--foo", "bar"];
+        /// let vars = Vars { debug: false, gnu_mode: false };
+        /// let out = parse_args(args, vars);
+        /// assert!(out.is_err());
+        /// ```
+        pub fn parse_args(mut args: Vec<String>, vars: Vars) -> ParseResult {
+            args.insert(0, "rm".to_owned());
+            match super::parse_args(args, vars) {
+                Ok(args) => Ok(args),
+                Err(_) => Err(()),
+            }
+        }
+
+        /// Struct wrapping a [`String`] that implements [`Arbitrary`] to generate a valid argument
+        /// for the CLI.
+        #[derive(Clone, Debug)]
+        struct TestArg(String);
+
+        impl TestArg {
+            /// Returns the contained value, consuming the `self` value.
+            fn inner(self) -> String {
+                self.0
+            }
+        }
+
+        impl Arbitrary for TestArg {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                const KNOWN_FLAG_PATTERN: &str = "\
+                    --blind|-b|\
+                    --confirm-home|\
+                    --contents|\
+                    --dir|-d|\
+                    --edit|\
+                    --empty-dirs|\
+                    --empty-files|\
+                    --force|-f|\
+                    --force-recent|\
+                    --interactive|-i|\
+                    --no-details|\
+                    --no_preserver_root|\
+                    --one_file_system|\
+                    --one-file-system|-x|\
+                    --parents|-p|\
+                    --quiet|-q|\
+                    --recursive|-r|\
+                    --trash|-t|\
+                    --verbose|-v|\
+                    -I|-P|-W|\
+                    --\
+                ";
+                const NON_FLAG_PATTERN: &str = "[^-].*";
+
+                let strategies = vec![(1, KNOWN_FLAG_PATTERN), (10, NON_FLAG_PATTERN)];
+
+                prop::strategy::Union::new_weighted(strategies).prop_map(Self).boxed()
+            }
+        }
+
+        /// Struct wrapping a list of [`String`]s that implements [`Arbitrary`] to generate valid
+        /// lists of arguments for the CLI.
+        ///
+        /// See also [`TestArg`].
+        #[derive(Clone, Debug)]
+        pub struct TestArgs(Vec<String>);
+
+        impl TestArgs {
+            /// Returns `true` if the contained list contains the given value.
+            pub fn contains(&self, arg: &str) -> bool {
+                self.0.contains(&arg.to_owned())
+            }
+
+            /// Returns the contained value, consuming the `self` value.
+            pub fn inner(self) -> Vec<String> {
+                self.0
+            }
+        }
+
+        impl Arbitrary for TestArgs {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                let size_range = 1..=16;
+                prop::collection::vec(TestArg::arbitrary(), size_range)
+                    .prop_map(|v| Self(v.into_iter().map(TestArg::inner).collect()))
+                    .boxed()
+            }
+        }
+
+        /// Struct wrapping a list of [`String`]s that implements [`Arbitrary`] to generate valid
+        /// lists of arguments for the CLI. To use the contained value one more [`String`] has to be
+        /// inserted.
+        ///
+        /// See also [`TestArgs`].
+        #[derive(Clone, Debug)]
+        pub struct TestArgsAndIndex(Vec<String>, usize);
+
+        impl TestArgsAndIndex {
+            /// Returns the contained value with the given value at the associated index, consuming
+            /// the `self` value.
+            pub fn insert(self, arg: &str) -> Vec<String> {
+                let Self(mut args, index) = self;
+                args.insert(index, arg.to_owned());
+                args
+            }
+
+            /// Returns `true` if the given value occurs in the list of arguments, and `false`
+            /// otherwise.
+            pub fn contains(&self, val: &str) -> bool {
+                self.0.iter().any(|arg| arg == val)
+            }
+
+            /// Returns `true` if the given value occurs in the list of arguments before the
+            /// associated index, and `false` otherwise.
+            pub fn has_arg_before_index(&self, val: &str) -> bool {
+                self.0.iter().take(self.1).any(|arg| arg == val)
+            }
+        }
+
+        impl Arbitrary for TestArgsAndIndex {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                let size_range = 1..=16;
+                prop::collection::vec(TestArg::arbitrary(), size_range)
+                    .prop_flat_map(|vec| (0..vec.len(), Just(vec)))
+                    .prop_map(|(i, vec)| Self(vec.into_iter().map(TestArg::inner).collect(), i))
+                    .boxed()
+            }
+        }
+
+        /// Struct wrapping a [`String`]-based (key, value) pair that implements [`Arbitrary`] to
+        /// generate a valid environment variable for the CLI.
+        #[derive(Clone, Debug)]
+        struct TestVar((String, String));
+
+        impl TestVar {
+            /// Returns the contained value, consuming the `self` value.
+            fn inner(self) -> (String, String) {
+                self.0
+            }
+        }
+
+        impl Arbitrary for TestVar {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                const KNOWN_VAR_PATTERN: &str = "RUST_RM_BSD_MODE|RUST_RM_CONFIRM_COUNT|RUST_RM_CONFIRM_SIZE|RUST_RM_GNU_MODE|RUST_RM_MAX_COUNT|RUST_RM_PROTECT_RECENT|DEBUG";
+                const GENERAL_VAR_PATTERN: &str = "[a-zA-Z_]+";
+
+                let strategies = vec![(1, KNOWN_VAR_PATTERN), (10, GENERAL_VAR_PATTERN)];
+
+                (prop::strategy::Union::new_weighted(strategies), String::arbitrary())
+                    .prop_map(|(key, val)| TestVar((key, val)))
+                    .boxed()
+            }
+        }
+
+        /// Struct wrapping a list of [`String`]-based (key, value) pairs that implements
+        /// [`Arbitrary`] to generate valid environment variables for the CLI.
+        ///
+        /// See also [`TestVar`].
+        #[derive(Clone, Debug)]
+        pub struct TestVars(Vec<(String, String)>);
+
+        impl TestVars {
+            /// Returns `true` if the contained list contains the given key.
+            pub fn contains_key(&self, key: &str) -> bool {
+                self.0.iter().map(|(name, _)| name).any(|name| name == key)
+            }
+
+            /// Returns the contained value, consuming the `self` value.
+            pub fn inner(self) -> Vec<(String, String)> {
+                self.0
+            }
+        }
+
+        impl Arbitrary for TestVars {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                let size_range = 1..=16;
+                prop::collection::vec(TestVar::arbitrary(), size_range)
+                    .prop_map(|v| Self(v.into_iter().map(TestVar::inner).collect()))
+                    .boxed()
+            }
+        }
+
+        /// Struct wrapping a list of [`String`]-based (key, value) pairs that implements
+        /// [`Arbitrary`] to generate valid environment variables for the CLI. To use the contained
+        /// value one more pair has to be inserted.
+        ///
+        /// See also [`TestVars`].
+        #[derive(Clone, Debug)]
+        pub struct TestVarsAndIndex(Vec<(String, String)>, usize);
+
+        impl TestVarsAndIndex {
+            /// Returns the contained value with the given value at the associated index, consuming
+            /// the `self` value.
+            pub fn insert(self, env_var: (&str, &str)) -> Vec<(String, String)> {
+                let Self(mut vars, index) = self;
+                vars.insert(index, (env_var.0.to_owned(), env_var.1.to_owned()));
+                vars
+            }
+        }
+
+        impl Arbitrary for TestVarsAndIndex {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                let size_range = 1..=16;
+                prop::collection::vec(TestVar::arbitrary(), size_range)
+                    .prop_flat_map(|vec| (0..vec.len(), Just(vec)))
+                    .prop_map(|(i, vec)| Self(vec.into_iter().map(TestVar::inner).collect(), i))
+                    .boxed()
+            }
+        }
+    }
+}
+
+/// File system utilities.
+mod fs {
+    use std::collections::HashSet;
+    use std::error;
+    use std::ffi::OsString;
+    use std::fmt;
+    use std::fs::{File, Metadata, read_dir, symlink_metadata};
+    use std::io::{self, Read as _};
+    use std::path::{Path, PathBuf};
+    use std::result;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use log::trace;
+    use owo_colors::OwoColorize as _;
+
+    #[cfg(unix)]
+    use std::collections::BTreeMap;
+    #[cfg(target_os = "linux")]
+    use std::fs::{metadata, read_to_string};
+    #[cfg(target_os = "linux")]
+    use std::process;
+
+    #[cfg(test)]
+    use proptest_derive::Arbitrary;
+
+    /// The `Result` type for interacting with the file system.
+    pub type Result = result::Result<Entry, Error>;
+
+    /// Open a handle for a file system [`Entry`].
+    ///
+    /// # Errors
+    ///
+    /// If nothing is accessible at the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result {
+        #[cfg(unix)]
+        use std::os::unix::fs::FileTypeExt as _;
+
+        match symlink_metadata(&path) {
+            Ok(metadata) if metadata.is_file() => {
+                trace!("found file at {}", path.as_ref().display());
+                Ok(Entry::new(path, EntryKind::File))
+            },
+            Ok(metadata) if metadata.is_dir() => {
+                trace!("found directory at {}", path.as_ref().display());
+                Ok(Entry::new(path, EntryKind::Dir))
+            },
+            Ok(metadata) if metadata.is_symlink() => {
+                trace!("found symbolic link at {}", path.as_ref().display());
+                Ok(Entry::new(path, EntryKind::Symlink))
+            },
+            #[cfg(unix)]
+            Ok(metadata) if metadata.file_type().is_fifo() => {
+                trace!("found named pipe at {}", path.as_ref().display());
+                Ok(Entry::new(path, EntryKind::Fifo))
+            },
+            #[cfg(unix)]
+            Ok(metadata) if metadata.file_type().is_socket() => {
+                trace!("found socket at {}", path.as_ref().display());
+                Ok(Entry::new(path, EntryKind::Socket))
+            },
+            Err(err) => {
+                trace!("found nothing at {}", path.as_ref().display());
+                Err(Error::new(path, err.kind().into()))
+            },
+            Ok(_) => unreachable!(),
+        }
+    }
+
+    /// Tests for the [`open`] function.
+    #[cfg(test)]
+    mod test_open {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Entry, EntryKind, Error, ErrorKind, open};
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let path = file.path();
+
+                let out = open(path);
+                assert_eq!(out, Ok(Entry::new(path, EntryKind::File)));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let path = dir.path();
+
+                let out = open(path);
+                assert_eq!(out, Ok(Entry::new(path, EntryKind::Dir)));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&file)?;
+
+                let path = link.path();
+
+                let out = open(path);
+                assert_eq!(out, Ok(Entry::new(path, EntryKind::Symlink)));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn fifo() -> TestResult {
+            use std::process::Command;
+
+            with_test_dir(|test_dir| {
+                let path = test_dir.child("fifo");
+                Command::new("mkfifo").arg(path.path()).status()?;
+
+                let out = open(&path);
+                assert_eq!(out, Ok(Entry::new(path, EntryKind::Fifo)));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn socket() -> TestResult {
+            use std::os::unix::net::UnixListener;
+
+            with_test_dir(|test_dir| {
+                let path = test_dir.child("socket");
+                let _listener = UnixListener::bind(&path)?;
+
+                let out = open(&path);
+                assert_eq!(out, Ok(Entry::new(path, EntryKind::Socket)));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn not_found() -> TestResult {
+            with_test_dir(|test_dir| {
+                let path = test_dir.child("missing");
+
+                let out = open(&path);
+                assert_eq!(out, Err(Error::new(path, ErrorKind::NotFound)));
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Check if the [`Entry`] is an empty file or directory.
+    pub fn is_empty(entry: &Entry) -> bool {
+        match entry.kind() {
+            EntryKind::Dir => {
+                read_dir(entry.path()).map_or(true, |mut content| content.next().is_none())
+            },
+            EntryKind::File => File::open(entry.path())
+                .map_or(true, |mut f| f.read(&mut [0; 1]).map_or(true, |n| n == 0)),
+            EntryKind::Fifo | EntryKind::Socket | EntryKind::Symlink => true,
+        }
+    }
+
+    #[cfg(test)]
+    mod test_is_empty {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Entry, EntryKind, is_empty};
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn file_empty() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let path = file.path();
+
+                let entry = Entry::new(path, EntryKind::File);
+                assert!(is_empty(&entry));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn file_filled() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+
+                let path = file;
+
+                let entry = Entry::new(path, EntryKind::File);
+                assert!(!is_empty(&entry));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn missing() -> TestResult {
+            with_test_dir(|test_dir| {
+                let path = test_dir.child("missing");
+
+                let entry = Entry::new(path, EntryKind::File);
+                assert!(is_empty(&entry));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir_empty() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let path = dir.path();
+
+                let entry = Entry::new(path, EntryKind::Dir);
+                assert!(is_empty(&entry));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir_filled() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                dir.child("file").touch()?;
+
+                let path = dir.path();
+
+                let entry = Entry::new(path, EntryKind::Dir);
+                assert!(!is_empty(&entry));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir_missing() -> TestResult {
+            with_test_dir(|test_dir| {
+                let path = test_dir.child("missing");
+
+                let entry = Entry::new(path, EntryKind::Dir);
+                assert!(is_empty(&entry));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_empty_file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&file)?;
+
+                let path = link.path();
+
+                let entry = Entry::new(path, EntryKind::Symlink);
+                assert!(is_empty(&entry));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_filled_file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&file)?;
+
+                let path = link.path();
+
+                let entry = Entry::new(path, EntryKind::Symlink);
+                assert!(is_empty(&entry));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_empty_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&dir)?;
+
+                let path = link.path();
+
+                let entry = Entry::new(path, EntryKind::Symlink);
+                assert!(is_empty(&entry));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_filled_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                dir.child("file").touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&dir)?;
+
+                let path = link.path();
+
+                let entry = Entry::new(path, EntryKind::Symlink);
+                assert!(is_empty(&entry));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn symlink_missing() -> TestResult {
+            with_test_dir(|test_dir| {
+                let path = test_dir.child("missing");
+
+                let entry = Entry::new(path, EntryKind::Symlink);
+                assert!(is_empty(&entry));
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Check if the given [`Entry`] is empty, see [`is_empty`], disregarding the contents of a
+    /// directory that are `removed`. Useful to check if a directory would be empty once something
+    /// is removed, without actually removing it.
+    pub fn is_empty_after(entry: &Entry, removed: &HashSet<PathBuf>) -> bool {
+        if entry.kind() != EntryKind::Dir {
+            return is_empty(entry);
+        }
+
+        read_dir(entry.path()).map_or(true, |mut contents| {
+            contents.all(|child| child.is_ok_and(|child| removed.contains(&child.path())))
+        })
+    }
+
+    /// Tests for the [`is_empty_after`] function.
+    #[cfg(test)]
+    mod test_is_empty_after {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Entry, EntryKind, is_empty_after};
+
+        use std::collections::HashSet;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn dir_empty() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let entry = Entry::new(dir.path(), EntryKind::Dir);
+                assert!(is_empty_after(&entry, &HashSet::new()));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir_filled_all_removed() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file1").touch()?;
+                dir.child("file2").touch()?;
+
+                let removed = HashSet::from([
+                    dir.child("file1").path().to_owned(),
+                    dir.child("file2").path().to_owned(),
+                ]);
+
+                let entry = Entry::new(dir.path(), EntryKind::Dir);
+                assert!(is_empty_after(&entry, &removed));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir_filled_some_removed() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file1").touch()?;
+                dir.child("file2").touch()?;
+
+                let removed = HashSet::from([dir.child("file1").path().to_owned()]);
+
+                let entry = Entry::new(dir.path(), EntryKind::Dir);
+                assert!(!is_empty_after(&entry, &removed));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn file_filled() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+
+                let removed = HashSet::from([file.path().to_owned()]);
+
+                let entry = Entry::new(file.path(), EntryKind::File);
+                assert!(!is_empty_after(&entry, &removed));
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Get an identifier for the file system the given [`Path`] is on, if it can be determined.
+    #[cfg(unix)]
+    pub fn file_system_of<P: AsRef<Path>>(path: P) -> Option<u64> {
+        use std::os::unix::fs::MetadataExt as _;
+
+        symlink_metadata(path).ok().map(|metadata| metadata.dev())
+    }
+
+    /// Get an identifier for the file system the given [`Path`] is on, which is never possible on
+    /// this platform.
+    #[cfg(not(unix))]
+    pub fn file_system_of<P: AsRef<Path>>(_: P) -> Option<u64> {
+        None
+    }
+
+    /// Tests for the [`file_system_of`] function.
+    #[cfg(test)]
+    mod test_file_system_of {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::file_system_of;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        #[cfg(unix)]
+        fn same_file_system() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                assert!(file_system_of(&file).is_some());
+                assert_eq!(file_system_of(&file), file_system_of(&dir));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn missing() -> TestResult {
+            with_test_dir(|test_dir| {
+                let path = test_dir.child("missing");
+
+                assert_eq!(file_system_of(&path), None);
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Get the device and inode numbers identifying the file at the given [`Path`], if they can be
+    /// determined.
+    #[cfg(unix)]
+    pub fn file_id_of<P: AsRef<Path>>(path: P) -> Option<(u64, u64)> {
+        use std::os::unix::fs::MetadataExt as _;
+
+        symlink_metadata(path).ok().map(|metadata| (metadata.dev(), metadata.ino()))
+    }
+
+    /// Get the number of other hard links to the [`Entry`], if it can be determined. Always `None`
+    /// for directories.
+    #[cfg(unix)]
+    pub fn other_links_of(entry: &Entry) -> Option<u64> {
+        use std::os::unix::fs::MetadataExt as _;
+
+        symlink_metadata(entry.path())
+            .ok()
+            .filter(|metadata| !metadata.is_dir())
+            .map(|metadata| metadata.nlink().saturating_sub(1))
+    }
+
+    /// Get the number of other hard links to the [`Entry`], which is never possible on this
+    /// platform.
+    #[cfg(not(unix))]
+    pub fn other_links_of(_: &Entry) -> Option<u64> {
+        None
+    }
+
+    /// Find the hard links in the directory at the given [`Path`], recursively, to files that have
+    /// more than one. Returns the paths of the links by the identifier of their file, see
+    /// [`file_id_of`]. Symbolic links are not followed and contents that can't be read are ignored.
+    #[cfg(unix)]
+    pub fn hard_links_in(dir: &Path) -> BTreeMap<(u64, u64), Vec<PathBuf>> {
+        use std::os::unix::fs::MetadataExt as _;
+
+        let mut links: BTreeMap<(u64, u64), Vec<PathBuf>> = BTreeMap::new();
+        let mut dirs = vec![dir.to_owned()];
+        while let Some(dir) = dirs.pop() {
+            for child in read_dir(dir).into_iter().flatten().flatten() {
+                let Ok(metadata) = symlink_metadata(child.path()) else {
+                    continue;
+                };
+                if metadata.is_dir() {
+                    dirs.push(child.path());
+                } else if metadata.nlink() > 1 {
+                    links.entry((metadata.dev(), metadata.ino())).or_default().push(child.path());
+                }
+            }
+        }
+
+        links
+    }
+
+    /// Tests for the [`file_id_of`], [`other_links_of`] and [`hard_links_in`] functions.
+    #[cfg(test)]
+    #[cfg(unix)]
+    mod test_links {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{file_id_of, hard_links_in, other_links_of, test_helpers};
+
+        use std::fs::hard_link;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn single_link() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                assert_eq!(other_links_of(&test_helpers::new_file(file.path())), Some(0));
+                assert!(hard_links_in(test_dir.path()).is_empty());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn multiple_links() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let link = test_dir.child("dir").child("link");
+                test_dir.child("dir").create_dir_all()?;
+                hard_link(&file, &link)?;
+
+                assert_eq!(other_links_of(&test_helpers::new_file(file.path())), Some(1));
+                assert_eq!(file_id_of(&file), file_id_of(&link));
+
+                let links = hard_links_in(test_dir.path());
+                let Some(id) = file_id_of(&file) else {
+                    panic!("expected a file identifier");
+                };
+                let mut paths = links.get(&id).cloned().unwrap_or_default();
+                paths.sort();
+                assert_eq!(paths, vec![link.to_path_buf(), file.to_path_buf()]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                assert_eq!(other_links_of(&test_helpers::new_dir(dir.path())), None);
+                assert_eq!(other_links_of(&test_helpers::new_file(dir.path())), None);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn missing() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("missing");
+
+                assert_eq!(file_id_of(&file), None);
+                assert_eq!(other_links_of(&test_helpers::new_file(file.path())), None);
+                assert!(hard_links_in(file.path()).is_empty());
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Enum representing the kinds of timestamps of a file system [`Entry`].
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub enum Timestamp {
+        /// The time the entry was last accessed.
+        Accessed,
+        /// The time the entry's status last changed, or when it was created on non-Unix systems.
+        Changed,
+        /// The time the entry was last modified.
+        Modified,
+    }
+
+    /// Get the given kind of [`Timestamp`] of the [`Entry`], if it can be determined.
+    pub fn timestamp_of(entry: &Entry, timestamp: Timestamp) -> Option<SystemTime> {
+        let metadata = symlink_metadata(entry.path()).ok()?;
+        match timestamp {
+            Timestamp::Accessed => metadata.accessed().ok(),
+            Timestamp::Changed => changed(&metadata),
+            Timestamp::Modified => metadata.modified().ok(),
+        }
+    }
+
+    /// Get the time of the last status change from the given [`Metadata`].
+    #[cfg(unix)]
+    fn changed(metadata: &Metadata) -> Option<SystemTime> {
+        use std::os::unix::fs::MetadataExt as _;
+
+        let secs = u64::try_from(metadata.ctime()).ok()?;
+        let nanos = u32::try_from(metadata.ctime_nsec()).ok()?;
+        UNIX_EPOCH.checked_add(Duration::new(secs, nanos))
+    }
+
+    /// Get the creation time from the given [`Metadata`], which is the closest equivalent of the
+    /// time of the last status change on this platform.
+    #[cfg(not(unix))]
+    fn changed(metadata: &Metadata) -> Option<SystemTime> {
+        metadata.created().ok()
+    }
+
+    /// Tests for the [`timestamp_of`] function.
+    #[cfg(test)]
+    mod test_timestamp_of {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use crate::test_helpers::unix_time;
+
+        use super::{Entry, EntryKind, Timestamp, timestamp_of};
+
+        use std::fs::{File, FileTimes};
+        use std::time::{Duration, SystemTime};
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn accessed_and_modified() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let accessed = unix_time(1_000_000);
+                let modified = unix_time(2_000_000);
+                let times = FileTimes::new().set_accessed(accessed).set_modified(modified);
+                File::options().write(true).open(&file)?.set_times(times)?;
+
+                let entry = Entry::new(file.path(), EntryKind::File);
+                assert_eq!(timestamp_of(&entry, Timestamp::Accessed), Some(accessed));
+                assert_eq!(timestamp_of(&entry, Timestamp::Modified), Some(modified));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn changed() -> TestResult {
+            with_test_dir(|test_dir| {
+                let before = SystemTime::now().checked_sub(Duration::from_secs(60));
+
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let entry = Entry::new(file.path(), EntryKind::File);
+                assert!(timestamp_of(&entry, Timestamp::Changed) > before);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn missing() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("missing");
+
+                let entry = Entry::new(file.path(), EntryKind::File);
+                assert_eq!(timestamp_of(&entry, Timestamp::Modified), None);
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Get the size in bytes of the [`Entry`], if it can be determined.
+    pub fn size_of(entry: &Entry) -> Option<u64> {
+        symlink_metadata(entry.path()).ok().map(|metadata| metadata.len())
+    }
+
+    /// Tests for the [`size_of`] function.
+    #[cfg(test)]
+    mod test_size_of {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Entry, EntryKind, size_of};
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+
+                let entry = Entry::new(file.path(), EntryKind::File);
+                assert_eq!(size_of(&entry), Some(12));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn missing() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("missing");
+
+                let entry = Entry::new(file.path(), EntryKind::File);
+                assert_eq!(size_of(&entry), None);
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Struct summarizing the contents of a directory [`Entry`], see [`contents_of`].
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct Contents {
+        /// The number of entries in the directory, recursively.
+        pub entries: usize,
+
+        /// The total size in bytes of all regular files in the directory, recursively.
+        pub size: u64,
+
+        /// Whether counting stopped before all entries were seen.
+        pub capped: bool,
+    }
+
+    /// Summarize the [`Contents`] of the directory [`Entry`], recursively, looking at no more than
+    /// `limit` entries. Contents that can't be read are not counted.
+    pub fn contents_of(entry: &Entry, limit: usize) -> Contents {
+        let mut contents = Contents { entries: 0, size: 0, capped: false };
+        let mut dirs = vec![entry.path()];
+        while let Some(dir) = dirs.pop() {
+            for child in read_dir(dir).into_iter().flatten().flatten() {
+                if contents.entries >= limit {
+                    contents.capped = true;
+                    return contents;
+                }
+
+                let Ok(metadata) = symlink_metadata(child.path()) else {
+                    continue;
+                };
+                contents.entries = contents.entries.saturating_add(1);
+                if metadata.is_dir() {
+                    dirs.push(child.path());
+                } else if metadata.is_file() {
+                    contents.size = contents.size.saturating_add(metadata.len());
+                }
+            }
+        }
+
+        contents
+    }
+
+    /// Tests for the [`contents_of`] function.
+    #[cfg(test)]
+    mod test_contents_of {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Contents, Entry, EntryKind, contents_of};
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn dir_empty() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let entry = Entry::new(dir.path(), EntryKind::Dir);
+                assert_eq!(
+                    contents_of(&entry, 10),
+                    Contents { entries: 0, size: 0, capped: false }
+                );
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir_filled() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file").write_str("Hello world!")?;
+                dir.child("nested_dir").child("file").write_str("Hello")?;
+
+                let entry = Entry::new(dir.path(), EntryKind::Dir);
+                assert_eq!(
+                    contents_of(&entry, 10),
+                    Contents { entries: 3, size: 17, capped: false }
+                );
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn limit() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file1").touch()?;
+                dir.child("file2").touch()?;
+                dir.child("file3").touch()?;
+
+                let entry = Entry::new(dir.path(), EntryKind::Dir);
+                assert_eq!(contents_of(&entry, 2), Contents { entries: 2, size: 0, capped: true });
+                assert_eq!(contents_of(&entry, 3), Contents { entries: 3, size: 0, capped: false });
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn missing() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("missing");
+
+                let entry = Entry::new(dir.path(), EntryKind::Dir);
+                assert_eq!(
+                    contents_of(&entry, 10),
+                    Contents { entries: 0, size: 0, capped: false }
+                );
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Struct representing a process that holds a file open, see [`OpenFiles`].
+    #[cfg(target_os = "linux")]
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct Holder {
+        /// The ID of the process.
+        pub pid: u32,
+
+        /// The name of the process' command.
+        pub name: String,
+    }
+
+    #[cfg(target_os = "linux")]
+    impl fmt::Display for Holder {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}[{}]", self.name, self.pid)
+        }
+    }
+
+    /// Struct representing the files held open by running processes at one point in time.
+    #[cfg(target_os = "linux")]
+    #[derive(Debug, Default)]
+    pub struct OpenFiles {
+        /// The processes holding a file open, by the device and inode number of the file.
+        holders: BTreeMap<(u64, u64), Vec<Holder>>,
+    }
+
+    #[cfg(target_os = "linux")]
+    impl OpenFiles {
+        /// Find the files held open by running processes, other than this one, by scanning the
+        /// file descriptors in `/proc/*/fd`. Processes that can't be inspected are ignored.
+        pub fn scan() -> Self {
+            use std::os::unix::fs::MetadataExt as _;
+
+            let mut open_files = Self::default();
+            for process in read_dir("/proc").into_iter().flatten().flatten() {
+                let Some(pid) = process.file_name().to_str().and_then(|pid| pid.parse().ok())
+                else {
+                    continue;
+                };
+                if pid == process::id() {
+                    continue;
+                }
+
+                let path = process.path();
+                let name = read_to_string(path.join("comm")).unwrap_or_default().trim().to_owned();
+                for fd in read_dir(path.join("fd")).into_iter().flatten().flatten() {
+                    let Ok(metadata) = metadata(fd.path()) else {
+                        continue;
+                    };
+                    let holders =
+                        open_files.holders.entry((metadata.dev(), metadata.ino())).or_default();
+                    if !holders.iter().any(|holder| holder.pid == pid) {
+                        holders.push(Holder { pid, name: name.clone() });
+                    }
+                }
+            }
+
+            open_files
+        }
+
+        /// Get the processes holding the given [`Entry`] open.
+        pub fn holders_of(&self, entry: &Entry) -> &[Holder] {
+            use std::os::unix::fs::MetadataExt as _;
+
+            symlink_metadata(entry.path())
+                .ok()
+                .and_then(|metadata| self.holders.get(&(metadata.dev(), metadata.ino())))
+                .map_or(&[], Vec::as_slice)
+        }
+    }
+
+    /// Tests for the [`OpenFiles`] struct.
+    #[cfg(test)]
+    #[cfg(target_os = "linux")]
+    mod test_open_files {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Holder, OpenFiles, test_helpers};
+
+        use std::fs::File;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn held_open() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let mut child = test_helpers::hold_open(&file)?;
+
+                let open_files = OpenFiles::scan();
+                child.kill()?;
+                child.wait()?;
+
+                let entry = test_helpers::new_file(file.path());
+                assert_eq!(
+                    open_files.holders_of(&entry),
+                    [Holder { pid: child.id(), name: "sleep".to_owned() }]
+                );
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn not_held_open() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let _handle = File::open(&file)?;
+
+                let entry = test_helpers::new_file(file.path());
+                assert_eq!(OpenFiles::scan().holders_of(&entry), []);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn display_holder() {
+            let holder = Holder { pid: 1234, name: "nginx".to_owned() };
+            assert_eq!(holder.to_string(), "nginx[1234]");
+        }
+    }
+
+    /// Get the ID of the user that owns the [`Entry`], if it can be determined.
+    #[cfg(unix)]
+    pub fn user_of(entry: &Entry) -> Option<u32> {
+        use std::os::unix::fs::MetadataExt as _;
+
+        symlink_metadata(entry.path()).ok().map(|metadata| metadata.uid())
+    }
+
+    /// Get the ID of the group that owns the [`Entry`], if it can be determined.
+    #[cfg(unix)]
+    pub fn group_of(entry: &Entry) -> Option<u32> {
+        use std::os::unix::fs::MetadataExt as _;
+
+        symlink_metadata(entry.path()).ok().map(|metadata| metadata.gid())
+    }
+
+    /// Tests for the [`user_of`] and [`group_of`] functions.
+    #[cfg(all(test, unix))]
+    mod test_user_of_and_group_of {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Entry, EntryKind, group_of, user_of};
+
+        use std::os::unix::fs::MetadataExt as _;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let metadata = test_dir.path().metadata()?;
+
+                let entry = Entry::new(file.path(), EntryKind::File);
+                assert_eq!(user_of(&entry), Some(metadata.uid()));
+                assert_eq!(group_of(&entry), Some(metadata.gid()));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn missing() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("missing");
+
+                let entry = Entry::new(file.path(), EntryKind::File);
+                assert_eq!(user_of(&entry), None);
+                assert_eq!(group_of(&entry), None);
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Struct representing the identity and metadata of an [`Entry`] at some point in time.
+    #[derive(Eq, PartialEq)]
+    #[cfg_attr(test, derive(Arbitrary, Clone, Debug))]
+    pub struct Snapshot {
+        /// The kind of the [`Entry`].
+        kind: EntryKind,
+
+        /// The identifier of the device the [`Entry`] is on, or 0 if unknown.
+        device: u64,
+
+        /// The inode number of the [`Entry`], or 0 if unknown.
+        inode: u64,
+
+        /// The size of the [`Entry`] in bytes.
+        size: u64,
+
+        /// The time of the last modification of the [`Entry`], relative to the Unix epoch.
+        modified: Duration,
+    }
+
+    impl Snapshot {
+        /// Parse a [`Snapshot`] from its textual representation, see [`fmt::Display`].
+        pub fn parse(s: &str) -> Option<Self> {
+            let mut fields = s.split('\t');
+            let kind = match fields.next()? {
+                "dir" => EntryKind::Dir,
+                "fifo" => EntryKind::Fifo,
+                "file" => EntryKind::File,
+                "socket" => EntryKind::Socket,
+                "symlink" => EntryKind::Symlink,
+                _ => return None,
+            };
+            let device = fields.next()?.parse().ok()?;
+            let inode = fields.next()?.parse().ok()?;
+            let size = fields.next()?.parse().ok()?;
+            let (secs, nanos) = fields.next()?.split_once('.')?;
+            if nanos.len() != 9 || fields.next().is_some() {
+                return None;
+            }
+
+            let modified = Duration::new(secs.parse().ok()?, nanos.parse().ok()?);
+            Some(Self { kind, device, inode, size, modified })
+        }
+    }
+
+    impl fmt::Display for Snapshot {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let kind = match self.kind {
+                EntryKind::Dir => "dir",
+                EntryKind::Fifo => "fifo",
+                EntryKind::File => "file",
+                EntryKind::Socket => "socket",
+                EntryKind::Symlink => "symlink",
+            };
+            write!(
+                f,
+                "{kind}\t{}\t{}\t{}\t{}.{:09}",
+                self.device,
+                self.inode,
+                self.size,
+                self.modified.as_secs(),
+                self.modified.subsec_nanos(),
+            )
+        }
+    }
+
+    /// Tests for the [`Snapshot`] struct.
+    #[cfg(test)]
+    mod test_snapshot {
+        use super::{EntryKind, Snapshot};
+
+        use std::time::Duration;
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn parse_display(snapshot: Snapshot) {
+            prop_assert_eq!(Snapshot::parse(&snapshot.to_string()), Some(snapshot));
+        }
+
+        #[test]
+        fn display() {
+            let snapshot = Snapshot {
+                kind: EntryKind::File,
+                device: 1,
+                inode: 2,
+                size: 3,
+                modified: Duration::new(4, 5),
+            };
+            assert_eq!(snapshot.to_string(), "file\t1\t2\t3\t4.000000005");
+        }
+
+        #[test]
+        fn parse_invalid() {
+            assert_eq!(Snapshot::parse(""), None);
+            assert_eq!(Snapshot::parse("door\t1\t2\t3\t4.000000005"), None);
+            assert_eq!(Snapshot::parse("file\t1\t2\t3"), None);
+            assert_eq!(Snapshot::parse("file\t1\t2\t3\t4"), None);
+            assert_eq!(Snapshot::parse("file\t1\t2\t3\t4.5"), None);
+            assert_eq!(Snapshot::parse("file\t1\t2\tmany\t4.000000005"), None);
+            assert_eq!(Snapshot::parse("file\t1\t2\t3\t4.000000005\tpath"), None);
+        }
+    }
+
+    /// Take a [`Snapshot`] of the given [`Entry`], if its metadata can be read.
+    pub fn snapshot_of(entry: &Entry) -> Option<Snapshot> {
+        let metadata = symlink_metadata(entry.path()).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        let (device, inode) = identity_of(&metadata);
+        Some(Snapshot { kind: entry.kind(), device, inode, size: metadata.len(), modified })
+    }
+
+    /// Get the device and inode numbers from the given [`Metadata`].
+    #[cfg(unix)]
+    pub fn identity_of(metadata: &Metadata) -> (u64, u64) {
+        use std::os::unix::fs::MetadataExt as _;
+
+        (metadata.dev(), metadata.ino())
+    }
+
+    /// Get the device and inode numbers from the given [`Metadata`], which is not possible on this
+    /// platform.
+    #[cfg(not(unix))]
+    pub fn identity_of(_: &Metadata) -> (u64, u64) {
+        (0, 0)
+    }
+
+    /// Tests for the [`snapshot_of`] function.
+    #[cfg(test)]
+    mod test_snapshot_of {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Entry, EntryKind, snapshot_of};
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+
+                let entry = Entry::new(file.path(), EntryKind::File);
+                let snapshot = snapshot_of(&entry).ok_or("should be readable")?;
+                assert!(snapshot.kind == EntryKind::File);
+                assert_eq!(snapshot.size, 12);
+                assert!(snapshot_of(&entry) == Some(snapshot));
+
+                file.write_str("Hello")?;
+                assert!(snapshot_of(&entry).is_some_and(|other| other.size == 5));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn missing() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("missing");
+
+                let entry = Entry::new(file.path(), EntryKind::File);
+                assert!(snapshot_of(&entry).is_none());
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Open a handle for a file system [`Entry`] if it still matches the given [`Snapshot`].
+    ///
+    /// # Errors
+    ///
+    /// If nothing is accessible at the given path or the [`Entry`] no longer matches.
+    pub fn open_unchanged<P: AsRef<Path>>(path: P, snapshot: &Snapshot) -> Result {
+        let entry = open(path)?;
+        if snapshot_of(&entry).as_ref() == Some(snapshot) {
+            Ok(entry)
+        } else {
+            Err(entry.into_err(ErrorKind::Refused).with_tip("changed since the plan was made"))
+        }
+    }
+
+    /// Tests for the [`open_unchanged`] function.
+    #[cfg(test)]
+    mod test_open_unchanged {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Entry, EntryKind, Error, ErrorKind, open, open_unchanged, snapshot_of};
+
+        use std::fs::remove_file;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn unchanged() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let snapshot = snapshot_of(&open(&file)?).ok_or("should be readable")?;
+                assert_eq!(open_unchanged(&file, &snapshot), open(&file));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn changed() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let snapshot = snapshot_of(&open(&file)?).ok_or("should be readable")?;
+                file.write_str("Hello world!")?;
+                assert_eq!(
+                    open_unchanged(&file, &snapshot),
+                    Err(Error::new(file.path(), ErrorKind::Refused)
+                        .with_tip("changed since the plan was made"))
+                );
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn missing() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let entry = Entry::new(file.path(), EntryKind::File);
+                let snapshot = snapshot_of(&entry).ok_or("should be readable")?;
+                remove_file(&file)?;
+                assert_eq!(open_unchanged(&file, &snapshot), open(&file));
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Struct representing a file system entry.
+    #[cfg_attr(test, derive(Arbitrary, Clone, Debug, Eq, PartialEq))]
+    pub struct Entry {
+        /// The kind of file system entry.
+        kind: EntryKind,
+
+        /// The path to the file system entry.
+        path: OsString,
+    }
+
+    impl Entry {
+        /// Create a new [`Entry`].
+        fn new<P: AsRef<Path>>(path: P, kind: EntryKind) -> Self {
+            Self { kind, path: path.as_ref().as_os_str().to_owned() }
+        }
+
+        /// Convert the [`Entry`] into an [`Error`] for the [`Entry`]'s path with the given
+        /// [`ErrorKind`].
+        pub fn into_err(self, kind: ErrorKind) -> Error {
+            Error::new(self.path(), kind)
+        }
+
+        /// Returns `true` if the [`Entry`] is a directory.
+        pub fn is_dir(&self) -> bool {
+            matches!(self.kind, EntryKind::Dir)
+        }
+
+        /// Get the kind of the [`Entry`].
+        pub fn kind(&self) -> EntryKind {
+            self.kind.clone()
+        }
+
+        /// Get the path to the [`Entry`].
+        pub fn path(&self) -> PathBuf {
+            Path::new(&self.path).to_owned()
+        }
+    }
+
+    impl fmt::Display for Entry {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.path().display())
+        }
+    }
+
+    /// Enum representing the kind of a file system [`Entry`].
+    #[derive(Clone, Eq, PartialEq)]
+    #[cfg_attr(test, derive(Arbitrary, Debug))]
+    pub enum EntryKind {
+        /// An [`Entry`] that is a directory.
+        Dir,
+
+        /// An [`Entry`] that is a named pipe.
+        Fifo,
+
+        /// An [`Entry`] that is a file.
+        File,
+
+        /// An [`Entry`] that is a socket.
+        Socket,
+
+        /// An [`Entry`] that is a symbolic link.
+        Symlink,
+    }
+
+    /// Tests for the [`Entry`] struct.
+    #[cfg(test)]
+    mod test_entry {
+        use super::{Entry, EntryKind, Error, ErrorKind};
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn new(path: String, kind: EntryKind) {
+            prop_assert_eq!(Entry::new(&path, kind.clone()), Entry { kind, path: path.into() });
+        }
+
+        #[proptest]
+        fn display(entry: Entry) {
+            prop_assert_eq!(entry.to_string(), format!("{}", entry.path().display()));
+        }
+
+        #[proptest]
+        fn into_err(entry: Entry, err_kind: ErrorKind) {
+            let path = entry.path.clone();
+
+            let err = entry.into_err(err_kind.clone());
+            prop_assert_eq!(err, Error { kind: err_kind, path, tip: None });
+        }
+
+        #[proptest]
+        fn is_dir(entry: Entry) {
+            prop_assert_eq!(entry.is_dir(), matches!(entry.kind, EntryKind::Dir));
+        }
+
+        #[proptest]
+        fn kind(entry: Entry) {
+            prop_assert_eq!(entry.kind(), entry.kind);
+        }
+
+        #[proptest]
+        fn path(entry: Entry) {
+            prop_assert_eq!(entry.path(), entry.path);
+        }
+    }
+
+    /// Struct representing a file system error.
+    #[derive(Debug)]
+    #[cfg_attr(test, derive(Arbitrary, Clone, Eq, PartialEq))]
+    pub struct Error {
+        /// The kind of error that occurred.
+        kind: ErrorKind,
+
+        /// The path for which the error occurred.
+        path: OsString,
+
+        /// A tip to deal with the error, if any.
+        tip: Option<String>,
+    }
+
+    impl Error {
+        /// Create a new [`Error`] with a given `path` and [`ErrorKind`].
+        fn new<P: AsRef<Path>>(path: P, kind: ErrorKind) -> Self {
+            Self { kind, path: path.as_ref().as_os_str().to_owned(), tip: None }
+        }
+
+        /// Get the kind of the [`Error`].
+        pub fn kind(&self) -> ErrorKind {
+            self.kind.clone()
+        }
+
+        /// Get the file system path this [`Error`] is associated with.
+        pub fn path(&self) -> PathBuf {
+            Path::new(&self.path).to_owned()
+        }
+
+        /// Convert this [`Error`] into an [`Error`] with the provided tip associated to it.
+        pub fn with_tip(mut self, tip: &str) -> Self {
+            self.tip = Some(tip.to_owned());
+            self
+        }
+
+        /// Convert this [`Error`] into an [`Error`] without any tip associated to it.
+        pub fn without_tip(mut self) -> Self {
+            self.tip = None;
+            self
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            if let Some(tip) = &self.tip {
+                write!(
+                    f,
+                    "Cannot remove {}: {} {}",
+                    self.path().display().bold(),
+                    self.kind,
+                    format!("({tip})").italic()
+                )
+            } else {
+                write!(f, "Cannot remove {}: {}", self.path().display().bold(), self.kind)
+            }
+        }
+    }
+
+    impl error::Error for Error {}
+
+    /// Tests for the [`Error`] struct.
+    #[cfg(test)]
+    mod test_error {
+        use super::{Error, ErrorKind};
+
+        use owo_colors::OwoColorize as _;
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn new(path: String, kind: ErrorKind) {
+            let err = Error::new(&path, kind.clone());
+            prop_assert_eq!(err, Error { kind, path: path.into(), tip: None });
+        }
+
+        #[proptest]
+        fn display_with_tip(err: Error) {
+            prop_assume!(err.tip.is_some());
+
+            prop_assert_eq!(
+                err.to_string(),
+                format!(
+                    "Cannot remove {}: {} {}",
+                    err.path().display().bold(),
+                    err.kind(),
+                    format!("({})", err.tip.expect("is_some() should be asserted")).italic(),
+                )
+            );
+        }
+
+        #[proptest]
+        fn display_without_tip(err: Error) {
+            prop_assume!(err.tip.is_none());
+
+            prop_assert_eq!(
+                err.to_string(),
+                format!("Cannot remove {}: {}", err.path().display().bold(), err.kind())
+            );
+        }
+
+        #[proptest]
+        fn kind(err: Error) {
+            prop_assert_eq!(err.kind(), err.kind);
+        }
+
+        #[proptest]
+        fn path(err: Error) {
+            prop_assert_eq!(err.path(), err.path);
+        }
+
+        #[proptest]
+        fn with_tip(err: Error, tip: String) {
+            let kind = err.kind();
+            let path = err.path();
+
+            prop_assert_eq!(err.with_tip(&tip), Error { kind, path: path.into(), tip: Some(tip) });
+        }
+
+        #[proptest]
+        fn without_tip(err: Error) {
+            let kind = err.kind();
+            let path = err.path();
+
+            prop_assert_eq!(err.without_tip(), Error { kind, path: path.into(), tip: None });
+        }
+    }
+
+    /// Enum representing kinds of file system [`Error`]s.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    #[cfg_attr(test, derive(Arbitrary))]
+    pub enum ErrorKind {
+        /// This kind corresponds to an error due to a directory not being empty.
+        DirectoryNotEmpty,
+
+        /// This kind corresponds to an error due to an [`Entry`] being a directory.
+        IsADirectory,
+
+        /// This kind corresponds to an error due to an [`Entry`] not being a directory.
+        NotADirectory,
+
+        /// This kind corresponds to an [`Entry`] not being found on the system.
+        NotFound,
+
+        /// This kind corresponds to the user not having access to an [`Entry`].
+        PermissionDenied,
+
+        /// This kind corresponds to the CLI refusing to remove an [`Entry`] because removing it
+        /// is potentially dangerous (e.g. it is the current directory).
+        Refused,
+
+        /// This kind is a catch all for any unknown error.
+        Unknown,
+    }
+
+    impl fmt::Display for ErrorKind {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::DirectoryNotEmpty => write!(f, "Directory not empty"),
+                Self::IsADirectory => write!(f, "Is a directory"),
+                Self::NotADirectory => write!(f, "Not a directory"),
+                Self::NotFound => write!(f, "Not found"),
+                Self::PermissionDenied => write!(f, "Permission denied"),
+                Self::Refused => write!(f, "Refused to remove"),
+                Self::Unknown => write!(f, "Unknown error"),
+            }
+        }
+    }
+
+    impl From<io::ErrorKind> for ErrorKind {
+        fn from(val: io::ErrorKind) -> Self {
+            match val {
+                io::ErrorKind::DirectoryNotEmpty => Self::DirectoryNotEmpty,
+                io::ErrorKind::IsADirectory => Self::IsADirectory,
+                io::ErrorKind::NotADirectory => Self::NotADirectory,
+                io::ErrorKind::NotFound => Self::NotFound,
+                io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+                _ => Self::Unknown,
+            }
+        }
+    }
+
+    #[cfg(feature = "trash")]
+    impl From<trash::Error> for ErrorKind {
+        fn from(val: trash::Error) -> Self {
+            match val {
+                trash::Error::CouldNotAccess { .. } => Self::PermissionDenied,
+                #[cfg(all(unix, not(target_os = "macos")))]
+                trash::Error::FileSystem { source, .. } => source.kind().into(),
+                trash::Error::TargetedRoot => Self::Refused,
+                _ => Self::Unknown,
+            }
+        }
+    }
+
+    /// Tests for the [`ErrorKind`] enum.
+    #[cfg(test)]
+    mod test_error_kind {
+        use super::ErrorKind;
+
+        use std::io;
+        use std::path;
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[test]
+        fn from_io_directory_not_empty() {
+            assert_eq!(ErrorKind::DirectoryNotEmpty, io::ErrorKind::DirectoryNotEmpty.into());
+        }
+
+        #[test]
+        fn from_io_is_a_directory() {
+            assert_eq!(ErrorKind::IsADirectory, io::ErrorKind::IsADirectory.into());
+        }
+
+        #[test]
+        fn from_io_not_a_directory() {
+            assert_eq!(ErrorKind::NotADirectory, io::ErrorKind::NotADirectory.into());
+        }
+
+        #[test]
+        fn from_io_not_found() {
+            assert_eq!(ErrorKind::NotFound, io::ErrorKind::NotFound.into());
+        }
+
+        #[test]
+        fn from_io_permission_denied() {
+            assert_eq!(ErrorKind::PermissionDenied, io::ErrorKind::PermissionDenied.into());
+        }
+
+        #[proptest]
+        #[cfg(feature = "trash")]
+        fn from_trash_could_not_access(target: String) {
+            let err = trash::Error::CouldNotAccess { target };
+            prop_assert_eq!(ErrorKind::PermissionDenied, err.into());
+        }
+
+        #[proptest]
+        #[cfg(feature = "trash")]
+        #[cfg(all(unix, not(target_os = "macos")))]
+        fn from_trash_file_system(source: io::Error, path: String) {
+            let expected: ErrorKind = source.kind().into();
+            let err = trash::Error::FileSystem { source, path: path::Path::new(&path).into() };
+            prop_assert_eq!(expected, err.into());
+        }
+
+        #[test]
+        #[cfg(feature = "trash")]
+        fn from_trash_targeted_root() {
+            assert_eq!(ErrorKind::Refused, trash::Error::TargetedRoot.into());
+        }
+    }
+
+    /// Helpers for writing unit tests in or using this module.
+    #[cfg(test)]
+    pub mod test_helpers {
+        use super::{Entry, EntryKind, Error};
+
+        #[cfg(target_os = "linux")]
+        use std::io;
+        use std::path::Path;
+        #[cfg(target_os = "linux")]
+        use std::process::Child;
+
+        impl Error {
+            /// Get the tip associated with this [`Error`], if any.
+            pub fn tip(&self) -> Option<&str> {
+                match &self.tip {
+                    Some(tip) => Some(tip),
+                    None => None,
+                }
+            }
+        }
+
+        /// Create an [`Entry`] representing a file for testing purposes.
+        pub fn new_file<P: AsRef<Path>>(path: P) -> Entry {
+            Entry::new(&path, EntryKind::File)
+        }
+
+        /// Create an [`Entry`] representing a directory for testing purposes.
+        pub fn new_dir<P: AsRef<Path>>(path: P) -> Entry {
+            Entry::new(&path, EntryKind::Dir)
+        }
+
+        /// Create an [`Entry`] representing a symbolic link for testing purposes.
+        pub fn new_symlink<P: AsRef<Path>>(path: P) -> Entry {
+            Entry::new(&path, EntryKind::Symlink)
+        }
+
+        /// Spawn a `sleep` process that holds the file at the given `path` open until it is
+        /// killed. Waits for the process to run `sleep`, so that it is named accordingly.
+        #[cfg(target_os = "linux")]
+        pub fn hold_open<P: AsRef<Path>>(path: P) -> io::Result<Child> {
+            use std::fs::{File, read_to_string};
+            use std::process::Command;
+            use std::thread;
+            use std::time::Duration;
+
+            let child = Command::new("sleep").arg("60").stdin(File::open(path)?).spawn()?;
+            let comm = format!("/proc/{}/comm", child.id());
+            for _ in 0..100 {
+                if read_to_string(&comm)?.trim() == "sleep" {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+
+            Ok(child)
+        }
+    }
+}
+
+/// File system walking strategies.
+mod walk {
+    use super::{fs, transform};
+
+    use std::cell::RefCell;
+    #[cfg(unix)]
+    use std::collections::BTreeMap;
+    use std::collections::{HashMap, HashSet};
+    #[cfg(unix)]
+    use std::fs::canonicalize;
+    use std::fs::read_dir;
+    use std::iter;
+    use std::ops::ControlFlow;
+    use std::path::{Path, PathBuf};
+    use std::rc::Rc;
+    use std::result;
+
+    use log::trace;
+
+    #[cfg(test)]
+    use proptest_derive::Arbitrary;
+
+    /// The return type of a file system [`Walker`].
+    type FileIterator = Box<dyn Iterator<Item = fs::Result>>;
+
+    /// The collection of [`transform::Transformer`]s supported by this module.
+    pub type Transformers = Rc<[transform::Transformer]>;
+
+    /// A "file system walker" - a function that iterates over entries on a file system.
+    pub type Walker = Box<dyn Fn(&dyn AsRef<Path>) -> FileIterator>;
+
+    /// Struct representing an item while walking the file system.
+    #[cfg_attr(test, derive(Arbitrary, Clone, Debug, Eq, PartialEq))]
+    pub struct Item {
+        /// The [`fs::Result`] this item represents.
+        pub inner: fs::Result,
+
+        /// Why, if at all, the item must be skipped.
+        skip_reason: Option<String>,
+
+        /// Whether or not the item has already been visited.
+        visited: bool,
+
+        /// Whether walking the directory of the item must stop at the item.
+        stop: bool,
+    }
+
+    impl Item {
+        /// Convert the [`Item`] into an [`Item`] that will be skipped. Must be provided with the
+        /// reason why it is skipped.
+        pub fn into_skipped(mut self, reason: &str) -> Self {
+            self.skip_reason = Some(reason.to_owned());
+            self
+        }
+
+        /// Convert the [`Item`] into an [`Item`] that will be skipped, along with the rest of the
+        /// directory it is in. Must be provided with the reason why it is skipped.
+        pub fn into_stopped(mut self, reason: &str) -> Self {
+            self.stop = true;
+            self.into_skipped(reason)
+        }
+
+        /// Convert the [`Item`] into an [`Item`] that's marked as visited.
+        fn into_visited(mut self) -> Self {
+            self.visited = true;
+            self
+        }
+
+        /// Returns `true` if the [`Item`] will be skipped.
+        pub fn is_skipped(&self) -> bool {
+            self.skip_reason.is_some()
+        }
+
+        /// Returns `true` if the [`Item`] has been visited before.
+        pub fn is_visited(&self) -> bool {
+            self.visited
+        }
+
+        /// Get the file system path this [`Item`] is associated with.
+        pub fn path(&self) -> PathBuf {
+            self.inner.as_ref().map_or_else(fs::Error::path, fs::Entry::path)
+        }
+    }
+
+    /// Tests for the [`Item`] struct.
+    #[cfg(test)]
+    mod test_item {
+        use super::{Item, fs};
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn into_skipped(item: Item, reason: String) {
+            let inner = item.inner.clone();
+            let visited = item.visited;
+            let stop = item.stop;
+
+            prop_assert_eq!(
+                item.into_skipped(&reason),
+                Item { inner, skip_reason: Some(reason), visited, stop }
+            );
+        }
+
+        #[proptest]
+        fn into_stopped(item: Item, reason: String) {
+            let inner = item.inner.clone();
+            let visited = item.visited;
+
+            prop_assert_eq!(
+                item.into_stopped(&reason),
+                Item { inner, skip_reason: Some(reason), visited, stop: true }
+            );
+        }
+
+        #[proptest]
+        fn into_visited(item: Item) {
+            let inner = item.inner.clone();
+            let skip_reason = item.skip_reason.clone();
+            let stop = item.stop;
+
+            prop_assert_eq!(item.into_visited(), Item { inner, skip_reason, visited: true, stop });
+        }
+
+        #[proptest]
+        fn is_skipped(item: Item) {
+            prop_assert_eq!(item.is_skipped(), item.skip_reason.is_some());
+        }
+
+        #[proptest]
+        fn is_visited(item: Item) {
+            prop_assert_eq!(item.is_visited(), item.visited);
+        }
+
+        #[proptest]
+        fn path_entry(entry: fs::Entry) {
+            let item: Item = entry.clone().into();
+            prop_assert_eq!(item.path(), entry.path());
+        }
+
+        #[proptest]
+        fn path_error(err: fs::Error) {
+            let item: Item = err.clone().into();
+            prop_assert_eq!(item.path(), err.path());
+        }
+    }
+
+    /// Open an [`Item`] for walking the file system.
+    ///
+    /// # Errors
+    ///
+    /// If nothing is accessible at the given path.
+    fn open<P: AsRef<Path>>(path: P) -> Item {
+        Item { inner: fs::open(path), skip_reason: None, visited: false, stop: false }
+    }
+
+    /// Create a [`Walker`] that only visits the given file system entry.
+    pub fn given(transformers: Transformers) -> Walker {
+        Box::new(move |path| Box::new(visit(open(path).into_visited(), &transformers).into_iter()))
+    }
+
+    /// Tests for the [`given`] function.
+    #[cfg(test)]
+    mod test_given {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::fs;
+
+        use std::path;
+        use std::rc::Rc;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let path = file.path();
+
+                let out = given(path);
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn empty_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let path = dir.path();
+
+                let out = given(path);
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn filled_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                dir.child("file").touch()?;
+
+                let path = dir.path();
+
+                let out = given(path);
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&file)?;
+
+                let path = link.path();
+
+                let out = given(path);
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_empty_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&dir)?;
+
+                let path = link.path();
+
+                let out = given(path);
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_filled_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                dir.child("file").touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&dir)?;
+
+                let path = link.path();
+
+                let out = given(path);
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn not_found() -> TestResult {
+            with_test_dir(|test_dir| {
+                let path = test_dir.child("missing");
+
+                let out = given(&path);
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        /// Convenience wrapper around [`super::given`] for use in tests.
+        fn given<P: AsRef<path::Path>>(path: P) -> Vec<fs::Result> {
+            let given_closure = super::given(Rc::new([]));
+
+            given_closure(&path).collect()
+        }
+    }
+
+    /// Walk the subsection of the file system with `path` as root. If a `file_system` is provided,
+    /// entries on any other file system are not visited.
+    fn recurse_path<P: AsRef<Path>>(
+        path: P,
+        transformers: Transformers,
+        file_system: Option<u64>,
+    ) -> FileIterator {
+        let visited = visit(open(path), &transformers);
+        descend(visited, transformers, file_system)
+    }
+
+    /// Walk the subsection of the file system with the given `visited` entry as root, see
+    /// [`recurse_path`]. The rest of a directory is not visited once a transformer stops at one of
+    /// its entries.
+    fn descend(
+        visited: Option<fs::Result>,
+        transformers: Transformers,
+        file_system: Option<u64>,
+    ) -> FileIterator {
+        Box::new(visited.into_iter().flat_map(move |result| match result {
+            Ok(dir) if dir.is_dir() && !fs::is_empty(&dir) => match read_dir(dir.path()) {
+                Ok(content) => {
+                    let children_transformers = Rc::clone(&transformers);
+                    let descend_transformers = Rc::clone(&transformers);
+                    let dir_transformers = Rc::clone(&transformers);
+                    Box::new(
+                        content
+                            .into_iter()
+                            .map_while(result::Result::ok)
+                            .map(|entry| entry.path())
+                            .filter(move |path| is_on_file_system(path, file_system))
+                            .map_while(move |path| {
+                                visit_or_stop(open(path), &children_transformers).continue_value()
+                            })
+                            .flat_map(move |visited| {
+                                descend(visited, Rc::clone(&descend_transformers), file_system)
+                            })
+                            .chain(
+                                iter::once_with(move || {
+                                    let item = Item {
+                                        inner: Ok(dir),
+                                        skip_reason: None,
+                                        visited: true,
+                                        stop: false,
+                                    };
+                                    visit(item, &dir_transformers)
+                                })
+                                .flatten(),
+                            ),
+                    ) as FileIterator
+                },
+                Err(err) => Box::new(iter::once(Err(dir.into_err(err.kind().into())))),
+            },
+            _ => Box::new(iter::once(result)),
+        }))
+    }
+
+    /// The explanation for why an entry on a different file system is skipped.
+    const SKIP_REASON_OTHER_FILE_SYSTEM: &str = "On a different file system";
+
+    /// Check if the given `path` is on the given `file_system`. Always `true` if no `file_system`
+    /// is provided.
+    fn is_on_file_system(path: &Path, file_system: Option<u64>) -> bool {
+        let on_file_system = file_system.is_none_or(|id| fs::file_system_of(path) == Some(id));
+        if !on_file_system {
+            trace!("skipped {}: {SKIP_REASON_OTHER_FILE_SYSTEM}", path.display());
+        }
+
+        on_file_system
+    }
+
+    /// Create a [`Walker`] that recurse directories in order to visits entries on the file system.
+    pub fn recurse(transformers: Transformers) -> Walker {
+        Box::new(move |path| recurse_path(path, Rc::clone(&transformers), None))
+    }
+
+    /// Tests for the [`recurse`] function.
+    #[cfg(test)]
+    mod test_recurse {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Item, fs, transform};
+
+        use std::cell::Cell;
+        use std::path;
+        use std::rc::Rc;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn empty_file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let path = file.path();
+
+                let out = recurse(path);
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn filled_file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+
+                let path = file.path();
+
+                let out = recurse(path);
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn empty_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let path = dir.path();
+
+                let out = recurse(path);
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn filled_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let file = dir.child("file");
+                file.touch()?;
+
+                let dir_path = dir.path();
+                let file_path = file.path();
+
+                let out = recurse(dir_path);
+                assert_eq!(out, vec![fs::open(file_path), fs::open(dir_path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn nested_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let nested_dir = dir.child("nested_dir");
+                nested_dir.create_dir_all()?;
+                let nested_file = nested_dir.child("file1");
+                nested_file.touch()?;
+                let file = dir.child("file2");
+                file.touch()?;
+
+                let dir_path = dir.path();
+                let nested_dir_path = nested_dir.path();
+                let nested_file_path = nested_file.path();
+                let file_path = file.path();
+
+                let out = recurse(dir_path);
+                assert_eq!(out.len(), 4);
+                assert!(out.contains(&fs::open(file_path)));
+                assert!(out.contains(&fs::open(nested_file_path)));
+                assert!(out.contains(&fs::open(nested_dir_path)));
+                assert!(out.contains(&fs::open(dir_path)));
+
+                assert!(
+                    out.iter()
+                        .filter_map(|x| x.clone().ok())
+                        .position(|x| x.path() == nested_file_path)
+                        < out
+                            .iter()
+                            .filter_map(|x| x.clone().ok())
+                            .position(|x| x.path() == nested_dir_path)
+                );
+                assert_eq!(out.last(), Some(&fs::open(dir_path)));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&file)?;
+
+                let path = link.path();
+
+                let out = recurse(path);
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_empty_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&dir)?;
+
+                let path = link.path();
+
+                let out = recurse(path);
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_filled_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                dir.child("file").touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&dir)?;
+
+                let path = link.path();
+
+                let out = recurse(path);
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn not_found() -> TestResult {
+            with_test_dir(|test_dir| {
+                let path = test_dir.child("missing");
+
+                let out = recurse(&path);
+                assert_eq!(out, vec![fs::open(&path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn stopped() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file1").touch()?;
+                dir.child("file2").touch()?;
+
+                let seen = Rc::new(Cell::new(0));
+                let stop_at_files: transform::Transformer = {
+                    let seen = Rc::clone(&seen);
+                    Box::new(move |item: Item| {
+                        if item.path().is_file() {
+                            seen.set(seen.get() + 1);
+                            item.into_stopped("stopped")
+                        } else {
+                            item
+                        }
+                    })
+                };
+
+                let out: Vec<fs::Result> =
+                    super::recurse(Rc::new([stop_at_files]))(&dir.path()).collect();
+                assert_eq!(out, vec![fs::open(dir.path())]);
+                assert_eq!(seen.get(), 1);
+
+                Ok(())
+            })
+        }
+
+        /// Convenience wrapper around [`super::recurse`] for use in tests.
+        fn recurse<P: AsRef<path::Path>>(path: P) -> Vec<fs::Result> {
+            let recurse_closure = super::recurse(Rc::new([]));
+
+            recurse_closure(&path).collect()
+        }
+    }
+
+    /// Create a [`Walker`] that recurse directories in order to visits entries on the file system,
+    /// without crossing into other file systems.
+    pub fn recurse_within_file_system(transformers: Transformers) -> Walker {
+        Box::new(move |path| recurse_path(path, Rc::clone(&transformers), fs::file_system_of(path)))
+    }
+
+    /// Tests for the [`recurse_within_file_system`] function.
+    #[cfg(test)]
+    mod test_recurse_within_file_system {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::fs;
+
+        use std::path;
+        use std::rc::Rc;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let path = file.path();
+
+                let out = recurse_within_file_system(path);
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn filled_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let nested_dir = dir.child("nested_dir");
+                nested_dir.create_dir_all()?;
+                let nested_file = nested_dir.child("file");
+                nested_file.touch()?;
+
+                let dir_path = dir.path();
+                let nested_dir_path = nested_dir.path();
+                let nested_file_path = nested_file.path();
+
+                let out = recurse_within_file_system(dir_path);
+                assert_eq!(
+                    out,
+                    vec![fs::open(nested_file_path), fs::open(nested_dir_path), fs::open(dir_path)]
+                );
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn not_found() -> TestResult {
+            with_test_dir(|test_dir| {
+                let path = test_dir.child("missing");
+
+                let out = recurse_within_file_system(&path);
+                assert_eq!(out, vec![fs::open(&path)]);
+
+                Ok(())
+            })
+        }
+
+        /// Convenience wrapper around [`super::recurse_within_file_system`] for use in tests.
+        fn recurse_within_file_system<P: AsRef<path::Path>>(path: P) -> Vec<fs::Result> {
+            let recurse_closure = super::recurse_within_file_system(Rc::new([]));
+
+            recurse_closure(&path).collect()
+        }
+    }
+
+    /// Walk the contents of the directory at `path` using the given `walker`, without visiting the
+    /// directory itself. Empty directories are not visited at all since there is nothing to do.
+    fn contents_of<P: AsRef<Path>>(
+        path: P,
+        walker: &Rc<Walker>,
+        transformers: &Transformers,
+    ) -> FileIterator {
+        let item = open(path);
+        if item.inner.as_ref().is_ok_and(|entry| entry.is_dir() && fs::is_empty(entry)) {
+            return Box::new(iter::empty());
+        }
+
+        let walker = Rc::clone(walker);
+        Box::new(visit(item, transformers).into_iter().flat_map(move |result| match result {
+            Ok(dir) if dir.is_dir() => match read_dir(dir.path()) {
+                Ok(content) => {
+                    let walker = Rc::clone(&walker);
+                    Box::new(
+                        content
+                            .into_iter()
+                            .map_while(result::Result::ok)
+                            .map(|entry| entry.path())
+                            .flat_map(move |path| walker(&path)),
+                    ) as FileIterator
+                },
+                Err(err) => Box::new(iter::once(Err(dir.into_err(err.kind().into())))),
+            },
+            Ok(entry) => Box::new(iter::once(Err(entry.into_err(fs::ErrorKind::NotADirectory)))),
+            Err(err) => Box::new(iter::once(Err(err))),
+        }))
+    }
+
+    /// Create a [`Walker`] that visits the contents of directories using the given `walker`,
+    /// without visiting the directories themselves.
+    pub fn contents(walker: Walker, transformers: Transformers) -> Walker {
+        let walker = Rc::new(walker);
+        Box::new(move |path| contents_of(path, &walker, &transformers))
+    }
+
+    /// Tests for the [`contents`] function.
+    #[cfg(test)]
+    mod test_contents {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Item, fs, given, recurse};
+
+        use std::path;
+        use std::rc::Rc;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn empty_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let out = contents(recurse(identities()), dir.path());
+                assert_eq!(out, vec![]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn filled_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let nested_dir = dir.child("nested_dir");
+                let nested_file = nested_dir.child("file");
+                nested_file.touch()?;
+
+                let out = contents(recurse(identities()), dir.path());
+                assert_eq!(out, vec![fs::open(nested_file.path()), fs::open(nested_dir.path())]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn filled_dir_given() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let nested_dir = dir.child("nested_dir");
+                nested_dir.child("file").touch()?;
+
+                let out = contents(given(identities()), dir.path());
+                assert_eq!(out, vec![fs::open(nested_dir.path())]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let path = file.path();
+
+                let out = contents(recurse(identities()), path);
+                assert_eq!(out, vec![Err(fs::open(path)?.into_err(fs::ErrorKind::NotADirectory))]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn not_found() -> TestResult {
+            with_test_dir(|test_dir| {
+                let path = test_dir.child("missing");
+
+                let out = contents(recurse(identities()), &path);
+                assert_eq!(out, vec![fs::open(&path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn skipped() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file").touch()?;
+
+                let walker =
+                    super::contents(recurse(identities()), Rc::new([Box::new(transform_skip)]));
+
+                let out: Vec<fs::Result> = walker(&dir.path()).collect();
+                assert_eq!(out, vec![]);
+
+                Ok(())
+            })
+        }
+
+        /// Convenience wrapper around [`super::contents`] for use in tests.
+        fn contents<P: AsRef<path::Path>>(walker: super::Walker, path: P) -> Vec<fs::Result> {
+            let contents_closure = super::contents(walker, identities());
+
+            contents_closure(&path).collect()
+        }
+
+        /// Get a set of [`super::Transformers`] that do not transform anything.
+        fn identities() -> super::Transformers {
+            Rc::new([])
+        }
+
+        /// A [`super::transform::Transformer`] that transforms all values into the skipped item.
+        fn transform_skip(item: Item) -> Item {
+            item.into_skipped("some reason")
+        }
+    }
+
+    /// Create a [`Walker`] that visits the contents of directories using the given `walker`,
+    /// without visiting the directories themselves nor crossing into other file systems.
+    pub fn contents_within_file_system(walker: Walker, transformers: Transformers) -> Walker {
+        let walker = Rc::new(walker);
+        Box::new(move |path| {
+            let file_system = fs::file_system_of(path);
+            let walker = Rc::clone(&walker);
+            let walker: Rc<Walker> = Rc::new(Box::new(move |path| {
+                if is_on_file_system(path.as_ref(), file_system) {
+                    walker(path)
+                } else {
+                    Box::new(iter::empty())
+                }
+            }));
+
+            contents_of(path, &walker, &transformers)
+        })
+    }
+
+    /// Tests for the [`contents_within_file_system`] function.
+    #[cfg(test)]
+    mod test_contents_within_file_system {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{fs, recurse_within_file_system};
+
+        use std::path;
+        use std::rc::Rc;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn empty_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let out = contents_within_file_system(dir.path());
+                assert_eq!(out, vec![]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn filled_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let nested_dir = dir.child("nested_dir");
+                let nested_file = nested_dir.child("file");
+                nested_file.touch()?;
+
+                let out = contents_within_file_system(dir.path());
+                assert_eq!(out, vec![fs::open(nested_file.path()), fs::open(nested_dir.path())]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn not_found() -> TestResult {
+            with_test_dir(|test_dir| {
+                let path = test_dir.child("missing");
+
+                let out = contents_within_file_system(&path);
+                assert_eq!(out, vec![fs::open(&path)]);
+
+                Ok(())
+            })
+        }
+
+        /// Convenience wrapper around [`super::contents_within_file_system`] for use in tests.
+        fn contents_within_file_system<P: AsRef<path::Path>>(path: P) -> Vec<fs::Result> {
+            let contents_closure = super::contents_within_file_system(
+                recurse_within_file_system(Rc::new([])),
+                Rc::new([]),
+            );
+
+            contents_closure(&path).collect()
+        }
+    }
+
+    /// The paths of entries that would have been removed, in a dry run where nothing is actually
+    /// removed.
+    pub type Removed = Rc<RefCell<HashSet<PathBuf>>>;
+
+    /// Create a [`Walker`] that visits everything the given `walker` visits followed by the
+    /// ancestors of the given file system entry, from the closest to the furthest.
+    ///
+    /// An ancestor is only visited if the given entry existed and the ancestor's descendant no
+    /// longer exists or is in `removed`, and no further ancestors are visited once one is skipped.
+    pub fn with_ancestors(walker: Walker, transformers: Transformers, removed: Removed) -> Walker {
+        Box::new(move |path| {
+            let path = path.as_ref().to_owned();
+            let existed = path.symlink_metadata().is_ok();
+            let ancestors: Vec<PathBuf> = path
+                .ancestors()
+                .skip(1)
+                .take_while(|ancestor| existed && !ancestor.as_os_str().is_empty())
+                .map(Path::to_owned)
+                .collect();
+            let descendants = iter::once(path.clone()).chain(ancestors.clone());
+            let transformers = Rc::clone(&transformers);
+            let removed = Rc::clone(&removed);
+
+            Box::new(walker(&path).chain(descendants.zip(ancestors).map_while(
+                move |(descendant, ancestor)| {
+                    if descendant.symlink_metadata().is_ok()
+                        && !removed.borrow().contains(&descendant)
+                    {
+                        None
+                    } else {
+                        visit(open(ancestor).into_visited(), &transformers)
+                    }
+                },
+            )))
+        })
+    }
+
+    /// Tests for the [`with_ancestors`] function.
+    #[cfg(test)]
+    mod test_with_ancestors {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Item, Removed, Transformers, fs, given};
+
+        use std::fs::remove_dir;
+        use std::path::PathBuf;
+        use std::rc::Rc;
+
+        use assert_fs::prelude::*;
+        use predicates::prelude::*;
+
+        #[test]
+        fn descendant_exists() -> TestResult {
+            with_test_dir(|test_dir| {
+                let nested_dir = test_dir.child("dir").child("nested_dir");
+                nested_dir.create_dir_all()?;
+
+                let path = nested_dir.path();
+
+                let out: Vec<fs::Result> = with_ancestors(identities())(&path).collect();
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn descendant_removed() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let nested_dir = dir.child("nested_dir");
+                nested_dir.create_dir_all()?;
+
+                let out: Vec<PathBuf> = with_ancestors(identities())(&nested_dir.path())
+                    .map(|result| result.expect("should be found"))
+                    .map(|entry| entry.path())
+                    .inspect(|path| {
+                        if path.starts_with(dir.path()) {
+                            remove_dir(path).expect("should be removable");
+                        }
+                    })
+                    .collect();
+                assert_eq!(
+                    out,
+                    vec![
+                        nested_dir.path().to_owned(),
+                        dir.path().to_owned(),
+                        test_dir.path().to_owned(),
+                    ]
+                );
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn descendant_would_be_removed() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let nested_dir = dir.child("nested_dir");
+                nested_dir.create_dir_all()?;
+
+                let removed = Removed::default();
+                let walker =
+                    super::with_ancestors(given(identities()), identities(), Rc::clone(&removed));
+                let out: Vec<PathBuf> = walker(&nested_dir.path())
+                    .map(|result| result.expect("should be found"))
+                    .map(|entry| entry.path())
+                    .inspect(|path| {
+                        if path.starts_with(dir.path()) {
+                            removed.borrow_mut().insert(path.clone());
+                        }
+                    })
+                    .collect();
+                assert_eq!(
+                    out,
+                    vec![
+                        nested_dir.path().to_owned(),
+                        dir.path().to_owned(),
+                        test_dir.path().to_owned(),
+                    ]
+                );
+                nested_dir.assert(predicate::path::exists());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn not_found() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let path = dir.child("missing").path().to_owned();
+
+                let out: Vec<fs::Result> = with_ancestors(identities())(&path).collect();
+                assert_eq!(out, vec![fs::open(path)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn stops_on_skip() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let nested_dir = dir.child("nested_dir");
+                nested_dir.create_dir_all()?;
+
+                let out: Vec<PathBuf> =
+                    with_ancestors(Rc::new([Box::new(transform_skip)]))(&nested_dir.path())
+                        .map(|result| result.expect("should be found"))
+                        .map(|entry| entry.path())
+                        .inspect(|path| remove_dir(path).expect("should be removable"))
+                        .collect();
+                assert_eq!(out, vec![nested_dir.path().to_owned()]);
+                dir.assert(predicate::path::exists());
+
+                Ok(())
+            })
+        }
+
+        /// Convenience wrapper around [`super::with_ancestors`] for use in tests, wrapping a
+        /// [`given`] walker.
+        fn with_ancestors(transformers: Transformers) -> super::Walker {
+            super::with_ancestors(given(identities()), transformers, Removed::default())
+        }
+
+        /// Get a set of [`Transformers`] that do not transform anything.
+        fn identities() -> Transformers {
+            Rc::new([])
+        }
+
+        /// A [`super::transform::Transformer`] that transforms all values into the skipped item.
+        fn transform_skip(item: Item) -> Item {
+            item.into_skipped("some reason")
+        }
+    }
+
+    /// The identifiers and canonical paths of the hard linked files visited by a [`Walker`].
+    #[cfg(unix)]
+    type VisitedLinks = Rc<RefCell<Vec<((u64, u64), PathBuf)>>>;
+
+    /// Create a [`Walker`] that visits everything the given `walker` visits followed by the other
+    /// hard links, out of the given `links` (see [`fs::hard_links_in`]), to the files it visited.
+    ///
+    /// The other links are only visited if they still exist once the `walker` is done and weren't
+    /// visited by the `walker` itself.
+    #[cfg(unix)]
+    pub fn with_links(
+        walker: Walker,
+        links: BTreeMap<(u64, u64), Vec<PathBuf>>,
+        transformers: Transformers,
+    ) -> Walker {
+        let links = Rc::new(links);
+        Box::new(move |path| {
+            let visited: VisitedLinks = Rc::default();
+            let visited_before = Rc::clone(&visited);
+            let links_before = Rc::clone(&links);
+            let links = Rc::clone(&links);
+            let transformers = Rc::clone(&transformers);
+
+            Box::new(
+                walker(path)
+                    .inspect(move |result| {
+                        if let Ok(entry) = result
+                            && let Some(id) = fs::file_id_of(entry.path())
+                            && links_before.contains_key(&id)
+                            && let Ok(path) = canonicalize(entry.path())
+                        {
+                            visited_before.borrow_mut().push((id, path));
+                        }
+                    })
+                    .chain(
+                        iter::once_with(move || {
+                            let visited = visited.borrow();
+                            let mut others: Vec<PathBuf> = vec![];
+                            for (id, _) in visited.iter() {
+                                for link in links.get(id).into_iter().flatten() {
+                                    if let Ok(path) = canonicalize(link)
+                                        && !visited.iter().any(|(_, visited)| *visited == path)
+                                        && !others.contains(link)
+                                    {
+                                        others.push(link.clone());
+                                    }
+                                }
+                            }
+
+                            others.into_iter().filter_map(move |link| {
+                                visit(open(link).into_visited(), &transformers)
+                            })
+                        })
+                        .flatten(),
+                    ),
+            )
+        })
+    }
+
+    /// Tests for the [`with_links`] function.
+    #[cfg(test)]
+    #[cfg(unix)]
+    mod test_with_links {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Item, Transformers, fs, given, recurse};
+
+        use std::fs::{hard_link, remove_file};
+        use std::path::{Path, PathBuf};
+        use std::rc::Rc;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn other_links_removed() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let links = test_dir.child("links");
+                links.create_dir_all()?;
+                hard_link(&file, links.child("link1"))?;
+                hard_link(&file, links.child("link2"))?;
+                links.child("unrelated").touch()?;
+
+                let walk = with_links(links.path(), identities());
+                let mut out: Vec<PathBuf> = walk(&file.path())
+                    .map(|result| result.expect("should be found"))
+                    .map(|entry| entry.path())
+                    .inspect(|path| remove_file(path).expect("should be removable"))
+                    .collect();
+                if let Some(others) = out.get_mut(1..) {
+                    others.sort();
+                }
+                assert_eq!(
+                    out,
+                    vec![
+                        file.to_path_buf(),
+                        links.child("link1").to_path_buf(),
+                        links.child("link2").to_path_buf(),
+                    ]
+                );
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn other_links_visited() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file").touch()?;
+                hard_link(dir.child("file"), dir.child("link"))?;
+
+                let walk = super::with_links(
+                    recurse(identities()),
+                    fs::hard_links_in(test_dir.path()),
+                    identities(),
+                );
+                let mut out: Vec<PathBuf> = walk(&dir.path())
+                    .map(|result| result.expect("should be found"))
+                    .map(|entry| entry.path())
+                    .collect();
+                out.sort();
+                assert_eq!(
+                    out,
+                    vec![
+                        dir.to_path_buf(),
+                        dir.child("file").to_path_buf(),
+                        dir.child("link").to_path_buf(),
+                    ]
+                );
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn single_link() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let walk = with_links(test_dir.path(), identities());
+                let out: Vec<fs::Result> = walk(&file.path()).collect();
+                assert_eq!(out, vec![fs::open(file.path())]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn skipped() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                hard_link(&file, test_dir.child("link"))?;
+
+                let walk = super::with_links(
+                    given(identities()),
+                    fs::hard_links_in(test_dir.path()),
+                    Rc::new([Box::new(transform_skip_links)]),
+                );
+                let out: Vec<fs::Result> = walk(&file.path()).collect();
+                assert_eq!(out, vec![fs::open(file.path())]);
+
+                Ok(())
+            })
+        }
+
+        /// Convenience wrapper around [`super::with_links`] for use in tests, wrapping a [`given`]
+        /// walker and using the links in the given `dir`.
+        fn with_links(dir: &Path, transformers: Transformers) -> super::Walker {
+            super::with_links(given(identities()), fs::hard_links_in(dir), transformers)
+        }
+
+        /// Get a set of [`Transformers`] that do not transform anything.
+        fn identities() -> Transformers {
+            Rc::new([])
+        }
+
+        /// A [`super::transform::Transformer`] that skips all items named "link".
+        fn transform_skip_links(item: Item) -> Item {
+            if item.inner.as_ref().is_ok_and(|entry| entry.path().ends_with("link")) {
+                item.into_skipped("some reason")
+            } else {
+                item
+            }
+        }
+    }
+
+    /// The [`fs::Result`]s a [`Walker`] visited for each path it was given, see [`plan`].
+    pub type Plan = Vec<(PathBuf, Vec<fs::Result>)>;
+
+    /// Walk all the given `paths` using the given `walker` ahead of time and record everything it
+    /// visits in a [`Plan`].
+    ///
+    /// Note that the [`Transformers`] of the `walker` are applied while planning, not when the
+    /// [`Plan`] is walked using [`planned`].
+    pub fn plan<P: AsRef<Path>>(paths: &[P], walker: &Walker) -> Plan {
+        paths.iter().map(|path| (path.as_ref().to_owned(), walker(path).collect())).collect()
+    }
+
+    /// Tests for the [`plan`] function.
+    #[cfg(test)]
+    mod test_plan {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{fs, given, plan, recurse};
+
+        use std::rc::Rc;
+
+        use assert_fs::prelude::*;
+        use predicates::prelude::*;
+
+        #[test]
+        fn given_paths() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let missing = test_dir.child("missing");
+
+                let paths = [file.path(), missing.path()];
+
+                let out = plan(&paths, &given(Rc::new([])));
+                assert_eq!(
+                    out,
+                    vec![
+                        (file.path().to_owned(), vec![fs::open(file.path())]),
+                        (missing.path().to_owned(), vec![fs::open(missing.path())]),
+                    ]
+                );
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn recursive_path() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let file = dir.child("file");
+                file.touch()?;
+
+                let out = plan(&[dir.path()], &recurse(Rc::new([])));
+                assert_eq!(
+                    out,
+                    vec![(
+                        dir.path().to_owned(),
+                        vec![fs::open(file.path()), fs::open(dir.path())]
+                    )]
+                );
+                file.assert(predicate::path::exists());
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Create a [`Walker`] that visits what the given [`Plan`] recorded for a path instead of
+    /// walking the file system.
+    ///
+    /// Each planned path is visited at most once, paths that were not planned visit nothing.
+    pub fn planned(plan: Plan) -> Walker {
+        let plan = RefCell::new(by_path(plan));
+        Box::new(move |path| {
+            let results = plan.borrow_mut().remove(path.as_ref()).unwrap_or_default();
+            Box::new(results.into_iter())
+        })
+    }
+
+    /// Index the given [`Plan`] by path, keeping what was recorded first for paths that were
+    /// planned more than once.
+    fn by_path(plan: Plan) -> HashMap<PathBuf, Vec<fs::Result>> {
+        let mut index = HashMap::with_capacity(plan.len());
+        for (path, results) in plan {
+            index.entry(path).or_insert(results);
+        }
+
+        index
+    }
+
+    /// Tests for the [`planned`] function.
+    #[cfg(test)]
+    mod test_planned {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{fs, planned};
+
+        use std::path::PathBuf;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn planned_path() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let path = file.path();
+                let walker = planned(vec![(path.to_owned(), vec![fs::open(path)])]);
+
+                let out: Vec<fs::Result> = walker(&path).collect();
+                assert_eq!(out, vec![fs::open(path)]);
+
+                let out: Vec<fs::Result> = walker(&path).collect();
+                assert_eq!(out, vec![]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn planned_twice() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let path = file.path();
+                let walker = planned(vec![
+                    (path.to_owned(), vec![fs::open(path)]),
+                    (path.to_owned(), vec![fs::open(test_dir.path())]),
+                ]);
+
+                let out: Vec<fs::Result> = walker(&path).collect();
+                assert_eq!(out, vec![fs::open(path)]);
+
+                let out: Vec<fs::Result> = walker(&path).collect();
+                assert_eq!(out, vec![]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn unplanned_path() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let path = file.path();
+                let walker = planned(vec![(PathBuf::from("other"), vec![fs::open(path)])]);
+
+                let out: Vec<fs::Result> = walker(&path).collect();
+                assert_eq!(out, vec![]);
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Create a [`Walker`] that walks like the given `walker`, but only visits the entries recorded
+    /// in the given [`Plan`], as they were recorded. Errors of the `walker` for planned entries are
+    /// visited instead, as are recorded errors for planned entries the `walker` did not visit.
+    ///
+    /// Each planned path is visited at most once, paths that were not planned visit nothing.
+    pub fn replay(walker: Walker, plan: Plan) -> Walker {
+        let plan = RefCell::new(by_path(plan));
+        Box::new(move |path| {
+            let recorded = plan.borrow_mut().remove(path.as_ref()).unwrap_or_default();
+            if recorded.is_empty() {
+                return Box::new(iter::empty());
+            }
+
+            let index: HashMap<PathBuf, usize> = recorded
+                .iter()
+                .enumerate()
+                .map(|(index, result)| (path_of(result), index))
+                .collect();
+            let recorded: Rc<RefCell<Vec<Option<fs::Result>>>> =
+                Rc::new(RefCell::new(recorded.into_iter().map(Some).collect()));
+
+            let visited = {
+                let recorded = Rc::clone(&recorded);
+                walker(path).filter_map(move |result| {
+                    let index = *index.get(&path_of(&result))?;
+                    let planned = recorded.borrow_mut().get_mut(index)?.take()?;
+                    Some(if result.is_err() { result } else { planned })
+                })
+            };
+            let missed = iter::once(()).flat_map(move |()| {
+                recorded.take().into_iter().flatten().filter(result::Result::is_err)
+            });
+
+            Box::new(visited.chain(missed))
+        })
+    }
+
+    /// Tests for the [`replay`] function.
+    #[cfg(test)]
+    mod test_replay {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{fs, recurse, replay, transform};
+
+        use std::path::PathBuf;
+        use std::rc::Rc;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn planned_entries() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let file = dir.child("file");
+                file.touch()?;
+                dir.child("unplanned").touch()?;
+
+                let plan = vec![(dir.to_path_buf(), vec![fs::open(&file), fs::open(&dir)])];
+                let walker = replay(recurse(Rc::new([])), plan);
+
+                let out: Vec<fs::Result> = walker(&dir.path()).collect();
+                assert_eq!(out, vec![fs::open(&file), fs::open(&dir)]);
+
+                let out: Vec<fs::Result> = walker(&dir.path()).collect();
+                assert_eq!(out, vec![]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn transformed_entries() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let file = dir.child("file");
+                file.touch()?;
+
+                let plan = vec![(dir.to_path_buf(), vec![fs::open(&file), fs::open(&dir)])];
+                let walker =
+                    replay(recurse(Rc::new([Box::new(transform::disallow_all_dirs)])), plan);
+
+                let out: Vec<fs::Result> = walker(&dir.path()).collect();
+                let [item] = out.as_slice() else { panic!("expected one item, got {out:?}") };
+                assert!(item.as_ref().is_err_and(|err| err.path() == dir.path()));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn missed_errors() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let missing = dir.child("missing");
+
+                let plan = vec![(dir.to_path_buf(), vec![fs::open(&missing), fs::open(&dir)])];
+                let walker = replay(recurse(Rc::new([])), plan);
+
+                let out: Vec<fs::Result> = walker(&dir.path()).collect();
+                assert_eq!(out, vec![fs::open(&dir), fs::open(&missing)]);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn unplanned_path() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let plan = vec![(PathBuf::from("other"), vec![fs::open(&file)])];
+                let walker = replay(recurse(Rc::new([])), plan);
+
+                let out: Vec<fs::Result> = walker(&file.path()).collect();
+                assert_eq!(out, vec![]);
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Get the file system path the given [`fs::Result`] is associated with.
+    fn path_of(result: &fs::Result) -> PathBuf {
+        result.as_ref().map_or_else(fs::Error::path, fs::Entry::path)
+    }
+
+    /// Visit the given [`Item`] and return some [`fs::Result`] or  [`None`] if the [`Item`] is
+    /// skipped.
+    fn visit(item: Item, transformers: &[transform::Transformer]) -> Option<fs::Result> {
+        match visit_or_stop(item, transformers) {
+            ControlFlow::Continue(visited) => visited,
+            ControlFlow::Break(()) => None,
+        }
+    }
+
+    /// Visit the given [`Item`] like [`visit`], but break if walking must stop at the [`Item`], see
+    /// [`Item::into_stopped`].
+    fn visit_or_stop(
+        item: Item,
+        transformers: &[transform::Transformer],
+    ) -> ControlFlow<(), Option<fs::Result>> {
+        let item = transformers.iter().fold(item, |item, transform| transform(item));
+        if let Some(reason) = &item.skip_reason {
+            trace!("skipped {}: {reason}", item.path().display());
+            if item.stop { ControlFlow::Break(()) } else { ControlFlow::Continue(None) }
+        } else {
+            ControlFlow::Continue(Some(item.inner))
+        }
+    }
+
+    /// Tests for the [`visit`] function.
+    #[cfg(test)]
+    mod test_visit {
+        use super::{Item, fs, transform, visit};
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+        use proptest_derive::Arbitrary;
+
+        #[proptest]
+        fn transforms(item: Item, index: TransformersIndex) {
+            prop_assume!(item.skip_reason.is_none());
+
+            let mut transformers: Vec<transform::Transformer> = vec![
+                Box::new(transform_identity),
+                Box::new(transform_identity),
+                Box::new(transform_identity),
+                Box::new(transform_identity),
+                Box::new(transform_identity),
+            ];
+
+            if let Some(transformer) = transformers.get_mut(index.0) {
+                *transformer = Box::new(transform_fixed);
+            }
+
+            prop_assert_eq!(visit(item.clone(), &transformers), Some(transform_fixed(item).inner));
+        }
+
+        #[proptest]
+        fn skips(item: Item, index: TransformersIndex) {
+            let mut transformers: Vec<transform::Transformer> = vec![
+                Box::new(transform_identity),
+                Box::new(transform_identity),
+                Box::new(transform_identity),
+                Box::new(transform_identity),
+                Box::new(transform_identity),
+            ];
+
+            if let Some(transformer) = transformers.get_mut(index.0) {
+                *transformer = Box::new(transform_skip);
+            }
+
+            prop_assert_eq!(visit(item, &transformers), None);
+        }
+
+        /// A [`super::transform::Transformer`] that does not transform the given value.
+        fn transform_identity(item: Item) -> Item {
+            item
+        }
+
+        /// A [`super::transform::Transformer`] that transforms all values into the same value.
+        fn transform_fixed(mut item: Item) -> Item {
+            item.inner = Err(fs::test_helpers::new_file("file").into_err(fs::ErrorKind::Unknown));
+            item
+        }
+
+        /// A [`super::transform::Transformer`] that transforms all values into the skipped item.
+        fn transform_skip(item: Item) -> Item {
+            item.into_skipped("some reason")
+        }
+
+        /// Struct wrapping a [`usize`] that implements [`Arbitrary`] to generate a valid index for
+        /// a list of five [`transform::Transformer`]s.
+        #[derive(Arbitrary, Debug)]
+        struct TransformersIndex(#[proptest(strategy = "0usize..=4")] usize);
+    }
+
+    /// Helpers for writing unit tests in or using this module.
+    #[cfg(test)]
+    pub mod test_helpers {
+        use super::{Item, fs};
+
+        /// Create an [`Item`] for the given [`fs::Result`] that has been visited before.
+        pub fn visited(inner: fs::Result) -> Item {
+            Item { inner, skip_reason: None, visited: true, stop: false }
+        }
+
+        impl Item {
+            /// Returns the reason why the [`Item`] should *not* be removed, if any.
+            pub fn skip_reason(&self) -> Option<String> {
+                self.skip_reason.clone()
+            }
+        }
+
+        impl From<fs::Entry> for Item {
+            fn from(entry: fs::Entry) -> Self {
+                Item { inner: Ok(entry), skip_reason: None, visited: false, stop: false }
+            }
+        }
+
+        impl From<fs::Error> for Item {
+            fn from(err: fs::Error) -> Self {
+                Item { inner: Err(err), skip_reason: None, visited: false, stop: false }
+            }
+        }
+    }
+}
+
+/// File system removal strategies.
+mod rm {
+    use super::fs;
+
+    use std::cell::RefCell;
+    use std::fs::{File, Metadata};
+    use std::hash::{BuildHasher as _, RandomState};
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::rc::Rc;
+    use std::result;
+    use std::thread;
+    use std::time::Duration;
+
+    use log::trace;
+    use owo_colors::OwoColorize as _;
+
+    /// The `Result` type for removing an [`fs::Entry`].
+    pub type Result = result::Result<String, fs::Error>;
+
+    /// A function that removes an [`fs::Entry`] in some way.
+    pub type Remover = Box<dyn Fn(fs::Entry) -> Result>;
+
+    /// Dispose of the [`fs::Entry`]; move it to the trash.
+    ///
+    /// # Errors
+    ///
+    /// If the [`fs::Entry`] can't be moved to the trash.
+    #[cfg(feature = "trash")]
+    pub fn dispose(entry: fs::Entry) -> Result {
+        trace!("dispose of {entry}");
+
+        match trash::delete(entry.path()) {
+            Ok(()) => Ok(format!("Moved {} to trash", entry.bold())),
+            Err(err) => Err(entry.into_err(err.into())),
+        }
+    }
+
+    /// Tests for the [`dispose`] function.
+    #[cfg(test)]
+    #[cfg(feature = "trash")]
+    mod test_dispose {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{dispose, fs};
+
+        use assert_fs::prelude::*;
+        use owo_colors::OwoColorize as _;
+        use predicates::prelude::*;
+
+        #[test]
+        #[cfg_attr(not(feature = "test-trash"), ignore = "Only run with the test-trash feature")]
+        fn file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let path = file.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = dispose(entry);
+                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+
+                file.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(not(feature = "test-trash"), ignore = "Only run with the test-trash feature")]
+        #[cfg(all(unix, not(target_os = "macos")))]
+        fn file_not_found_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("missing");
+
+                let path = file.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = dispose(entry);
+                assert!(out.is_err());
+
+                let err = out.expect_err("is_err() should be asserted");
+                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
+                assert_eq!(err.path(), path);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(not(feature = "test-trash"), ignore = "Only run with the test-trash feature")]
+        fn dir_empty() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let path = dir.path();
+                let entry = fs::test_helpers::new_dir(path);
+
+                let out = dispose(entry);
+                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+
+                dir.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(not(feature = "test-trash"), ignore = "Only run with the test-trash feature")]
+        fn dir_filled() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                dir.child("file").touch()?;
+
+                let path = dir.path();
+                let entry = fs::test_helpers::new_dir(path);
+
+                let out = dispose(entry);
+                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+
+                dir.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(not(feature = "test-trash"), ignore = "Only run with the test-trash feature")]
+        #[cfg(all(unix, not(target_os = "macos")))]
+        fn dir_not_found_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("missing");
+
+                let path = dir.path();
+                let entry = fs::test_helpers::new_dir(path);
+
+                let out = dispose(entry);
+                assert!(out.is_err());
+
+                let err = out.expect_err("is_err() should be asserted");
+                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
+                assert_eq!(err.path(), path);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            any(not(feature = "test-trash"), all(windows, not(feature = "test-symlink"))),
+            ignore = "Only run with the test-trash (and test-symlink on Windows) feature"
+        )]
+        fn symlink_to_file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&file)?;
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_symlink(path);
+
+                let out = dispose(entry);
+                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+
+                file.assert(predicate::path::exists());
+                link.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            any(not(feature = "test-trash"), all(windows, not(feature = "test-symlink"))),
+            ignore = "Only run with the test-trash (and test-symlink on Windows) feature"
+        )]
+        fn symlink_to_empty_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let link = test_dir.child("link");
+                link.symlink_to_dir(&dir)?;
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_symlink(path);
+
+                let out = dispose(entry);
+                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+
+                dir.assert(predicate::path::exists());
+                link.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            any(not(feature = "test-trash"), all(windows, not(feature = "test-symlink"))),
+            ignore = "Only run with the test-trash feature"
+        )]
+        fn symlink_to_filled_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let nested_file = dir.child("file");
+                nested_file.touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_dir(&dir)?;
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_symlink(path);
+
+                let out = dispose(entry);
+                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+
+                dir.assert(predicate::path::exists());
+                nested_file.assert(predicate::path::exists());
+                link.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(not(feature = "test-trash"), ignore = "Only run with the test-trash feature")]
+        #[cfg(all(unix, not(target_os = "macos")))]
+        fn symlink_not_found_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let link = test_dir.child("missing");
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_symlink(path);
+
+                let out = dispose(entry);
+                assert!(out.is_err());
+
+                let err = out.expect_err("is_err() should be asserted");
+                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
+                assert_eq!(err.path(), path);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            any(not(feature = "test-trash"), all(windows, not(feature = "test-symlink"))),
+            ignore = "Only run with the test-trash (and test-symlink on Windows) feature"
+        )]
+        fn symlink_to_file_at_location_of_a_file_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&file)?;
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = dispose(entry);
+                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+
+                file.assert(predicate::path::exists());
+                link.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            any(not(feature = "test-trash"), all(windows, not(feature = "test-symlink"))),
+            ignore = "Only run with the test-trash (and test-symlink on Windows) feature"
+        )]
+        fn symlink_to_dir_at_location_of_a_dir_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let link = test_dir.child("link");
+                link.symlink_to_dir(&dir)?;
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_dir(path);
+
+                let out = dispose(entry);
+                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+
+                dir.assert(predicate::path::exists());
+                link.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Remove the [`fs::Entry`] from the file system.
+    ///
+    /// # Errors
+    ///
+    /// If the [`fs::Entry`] can't be removed.
+    pub fn remove(entry: fs::Entry) -> Result {
+        use std::fs::{remove_dir, remove_file};
+
+        trace!("remove {entry}");
+        let path = entry.path();
+        let other_links = fs::other_links_of(&entry);
+        let result = match entry.kind() {
+            fs::EntryKind::Dir => remove_dir(path),
+            fs::EntryKind::Fifo | fs::EntryKind::File | fs::EntryKind::Socket => remove_file(path),
+            #[cfg(not(windows))]
+            fs::EntryKind::Symlink => remove_file(path),
+            #[cfg(windows)]
+            fs::EntryKind::Symlink => match std::fs::metadata(&path) {
+                Ok(metadata) if metadata.is_dir() => remove_dir(path),
+                Ok(metadata) if metadata.is_file() => remove_file(path),
+                Ok(_) => unreachable!(),
+                Err(err) => Err(err),
+            },
+        };
+
+        match result {
+            Ok(()) => Ok(format!("Removed {}{}", entry.bold(), other_links_note(other_links))),
+            Err(err) => Err(entry.into_err(err.kind().into())),
+        }
+    }
+
+    /// Describe the given number of other hard links to a removed [`fs::Entry`], if there are any,
+    /// as its data survives the removal.
+    fn other_links_note(other_links: Option<u64>) -> String {
+        match other_links {
+            None | Some(0) => String::new(),
+            Some(1) => format!(" {}", "(1 other link remains)".italic()),
+            Some(count) => format!(" {}", format!("({count} other links remain)").italic()),
+        }
+    }
+
+    /// Tests for the [`other_links_note`] function.
+    #[cfg(test)]
+    mod test_other_links_note {
+        use super::other_links_note;
+
+        use owo_colors::OwoColorize as _;
+
+        #[test]
+        fn no_other_links() {
+            assert_eq!(other_links_note(None), "");
+            assert_eq!(other_links_note(Some(0)), "");
+        }
+
+        #[test]
+        fn other_links() {
+            assert_eq!(
+                other_links_note(Some(1)),
+                format!(" {}", "(1 other link remains)".italic())
+            );
+            assert_eq!(
+                other_links_note(Some(3)),
+                format!(" {}", "(3 other links remain)".italic())
+            );
+        }
+    }
+
+    /// Tests for the [`remove`] function.
+    #[cfg(test)]
+    mod test_remove {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{fs, remove};
+
+        #[cfg(unix)]
+        use std::fs::hard_link;
+
+        use assert_fs::prelude::*;
+        use owo_colors::OwoColorize as _;
+        use predicates::prelude::*;
+
+        #[test]
+        fn file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let path = file.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = remove(entry);
+                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+
+                file.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn file_hard_linked() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                hard_link(&file, test_dir.child("link1"))?;
+                hard_link(&file, test_dir.child("link2"))?;
+
+                let path = file.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = remove(entry);
+                assert_eq!(
+                    out,
+                    Ok(format!(
+                        "Removed {} {}",
+                        path.display().bold(),
+                        "(2 other links remain)".italic()
+                    ))
+                );
+
+                file.assert(predicate::path::missing());
+                test_dir.child("link1").assert(predicate::path::exists());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn file_not_found_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("missing");
+
+                let path = file.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = remove(entry);
+                assert!(out.is_err());
+
+                let err = out.expect_err("is_err() should be asserted");
+                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
+                assert_eq!(err.path(), path);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir_empty() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let path = dir.path();
+                let entry = fs::test_helpers::new_dir(path);
+
+                let out = remove(entry);
+                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+
+                dir.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir_filled_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                dir.child("file").touch()?;
+
+                let path = dir.path();
+                let entry = fs::test_helpers::new_dir(path);
+
+                let out = remove(entry);
+                assert!(out.is_err());
+
+                let err = out.expect_err("is_err() should be asserted");
+                assert_eq!(err.kind(), fs::ErrorKind::DirectoryNotEmpty);
+                assert_eq!(err.path(), path);
+
+                dir.assert(predicate::path::exists());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir_not_found_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("missing");
+
+                let path = dir.path();
+                let entry = fs::test_helpers::new_dir(path);
+
+                let out = remove(entry);
+                assert!(out.is_err());
+
+                let err = out.expect_err("is_err() should be asserted");
+                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
+                assert_eq!(err.path(), path);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&file)?;
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_symlink(path);
+
+                let out = remove(entry);
+                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+
+                file.assert(predicate::path::exists());
+                link.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_empty_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let link = test_dir.child("link");
+                link.symlink_to_dir(&dir)?;
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_symlink(path);
+
+                let out = remove(entry);
+                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+
+                dir.assert(predicate::path::exists());
+                link.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_filled_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let nested_file = dir.child("file");
+                nested_file.touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_dir(&dir)?;
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_symlink(path);
+
+                let out = remove(entry);
+                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+
+                dir.assert(predicate::path::exists());
+                nested_file.assert(predicate::path::exists());
+                link.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn symlink_not_found_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let link = test_dir.child("missing");
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_symlink(path);
+
+                let out = remove(entry);
+                assert!(out.is_err());
+
+                let err = out.expect_err("is_err() should be asserted");
+                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
+                assert_eq!(err.path(), path);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_file_at_location_of_a_file_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&file)?;
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = remove(entry);
+                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+
+                file.assert(predicate::path::exists());
+                link.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg(not(windows))]
+        fn symlink_to_dir_at_location_of_a_dir_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let link = test_dir.child("link");
+                link.symlink_to_dir(&dir)?;
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_dir(path);
+
+                let out = remove(entry.clone());
+                assert_eq!(out, Err(entry.into_err(fs::ErrorKind::NotADirectory)));
+
+                dir.assert(predicate::path::exists());
+                link.assert(predicate::path::exists());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg(windows)]
+        #[cfg_attr(
+            not(feature = "test-symlink"),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_dir_at_location_of_a_dir_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let link = test_dir.child("link");
+                link.symlink_to_dir(&dir)?;
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_dir(path);
+
+                let out = remove(entry);
+                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+
+                dir.assert(predicate::path::exists());
+                link.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+    }
+
+    /// How to gradually truncate large files before removing them, see [`remove_gradually`].
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub struct Gradual {
+        /// The number of bytes to truncate at a time. Files no larger than this are not truncated.
+        pub chunk: u64,
+        /// The time to pause after every truncation.
+        pub pause: Duration,
+    }
+
+    /// Create a [`Remover`] that gradually truncates regular files larger than one chunk before
+    /// removing them, to avoid freeing a lot of space at once (see [`truncate_gradually`]).
+    ///
+    /// Files with multiple (hard) links are not truncated, only removed.
+    ///
+    /// See also [`remove`].
+    pub fn remove_gradually(gradual: Gradual) -> Remover {
+        Box::new(move |entry| {
+            if entry.kind() == fs::EntryKind::File
+                && fs::other_links_of(&entry).is_none_or(|count| count == 0)
+            {
+                match truncate_gradually(&entry.path(), gradual) {
+                    Ok(()) => {},
+                    Err(err) if err.kind() == io::ErrorKind::PermissionDenied => {
+                        trace!("cannot truncate {entry}: {err}");
+                    },
+                    Err(err) => return Err(entry.into_err(err.kind().into())),
+                }
+            }
+
+            remove(entry)
+        })
+    }
+
+    /// Tests for the [`remove_gradually`] function.
+    #[cfg(test)]
+    mod test_remove_gradually {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Gradual, fs, remove_gradually};
+
+        use std::fs::File;
+        #[cfg(unix)]
+        use std::fs::{hard_link, metadata};
+        use std::time::Duration;
+
+        use assert_fs::prelude::*;
+        use owo_colors::OwoColorize as _;
+        use predicates::prelude::*;
+
+        /// A [`Gradual`] truncating one mebibyte at a time without pausing.
+        const GRADUAL: Gradual = Gradual { chunk: 1 << 20, pause: Duration::ZERO };
+
+        #[test]
+        fn file_sparse() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                File::create(&file)?.set_len(10 << 20)?;
+
+                let path = file.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = remove_gradually(GRADUAL)(entry);
+                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+
+                file.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn file_read_only() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                let handle = File::create(&file)?;
+                handle.set_len(10 << 20)?;
+                let mut permissions = handle.metadata()?.permissions();
+                permissions.set_readonly(true);
+                handle.set_permissions(permissions)?;
+
+                let path = file.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = remove_gradually(GRADUAL)(entry);
+                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+
+                file.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn file_small() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+
+                let path = file.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = remove_gradually(GRADUAL)(entry);
+                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+
+                file.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn file_not_found_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("missing");
+
+                let path = file.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = remove_gradually(GRADUAL)(entry);
+                assert!(out.is_err());
+
+                let err = out.expect_err("is_err() should be asserted");
+                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
+                assert_eq!(err.path(), path);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn file_with_multiple_links() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                File::create(&file)?.set_len(10 << 20)?;
+                let link = test_dir.child("link");
+                hard_link(&file, &link)?;
+
+                let entry = fs::test_helpers::new_file(file.path());
+
+                let out = remove_gradually(GRADUAL)(entry);
+                assert!(out.is_ok());
+
+                file.assert(predicate::path::missing());
+                assert_eq!(metadata(&link)?.len(), 10 << 20);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir_empty() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let path = dir.path();
+                let entry = fs::test_helpers::new_dir(path);
+
+                let out = remove_gradually(GRADUAL)(entry);
+                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+
+                dir.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Truncate the regular file at the given [`Path`] one [`Gradual::chunk`] at a time, pausing
+    /// after every truncation, until it is no larger than one chunk. Anything other than a regular
+    /// file is left alone, including a file that is replaced while it is being opened.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be opened or truncated.
+    fn truncate_gradually(path: &Path, gradual: Gradual) -> io::Result<()> {
+        use std::fs::symlink_metadata;
+
+        let metadata = symlink_metadata(path)?;
+        if !metadata.is_file() {
+            return Ok(());
+        }
+
+        let Some(file) = open_for_writing(path, &metadata)? else {
+            return Ok(());
+        };
+        let mut len = file.metadata()?.len();
+        while len > gradual.chunk {
+            len = len.saturating_sub(gradual.chunk);
+            file.set_len(len)?;
+            trace!("truncated {} to {len} bytes", path.display());
+            thread::sleep(gradual.pause);
+        }
+
+        Ok(())
+    }
+
+    /// Tests for the [`truncate_gradually`] function.
+    #[cfg(test)]
+    mod test_truncate_gradually {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Gradual, truncate_gradually};
+
+        use std::fs::{File, metadata};
+        use std::io;
+        use std::time::{Duration, Instant};
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn sparse_file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                File::create(&file)?.set_len(10 << 20)?;
+
+                let gradual = Gradual { chunk: 1 << 20, pause: Duration::ZERO };
+                truncate_gradually(file.path(), gradual)?;
+                assert_eq!(metadata(&file)?.len(), 1 << 20);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn sparse_file_uneven() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                File::create(&file)?.set_len((10 << 20) + 1)?;
+
+                let gradual = Gradual { chunk: 1 << 20, pause: Duration::ZERO };
+                truncate_gradually(file.path(), gradual)?;
+                assert_eq!(metadata(&file)?.len(), 1);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn pauses() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                File::create(&file)?.set_len(4 << 20)?;
+
+                let start = Instant::now();
+                let gradual = Gradual { chunk: 1 << 20, pause: Duration::from_millis(20) };
+                truncate_gradually(file.path(), gradual)?;
+                assert!(start.elapsed() >= Duration::from_millis(60));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn small_file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+
+                let gradual = Gradual { chunk: 1 << 20, pause: Duration::ZERO };
+                truncate_gradually(file.path(), gradual)?;
+                file.assert("Hello world!");
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn symlink() -> TestResult {
+            with_test_dir(|test_dir| {
+                let target = test_dir.child("target");
+                File::create(&target)?.set_len(10 << 20)?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&target)?;
+
+                let gradual = Gradual { chunk: 1 << 20, pause: Duration::ZERO };
+                truncate_gradually(link.path(), gradual)?;
+                assert_eq!(metadata(&target)?.len(), 10 << 20);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn not_found() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("missing");
+
+                let gradual = Gradual { chunk: 1 << 20, pause: Duration::ZERO };
+                let out = truncate_gradually(file.path(), gradual);
+                assert_eq!(out.map_err(|err| err.kind()), Err(io::ErrorKind::NotFound));
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Open the regular file at the given [`Path`] for writing, without following symbolic links.
+    /// Returns `None` if it is no longer the file described by the `expected` [`Metadata`], for
+    /// example because it was replaced by a symbolic link after the metadata was read.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be opened.
+    fn open_for_writing(path: &Path, expected: &Metadata) -> io::Result<Option<File>> {
+        use std::fs::OpenOptions;
+
+        let mut options = OpenOptions::new();
+        options.write(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt as _;
+
+            options.custom_flags(libc::O_NOFOLLOW | libc::O_NONBLOCK);
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::fs::OpenOptionsExt as _;
+
+            /// Open a symbolic link itself rather than its target.
+            const FILE_FLAG_OPEN_REPARSE_POINT: u32 = 0x0020_0000;
+
+            options.custom_flags(FILE_FLAG_OPEN_REPARSE_POINT);
+        }
+
+        let file = match options.open(path) {
+            Ok(file) => file,
+            #[cfg(unix)]
+            Err(err) if matches!(err.raw_os_error(), Some(libc::ELOOP | libc::EMLINK)) => {
+                return Ok(None); // a symbolic link, which O_NOFOLLOW refuses to open
+            },
+            Err(err) => return Err(err),
+        };
+
+        let metadata = file.metadata()?;
+        if metadata.is_file() && fs::identity_of(&metadata) == fs::identity_of(expected) {
+            Ok(Some(file))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Tests for the [`open_for_writing`] function.
+    #[cfg(test)]
+    mod test_open_for_writing {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::open_for_writing;
+
+        #[cfg(unix)]
+        use std::fs::rename;
+        use std::fs::{remove_file, symlink_metadata};
+        use std::io;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn unchanged() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let metadata = symlink_metadata(&file)?;
+                assert!(open_for_writing(file.path(), &metadata)?.is_some());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn replaced_by_symlink() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let target = test_dir.child("target");
+                target.write_str("Hello world!")?;
+
+                let metadata = symlink_metadata(&file)?;
+                remove_file(&file)?;
+                file.symlink_to_file(&target)?;
+
+                assert!(open_for_writing(file.path(), &metadata)?.is_none());
+                target.assert("Hello world!");
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn replaced_by_file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let other = test_dir.child("other");
+                other.write_str("Hello world!")?;
+
+                let metadata = symlink_metadata(&file)?;
+                rename(&other, &file)?;
+
+                assert!(open_for_writing(file.path(), &metadata)?.is_none());
+                file.assert("Hello world!");
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn not_found() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let metadata = symlink_metadata(&file)?;
+                remove_file(&file)?;
+
+                let out = open_for_writing(file.path(), &metadata);
+                assert_eq!(out.map(|_| ()).map_err(|err| err.kind()), Err(io::ErrorKind::NotFound));
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Pretend to dispose of the [`fs::Entry`].
+    ///
+    /// See also [`dispose`].
+    ///
+    /// # Errors
+    ///
+    /// This function will never return an error.
+    #[cfg(feature = "trash")]
+    #[allow(clippy::needless_pass_by_value, reason = "Should consume since file is removed")]
+    #[allow(clippy::unnecessary_wraps, reason = "Wrap for consistent function signature")]
+    pub fn show_dispose(entry: fs::Entry) -> Result {
+        Ok(format!("Would move {} to trash", entry.bold()))
+    }
+
+    /// Tests for the [`show_dispose`] function.
+    #[cfg(test)]
+    #[cfg(feature = "trash")]
+    mod test_show_dispose {
+        use super::{fs, show_dispose};
+
+        use owo_colors::OwoColorize as _;
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn anything(entry: fs::Entry) {
+            let path = entry.path();
+            let out = show_dispose(entry);
+            prop_assert_eq!(out, Ok(format!("Would move {} to trash", path.display().bold())));
+        }
+    }
+
+    /// Pretend to remove the [`fs::Entry`].
+    ///
+    /// See also [`remove`].
+    ///
+    /// # Errors
+    ///
+    /// This function will never return an error.
+    #[allow(clippy::needless_pass_by_value, reason = "Should consume since file is removed")]
+    #[allow(clippy::unnecessary_wraps, reason = "Wrap for consistent function signature")]
+    pub fn show_remove(entry: fs::Entry) -> Result {
+        let other_links = fs::other_links_of(&entry);
+        Ok(format!("Would remove {}{}", entry.bold(), other_links_note(other_links)))
+    }
+
+    /// Tests for the [`show_remove`] function.
+    #[cfg(test)]
+    mod test_show_remove {
+        use super::{fs, show_remove};
+
+        use owo_colors::OwoColorize as _;
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn anything(entry: fs::Entry) {
+            let path = entry.path();
+            let out = show_remove(entry);
+            prop_assert_eq!(out, Ok(format!("Would remove {}", path.display().bold())));
+        }
+    }
+
+    /// The tip for refusing to shred a file that has other hard links.
+    const TIP_OTHER_LINKS: &str = "shredding would overwrite its other links too";
+
+    /// The tip for failing to shred a file that cannot be written to.
+    const TIP_READ_ONLY: &str = "use 'chmod u+w' to allow shredding it";
+
+    /// The tip for refusing to shred a file that was replaced while it was being opened.
+    const TIP_REPLACED: &str = "it was replaced while being shredded";
+
+    /// Create a [`Remover`] that shreds regular files, overwriting their contents the given number
+    /// of `passes` before removing them (see [`shred_file`]). Other entries are only removed.
+    ///
+    /// Files with multiple (hard) links are refused, as shredding would destroy the data of the
+    /// other links too.
+    ///
+    /// See also [`remove`].
+    pub fn shred(passes: u8) -> Remover {
+        use std::fs::symlink_metadata;
+
+        Box::new(move |entry| {
+            let path = entry.path();
+            let metadata = match symlink_metadata(&path) {
+                Ok(metadata) if entry.kind() == fs::EntryKind::File && metadata.is_file() => {
+                    metadata
+                },
+                _ => return remove(entry),
+            };
+
+            if fs::other_links_of(&entry).is_some_and(|count| count > 0) {
+                return Err(entry.into_err(fs::ErrorKind::Refused).with_tip(TIP_OTHER_LINKS));
+            }
+
+            trace!("shred {entry}");
+            let mut file = match open_for_writing(&path, &metadata) {
+                Ok(Some(file)) => file,
+                Ok(None) => {
+                    return Err(entry.into_err(fs::ErrorKind::Refused).with_tip(TIP_REPLACED));
+                },
+                Err(err) if err.kind() == io::ErrorKind::PermissionDenied => {
+                    return Err(entry
+                        .into_err(fs::ErrorKind::PermissionDenied)
+                        .with_tip(TIP_READ_ONLY));
+                },
+                Err(err) => return Err(entry.into_err(err.kind().into())),
+            };
+
+            let shredded = shred_file(&OsFs, &mut file, passes).and_then(|()| {
+                drop(file);
+                unlink_renamed(&OsFs, &path)
+            });
+            match shredded {
+                Ok(()) => Ok(format!("Shredded {}", entry.bold())),
+                Err(err) => Err(entry.into_err(err.kind().into())),
+            }
+        })
+    }
+
+    /// Tests for the [`shred`] function.
+    #[cfg(test)]
+    mod test_shred {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{fs, shred};
+
+        #[cfg(unix)]
+        use std::fs::hard_link;
+
+        use assert_fs::prelude::*;
+        use owo_colors::OwoColorize as _;
+        use predicates::prelude::*;
+
+        #[test]
+        fn file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+
+                let path = file.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = shred(3)(entry);
+                assert_eq!(out, Ok(format!("Shredded {}", path.display().bold())));
+
+                file.assert(predicate::path::missing());
+                test_dir.assert(predicate::path::is_dir());
+                assert_eq!(test_dir.read_dir()?.count(), 0);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn file_not_found_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("missing");
+
+                let path = file.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = shred(3)(entry);
+                assert!(out.is_err());
+
+                let err = out.expect_err("is_err() should be asserted");
+                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
+                assert_eq!(err.path(), path);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn file_with_multiple_links() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+                let link = test_dir.child("link");
+                hard_link(&file, &link)?;
+
+                let path = file.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = shred(3)(entry);
+                assert!(out.is_err());
+
+                let err = out.expect_err("is_err() should be asserted");
+                assert_eq!(err.kind(), fs::ErrorKind::Refused);
+                assert_eq!(err.path(), path);
+
+                file.assert("Hello world!");
+                link.assert("Hello world!");
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir_empty() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let path = dir.path();
+                let entry = fs::test_helpers::new_dir(path);
+
+                let out = shred(3)(entry);
+                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+
+                dir.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink_to_file_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&file)?;
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let out = shred(3)(entry);
+                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+
+                file.assert("Hello world!");
+                link.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Shred the given open regular `file` using the given [`ShredFs`]. The contents of the file
+    /// are overwritten the given number of `passes`, using random data for all but the last pass
+    /// which uses zeros. The file is then truncated. See also [`unlink_renamed`].
+    ///
+    /// # Errors
+    ///
+    /// If any of the steps fails, in which case the file may be left partially shredded.
+    fn shred_file<F: ShredFs>(shred_fs: &F, file: &mut File, passes: u8) -> io::Result<()> {
+        use std::io::Read as _;
+
+        let len = file.metadata()?.len();
+        for pass in (1..=passes).rev() {
+            if pass == 1 {
+                shred_fs.overwrite(file, &mut io::repeat(0).take(len))?;
+            } else {
+                shred_fs.overwrite(file, &mut Random::new().take(len))?;
+            }
+        }
+
+        shred_fs.truncate(file)
+    }
+
+    /// Rename the file at the given [`Path`] to a random name using the given [`ShredFs`] and
+    /// then unlink it, so that its name is not left behind either.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be renamed or unlinked.
+    fn unlink_renamed<F: ShredFs>(shred_fs: &F, path: &Path) -> io::Result<()> {
+        let renamed = rename_unused(
+            |renamed| shred_fs.rename(path, renamed),
+            |attempt| random_name_for(path, attempt),
+        )?;
+        shred_fs.unlink(&renamed)
+    }
+
+    /// Tests for the [`shred_file`] and [`unlink_renamed`] functions.
+    #[cfg(test)]
+    mod test_shred_file {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{OsFs, ShredFs, shred_file, unlink_renamed};
+
+        use std::cell::RefCell;
+        use std::fs::{File, OpenOptions, read};
+        use std::io;
+        use std::path::{Path, PathBuf};
+
+        use assert_fs::prelude::*;
+        use predicates::prelude::*;
+
+        #[test]
+        fn overwritten_before_unlinked() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+
+                let recording = Recording::new(file.path());
+                shred_path(&recording, file.path(), 3)?;
+
+                let events = recording.events.into_inner();
+                let ops: Vec<&str> = events.iter().map(|(op, _, _)| *op).collect();
+                assert_eq!(
+                    ops,
+                    ["overwrite", "overwrite", "overwrite", "truncate", "rename", "unlink"]
+                );
+
+                let contents: Vec<&[u8]> =
+                    events.iter().map(|(_, _, contents)| contents.as_slice()).collect();
+                let [random1, random2, zeros, truncated, renamed, unlinked] = contents.as_slice()
+                else {
+                    unreachable!("the number of events should be asserted");
+                };
+                for random in [random1, random2] {
+                    assert_eq!(random.len(), "Hello world!".len());
+                    assert_ne!(*random, b"Hello world!");
+                }
+                assert_eq!(*zeros, [0; 12]);
+                assert_eq!(*truncated, []);
+                assert_eq!(*renamed, []);
+                assert_eq!(*unlinked, []);
+
+                let paths: Vec<&Path> = events.iter().map(|(_, path, _)| path.as_path()).collect();
+                let [.., renamed, unlinked] = paths.as_slice() else {
+                    unreachable!("the number of events should be asserted");
+                };
+                assert_ne!(*unlinked, file.path());
+                assert_eq!(unlinked, renamed);
+                assert_eq!(unlinked.parent(), file.path().parent());
+
+                file.assert(predicate::path::missing());
+                assert_eq!(test_dir.read_dir()?.count(), 0);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn one_pass() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+
+                let recording = Recording::new(file.path());
+                shred_path(&recording, file.path(), 1)?;
+
+                let events = recording.events.into_inner();
+                let overwrites: Vec<&[u8]> = events
+                    .iter()
+                    .filter(|(op, _, _)| *op == "overwrite")
+                    .map(|(_, _, contents)| contents.as_slice())
+                    .collect();
+                assert_eq!(overwrites, [[0; 12]]);
+
+                file.assert(predicate::path::missing());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn empty() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                shred_path(&OsFs, file.path(), 3)?;
+                file.assert(predicate::path::missing());
+                assert_eq!(test_dir.read_dir()?.count(), 0);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn not_found() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("missing");
+
+                let out = shred_path(&OsFs, file.path(), 3);
+                assert_eq!(out.map_err(|err| err.kind()), Err(io::ErrorKind::NotFound));
+
+                Ok(())
+            })
+        }
+
+        /// Shred the file at the given [`Path`] like [`super::shred`] does, using the given
+        /// [`ShredFs`].
+        fn shred_path<F: ShredFs>(shred_fs: &F, path: &Path, passes: u8) -> io::Result<()> {
+            let mut file = OpenOptions::new().write(true).open(path)?;
+            shred_file(shred_fs, &mut file, passes)?;
+            drop(file);
+            unlink_renamed(shred_fs, path)
+        }
+
+        /// A [`ShredFs`] that performs its operations on the operating system, recording every
+        /// operation along with the path of the file and its contents right after it.
+        struct Recording {
+            /// The operations, by name, the paths and the contents in the order they happened.
+            events: RefCell<Vec<(&'static str, PathBuf, Vec<u8>)>>,
+            /// The path of the file being shredded.
+            path: RefCell<PathBuf>,
+        }
+
+        impl Recording {
+            /// Create a new [`Recording`] for shredding the file at the given [`Path`].
+            fn new(path: &Path) -> Self {
+                Self { events: RefCell::default(), path: RefCell::new(path.to_owned()) }
+            }
+
+            /// Record the operation `op` on the file currently being shredded.
+            fn record(&self, op: &'static str) -> io::Result<()> {
+                let path = self.path.borrow().clone();
+                let contents = read(&path)?;
+                self.events.borrow_mut().push((op, path, contents));
+                Ok(())
+            }
+        }
+
+        impl ShredFs for Recording {
+            fn overwrite(&self, file: &mut File, data: &mut dyn io::Read) -> io::Result<()> {
+                OsFs.overwrite(file, data)?;
+                self.record("overwrite")
+            }
+
+            fn truncate(&self, file: &File) -> io::Result<()> {
+                OsFs.truncate(file)?;
+                self.record("truncate")
+            }
+
+            fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+                OsFs.rename(from, to)?;
+                self.path.replace(to.to_owned());
+                self.record("rename")
+            }
+
+            fn unlink(&self, path: &Path) -> io::Result<()> {
+                self.path.replace(path.to_owned());
+                self.record("unlink")?;
+                OsFs.unlink(path)
+            }
+        }
+    }
+
+    /// The file system operations used to shred a file, see [`shred_file`].
+    trait ShredFs {
+        /// Overwrite the `file` from the start with the given `data`, flushing it to the disk.
+        fn overwrite(&self, file: &mut File, data: &mut dyn io::Read) -> io::Result<()>;
+
+        /// Truncate the `file` to be empty, flushing it to the disk.
+        fn truncate(&self, file: &File) -> io::Result<()>;
+
+        /// Rename the file at the path `from` to the path `to`, unless something exists at `to`.
+        fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+
+        /// Unlink the file at the given [`Path`].
+        fn unlink(&self, path: &Path) -> io::Result<()>;
+    }
+
+    /// The [`ShredFs`] of the operating system.
+    struct OsFs;
+
+    impl ShredFs for OsFs {
+        fn overwrite(&self, file: &mut File, data: &mut dyn io::Read) -> io::Result<()> {
+            use std::io::{Seek as _, SeekFrom};
+
+            file.seek(SeekFrom::Start(0))?;
+            io::copy(data, file)?;
+            file.sync_data()
+        }
+
+        fn truncate(&self, file: &File) -> io::Result<()> {
+            file.set_len(0)?;
+            file.sync_all()
+        }
+
+        fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+            rename_no_replace(from, to)
+        }
+
+        fn unlink(&self, path: &Path) -> io::Result<()> {
+            use std::fs::remove_file;
+
+            remove_file(path)
+        }
+    }
+
+    /// The prefix of the name of tombstones, see [`detach`].
+    pub const TOMBSTONE_PREFIX: &str = ".rust-rm-tombstone-";
+
+    /// The tombstones of entries detached by [`detach`].
+    pub type Tombstones = Rc<RefCell<Vec<PathBuf>>>;
+
+    /// Create a [`Remover`] that detaches the [`fs::Entry`]; renames it to a hidden tombstone in
+    /// the same directory, so that its path is free immediately. The tombstone is recorded in
+    /// `tombstones` so that it can be removed later.
+    pub fn detach(tombstones: Tombstones) -> Remover {
+        Box::new(move |entry| {
+            trace!("detach {entry}");
+
+            let path = entry.path();
+            let detached = rename_unused(
+                |tombstone| rename_no_replace(&path, tombstone),
+                |_| tombstone_for(&path),
+            );
+            match detached {
+                Ok(tombstone) => {
+                    tombstones.borrow_mut().push(tombstone);
+                    Ok(format!("Detached {}", entry.bold()))
+                },
+                Err(err) => Err(entry.into_err(err.kind().into())),
+            }
+        })
+    }
+
+    /// Tests for the [`detach`] function.
+    #[cfg(test)]
+    mod test_detach {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{Tombstones, detach, fs, is_tombstone};
+
+        use std::fs::read_to_string;
+
+        use assert_fs::prelude::*;
+        use owo_colors::OwoColorize as _;
+        use predicates::prelude::*;
+
+        #[test]
+        fn file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+
+                let path = file.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let tombstones = Tombstones::default();
+                let out = detach(tombstones.clone())(entry);
+                assert_eq!(out, Ok(format!("Detached {}", path.display().bold())));
+
+                file.assert(predicate::path::missing());
+
+                let tombstones = tombstones.take();
+                assert_eq!(tombstones.len(), 1);
+                let tombstone = tombstones.first().ok_or("no tombstone")?;
+                assert!(is_tombstone(tombstone));
+                assert_eq!(tombstone.parent(), path.parent());
+                assert_eq!(read_to_string(tombstone)?, "Hello world!");
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir_filled() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file").touch()?;
+
+                let path = dir.path();
+                let entry = fs::test_helpers::new_dir(path);
+
+                let tombstones = Tombstones::default();
+                let out = detach(tombstones.clone())(entry);
+                assert_eq!(out, Ok(format!("Detached {}", path.display().bold())));
+
+                dir.assert(predicate::path::missing());
+
+                let tombstones = tombstones.take();
+                let tombstone = tombstones.first().ok_or("no tombstone")?;
+                assert!(tombstone.join("file").exists());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn not_found_toctou() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("missing");
+
+                let path = file.path();
+                let entry = fs::test_helpers::new_file(path);
+
+                let tombstones = Tombstones::default();
+                let out = detach(tombstones.clone())(entry);
+
+                let err = out.expect_err("a missing entry cannot be detached");
+                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
+                assert_eq!(err.path(), path);
+                assert!(tombstones.borrow().is_empty());
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Get a random path for a tombstone next to the given [`Path`].
+    ///
+    /// # Errors
+    ///
+    /// If no random name can be generated.
+    fn tombstone_for(path: &Path) -> io::Result<PathBuf> {
+        Ok(path.with_file_name(format!("{TOMBSTONE_PREFIX}{}", random_name(12)?)))
+    }
+
+    /// Check if the given [`Path`] is (the name of) a tombstone, see [`detach`].
+    pub fn is_tombstone(path: &Path) -> bool {
+        path.file_name().and_then(|name| name.to_str()).is_some_and(|name| {
+            name.len() > TOMBSTONE_PREFIX.len() && name.starts_with(TOMBSTONE_PREFIX)
+        })
+    }
+
+    /// Tests for the [`is_tombstone`] function.
+    #[cfg(test)]
+    mod test_is_tombstone {
+        use crate::test_helpers::TestResult;
+
+        use super::{is_tombstone, tombstone_for};
+
+        use std::path::Path;
+
+        #[test]
+        fn tombstones() -> TestResult {
+            assert!(is_tombstone(&tombstone_for(Path::new("file"))?));
+            assert!(is_tombstone(&tombstone_for(Path::new("dir/file"))?));
+            assert!(is_tombstone(Path::new("dir/.rust-rm-tombstone-abc")));
+
+            Ok(())
+        }
+
+        #[test]
+        fn not_tombstones() {
+            assert!(!is_tombstone(Path::new("file")));
+            assert!(!is_tombstone(Path::new(".rust-rm-tombstone-")));
+            assert!(!is_tombstone(Path::new(".rust-rm-tombstone-abc/file")));
+            assert!(!is_tombstone(Path::new("rust-rm-tombstone-abc")));
+        }
+    }
+
+    /// The number of attempts after which [`random_name_for`] uses a name that is one character
+    /// longer, so that short names cannot run out.
+    const ATTEMPTS_PER_LENGTH: usize = 4;
+
+    /// Get a path next to the given [`Path`] with a random name of the same length for the given
+    /// (zero-based) `attempt`, or a longer one after [`ATTEMPTS_PER_LENGTH`] attempts.
+    ///
+    /// # Errors
+    ///
+    /// If no random name can be generated.
+    fn random_name_for(path: &Path, attempt: usize) -> io::Result<PathBuf> {
+        use std::ffi::OsStr;
+
+        let len = path.file_name().map_or(0, OsStr::len).max(1);
+        let len = len.saturating_add(attempt / ATTEMPTS_PER_LENGTH);
+        Ok(path.with_file_name(random_name(len)?))
+    }
+
+    /// Tests for the [`random_name_for`] function.
+    #[cfg(test)]
+    mod test_random_name_for {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{ATTEMPTS_PER_LENGTH, random_name_for};
+
+        use std::ffi::OsStr;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn random_name_of_same_length() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let out = random_name_for(file.path(), 0)?;
+                assert_eq!(out.parent(), file.path().parent());
+                assert_eq!(out.file_name().map(OsStr::len), Some("file".len()));
+                assert_ne!(out, file.path());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn longer_for_later_attempts() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("f");
+
+                let out = random_name_for(file.path(), ATTEMPTS_PER_LENGTH - 1)?;
+                assert_eq!(out.file_name().map(OsStr::len), Some(1));
+
+                let out = random_name_for(file.path(), ATTEMPTS_PER_LENGTH)?;
+                assert_eq!(out.file_name().map(OsStr::len), Some(2));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn differs_every_time() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("long_file_name");
+
+                assert_ne!(random_name_for(file.path(), 0)?, random_name_for(file.path(), 0)?);
+
+                Ok(())
+            })
+        }
+    }
+
+    /// The maximum number of paths tried by [`rename_unused`].
+    const MAX_RENAME_ATTEMPTS: usize = 16;
+
+    /// Rename something using `rename` to the first path produced by `candidate`, for the attempt,
+    /// at which nothing exists yet. Returns the path it was renamed to.
+    ///
+    /// # Errors
+    ///
+    /// If every path tried is in use, `candidate` fails, or renaming fails otherwise.
+    fn rename_unused<R, C>(rename: R, candidate: C) -> io::Result<PathBuf>
+    where
+        R: Fn(&Path) -> io::Result<()>,
+        C: Fn(usize) -> io::Result<PathBuf>,
+    {
+        for attempt in 0..MAX_RENAME_ATTEMPTS {
+            let candidate = candidate(attempt)?;
+            match rename(&candidate) {
+                Ok(()) => return Ok(candidate),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {},
+                Err(err) => return Err(err),
+            }
+        }
+
+        Err(io::ErrorKind::AlreadyExists.into())
+    }
+
+    /// Tests for the [`rename_unused`] function.
+    #[cfg(test)]
+    mod test_rename_unused {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{MAX_RENAME_ATTEMPTS, rename_no_replace, rename_unused};
+
+        use std::cell::Cell;
+        use std::io;
+
+        use assert_fs::prelude::*;
+        use predicates::prelude::*;
+
+        #[test]
+        fn skips_used_paths() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+                let used = test_dir.child("used");
+                used.write_str("Hey world!")?;
+                let unused = test_dir.child("unused");
+
+                let out = rename_unused(
+                    |to| rename_no_replace(file.path(), to),
+                    |attempt| {
+                        Ok(if attempt == 0 { used.to_path_buf() } else { unused.to_path_buf() })
+                    },
+                )?;
+                assert_eq!(out, unused.path());
+
+                file.assert(predicate::path::missing());
+                used.assert("Hey world!");
+                unused.assert("Hello world!");
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn all_used() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+                let used = test_dir.child("used");
+                used.write_str("Hey world!")?;
+
+                let attempts = Cell::new(0);
+                let out = rename_unused(
+                    |to| rename_no_replace(file.path(), to),
+                    |attempt| {
+                        attempts.set(attempt + 1);
+                        Ok(used.to_path_buf())
+                    },
+                );
+                assert_eq!(out.map_err(|err| err.kind()), Err(io::ErrorKind::AlreadyExists));
+                assert_eq!(attempts.get(), MAX_RENAME_ATTEMPTS);
+
+                file.assert("Hello world!");
+                used.assert("Hey world!");
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Rename the entry at the path `from` to the path `to`, failing if something exists at `to`
+    /// instead of replacing it. Where this cannot be done atomically, `to` is checked beforehand.
+    ///
+    /// # Errors
+    ///
+    /// If something exists at `to` or the entry cannot be renamed.
+    fn rename_no_replace(from: &Path, to: &Path) -> io::Result<()> {
+        use std::fs::{rename, symlink_metadata};
+
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        match rename_exclusive(from, to) {
+            Err(err) if matches!(err.raw_os_error(), Some(libc::EINVAL | libc::ENOSYS)) => {
+                trace!("cannot rename {} exclusively: {err}", from.display());
+            },
+            result => return result,
+        }
+
+        if symlink_metadata(to).is_ok() {
+            return Err(io::ErrorKind::AlreadyExists.into());
+        }
+
+        rename(from, to)
+    }
+
+    /// Rename the entry at the path `from` to the path `to`, atomically failing if something exists
+    /// at `to`, see [`rename_no_replace`].
+    ///
+    /// # Errors
+    ///
+    /// If something exists at `to`, the entry cannot be renamed, or the file system does not
+    /// support it.
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn rename_exclusive(from: &Path, to: &Path) -> io::Result<()> {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt as _;
+
+        let from = CString::new(from.as_os_str().as_bytes())?;
+        let to = CString::new(to.as_os_str().as_bytes())?;
+
+        // SAFETY: both paths are valid, NUL-terminated C strings that outlive the call.
+        #[cfg(target_os = "linux")]
+        let code = unsafe {
+            libc::renameat2(
+                libc::AT_FDCWD,
+                from.as_ptr(),
+                libc::AT_FDCWD,
+                to.as_ptr(),
+                libc::RENAME_NOREPLACE,
+            )
+        };
+        // SAFETY: both paths are valid, NUL-terminated C strings that outlive the call.
+        #[cfg(target_os = "macos")]
+        let code = unsafe { libc::renamex_np(from.as_ptr(), to.as_ptr(), libc::RENAME_EXCL) };
+
+        if code == 0 { Ok(()) } else { Err(io::Error::last_os_error()) }
+    }
+
+    /// Tests for the [`rename_no_replace`] function.
+    #[cfg(test)]
+    mod test_rename_no_replace {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::rename_no_replace;
+
+        use std::io;
+
+        use assert_fs::prelude::*;
+        use predicates::prelude::*;
+
+        #[test]
+        fn unused() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+                let renamed = test_dir.child("renamed");
+
+                rename_no_replace(file.path(), renamed.path())?;
+
+                file.assert(predicate::path::missing());
+                renamed.assert("Hello world!");
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn used() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+                let used = test_dir.child("used");
+                used.write_str("Hey world!")?;
+
+                let out = rename_no_replace(file.path(), used.path());
+                assert_eq!(out.map_err(|err| err.kind()), Err(io::ErrorKind::AlreadyExists));
+
+                file.assert("Hello world!");
+                used.assert("Hey world!");
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file").touch()?;
+                let renamed = test_dir.child("renamed");
+
+                rename_no_replace(dir.path(), renamed.path())?;
+
+                dir.assert(predicate::path::missing());
+                renamed.child("file").assert(predicate::path::exists());
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Generate a random name of `len` lowercase letters and digits.
+    ///
+    /// # Errors
+    ///
+    /// If no random data can be generated.
+    pub fn random_name(len: usize) -> io::Result<String> {
+        use std::io::Read as _;
+
+        let mut name = vec![0; len];
+        Random::new().read_exact(&mut name)?;
+        Ok(name.into_iter().filter_map(|byte| char::from_digit(u32::from(byte) % 36, 36)).collect())
+    }
+
+    /// A source of random data, for overwriting the contents of files. Not cryptographically
+    /// secure, but unpredictable without knowing the randomly chosen keys of its [`RandomState`].
+    struct Random {
+        /// The randomly keyed state used to generate the data.
+        state: RandomState,
+        /// The number of blocks of data generated so far.
+        blocks: u64,
+    }
+
+    impl Random {
+        /// Create a new [`Random`] with freshly chosen keys.
+        fn new() -> Self {
+            Self { state: RandomState::new(), blocks: 0 }
+        }
+    }
+
+    impl io::Read for Random {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            for chunk in buf.chunks_mut(8) {
+                self.blocks = self.blocks.wrapping_add(1);
+                let block = self.state.hash_one(self.blocks).to_ne_bytes();
+                for (byte, random) in chunk.iter_mut().zip(block) {
+                    *byte = random;
+                }
+            }
+
+            Ok(buf.len())
+        }
+    }
+}
+
+/// Transformers for [`walk::Item`]s.
+mod transform {
+    use super::{fs, walk};
+
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashSet;
+    use std::env;
+    use std::fs::{File, canonicalize};
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::SystemTime;
+
+    use anstream::AutoStream;
+    #[cfg(target_os = "linux")]
+    use log::warn;
+    use owo_colors::OwoColorize as _;
+
+    /// A function that may change a [`walk::Item`] into a different-but-related [`walk::Item`].
+    pub type Transformer = Box<dyn Fn(walk::Item) -> walk::Item>;
+
+    /// Does nothing, returns any value untouched.
+    pub fn identity(item: walk::Item) -> walk::Item {
+        item
+    }
+
+    /// Tests for the [`identity`] function.
+    #[cfg(test)]
+    mod test_identity {
+        use super::{identity, walk};
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn any_item(item: walk::Item) {
+            let out = identity(item.clone());
+            prop_assert_eq!(out, item);
+        }
+    }
+
+    /// The tip for avoiding [`fs::ErrorKind::IsADirectory`] errors.
+    const TIP_IS_DIR: &str = "use '--dir' to remove";
+
+    /// Transform all directories into a [`fs::ErrorKind::IsADirectory`] error. Return all other
+    /// values untouched.
+    pub fn disallow_all_dirs(mut item: walk::Item) -> walk::Item {
+        item.inner = item.inner.and_then(|entry| {
+            if entry.is_dir() {
+                Err(entry.into_err(fs::ErrorKind::IsADirectory).with_tip(TIP_IS_DIR))
+            } else {
+                Ok(entry)
+            }
+        });
+
+        item
+    }
+
+    /// Tests for the [`disallow_all_dirs`] function.
+    #[cfg(test)]
+    mod test_disallow_all_dirs {
+        use super::{TIP_IS_DIR, disallow_all_dirs, fs, walk};
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn not_a_directory(item: walk::Item) {
+            if let Ok(entry) = item.inner.as_ref() {
+                prop_assume!(!entry.is_dir());
+            }
+
+            let out = disallow_all_dirs(item.clone());
+            prop_assert_eq!(out, item);
+        }
+
+        #[proptest]
+        fn a_directory(path: String) {
+            let entry = fs::test_helpers::new_dir(path);
+
+            let path = entry.path();
+
+            let out = disallow_all_dirs(entry.into());
+            prop_assert!(out.inner.is_err());
+
+            let err = out.inner.expect_err("is_err() should be asserted");
+            prop_assert_eq!(err.kind(), fs::ErrorKind::IsADirectory);
+            prop_assert_eq!(err.path(), path);
+            prop_assert_eq!(err.tip(), Some(TIP_IS_DIR));
+        }
+    }
+
+    /// Transform current directory and parent directory into a [`fs::ErrorKind::Refused`] error.
+    /// Return all other values untouched.
+    pub fn disallow_current_and_parent_dir(mut item: walk::Item) -> walk::Item {
+        item.inner = item.inner.and_then(|entry| {
+            if is_current_or_parent_dir(entry.path()) {
+                Err(entry.into_err(fs::ErrorKind::Refused))
+            } else {
+                Ok(entry)
+            }
+        });
+
+        item
+    }
+
+    /// Check if the given [`Path`] is the current directory or parent directory.
+    fn is_current_or_parent_dir<P: AsRef<Path>>(path: P) -> bool {
+        path.as_ref().ends_with(".") || path.as_ref().ends_with("..")
+    }
+
+    /// Tests for the [`disallow_current_and_parent_dir`] function.
+    #[cfg(test)]
+    mod test_disallow_current_and_parent_dir {
+        use super::{disallow_current_and_parent_dir, fs, walk};
+
+        use std::path::{MAIN_SEPARATOR_STR, Path};
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn not_current_nor_parent_dir(item: walk::Item) {
+            if let Ok(entry) = item.inner.as_ref() {
+                prop_assume!(!entry.path().ends_with("."));
+                prop_assume!(!entry.path().ends_with(".."));
+            }
+
+            let out = disallow_current_and_parent_dir(item.clone());
+            prop_assert_eq!(out, item);
+        }
+
+        #[proptest]
+        fn entry_current_directory(path: CurrentDirPath) {
+            let entry = fs::test_helpers::new_dir(&path.0);
+
+            let out = disallow_current_and_parent_dir(entry.into());
+            prop_assert!(out.inner.is_err());
+
+            let err = out.inner.expect_err("is_err() should be asserted");
+            prop_assert_eq!(err.kind(), fs::ErrorKind::Refused);
+            prop_assert_eq!(err.path(), Path::new(&path.0));
+        }
+
+        #[proptest]
+        fn entry_parent_directory(path: ParentDirPath) {
+            let entry = fs::test_helpers::new_dir(&path.0);
 
-        impl TestVars {
-            /// Returns `true` if the contained list contains the given key.
-            pub fn contains_key(&self, key: &str) -> bool {
-                self.0.iter().map(|(name, _)| name).any(|name| name == key)
-            }
+            let out = disallow_current_and_parent_dir(entry.into());
+            prop_assert!(out.inner.is_err());
 
-            /// Returns the contained value, consuming the `self` value.
-            pub fn inner(self) -> Vec<(String, String)> {
-                self.0
-            }
+            let err = out.inner.expect_err("is_err() should be asserted");
+            prop_assert_eq!(err.kind(), fs::ErrorKind::Refused);
+            prop_assert_eq!(err.path(), Path::new(&path.0));
         }
 
-        impl Arbitrary for TestVars {
+        /// Struct wrapping a [`String`] that implements [`Arbitrary`] to generate a current
+        /// directory path.
+        #[derive(Debug)]
+        struct CurrentDirPath(String);
+
+        impl Arbitrary for CurrentDirPath {
             type Parameters = ();
             type Strategy = BoxedStrategy<Self>;
 
             fn arbitrary_with((): ()) -> Self::Strategy {
-                let size_range = 1..=16;
-                prop::collection::vec(TestVar::arbitrary(), size_range)
-                    .prop_map(|v| Self(v.into_iter().map(TestVar::inner).collect()))
+                "(\\.\\.?SEPARATOR)*\\."
+                    .prop_map(|v| CurrentDirPath(v.replace("SEPARATOR", MAIN_SEPARATOR_STR)))
                     .boxed()
             }
         }
 
-        /// Struct wrapping a list of [`String`]-based (key, value) pairs that implements
-        /// [`Arbitrary`] to generate valid environment variables for the CLI. To use the contained
-        /// value one more pair has to be inserted.
-        ///
-        /// See also [`TestVars`].
-        #[derive(Clone, Debug)]
-        pub struct TestVarsAndIndex(Vec<(String, String)>, usize);
+        /// Struct wrapping a [`String`] that implements [`Arbitrary`] to generate a parent
+        /// directory path.
+        #[derive(Debug)]
 
-        impl TestVarsAndIndex {
-            /// Returns the contained value with the given value at the associated index, consuming
-            /// the `self` value.
-            pub fn insert(self, env_var: (&str, &str)) -> Vec<(String, String)> {
-                let Self(mut vars, index) = self;
-                vars.insert(index, (env_var.0.to_owned(), env_var.1.to_owned()));
-                vars
-            }
-        }
+        struct ParentDirPath(String);
 
-        impl Arbitrary for TestVarsAndIndex {
+        impl Arbitrary for ParentDirPath {
             type Parameters = ();
             type Strategy = BoxedStrategy<Self>;
 
             fn arbitrary_with((): ()) -> Self::Strategy {
-                let size_range = 1..=16;
-                prop::collection::vec(TestVar::arbitrary(), size_range)
-                    .prop_flat_map(|vec| (0..vec.len(), Just(vec)))
-                    .prop_map(|(i, vec)| Self(vec.into_iter().map(TestVar::inner).collect(), i))
+                "(\\.\\.?SEPARATOR)*\\.\\."
+                    .prop_map(|v| ParentDirPath(v.replace("SEPARATOR", MAIN_SEPARATOR_STR)))
                     .boxed()
             }
         }
     }
-}
-
-/// File system utilities.
-mod fs {
-    use std::error;
-    use std::ffi::OsString;
-    use std::fmt;
-    use std::fs::{File, read_dir, symlink_metadata};
-    use std::io::{self, Read as _};
-    use std::path::{Path, PathBuf};
-    use std::result;
 
-    use log::trace;
-    use owo_colors::OwoColorize as _;
-
-    #[cfg(test)]
-    use proptest_derive::Arbitrary;
+    /// The tip for avoiding [`fs::ErrorKind::DirectoryNotEmpty`] errors.
+    const TIP_DIR_NOT_EMPTY: &str = "use '--recursive' to remove";
 
-    /// The `Result` type for interacting with the file system.
-    pub type Result = result::Result<Entry, Error>;
+    /// Transform filled directories into a [`fs::ErrorKind::DirectoryNotEmpty`] error. Return all
+    /// other values untouched.
+    pub fn disallow_filled_dirs(mut item: walk::Item) -> walk::Item {
+        item.inner = item.inner.and_then(|entry| {
+            if entry.is_dir() && !fs::is_empty(&entry) {
+                Err(entry.into_err(fs::ErrorKind::DirectoryNotEmpty).with_tip(TIP_DIR_NOT_EMPTY))
+            } else {
+                Ok(entry)
+            }
+        });
 
-    /// Open a handle for a file system [`Entry`].
-    ///
-    /// # Errors
-    ///
-    /// If nothing is accessible at the given path.
-    pub fn open<P: AsRef<Path>>(path: P) -> Result {
-        match symlink_metadata(&path) {
-            Ok(metadata) if metadata.is_file() => {
-                trace!("found file at {}", path.as_ref().display());
-                Ok(Entry::new(path, EntryKind::File))
-            },
-            Ok(metadata) if metadata.is_dir() => {
-                trace!("found directory at {}", path.as_ref().display());
-                Ok(Entry::new(path, EntryKind::Dir))
-            },
-            Ok(metadata) if metadata.is_symlink() => {
-                trace!("found symbolic link at {}", path.as_ref().display());
-                Ok(Entry::new(path, EntryKind::Symlink))
-            },
-            Err(err) => {
-                trace!("found nothing at {}", path.as_ref().display());
-                Err(Error::new(path, err.kind().into()))
-            },
-            Ok(_) => unreachable!(),
-        }
+        item
     }
 
-    /// Tests for the [`open`] function.
+    /// Tests for the [`disallow_filled_dirs`] function.
     #[cfg(test)]
-    mod test_open {
+    mod test_disallow_filled_dirs {
         use crate::test_helpers::{TestResult, with_test_dir};
 
-        use super::{Entry, EntryKind, Error, ErrorKind, open};
+        use super::{TIP_DIR_NOT_EMPTY, disallow_filled_dirs, fs, walk};
 
         use assert_fs::prelude::*;
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn non_dir(item: walk::Item) {
+            if let Ok(entry) = item.inner.as_ref() {
+                prop_assume!(!entry.is_dir());
+            }
+
+            let out = disallow_filled_dirs(item.clone());
+            prop_assert_eq!(out, item);
+        }
 
         #[test]
-        fn file() -> TestResult {
+        fn entry_empty_dir() -> TestResult {
             with_test_dir(|test_dir| {
-                let file = test_dir.child("file");
-                file.touch()?;
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
 
-                let path = file.path();
+                let path = dir.path();
+                let entry = fs::test_helpers::new_dir(path);
 
-                let out = open(path);
-                assert_eq!(out, Ok(Entry::new(path, EntryKind::File)));
+                let out = disallow_filled_dirs(entry.clone().into());
+                assert_eq!(out, entry.into());
 
                 Ok(())
             })
         }
 
         #[test]
-        fn dir() -> TestResult {
+        fn entry_filled_dir() -> TestResult {
             with_test_dir(|test_dir| {
                 let dir = test_dir.child("dir");
                 dir.create_dir_all()?;
+                dir.child("file").touch()?;
 
                 let path = dir.path();
+                let entry = fs::test_helpers::new_dir(path);
 
-                let out = open(path);
-                assert_eq!(out, Ok(Entry::new(path, EntryKind::Dir)));
+                let out = disallow_filled_dirs(entry.into());
+                assert!(out.inner.is_err());
+
+                let err = out.inner.expect_err("is_err() should be asserted");
+                assert_eq!(err.kind(), fs::ErrorKind::DirectoryNotEmpty);
+                assert_eq!(err.path(), path);
+                assert_eq!(err.tip(), Some(TIP_DIR_NOT_EMPTY));
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Create a [`Transformer`] that transforms directories that are filled with anything but the
+    /// given `removed` entries into a [`fs::ErrorKind::DirectoryNotEmpty`] error, see also
+    /// [`disallow_filled_dirs`]. Return all other values untouched.
+    pub fn disallow_filled_dirs_after(removed: walk::Removed) -> Transformer {
+        Box::new(move |mut item| {
+            item.inner = item.inner.and_then(|entry| {
+                if entry.is_dir() && !fs::is_empty_after(&entry, &removed.borrow()) {
+                    Err(entry
+                        .into_err(fs::ErrorKind::DirectoryNotEmpty)
+                        .with_tip(TIP_DIR_NOT_EMPTY))
+                } else {
+                    Ok(entry)
+                }
+            });
+
+            item
+        })
+    }
+
+    /// Tests for the [`disallow_filled_dirs_after`] function.
+    #[cfg(test)]
+    mod test_disallow_filled_dirs_after {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{disallow_filled_dirs_after, fs, walk};
+
+        use std::rc::Rc;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn entry_filled_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file").touch()?;
+
+                let entry = fs::test_helpers::new_dir(dir.path());
+
+                let out = disallow_filled_dirs_after(walk::Removed::default())(entry.into());
+                let err = out.inner.expect_err("a filled dir should be disallowed");
+                assert_eq!(err.kind(), fs::ErrorKind::DirectoryNotEmpty);
 
                 Ok(())
             })
         }
 
         #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn symlink() -> TestResult {
+        fn entry_filled_dir_removed() -> TestResult {
             with_test_dir(|test_dir| {
-                let file = test_dir.child("file");
+                let dir = test_dir.child("dir");
+                let file = dir.child("file");
                 file.touch()?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(&file)?;
 
-                let path = link.path();
+                let removed = walk::Removed::default();
+                removed.borrow_mut().insert(file.path().to_owned());
+                let entry = fs::test_helpers::new_dir(dir.path());
+
+                let out = disallow_filled_dirs_after(Rc::clone(&removed))(entry.clone().into());
+                assert_eq!(out, entry.into());
+
+                Ok(())
+            })
+        }
+    }
+
+    /// Transform everything but directories into a [`fs::ErrorKind::NotADirectory`] error. Return
+    /// all other values untouched.
+    pub fn disallow_non_dirs(mut item: walk::Item) -> walk::Item {
+        item.inner = item.inner.and_then(|entry| {
+            if entry.is_dir() {
+                Ok(entry)
+            } else {
+                Err(entry.into_err(fs::ErrorKind::NotADirectory))
+            }
+        });
+
+        item
+    }
+
+    /// Tests for the [`disallow_non_dirs`] function.
+    #[cfg(test)]
+    mod test_disallow_non_dirs {
+        use super::{disallow_non_dirs, fs, walk};
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn a_directory_or_error(item: walk::Item) {
+            if let Ok(entry) = item.inner.as_ref() {
+                prop_assume!(entry.is_dir());
+            }
+
+            let out = disallow_non_dirs(item.clone());
+            prop_assert_eq!(out, item);
+        }
+
+        #[proptest]
+        fn not_a_directory(entry: fs::Entry) {
+            prop_assume!(!entry.is_dir());
+
+            let path = entry.path();
+
+            let out = disallow_non_dirs(entry.into());
+            prop_assert!(out.inner.is_err());
+
+            let err = out.inner.expect_err("is_err() should be asserted");
+            prop_assert_eq!(err.kind(), fs::ErrorKind::NotADirectory);
+            prop_assert_eq!(err.path(), path);
+            prop_assert_eq!(err.tip(), None);
+        }
+    }
+
+    /// Transform root directories into a [`fs::ErrorKind::Refused`] error. Return all other values
+    /// untouched.
+    pub fn disallow_root(mut item: walk::Item) -> walk::Item {
+        item.inner = item.inner.and_then(|entry| {
+            if is_root(entry.path()) {
+                Err(entry.into_err(fs::ErrorKind::Refused))
+            } else {
+                Ok(entry)
+            }
+        });
+
+        item
+    }
+
+    /// Check if the given [`Path`] is the file system root.
+    fn is_root<P: AsRef<Path>>(path: P) -> bool {
+        path.as_ref().parent().is_none()
+    }
+
+    /// Tests for the [`disallow_root`] function.
+    #[cfg(test)]
+    mod test_disallow_root {
+        use super::{disallow_root, fs, walk};
+
+        use std::path::Path;
 
-                let out = open(path);
-                assert_eq!(out, Ok(Entry::new(path, EntryKind::Symlink)));
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
 
-                Ok(())
-            })
+        #[proptest]
+        fn non_root(item: walk::Item) {
+            if let Ok(entry) = item.inner.as_ref() {
+                prop_assume!(entry.path().parent().is_some());
+                prop_assume!(!entry.path().as_os_str().is_empty());
+            }
+
+            let out = disallow_root(item.clone());
+            prop_assert_eq!(out, item);
         }
 
         #[test]
-        fn not_found() -> TestResult {
-            with_test_dir(|test_dir| {
-                let path = test_dir.child("missing");
-
-                let out = open(&path);
-                assert_eq!(out, Err(Error::new(path, ErrorKind::NotFound)));
+        fn entry_root() {
+            test_is_root("/");
+        }
 
-                Ok(())
-            })
+        #[test]
+        #[cfg(windows)]
+        fn entry_drive() {
+            test_is_root("C:\\");
         }
-    }
 
-    /// Check if the [`Entry`] is an empty file or directory.
-    pub fn is_empty(entry: &Entry) -> bool {
-        match entry.kind() {
-            EntryKind::Dir => {
-                read_dir(entry.path()).map_or(true, |mut content| content.next().is_none())
-            },
-            EntryKind::File => File::open(entry.path())
-                .map_or(true, |mut f| f.read(&mut [0; 1]).map_or(true, |n| n == 0)),
-            EntryKind::Symlink => true,
+        fn test_is_root<P: AsRef<Path>>(path: P) {
+            let entry = fs::test_helpers::new_dir(&path);
+
+            let out = disallow_root(entry.into());
+            assert!(out.inner.is_err());
+
+            let err = out.inner.expect_err("is_err() should be asserted");
+            assert_eq!(err.kind(), fs::ErrorKind::Refused);
+            assert_eq!(err.path(), path.as_ref().to_owned());
         }
     }
 
-    #[cfg(test)]
-    mod test_is_empty {
-        use crate::test_helpers::{TestResult, with_test_dir};
-
-        use super::{Entry, EntryKind, is_empty};
+    /// What is known about the contents of a directory when it is visited, see
+    /// [`tracking_contents`].
+    #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+    pub struct Contents {
+        /// Whether anything in the directory is kept, i.e. skipped or erroneous.
+        pub kept: bool,
 
-        use assert_fs::prelude::*;
+        /// Whether anything in the directory is not kept.
+        pub removed: bool,
+    }
 
-        #[test]
-        fn file_empty() -> TestResult {
-            with_test_dir(|test_dir| {
-                let file = test_dir.child("file");
-                file.touch()?;
+    /// Create a [`Transformer`] that applies `transform`, providing it with the [`Contents`] of
+    /// visited directories. Because the contents of a directory are visited before the directory
+    /// itself, these are recorded from the outcome of `transform` rather than by reading the
+    /// directory again. The [`Contents`] of anything else are empty.
+    pub fn tracking_contents<F>(transform: F) -> Transformer
+    where
+        F: Fn(walk::Item, Contents) -> walk::Item + 'static,
+    {
+        let kept = RefCell::new(HashSet::new());
+        let removed = RefCell::new(HashSet::new());
+
+        Box::new(move |item| {
+            let path = item.path();
+            let contents = if item.is_visited() && item.inner.as_ref().is_ok_and(fs::Entry::is_dir)
+            {
+                Contents {
+                    kept: kept.borrow_mut().remove(&path),
+                    removed: removed.borrow_mut().remove(&path),
+                }
+            } else {
+                Contents::default()
+            };
 
-                let path = file.path();
+            let item = transform(item, contents);
+            if let Some(parent) = path.parent() {
+                if item.is_skipped() || item.inner.is_err() {
+                    kept.borrow_mut().insert(parent.to_owned());
+                } else {
+                    removed.borrow_mut().insert(parent.to_owned());
+                }
+            }
 
-                let entry = Entry::new(path, EntryKind::File);
-                assert!(is_empty(&entry));
+            item
+        })
+    }
 
-                Ok(())
+    /// Tests for the [`tracking_contents`] function.
+    #[cfg(test)]
+    mod test_tracking_contents {
+        use super::{Contents, fs, tracking_contents, walk};
+
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        /// The skip reason used in tests.
+        const REASON: &str = "reason";
+
+        /// Create a [`super::Transformer`] that records the [`Contents`] it is provided with and
+        /// skips entries whose name starts with "kept".
+        fn recording(seen: &Rc<RefCell<Vec<Contents>>>) -> super::Transformer {
+            let seen = Rc::clone(seen);
+            tracking_contents(move |item, contents| {
+                seen.borrow_mut().push(contents);
+                if item
+                    .path()
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with("kept"))
+                {
+                    item.into_skipped(REASON)
+                } else {
+                    item
+                }
             })
         }
 
         #[test]
-        fn file_filled() -> TestResult {
-            with_test_dir(|test_dir| {
-                let file = test_dir.child("file");
-                file.write_str("Hello world!")?;
+        fn visited_dir_empty() {
+            let seen = Rc::default();
+            let transform = recording(&seen);
 
-                let path = file;
+            transform(walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir"))));
+            assert_eq!(*seen.borrow(), vec![Contents::default()]);
+        }
 
-                let entry = Entry::new(path, EntryKind::File);
-                assert!(!is_empty(&entry));
+        #[test]
+        fn visited_dir_kept() {
+            let seen = Rc::default();
+            let transform = recording(&seen);
 
-                Ok(())
-            })
+            transform(fs::test_helpers::new_file("dir/file").into());
+            transform(fs::test_helpers::new_file("dir/kept").into());
+            transform(walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir"))));
+            assert_eq!(seen.borrow().last(), Some(&Contents { kept: true, removed: true }));
         }
 
         #[test]
-        fn missing() -> TestResult {
-            with_test_dir(|test_dir| {
-                let path = test_dir.child("missing");
+        fn visited_dir_removed() {
+            let seen = Rc::default();
+            let transform = recording(&seen);
+
+            transform(fs::test_helpers::new_file("dir/nested_dir/file").into());
+            transform(walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir/nested_dir"))));
+            transform(walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir"))));
+            assert_eq!(
+                *seen.borrow(),
+                vec![
+                    Contents::default(),
+                    Contents { kept: false, removed: true },
+                    Contents { kept: false, removed: true },
+                ]
+            );
+        }
 
-                let entry = Entry::new(path, EntryKind::File);
-                assert!(is_empty(&entry));
+        #[test]
+        fn visited_dir_nested_kept() {
+            let seen = Rc::default();
+            let transform = recording(&seen);
 
-                Ok(())
-            })
+            transform(fs::test_helpers::new_file("dir/kept_dir/file").into());
+            transform(walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir/kept_dir"))));
+            transform(walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir"))));
+            assert_eq!(seen.borrow().last(), Some(&Contents { kept: true, removed: false }));
         }
 
         #[test]
-        fn dir_empty() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
+        fn visited_dir_error() {
+            let seen = Rc::default();
+            let transform = recording(&seen);
 
-                let path = dir.path();
+            let err = fs::test_helpers::new_file("dir/file").into_err(fs::ErrorKind::NotFound);
+            transform(err.into());
+            transform(walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir"))));
+            assert_eq!(seen.borrow().last(), Some(&Contents { kept: true, removed: false }));
+        }
 
-                let entry = Entry::new(path, EntryKind::Dir);
-                assert!(is_empty(&entry));
+        #[test]
+        fn unvisited_dir() {
+            let seen = Rc::default();
+            let transform = recording(&seen);
 
-                Ok(())
-            })
+            transform(fs::test_helpers::new_file("dir/file").into());
+            transform(fs::test_helpers::new_dir("dir").into());
+            assert_eq!(seen.borrow().last(), Some(&Contents::default()));
         }
+    }
 
-        #[test]
-        fn dir_filled() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                dir.child("file").touch()?;
+    /// The explanation for why a non-directory is skipped.
+    const SKIP_REASON_NOT_A_DIR: &str = "Not a directory";
+
+    /// The explanation for why a directory that is not an empty tree is skipped.
+    const SKIP_REASON_NOT_AN_EMPTY_TREE: &str = "Contains more than empty directories";
+
+    /// Create a [`Transformer`] that transforms everything but directories that contain nothing but
+    /// (empty) directories into skipped [`walk::Item`]s. Directories that have not been visited yet
+    /// are returned untouched so that they can be descended into.
+    pub fn skip_all_but_empty_trees() -> Transformer {
+        tracking_contents(|item, contents| match item.inner.as_ref() {
+            Ok(entry) if !entry.is_dir() => item.into_skipped(SKIP_REASON_NOT_A_DIR),
+            Ok(_) if contents.kept => item.into_skipped(SKIP_REASON_NOT_AN_EMPTY_TREE),
+            _ => item,
+        })
+    }
 
-                let path = dir.path();
+    /// Tests for the [`skip_all_but_empty_trees`] function.
+    #[cfg(test)]
+    mod test_skip_all_but_empty_trees {
+        use super::{
+            SKIP_REASON_NOT_A_DIR, SKIP_REASON_NOT_AN_EMPTY_TREE, fs, skip_all_but_empty_trees,
+            walk,
+        };
 
-                let entry = Entry::new(path, EntryKind::Dir);
-                assert!(!is_empty(&entry));
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
 
-                Ok(())
-            })
-        }
+        #[proptest]
+        fn error(err: fs::Error) {
+            let item: walk::Item = err.into();
 
-        #[test]
-        fn dir_missing() -> TestResult {
-            with_test_dir(|test_dir| {
-                let path = test_dir.child("missing");
+            let out = skip_all_but_empty_trees()(item.clone());
+            prop_assert_eq!(out, item);
+        }
 
-                let entry = Entry::new(path, EntryKind::Dir);
-                assert!(is_empty(&entry));
+        #[proptest]
+        fn non_dir(entry: fs::Entry) {
+            prop_assume!(!entry.is_dir());
 
-                Ok(())
-            })
+            let out = skip_all_but_empty_trees()(entry.into());
+            prop_assert_eq!(out.skip_reason(), Some(SKIP_REASON_NOT_A_DIR.to_owned()));
         }
 
         #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn symlink_to_empty_file() -> TestResult {
-            with_test_dir(|test_dir| {
-                let file = test_dir.child("file");
-                file.touch()?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(&file)?;
+        fn unvisited_dir() {
+            let item: walk::Item = fs::test_helpers::new_dir("dir").into();
 
-                let path = link.path();
+            let out = skip_all_but_empty_trees()(item.clone());
+            assert_eq!(out, item);
+        }
 
-                let entry = Entry::new(path, EntryKind::Symlink);
-                assert!(is_empty(&entry));
+        #[test]
+        fn visited_empty_tree() {
+            let transform = skip_all_but_empty_trees();
+            transform(fs::test_helpers::new_dir("dir/nested_dir").into());
 
-                Ok(())
-            })
+            let item = walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir")));
+
+            let out = transform(item.clone());
+            assert_eq!(out, item);
         }
 
         #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn symlink_to_filled_file() -> TestResult {
-            with_test_dir(|test_dir| {
-                let file = test_dir.child("file");
-                file.write_str("Hello world!")?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(&file)?;
-
-                let path = link.path();
+        fn visited_filled_dir() {
+            let transform = skip_all_but_empty_trees();
+            transform(fs::test_helpers::new_file("dir/nested_dir/file").into());
+            transform(walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir/nested_dir"))));
 
-                let entry = Entry::new(path, EntryKind::Symlink);
-                assert!(is_empty(&entry));
+            let item = walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir")));
 
-                Ok(())
-            })
+            let out = transform(item);
+            assert_eq!(out.skip_reason(), Some(SKIP_REASON_NOT_AN_EMPTY_TREE.to_owned()));
         }
+    }
 
-        #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn symlink_to_empty_dir() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(&dir)?;
+    /// The explanation for why a filled directory is skipped.
+    const SKIP_REASON_DIR_NOT_EMPTY: &str = "Directory not empty";
+
+    /// Create a [`Transformer`] that transforms directories that are filled with anything but the
+    /// given `removed` entries into skipped [`walk::Item`]s. Return all other values untouched.
+    pub fn skip_filled_dirs_after(removed: walk::Removed) -> Transformer {
+        Box::new(move |item| {
+            if item
+                .inner
+                .as_ref()
+                .is_ok_and(|entry| entry.is_dir() && !fs::is_empty_after(entry, &removed.borrow()))
+            {
+                item.into_skipped(SKIP_REASON_DIR_NOT_EMPTY)
+            } else {
+                item
+            }
+        })
+    }
 
-                let path = link.path();
+    /// Tests for the [`skip_filled_dirs_after`] function.
+    #[cfg(test)]
+    mod test_skip_filled_dirs_after {
+        use crate::test_helpers::{TestResult, with_test_dir};
 
-                let entry = Entry::new(path, EntryKind::Symlink);
-                assert!(is_empty(&entry));
+        use super::{SKIP_REASON_DIR_NOT_EMPTY, fs, skip_filled_dirs_after, walk};
 
-                Ok(())
-            })
+        use std::rc::Rc;
+
+        use assert_fs::prelude::*;
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn non_dir(item: walk::Item) {
+            if let Ok(entry) = item.inner.as_ref() {
+                prop_assume!(!entry.is_dir());
+            }
+
+            let out = skip_filled_dirs_after(walk::Removed::default())(item.clone());
+            prop_assert_eq!(out, item);
         }
 
         #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn symlink_to_filled_dir() -> TestResult {
+        fn entry_empty_dir() -> TestResult {
             with_test_dir(|test_dir| {
                 let dir = test_dir.child("dir");
                 dir.create_dir_all()?;
-                dir.child("file").touch()?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(&dir)?;
 
-                let path = link.path();
+                let entry = fs::test_helpers::new_dir(dir.path());
 
-                let entry = Entry::new(path, EntryKind::Symlink);
-                assert!(is_empty(&entry));
+                let out = skip_filled_dirs_after(walk::Removed::default())(entry.clone().into());
+                assert_eq!(out, entry.into());
 
                 Ok(())
             })
         }
 
         #[test]
-        fn symlink_missing() -> TestResult {
+        fn entry_filled_dir() -> TestResult {
             with_test_dir(|test_dir| {
-                let path = test_dir.child("missing");
-
-                let entry = Entry::new(path, EntryKind::Symlink);
-                assert!(is_empty(&entry));
-
-                Ok(())
-            })
-        }
-    }
-
-    /// Struct representing a file system entry.
-    #[cfg_attr(test, derive(Arbitrary, Clone, Debug, Eq, PartialEq))]
-    pub struct Entry {
-        /// The kind of file system entry.
-        kind: EntryKind,
-
-        /// The path to the file system entry.
-        path: OsString,
-    }
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                dir.child("file").touch()?;
 
-    impl Entry {
-        /// Create a new [`Entry`].
-        fn new<P: AsRef<Path>>(path: P, kind: EntryKind) -> Self {
-            Self { kind, path: path.as_ref().as_os_str().to_owned() }
-        }
+                let entry = fs::test_helpers::new_dir(dir.path());
 
-        /// Convert the [`Entry`] into an [`Error`] for the [`Entry`]'s path with the given
-        /// [`ErrorKind`].
-        pub fn into_err(self, kind: ErrorKind) -> Error {
-            Error::new(self.path(), kind)
-        }
+                let out = skip_filled_dirs_after(walk::Removed::default())(entry.into());
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_DIR_NOT_EMPTY.to_owned()));
 
-        /// Returns `true` if the [`Entry`] is a directory.
-        pub fn is_dir(&self) -> bool {
-            matches!(self.kind, EntryKind::Dir)
+                Ok(())
+            })
         }
 
-        /// Get the kind of the [`Entry`].
-        pub fn kind(&self) -> EntryKind {
-            self.kind.clone()
-        }
+        #[test]
+        fn entry_filled_dir_removed() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                let file = dir.child("file");
+                file.touch()?;
 
-        /// Get the path to the [`Entry`].
-        pub fn path(&self) -> PathBuf {
-            Path::new(&self.path).to_owned()
-        }
-    }
+                let removed = walk::Removed::default();
+                removed.borrow_mut().insert(file.path().to_owned());
+                let entry = fs::test_helpers::new_dir(dir.path());
 
-    impl fmt::Display for Entry {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            write!(f, "{}", self.path().display())
+                let out = skip_filled_dirs_after(Rc::clone(&removed))(entry.clone().into());
+                assert_eq!(out, entry.into());
+
+                Ok(())
+            })
         }
     }
 
-    /// Enum representing the kind of a file system [`Entry`].
-    #[derive(Clone, Eq, PartialEq)]
-    #[cfg_attr(test, derive(Arbitrary, Debug))]
-    pub enum EntryKind {
-        /// An [`Entry`] that is a directory.
-        Dir,
-
-        /// An [`Entry`] that is a file.
-        File,
+    /// The explanation for why a missing [`walk::Item`] is skipped.
+    const SKIP_REASON_NOT_FOUND: &str = "Not found";
 
-        /// An [`Entry`] that is a symbolic link.
-        Symlink,
+    /// Transform [`fs::ErrorKind::NotFound`] errors into skipped [`walk::Item`]s. Return all other
+    /// values untouched.
+    pub fn skip_not_found(item: walk::Item) -> walk::Item {
+        if item.inner.as_ref().is_err_and(|err| err.kind() == fs::ErrorKind::NotFound) {
+            item.into_skipped(SKIP_REASON_NOT_FOUND)
+        } else {
+            item
+        }
     }
 
-    /// Tests for the [`Entry`] struct.
+    /// Tests for the [`skip_not_found`] function.
     #[cfg(test)]
-    mod test_entry {
-        use super::{Entry, EntryKind, Error, ErrorKind};
+    mod test_skip_not_found {
+        use super::{fs, skip_not_found, walk};
 
         use proptest::prelude::*;
         use proptest_attr_macro::proptest;
 
         #[proptest]
-        fn new(path: String, kind: EntryKind) {
-            prop_assert_eq!(Entry::new(&path, kind.clone()), Entry { kind, path: path.into() });
-        }
-
-        #[proptest]
-        fn display(entry: Entry) {
-            prop_assert_eq!(entry.to_string(), format!("{}", entry.path().display()));
-        }
-
-        #[proptest]
-        fn into_err(entry: Entry, err_kind: ErrorKind) {
-            let path = entry.path.clone();
-
-            let err = entry.into_err(err_kind.clone());
-            prop_assert_eq!(err, Error { kind: err_kind, path, tip: None });
-        }
+        fn found_or_error(item: walk::Item) {
+            if let Err(err) = item.inner.as_ref() {
+                prop_assume!(err.kind() != fs::ErrorKind::NotFound);
+            }
 
-        #[proptest]
-        fn is_dir(entry: Entry) {
-            prop_assert_eq!(entry.is_dir(), matches!(entry.kind, EntryKind::Dir));
+            let out = skip_not_found(item.clone());
+            prop_assert_eq!(out, item);
         }
 
         #[proptest]
-        fn kind(entry: Entry) {
-            prop_assert_eq!(entry.kind(), entry.kind);
-        }
+        fn not_found(entry: fs::Entry) {
+            let err = entry.into_err(fs::ErrorKind::NotFound);
 
-        #[proptest]
-        fn path(entry: Entry) {
-            prop_assert_eq!(entry.path(), entry.path);
+            let out = skip_not_found(err.into());
+            prop_assert_eq!(out.skip_reason(), Some(super::SKIP_REASON_NOT_FOUND.to_owned()));
         }
     }
 
-    /// Struct representing a file system error.
-    #[derive(Debug)]
-    #[cfg_attr(test, derive(Arbitrary, Clone, Eq, PartialEq))]
-    pub struct Error {
-        /// The kind of error that occurred.
-        kind: ErrorKind,
+    /// The explanation for why a protected directory is skipped.
+    const SKIP_REASON_PROTECTED: &str = "Protected directory";
 
-        /// The path for which the error occurred.
-        path: OsString,
+    /// Transform the current directory, parent directory, working directory and root directories
+    /// into skipped [`walk::Item`]s. Return all other values untouched.
+    pub fn skip_protected_dirs(item: walk::Item) -> walk::Item {
+        let is_protected = item.inner.as_ref().is_ok_and(|entry| {
+            let path = entry.path();
+            is_current_or_parent_dir(&path) || is_root(&path) || is_working_dir(&path)
+        });
 
-        /// A tip to deal with the error, if any.
-        tip: Option<String>,
+        if is_protected { item.into_skipped(SKIP_REASON_PROTECTED) } else { item }
     }
 
-    impl Error {
-        /// Create a new [`Error`] with a given `path` and [`ErrorKind`].
-        fn new<P: AsRef<Path>>(path: P, kind: ErrorKind) -> Self {
-            Self { kind, path: path.as_ref().as_os_str().to_owned(), tip: None }
-        }
-
-        /// Get the kind of the [`Error`].
-        pub fn kind(&self) -> ErrorKind {
-            self.kind.clone()
-        }
-
-        /// Get the file system path this [`Error`] is associated with.
-        pub fn path(&self) -> PathBuf {
-            Path::new(&self.path).to_owned()
-        }
-
-        /// Convert this [`Error`] into an [`Error`] with the provided tip associated to it.
-        pub fn with_tip(mut self, tip: &str) -> Self {
-            self.tip = Some(tip.to_owned());
-            self
+    /// Check if the given [`Path`] is the current working directory.
+    fn is_working_dir<P: AsRef<Path>>(path: P) -> bool {
+        match (env::current_dir().and_then(canonicalize), canonicalize(path)) {
+            (Ok(working_dir), Ok(path)) => working_dir == path,
+            _ => false,
         }
     }
 
-    impl fmt::Display for Error {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            if let Some(tip) = &self.tip {
-                write!(
-                    f,
-                    "Cannot remove {}: {} {}",
-                    self.path().display().bold(),
-                    self.kind,
-                    format!("({tip})").italic()
-                )
-            } else {
-                write!(f, "Cannot remove {}: {}", self.path().display().bold(), self.kind)
-            }
-        }
-    }
+    /// Tests for the [`skip_protected_dirs`] function.
+    #[cfg(test)]
+    mod test_skip_protected_dirs {
+        use crate::test_helpers::{TestResult, with_test_dir};
 
-    impl error::Error for Error {}
+        use super::{SKIP_REASON_PROTECTED, fs, skip_protected_dirs, walk};
 
-    /// Tests for the [`Error`] struct.
-    #[cfg(test)]
-    mod test_error {
-        use super::{Error, ErrorKind};
+        use std::env;
 
-        use owo_colors::OwoColorize as _;
+        use assert_fs::prelude::*;
         use proptest::prelude::*;
         use proptest_attr_macro::proptest;
 
         #[proptest]
-        fn new(path: String, kind: ErrorKind) {
-            let err = Error::new(&path, kind.clone());
-            prop_assert_eq!(err, Error { kind, path: path.into(), tip: None });
+        fn error(err: fs::Error) {
+            let item: walk::Item = err.into();
+
+            let out = skip_protected_dirs(item.clone());
+            prop_assert_eq!(out, item);
         }
 
-        #[proptest]
-        fn display_with_tip(err: Error) {
-            prop_assume!(err.tip.is_some());
+        #[test]
+        fn entry_unprotected_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
 
-            prop_assert_eq!(
-                err.to_string(),
-                format!(
-                    "Cannot remove {}: {} {}",
-                    err.path().display().bold(),
-                    err.kind(),
-                    format!("({})", err.tip.expect("is_some() should be asserted")).italic(),
-                )
-            );
-        }
+                let entry = fs::test_helpers::new_dir(dir.path());
 
-        #[proptest]
-        fn display_without_tip(err: Error) {
-            prop_assume!(err.tip.is_none());
+                let out = skip_protected_dirs(entry.clone().into());
+                assert_eq!(out, entry.into());
 
-            prop_assert_eq!(
-                err.to_string(),
-                format!("Cannot remove {}: {}", err.path().display().bold(), err.kind())
-            );
+                Ok(())
+            })
         }
 
-        #[proptest]
-        fn kind(err: Error) {
-            prop_assert_eq!(err.kind(), err.kind);
+        #[test]
+        fn entry_current_dir() {
+            for path in [".", "..", "../.."] {
+                let entry = fs::test_helpers::new_dir(path);
+
+                let out = skip_protected_dirs(entry.into());
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_PROTECTED.to_owned()));
+            }
         }
 
-        #[proptest]
-        fn path(err: Error) {
-            prop_assert_eq!(err.path(), err.path);
+        #[test]
+        fn entry_root() {
+            let entry = fs::test_helpers::new_dir("/");
+
+            let out = skip_protected_dirs(entry.into());
+            assert_eq!(out.skip_reason(), Some(SKIP_REASON_PROTECTED.to_owned()));
         }
 
-        #[proptest]
-        fn with_tip(err: Error, tip: String) {
-            let kind = err.kind();
-            let path = err.path();
+        #[test]
+        fn entry_working_dir() -> TestResult {
+            let entry = fs::test_helpers::new_dir(env::current_dir()?);
 
-            prop_assert_eq!(err.with_tip(&tip), Error { kind, path: path.into(), tip: Some(tip) });
+            let out = skip_protected_dirs(entry.into());
+            assert_eq!(out.skip_reason(), Some(SKIP_REASON_PROTECTED.to_owned()));
+
+            Ok(())
         }
     }
 
-    /// Enum representing kinds of file system [`Error`]s.
-    #[derive(Clone, Debug, Eq, PartialEq)]
-    #[cfg_attr(test, derive(Arbitrary))]
-    pub enum ErrorKind {
-        /// This kind corresponds to an error due to a directory not being empty.
-        DirectoryNotEmpty,
-
-        /// This kind corresponds to an error due to an [`Entry`] being a directory.
-        IsADirectory,
+    /// The explanation for why a retained operand is skipped.
+    const SKIP_REASON_RETAINED: &str = "Retained";
 
-        /// This kind corresponds to an [`Entry`] not being found on the system.
-        NotFound,
+    /// Create a [`Transformer`] that transforms entries at any of the given `paths` into skipped
+    /// [`walk::Item`]s. Return all other values untouched.
+    pub fn skip_retained(paths: Vec<PathBuf>) -> Transformer {
+        Box::new(move |item| match item.inner.as_ref() {
+            Ok(entry) if paths.contains(&entry.path()) => item.into_skipped(SKIP_REASON_RETAINED),
+            _ => item,
+        })
+    }
 
-        /// This kind corresponds to the user not having access to an [`Entry`].
-        PermissionDenied,
+    /// Tests for the [`skip_retained`] function.
+    #[cfg(test)]
+    mod test_skip_retained {
+        use super::{SKIP_REASON_RETAINED, fs, skip_retained, walk};
 
-        /// This kind corresponds to the CLI refusing to remove an [`Entry`] because removing it
-        /// is potentially dangerous (e.g. it is the current directory).
-        Refused,
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
 
-        /// This kind is a catch all for any unknown error.
-        Unknown,
-    }
+        #[proptest]
+        fn error(err: fs::Error) {
+            let paths = vec![err.path()];
+            let item: walk::Item = err.into();
 
-    impl fmt::Display for ErrorKind {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            match self {
-                Self::DirectoryNotEmpty => write!(f, "Directory not empty"),
-                Self::IsADirectory => write!(f, "Is a directory"),
-                Self::NotFound => write!(f, "Not found"),
-                Self::PermissionDenied => write!(f, "Permission denied"),
-                Self::Refused => write!(f, "Refused to remove"),
-                Self::Unknown => write!(f, "Unknown error"),
-            }
+            let out = skip_retained(paths)(item.clone());
+            prop_assert_eq!(out, item);
         }
-    }
 
-    impl From<io::ErrorKind> for ErrorKind {
-        fn from(val: io::ErrorKind) -> Self {
-            match val {
-                io::ErrorKind::DirectoryNotEmpty => Self::DirectoryNotEmpty,
-                io::ErrorKind::NotFound => Self::NotFound,
-                io::ErrorKind::PermissionDenied => Self::PermissionDenied,
-                _ => Self::Unknown,
-            }
-        }
-    }
+        #[proptest]
+        fn retained(entry: fs::Entry) {
+            let paths = vec![entry.path()];
 
-    #[cfg(feature = "trash")]
-    impl From<trash::Error> for ErrorKind {
-        fn from(val: trash::Error) -> Self {
-            match val {
-                trash::Error::CouldNotAccess { .. } => Self::PermissionDenied,
-                #[cfg(all(unix, not(target_os = "macos")))]
-                trash::Error::FileSystem { source, .. } => source.kind().into(),
-                trash::Error::TargetedRoot => Self::Refused,
-                _ => Self::Unknown,
-            }
+            let out = skip_retained(paths)(entry.into());
+            prop_assert_eq!(out.skip_reason(), Some(SKIP_REASON_RETAINED.to_owned()));
+        }
+
+        #[proptest]
+        fn not_retained(entry: fs::Entry, paths: Vec<String>) {
+            prop_assume!(!paths.iter().any(|path| entry.path() == *path));
+            let item: walk::Item = entry.into();
+
+            let out = skip_retained(paths.into_iter().map(Into::into).collect())(item.clone());
+            prop_assert_eq!(out, item);
         }
     }
 
-    /// Tests for the [`ErrorKind`] enum.
+    /// The explanation for why a directory is skipped if not all of its contents would be removed.
+    const SKIP_REASON_CONTENTS_KEPT: &str = "Not all contents would be removed";
+
+    /// Create a [`Transformer`] that transforms entries for which the `predicate` does not hold into
+    /// skipped [`walk::Item`]s with the given `reason`. Directories are skipped if anything in them
+    /// is kept, and are only tested themselves if nothing in them is removed (e.g. if they are
+    /// empty). Directories that have not been visited yet are returned untouched, unless empty, so
+    /// that they can be descended into. Return all other values untouched.
+    pub fn skip_unless<P>(predicate: P, reason: &'static str) -> Transformer
+    where
+        P: Fn(&fs::Entry) -> bool + 'static,
+    {
+        tracking_contents(move |item, contents| match item.inner.as_ref() {
+            Ok(entry) if entry.is_dir() && !item.is_visited() && !fs::is_empty(entry) => item,
+            Ok(_) if contents.kept => item.into_skipped(SKIP_REASON_CONTENTS_KEPT),
+            Ok(entry) if !contents.removed && !predicate(entry) => item.into_skipped(reason),
+            _ => item,
+        })
+    }
+
+    /// Tests for the [`skip_unless`] function.
     #[cfg(test)]
-    mod test_error_kind {
-        use super::ErrorKind;
+    mod test_skip_unless {
+        use crate::test_helpers::{TestResult, with_test_dir};
 
-        use std::io;
-        use std::path;
+        use super::{SKIP_REASON_CONTENTS_KEPT, fs, skip_unless, walk};
 
+        use assert_fs::prelude::*;
         use proptest::prelude::*;
         use proptest_attr_macro::proptest;
 
-        #[test]
-        fn from_io_not_found() {
-            assert_eq!(ErrorKind::NotFound, io::ErrorKind::NotFound.into());
-        }
+        /// The skip reason used in tests.
+        const REASON: &str = "reason";
 
-        #[test]
-        fn from_io_permission_denied() {
-            assert_eq!(ErrorKind::PermissionDenied, io::ErrorKind::PermissionDenied.into());
+        #[proptest]
+        fn error(err: fs::Error) {
+            let item: walk::Item = err.into();
+
+            let out = skip_unless(|_| false, REASON)(item.clone());
+            prop_assert_eq!(out, item);
         }
 
         #[proptest]
-        #[cfg(feature = "trash")]
-        fn from_trash_could_not_access(target: String) {
-            let err = trash::Error::CouldNotAccess { target };
-            prop_assert_eq!(ErrorKind::PermissionDenied, err.into());
+        fn non_dir_match(entry: fs::Entry) {
+            prop_assume!(!entry.is_dir());
+            let item: walk::Item = entry.into();
+
+            let out = skip_unless(|_| true, REASON)(item.clone());
+            prop_assert_eq!(out, item);
         }
 
         #[proptest]
-        #[cfg(feature = "trash")]
-        #[cfg(all(unix, not(target_os = "macos")))]
-        fn from_trash_file_system(source: io::Error, path: String) {
-            let expected: ErrorKind = source.kind().into();
-            let err = trash::Error::FileSystem { source, path: path::Path::new(&path).into() };
-            prop_assert_eq!(expected, err.into());
+        fn non_dir_mismatch(entry: fs::Entry) {
+            prop_assume!(!entry.is_dir());
+
+            let out = skip_unless(|_| false, REASON)(entry.into());
+            prop_assert_eq!(out.skip_reason(), Some(REASON.to_owned()));
         }
 
         #[test]
-        #[cfg(feature = "trash")]
-        fn from_trash_targeted_root() {
-            assert_eq!(ErrorKind::Refused, trash::Error::TargetedRoot.into());
+        fn unvisited_filled_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file").touch()?;
+
+                let item: walk::Item = fs::open(dir.path())?.into();
+
+                let out = skip_unless(|_| false, REASON)(item.clone());
+                assert_eq!(out, item);
+
+                Ok(())
+            })
         }
-    }
 
-    /// Helpers for writing unit tests in or using this module.
-    #[cfg(test)]
-    pub mod test_helpers {
-        use super::{Entry, EntryKind, Error};
+        #[test]
+        fn unvisited_empty_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
 
-        use std::path::Path;
+                let item: walk::Item = fs::open(dir.path())?.into();
 
-        impl Error {
-            /// Get the tip associated with this [`Error`], if any.
-            pub fn tip(&self) -> Option<&str> {
-                match &self.tip {
-                    Some(tip) => Some(tip),
-                    None => None,
-                }
-            }
+                let out = skip_unless(|_| true, REASON)(item.clone());
+                assert_eq!(out, item);
+
+                let out = skip_unless(|_| false, REASON)(item);
+                assert_eq!(out.skip_reason(), Some(REASON.to_owned()));
+
+                Ok(())
+            })
         }
 
-        /// Create an [`Entry`] representing a file for testing purposes.
-        pub fn new_file<P: AsRef<Path>>(path: P) -> Entry {
-            Entry::new(&path, EntryKind::File)
+        #[test]
+        fn visited_dir_match() {
+            let transform = skip_unless(|entry| !entry.is_dir(), REASON);
+            transform(fs::test_helpers::new_file("dir/nested_dir/file").into());
+            transform(walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir/nested_dir"))));
+
+            let item = walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir")));
+
+            let out = transform(item.clone());
+            assert_eq!(out, item);
         }
 
-        /// Create an [`Entry`] representing a directory for testing purposes.
-        pub fn new_dir<P: AsRef<Path>>(path: P) -> Entry {
-            Entry::new(&path, EntryKind::Dir)
+        #[test]
+        fn visited_dir_mismatch() {
+            let transform = skip_unless(|entry| entry.path().ends_with("file1"), REASON);
+            transform(fs::test_helpers::new_file("dir/file1").into());
+            transform(fs::test_helpers::new_file("dir/nested_dir/file2").into());
+            transform(walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir/nested_dir"))));
+
+            let item = walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir")));
+
+            let out = transform(item);
+            assert_eq!(out.skip_reason(), Some(SKIP_REASON_CONTENTS_KEPT.to_owned()));
         }
 
-        /// Create an [`Entry`] representing a symbolic link for testing purposes.
-        pub fn new_symlink<P: AsRef<Path>>(path: P) -> Entry {
-            Entry::new(&path, EntryKind::Symlink)
+        #[test]
+        fn visited_dir_empty() {
+            let item = walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir")));
+
+            let out = skip_unless(|_| true, REASON)(item.clone());
+            assert_eq!(out, item);
+
+            let out = skip_unless(|_| false, REASON)(item);
+            assert_eq!(out.skip_reason(), Some(REASON.to_owned()));
         }
     }
-}
-
-/// File system walking strategies.
-mod walk {
-    use super::{fs, transform};
 
-    use std::fs::read_dir;
-    use std::iter;
-    use std::path::{Path, PathBuf};
-    use std::result;
+    /// The explanation for why an entry that is not an empty file is skipped.
+    const SKIP_REASON_NOT_AN_EMPTY_FILE: &str = "Not an empty file";
 
-    use log::trace;
+    /// Create a [`Transformer`] that skips everything but empty regular files. See [`skip_unless`]
+    /// for how directories are treated.
+    pub fn skip_unless_empty_file() -> Transformer {
+        skip_unless(
+            |entry| entry.kind() == fs::EntryKind::File && fs::is_empty(entry),
+            SKIP_REASON_NOT_AN_EMPTY_FILE,
+        )
+    }
 
+    /// Tests for the [`skip_unless_empty_file`] function.
     #[cfg(test)]
-    use proptest_derive::Arbitrary;
-
-    /// The return type of a file system [`Walker`].
-    type FileIterator = Box<dyn Iterator<Item = fs::Result>>;
+    mod test_skip_unless_empty_file {
+        use crate::test_helpers::{TestResult, with_test_dir};
 
-    /// The type of [`transform::Transformer`] supported by this module.
-    type Transformers = [transform::Transformer; 5];
+        use super::{SKIP_REASON_NOT_AN_EMPTY_FILE, fs, skip_unless_empty_file, walk};
 
-    /// A "file system walker" - a function that iterates over entries on a file system.
-    pub type Walker = Box<dyn Fn(&dyn AsRef<Path>) -> FileIterator>;
+        use assert_fs::prelude::*;
 
-    /// Struct representing an item while walking the file system.
-    #[cfg_attr(test, derive(Arbitrary, Clone, Debug, Eq, PartialEq))]
-    pub struct Item {
-        /// The [`fs::Result`] this item represents.
-        pub inner: fs::Result,
+        #[test]
+        fn file_empty() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
 
-        /// Why, if at all, the item must be skipped.
-        skip_reason: Option<String>,
+                let item: walk::Item = fs::open(file.path())?.into();
 
-        /// Whether or not the item has already been visited.
-        visited: bool,
-    }
+                let out = skip_unless_empty_file()(item.clone());
+                assert_eq!(out, item);
 
-    impl Item {
-        /// Convert the [`Item`] into an [`Item`] that will be skipped. Must be provided with the
-        /// reason why it is skipped.
-        pub fn into_skipped(mut self, reason: &str) -> Self {
-            self.skip_reason = Some(reason.to_owned());
-            self
+                Ok(())
+            })
         }
 
-        /// Convert the [`Item`] into an [`Item`] that's marked as visited.
-        fn into_visited(mut self) -> Self {
-            self.visited = true;
-            self
+        #[test]
+        fn file_filled() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
+
+                let item: walk::Item = fs::open(file.path())?.into();
+
+                let out = skip_unless_empty_file()(item);
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_NOT_AN_EMPTY_FILE.to_owned()));
+
+                Ok(())
+            })
         }
 
-        /// Returns `true` if the [`Item`] has been visited before.
-        pub fn is_visited(&self) -> bool {
-            self.visited
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn symlink() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_file(&file)?;
+
+                let item: walk::Item = fs::open(link.path())?.into();
+
+                let out = skip_unless_empty_file()(item);
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_NOT_AN_EMPTY_FILE.to_owned()));
+
+                Ok(())
+            })
         }
+    }
 
-        /// Get the file system path this [`Item`] is associated with.
-        fn path(&self) -> PathBuf {
-            self.inner.as_ref().map_or_else(fs::Error::path, fs::Entry::path)
+    /// The explanation for why an entry owned by a different group is skipped.
+    #[cfg(unix)]
+    const SKIP_REASON_OTHER_GROUP: &str = "Owned by a different group";
+
+    /// Create a [`Transformer`] that skips entries not owned by the group with the given `gid`.
+    /// See [`skip_unless_including_dirs`] for how directories are treated.
+    #[cfg(unix)]
+    pub fn skip_unless_group(gid: u32) -> Transformer {
+        skip_unless_including_dirs(
+            move |entry| fs::group_of(entry) == Some(gid),
+            SKIP_REASON_OTHER_GROUP,
+        )
+    }
+
+    /// Tests for the [`skip_unless_group`] function.
+    #[cfg(all(test, unix))]
+    mod test_skip_unless_group {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{SKIP_REASON_OTHER_GROUP, fs, skip_unless_group, walk};
+
+        use std::os::unix::fs::MetadataExt as _;
+
+        use assert_fs::prelude::*;
+
+        #[test]
+        fn same_group() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
+                let gid = file.path().metadata()?.gid();
+
+                let item: walk::Item = fs::open(file.path())?.into();
+
+                let out = skip_unless_group(gid)(item.clone());
+                assert_eq!(out, item);
+
+                let out = skip_unless_group(gid.wrapping_add(1))(item);
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_OTHER_GROUP.to_owned()));
+
+                Ok(())
+            })
         }
     }
 
-    /// Tests for the [`Item`] struct.
+    /// Create a [`Transformer`] that transforms entries for which the `predicate` does not hold into
+    /// skipped [`walk::Item`]s with the given `reason`. Unlike [`skip_unless`], directories are
+    /// always tested themselves and are also skipped if anything in them is kept. Directories that
+    /// have not been visited yet are returned untouched, unless empty, so that they can be
+    /// descended into. Return all other values untouched.
+    pub fn skip_unless_including_dirs<P>(predicate: P, reason: &'static str) -> Transformer
+    where
+        P: Fn(&fs::Entry) -> bool + 'static,
+    {
+        tracking_contents(move |item, contents| match item.inner.as_ref() {
+            Ok(entry) if entry.is_dir() && !item.is_visited() && !fs::is_empty(entry) => item,
+            Ok(entry) if !predicate(entry) => item.into_skipped(reason),
+            Ok(_) if contents.kept => item.into_skipped(SKIP_REASON_CONTENTS_KEPT),
+            _ => item,
+        })
+    }
+
+    /// Tests for the [`skip_unless_including_dirs`] function.
     #[cfg(test)]
-    mod test_item {
-        use super::{Item, fs};
+    mod test_skip_unless_including_dirs {
+        use crate::test_helpers::{TestResult, with_test_dir};
 
+        use super::{SKIP_REASON_CONTENTS_KEPT, fs, skip_unless_including_dirs, walk};
+
+        use assert_fs::prelude::*;
         use proptest::prelude::*;
         use proptest_attr_macro::proptest;
 
+        /// The skip reason used in tests.
+        const REASON: &str = "reason";
+
         #[proptest]
-        fn into_skipped(item: Item, reason: String) {
-            let inner = item.inner.clone();
-            let visited = item.visited;
+        fn error(err: fs::Error) {
+            let item: walk::Item = err.into();
 
-            prop_assert_eq!(
-                item.into_skipped(&reason),
-                Item { inner, skip_reason: Some(reason), visited }
-            );
+            let out = skip_unless_including_dirs(|_| false, REASON)(item.clone());
+            prop_assert_eq!(out, item);
         }
 
         #[proptest]
-        fn into_visited(item: Item) {
-            let inner = item.inner.clone();
-            let skip_reason = item.skip_reason.clone();
+        fn non_dir_mismatch(entry: fs::Entry) {
+            prop_assume!(!entry.is_dir());
 
-            prop_assert_eq!(item.into_visited(), Item { inner, skip_reason, visited: true });
+            let out = skip_unless_including_dirs(|_| false, REASON)(entry.into());
+            prop_assert_eq!(out.skip_reason(), Some(REASON.to_owned()));
         }
 
-        #[proptest]
-        fn is_visited(item: Item) {
-            prop_assert_eq!(item.is_visited(), item.visited);
+        #[test]
+        fn unvisited_filled_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file").touch()?;
+
+                let item: walk::Item = fs::open(dir.path())?.into();
+
+                let out = skip_unless_including_dirs(|_| false, REASON)(item.clone());
+                assert_eq!(out, item);
+
+                Ok(())
+            })
         }
 
-        #[proptest]
-        fn path_entry(entry: fs::Entry) {
-            let item: Item = entry.clone().into();
-            prop_assert_eq!(item.path(), entry.path());
+        #[test]
+        fn unvisited_empty_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let item: walk::Item = fs::open(dir.path())?.into();
+
+                let out = skip_unless_including_dirs(|_| false, REASON)(item);
+                assert_eq!(out.skip_reason(), Some(REASON.to_owned()));
+
+                Ok(())
+            })
         }
 
-        #[proptest]
-        fn path_error(err: fs::Error) {
-            let item: Item = err.clone().into();
-            prop_assert_eq!(item.path(), err.path());
+        #[test]
+        fn visited_dir_mismatch() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+
+                let item = walk::test_helpers::visited(fs::open(dir.path()));
+
+                let out = skip_unless_including_dirs(|entry| !entry.is_dir(), REASON)(item);
+                assert_eq!(out.skip_reason(), Some(REASON.to_owned()));
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn visited_dir_with_nested_dir_mismatch() {
+            let predicate = |entry: &fs::Entry| !entry.path().ends_with("nested_dir");
+            let transform = skip_unless_including_dirs(predicate, REASON);
+            transform(fs::test_helpers::new_file("dir/nested_dir/file").into());
+            transform(walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir/nested_dir"))));
+
+            let item = walk::test_helpers::visited(Ok(fs::test_helpers::new_dir("dir")));
+
+            let out = transform(item);
+            assert_eq!(out.skip_reason(), Some(SKIP_REASON_CONTENTS_KEPT.to_owned()));
+        }
+
+        #[test]
+        fn visited_dir_match() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("nested_dir").child("file").touch()?;
+
+                let item = walk::test_helpers::visited(fs::open(dir.path()));
+
+                let out = skip_unless_including_dirs(|_| true, REASON)(item.clone());
+                assert_eq!(out, item);
+
+                Ok(())
+            })
         }
     }
 
-    /// Open an [`Item`] for walking the file system.
-    ///
-    /// # Errors
-    ///
-    /// If nothing is accessible at the given path.
-    fn open<P: AsRef<Path>>(path: P) -> Item {
-        Item { inner: fs::open(path), skip_reason: None, visited: false }
+    /// The explanation for why an entry that is too small is skipped.
+    const SKIP_REASON_TOO_SMALL: &str = "Too small";
+
+    /// Get the size of the given [`fs::Entry`] as used by the size filters. Directories are only
+    /// tested by these if they are empty, see [`skip_unless`], so their size is always 0.
+    fn filtered_size_of(entry: &fs::Entry) -> Option<u64> {
+        if entry.is_dir() { Some(0) } else { fs::size_of(entry) }
     }
 
-    /// Create a [`Walker`] that only visits the given file system entry.
-    pub fn given(transformers: Transformers) -> Walker {
-        Box::new(move |path| Box::new(visit(open(path).into_visited(), transformers).into_iter()))
+    /// Create a [`Transformer`] that skips entries whose size is not larger than `size` bytes. See
+    /// [`skip_unless`] for how directories are treated.
+    pub fn skip_unless_larger_than(size: u64) -> Transformer {
+        skip_unless(
+            move |entry| filtered_size_of(entry).is_some_and(|actual| actual > size),
+            SKIP_REASON_TOO_SMALL,
+        )
     }
 
-    /// Tests for the [`given`] function.
+    /// Tests for the [`skip_unless_larger_than`] function.
     #[cfg(test)]
-    mod test_given {
+    mod test_skip_unless_larger_than {
         use crate::test_helpers::{TestResult, with_test_dir};
 
-        use super::{fs, transform};
-
-        use std::path;
+        use super::{SKIP_REASON_TOO_SMALL, fs, skip_unless_larger_than, walk};
 
         use assert_fs::prelude::*;
 
         #[test]
-        fn file() -> TestResult {
+        fn boundaries() -> TestResult {
             with_test_dir(|test_dir| {
                 let file = test_dir.child("file");
-                file.touch()?;
+                file.write_str("Hello world!")?;
 
-                let path = file.path();
+                let item: walk::Item = fs::open(file.path())?.into();
 
-                let out = given(path);
-                assert_eq!(out, vec![fs::open(path)]);
+                let out = skip_unless_larger_than(11)(item.clone());
+                assert_eq!(out, item);
+
+                let out = skip_unless_larger_than(12)(item.clone());
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_TOO_SMALL.to_owned()));
+
+                let out = skip_unless_larger_than(13)(item);
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_TOO_SMALL.to_owned()));
 
                 Ok(())
             })
@@ -1999,831 +13000,1400 @@ mod walk {
                 let dir = test_dir.child("dir");
                 dir.create_dir_all()?;
 
-                let path = dir.path();
+                let item = walk::test_helpers::visited(fs::open(dir.path()));
 
-                let out = given(path);
-                assert_eq!(out, vec![fs::open(path)]);
+                let out = skip_unless_larger_than(1)(item);
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_TOO_SMALL.to_owned()));
 
                 Ok(())
             })
         }
+    }
 
-        #[test]
-        fn filled_dir() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                dir.child("file").touch()?;
+    /// The explanation for why an entry that is too old is skipped.
+    const SKIP_REASON_TOO_OLD: &str = "Too old";
 
-                let path = dir.path();
+    /// Create a [`Transformer`] that skips entries whose `timestamp` is not after the `cutoff`. See
+    /// [`skip_unless`] for how directories are treated.
+    pub fn skip_unless_newer_than(timestamp: fs::Timestamp, cutoff: SystemTime) -> Transformer {
+        skip_unless(
+            move |entry| fs::timestamp_of(entry, timestamp).is_some_and(|time| time > cutoff),
+            SKIP_REASON_TOO_OLD,
+        )
+    }
 
-                let out = given(path);
-                assert_eq!(out, vec![fs::open(path)]);
+    /// Tests for the [`skip_unless_newer_than`] function.
+    #[cfg(test)]
+    mod test_skip_unless_newer_than {
+        use crate::test_helpers::{TestResult, unix_time, with_test_dir};
 
-                Ok(())
-            })
-        }
+        use super::{SKIP_REASON_TOO_OLD, fs, skip_unless_newer_than, walk};
+
+        use std::fs::{File, remove_file};
+        use std::time::UNIX_EPOCH;
+
+        use assert_fs::prelude::*;
 
         #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn symlink_to_file() -> TestResult {
+        fn boundaries() -> TestResult {
             with_test_dir(|test_dir| {
                 let file = test_dir.child("file");
                 file.touch()?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(&file)?;
+                let time = unix_time(1_000_000);
+                File::options().write(true).open(&file)?.set_modified(time)?;
 
-                let path = link.path();
+                let item: walk::Item = fs::open(file.path())?.into();
+                let transform = |cutoff| skip_unless_newer_than(fs::Timestamp::Modified, cutoff);
 
-                let out = given(path);
-                assert_eq!(out, vec![fs::open(path)]);
+                let out = transform(unix_time(999_999))(item.clone());
+                assert_eq!(out, item);
+
+                let out = transform(time)(item.clone());
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_TOO_OLD.to_owned()));
+
+                let out = transform(unix_time(1_000_001))(item);
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_TOO_OLD.to_owned()));
 
                 Ok(())
             })
         }
 
         #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn symlink_to_empty_dir() -> TestResult {
+        fn missing() -> TestResult {
             with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(&dir)?;
+                let file = test_dir.child("file");
+                file.touch()?;
 
-                let path = link.path();
+                let item: walk::Item = fs::open(file.path())?.into();
+                remove_file(&file)?;
 
-                let out = given(path);
-                assert_eq!(out, vec![fs::open(path)]);
+                let out = skip_unless_newer_than(fs::Timestamp::Modified, UNIX_EPOCH)(item);
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_TOO_OLD.to_owned()));
 
                 Ok(())
             })
         }
+    }
+
+    /// The explanation for why an entry that is too new is skipped.
+    const SKIP_REASON_TOO_NEW: &str = "Too new";
+
+    /// Create a [`Transformer`] that skips entries whose `timestamp` is not before the `cutoff`.
+    /// See [`skip_unless`] for how directories are treated.
+    pub fn skip_unless_older_than(timestamp: fs::Timestamp, cutoff: SystemTime) -> Transformer {
+        skip_unless(
+            move |entry| fs::timestamp_of(entry, timestamp).is_some_and(|time| time < cutoff),
+            SKIP_REASON_TOO_NEW,
+        )
+    }
+
+    /// Tests for the [`skip_unless_older_than`] function.
+    #[cfg(test)]
+    mod test_skip_unless_older_than {
+        use crate::test_helpers::{TestResult, unix_time, with_test_dir};
+
+        use super::{SKIP_REASON_TOO_NEW, fs, skip_unless_older_than, walk};
+
+        use std::fs::{File, FileTimes};
+
+        use assert_fs::prelude::*;
 
         #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn symlink_to_filled_dir() -> TestResult {
+        fn boundaries() -> TestResult {
             with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                dir.child("file").touch()?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(&dir)?;
+                let file = test_dir.child("file");
+                file.touch()?;
+                let time = unix_time(1_000_000);
+                File::options().write(true).open(&file)?.set_modified(time)?;
 
-                let path = link.path();
+                let item: walk::Item = fs::open(file.path())?.into();
+                let transform = |cutoff| skip_unless_older_than(fs::Timestamp::Modified, cutoff);
 
-                let out = given(path);
-                assert_eq!(out, vec![fs::open(path)]);
+                let out = transform(unix_time(1_000_001))(item.clone());
+                assert_eq!(out, item);
+
+                let out = transform(time)(item.clone());
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_TOO_NEW.to_owned()));
+
+                let out = transform(unix_time(999_999))(item);
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_TOO_NEW.to_owned()));
 
                 Ok(())
             })
         }
 
         #[test]
-        fn not_found() -> TestResult {
+        fn accessed() -> TestResult {
             with_test_dir(|test_dir| {
-                let path = test_dir.child("missing");
+                let file = test_dir.child("file");
+                file.touch()?;
+                let accessed = unix_time(1_000_000);
+                let times = FileTimes::new().set_accessed(accessed);
+                File::options().write(true).open(&file)?.set_times(times)?;
 
-                let out = given(&path);
-                assert_eq!(out, vec![fs::open(path)]);
+                let item: walk::Item = fs::open(file.path())?.into();
+                let cutoff = unix_time(1_000_001);
+
+                let out = skip_unless_older_than(fs::Timestamp::Accessed, cutoff)(item.clone());
+                assert_eq!(out, item);
+
+                let out = skip_unless_older_than(fs::Timestamp::Modified, cutoff)(item);
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_TOO_NEW.to_owned()));
 
                 Ok(())
             })
         }
+    }
 
-        /// Convenience wrapper around [`super::given`] for use in tests.
-        fn given<P: AsRef<path::Path>>(path: P) -> Vec<fs::Result> {
-            let given_closure = super::given([
-                transform::identity,
-                transform::identity,
-                transform::identity,
-                transform::identity,
-                transform::identity,
-            ]);
+    /// Create a [`Transformer`] that transforms (not skipped) entries modified after the `cutoff`
+    /// into a [`fs::ErrorKind::Refused`] error, with a tip saying how long before `now` they were
+    /// modified. Visited directories are skipped if anything in them is kept, such as entries
+    /// modified after the `cutoff`. Return all other values untouched.
+    pub fn refuse_recently_modified(cutoff: SystemTime, now: SystemTime) -> Transformer {
+        let is_recent = move |entry: &fs::Entry| {
+            fs::timestamp_of(entry, fs::Timestamp::Modified).filter(|&time| time > cutoff)
+        };
 
-            given_closure(&path).collect()
-        }
-    }
+        tracking_contents(move |mut item, contents| {
+            if item.is_skipped() {
+                return item;
+            }
 
-    /// Walk the subsection of the file system with `path` as root.
-    fn recurse_path<P: AsRef<Path>>(path: P, transformers: Transformers) -> FileIterator {
-        Box::new(visit(open(path), transformers).into_iter().flat_map(move |result| {
-            match result {
-                Ok(dir) if dir.is_dir() && !fs::is_empty(&dir) => match read_dir(dir.path()) {
-                    Ok(content) => Box::new(
-                        content
-                            .into_iter()
-                            .map_while(result::Result::ok)
-                            .map(|entry| entry.path())
-                            .flat_map(move |path| recurse_path(path, transformers))
-                            .chain(
-                                iter::once_with(move || {
-                                    visit(
-                                        Item { inner: Ok(dir), skip_reason: None, visited: true },
-                                        transformers,
-                                    )
-                                })
-                                .flatten(),
-                            ),
-                    ) as FileIterator,
-                    Err(err) => Box::new(iter::once(Err(dir.into_err(err.kind().into())))),
+            match item.inner.as_ref() {
+                Ok(entry) if entry.is_dir() => {
+                    if contents.kept {
+                        item.into_skipped(SKIP_REASON_CONTENTS_KEPT)
+                    } else {
+                        item
+                    }
+                },
+                Ok(entry) => {
+                    if let Some(modified) = is_recent(entry) {
+                        let tip = format!(
+                            "modified {}; use '--force-recent' to remove",
+                            format_age(modified, now)
+                        );
+                        item.inner = item.inner.and_then(|entry| {
+                            Err(entry.into_err(fs::ErrorKind::Refused).with_tip(&tip))
+                        });
+                    }
+                    item
                 },
-                _ => Box::new(iter::once(result)),
+                Err(_) => item,
             }
-        }))
-    }
-
-    /// Create a [`Walker`] that recurse directories in order to visits entries on the file system.
-    pub fn recurse(transformers: Transformers) -> Walker {
-        Box::new(move |path| recurse_path(path, transformers))
+        })
     }
 
-    /// Tests for the [`recurse`] function.
+    /// Tests for the [`refuse_recently_modified`] function.
     #[cfg(test)]
-    mod test_recurse {
-        use crate::test_helpers::{TestResult, with_test_dir};
+    mod test_refuse_recently_modified {
+        use crate::test_helpers::{TestResult, unix_time, with_test_dir};
 
-        use super::{fs, transform};
+        use super::{SKIP_REASON_CONTENTS_KEPT, fs, refuse_recently_modified, walk};
 
-        use std::path;
+        use std::fs::File;
 
         use assert_fs::prelude::*;
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
 
         #[test]
-        fn empty_file() -> TestResult {
+        fn recent() -> TestResult {
             with_test_dir(|test_dir| {
                 let file = test_dir.child("file");
                 file.touch()?;
+                File::options().write(true).open(&file)?.set_modified(unix_time(1_000_000))?;
 
-                let path = file.path();
+                let item: walk::Item = fs::open(file.path())?.into();
+                let transform = refuse_recently_modified(unix_time(999_999), unix_time(1_000_120));
 
-                let out = recurse(path);
-                assert_eq!(out, vec![fs::open(path)]);
+                let Err(err) = transform(item).inner else {
+                    panic!("expected an error");
+                };
+                assert_eq!(err.kind(), fs::ErrorKind::Refused);
+                assert_eq!(
+                    err.tip(),
+                    Some("modified 2 minutes ago; use '--force-recent' to remove")
+                );
 
                 Ok(())
             })
         }
 
         #[test]
-        fn filled_file() -> TestResult {
+        fn not_recent() -> TestResult {
             with_test_dir(|test_dir| {
                 let file = test_dir.child("file");
-                file.write_str("Hello world!")?;
+                file.touch()?;
+                File::options().write(true).open(&file)?.set_modified(unix_time(1_000_000))?;
 
-                let path = file.path();
+                let item: walk::Item = fs::open(file.path())?.into();
 
-                let out = recurse(path);
-                assert_eq!(out, vec![fs::open(path)]);
+                let out = refuse_recently_modified(unix_time(1_000_000), unix_time(1_000_120))(
+                    item.clone(),
+                );
+                assert_eq!(out, item);
 
                 Ok(())
             })
         }
 
         #[test]
-        fn empty_dir() -> TestResult {
+        #[cfg(unix)]
+        fn dir_with_recent_contents() -> TestResult {
             with_test_dir(|test_dir| {
                 let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
+                dir.child("file").touch()?;
+                File::options()
+                    .write(true)
+                    .open(dir.child("file"))?
+                    .set_modified(unix_time(1_000_000))?;
+                File::open(&dir)?.set_modified(unix_time(0))?;
 
-                let path = dir.path();
+                let transform = refuse_recently_modified(unix_time(999_999), unix_time(1_000_120));
 
-                let out = recurse(path);
-                assert_eq!(out, vec![fs::open(path)]);
+                let item: walk::Item = fs::open(dir.path())?.into();
+                let out = transform(item.clone());
+                assert_eq!(out, item);
+
+                transform(fs::open(dir.child("file").path())?.into());
+                let out = transform(walk::test_helpers::visited(fs::open(dir.path())));
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_CONTENTS_KEPT.to_owned()));
 
                 Ok(())
             })
         }
 
         #[test]
-        fn filled_dir() -> TestResult {
+        #[cfg(unix)]
+        fn dir_recent() -> TestResult {
             with_test_dir(|test_dir| {
                 let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                let file = dir.child("file");
-                file.touch()?;
+                dir.child("file").touch()?;
+                File::options().write(true).open(dir.child("file"))?.set_modified(unix_time(0))?;
+                File::open(&dir)?.set_modified(unix_time(1_000_000))?;
 
-                let dir_path = dir.path();
-                let file_path = file.path();
+                let transform = refuse_recently_modified(unix_time(999_999), unix_time(1_000_120));
 
-                let out = recurse(dir_path);
-                assert_eq!(out, vec![fs::open(file_path), fs::open(dir_path)]);
+                let item = walk::test_helpers::visited(fs::open(dir.path()));
+                let out = transform(item.clone());
+                assert_eq!(out, item);
 
                 Ok(())
             })
         }
 
+        #[proptest]
+        fn skipped(item: walk::Item, reason: String) {
+            let item = item.into_skipped(&reason);
+
+            let out = refuse_recently_modified(unix_time(0), unix_time(0))(item.clone());
+            prop_assert_eq!(out, item);
+        }
+    }
+
+    /// Describe the processes in `open_files` holding the given [`fs::Entry`] open, for example
+    /// "held open by nginx[1234]", if any.
+    #[cfg(target_os = "linux")]
+    fn held_open_by(open_files: &fs::OpenFiles, entry: &fs::Entry) -> Option<String> {
+        let holders = open_files.holders_of(entry);
+        if holders.is_empty() {
+            return None;
+        }
+
+        let holders: Vec<String> = holders.iter().map(ToString::to_string).collect();
+        Some(format!("held open by {}", holders.join(", ")))
+    }
+
+    /// Create a [`Transformer`] that warns about (not skipped) entries, other than directories, held
+    /// open by processes in `open_files`, as removing them frees no space until they are closed.
+    /// Return all values untouched.
+    #[cfg(target_os = "linux")]
+    pub fn warn_held_open(open_files: fs::OpenFiles) -> Transformer {
+        Box::new(move |item| {
+            if let Ok(entry) = item.inner.as_ref()
+                && !item.is_skipped()
+                && !entry.is_dir()
+                && let Some(holders) = held_open_by(&open_files, entry)
+            {
+                warn!(
+                    "Removing {} frees no space until it is closed {}",
+                    entry.bold(),
+                    format!("({holders})").italic()
+                );
+            }
+
+            item
+        })
+    }
+
+    /// Create a [`Transformer`] that transforms (not skipped) entries, other than directories, held
+    /// open by processes in `open_files` into a [`fs::ErrorKind::Refused`] error, with a tip
+    /// naming the processes. Return all other values untouched.
+    #[cfg(target_os = "linux")]
+    pub fn refuse_held_open(open_files: fs::OpenFiles) -> Transformer {
+        Box::new(move |mut item| {
+            if item.is_skipped() {
+                return item;
+            }
+
+            item.inner = item.inner.and_then(|entry| match held_open_by(&open_files, &entry) {
+                Some(tip) if !entry.is_dir() => {
+                    Err(entry.into_err(fs::ErrorKind::Refused).with_tip(&tip))
+                },
+                _ => Ok(entry),
+            });
+            item
+        })
+    }
+
+    /// Tests for the [`warn_held_open`] and [`refuse_held_open`] functions.
+    #[cfg(test)]
+    #[cfg(target_os = "linux")]
+    mod test_held_open {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{fs, refuse_held_open, walk, warn_held_open};
+
+        use assert_fs::prelude::*;
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
         #[test]
-        fn nested_dir() -> TestResult {
+        fn held_open() -> TestResult {
             with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                let nested_dir = dir.child("nested_dir");
-                nested_dir.create_dir_all()?;
-                let nested_file = nested_dir.child("file1");
-                nested_file.touch()?;
-                let file = dir.child("file2");
+                let file = test_dir.child("file");
                 file.touch()?;
+                let mut child = fs::test_helpers::hold_open(&file)?;
 
-                let dir_path = dir.path();
-                let nested_dir_path = nested_dir.path();
-                let nested_file_path = nested_file.path();
-                let file_path = file.path();
+                let refuse = refuse_held_open(fs::OpenFiles::scan());
+                let warn = warn_held_open(fs::OpenFiles::scan());
+                child.kill()?;
+                child.wait()?;
 
-                let out = recurse(dir_path);
-                assert_eq!(out.len(), 4);
-                assert!(out.contains(&fs::open(file_path)));
-                assert!(out.contains(&fs::open(nested_file_path)));
-                assert!(out.contains(&fs::open(nested_dir_path)));
-                assert!(out.contains(&fs::open(dir_path)));
+                let item: walk::Item = fs::test_helpers::new_file(file.path()).into();
+                assert_eq!(warn(item.clone()), item);
 
-                assert!(
-                    out.iter()
-                        .filter_map(|x| x.clone().ok())
-                        .position(|x| x.path() == nested_file_path)
-                        < out
-                            .iter()
-                            .filter_map(|x| x.clone().ok())
-                            .position(|x| x.path() == nested_dir_path)
-                );
-                assert_eq!(out.last(), Some(&fs::open(dir_path)));
+                let Err(err) = refuse(item).inner else {
+                    panic!("expected an error");
+                };
+                assert_eq!(err.kind(), fs::ErrorKind::Refused);
+                assert_eq!(err.tip(), Some(format!("held open by sleep[{}]", child.id()).as_str()));
 
                 Ok(())
             })
         }
 
+        #[proptest]
+        fn not_held_open(entry: fs::Entry) {
+            let item: walk::Item = entry.into();
+
+            let out = refuse_held_open(fs::OpenFiles::default())(item.clone());
+            prop_assert_eq!(out, item.clone());
+
+            let out = warn_held_open(fs::OpenFiles::default())(item.clone());
+            prop_assert_eq!(out, item);
+        }
+    }
+
+    /// The explanation for why an entry that is too large is skipped.
+    const SKIP_REASON_TOO_LARGE: &str = "Too large";
+
+    /// Create a [`Transformer`] that skips entries whose size is not smaller than `size` bytes. See
+    /// [`skip_unless`] for how directories are treated.
+    pub fn skip_unless_smaller_than(size: u64) -> Transformer {
+        skip_unless(
+            move |entry| filtered_size_of(entry).is_some_and(|actual| actual < size),
+            SKIP_REASON_TOO_LARGE,
+        )
+    }
+
+    /// Tests for the [`skip_unless_smaller_than`] function.
+    #[cfg(test)]
+    mod test_skip_unless_smaller_than {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{SKIP_REASON_TOO_LARGE, fs, skip_unless_smaller_than, walk};
+
+        use assert_fs::prelude::*;
+
         #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn symlink_to_file() -> TestResult {
+        fn boundaries() -> TestResult {
             with_test_dir(|test_dir| {
                 let file = test_dir.child("file");
-                file.touch()?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(&file)?;
+                file.write_str("Hello world!")?;
 
-                let path = link.path();
+                let item: walk::Item = fs::open(file.path())?.into();
 
-                let out = recurse(path);
-                assert_eq!(out, vec![fs::open(path)]);
+                let out = skip_unless_smaller_than(13)(item.clone());
+                assert_eq!(out, item);
+
+                let out = skip_unless_smaller_than(12)(item.clone());
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_TOO_LARGE.to_owned()));
+
+                let out = skip_unless_smaller_than(11)(item);
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_TOO_LARGE.to_owned()));
 
                 Ok(())
             })
         }
 
         #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn symlink_to_empty_dir() -> TestResult {
+        fn empty_dir() -> TestResult {
             with_test_dir(|test_dir| {
                 let dir = test_dir.child("dir");
                 dir.create_dir_all()?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(&dir)?;
 
-                let path = link.path();
+                let item = walk::test_helpers::visited(fs::open(dir.path()));
 
-                let out = recurse(path);
-                assert_eq!(out, vec![fs::open(path)]);
+                let out = skip_unless_smaller_than(1)(item.clone());
+                assert_eq!(out, item);
 
                 Ok(())
             })
         }
+    }
 
-        #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn symlink_to_filled_dir() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                dir.child("file").touch()?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(&dir)?;
+    /// The explanation for why an entry of a different type is skipped.
+    const SKIP_REASON_OTHER_TYPE: &str = "Not of the selected type";
 
-                let path = link.path();
+    /// Create a [`Transformer`] that skips entries that are not of one of the given `kinds`. See
+    /// [`skip_unless_including_dirs`] for how directories are treated.
+    pub fn skip_unless_type(kinds: Vec<fs::EntryKind>) -> Transformer {
+        skip_unless_including_dirs(
+            move |entry| kinds.contains(&entry.kind()),
+            SKIP_REASON_OTHER_TYPE,
+        )
+    }
 
-                let out = recurse(path);
-                assert_eq!(out, vec![fs::open(path)]);
+    /// Tests for the [`skip_unless_type`] function.
+    #[cfg(test)]
+    mod test_skip_unless_type {
+        use super::{SKIP_REASON_OTHER_TYPE, fs, skip_unless_type, walk};
 
-                Ok(())
-            })
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn non_dir(entry: fs::Entry, kinds: Vec<fs::EntryKind>) {
+            prop_assume!(!entry.is_dir());
+            let item: walk::Item = entry.clone().into();
+
+            let out = skip_unless_type(kinds.clone())(item.clone());
+            if kinds.contains(&entry.kind()) {
+                prop_assert_eq!(out, item);
+            } else {
+                prop_assert_eq!(out.skip_reason(), Some(SKIP_REASON_OTHER_TYPE.to_owned()));
+            }
+        }
+
+        #[proptest]
+        fn visited_dir(path: String) {
+            let item = walk::test_helpers::visited(Ok(fs::test_helpers::new_dir(path)));
+
+            let out = skip_unless_type(vec![fs::EntryKind::File])(item);
+            prop_assert_eq!(out.skip_reason(), Some(SKIP_REASON_OTHER_TYPE.to_owned()));
         }
+    }
+
+    /// The explanation for why an entry owned by a different user is skipped.
+    #[cfg(unix)]
+    const SKIP_REASON_OTHER_USER: &str = "Owned by a different user";
+
+    /// Create a [`Transformer`] that skips entries not owned by the user with the given `uid`. See
+    /// [`skip_unless_including_dirs`] for how directories are treated.
+    #[cfg(unix)]
+    pub fn skip_unless_user(uid: u32) -> Transformer {
+        skip_unless_including_dirs(
+            move |entry| fs::user_of(entry) == Some(uid),
+            SKIP_REASON_OTHER_USER,
+        )
+    }
+
+    /// Tests for the [`skip_unless_user`] function.
+    #[cfg(all(test, unix))]
+    mod test_skip_unless_user {
+        use crate::test_helpers::{TestResult, with_test_dir};
+
+        use super::{SKIP_REASON_OTHER_USER, fs, skip_unless_user, walk};
+
+        use std::os::unix::fs::MetadataExt as _;
+
+        use assert_fs::prelude::*;
 
         #[test]
-        fn not_found() -> TestResult {
+        fn same_user() -> TestResult {
             with_test_dir(|test_dir| {
-                let path = test_dir.child("missing");
+                let file = test_dir.child("file");
+                file.touch()?;
+                let uid = file.path().metadata()?.uid();
 
-                let out = recurse(&path);
-                assert_eq!(out, vec![fs::open(&path)]);
+                let item: walk::Item = fs::open(file.path())?.into();
+
+                let out = skip_unless_user(uid)(item.clone());
+                assert_eq!(out, item);
+
+                let out = skip_unless_user(uid.wrapping_add(1))(item);
+                assert_eq!(out.skip_reason(), Some(SKIP_REASON_OTHER_USER.to_owned()));
 
                 Ok(())
             })
         }
+    }
 
-        /// Convenience wrapper around [`super::recurse`] for use in tests.
-        fn recurse<P: AsRef<path::Path>>(path: P) -> Vec<fs::Result> {
-            let recurse_closure = super::recurse([
-                transform::identity,
-                transform::identity,
-                transform::identity,
-                transform::identity,
-                transform::identity,
-            ]);
+    /// Transform errors into equivalent errors without an associated tip. Return all other values
+    /// untouched.
+    pub fn strip_tips(mut item: walk::Item) -> walk::Item {
+        item.inner = item.inner.map_err(fs::Error::without_tip);
+        item
+    }
 
-            recurse_closure(&path).collect()
+    /// Tests for the [`strip_tips`] function.
+    #[cfg(test)]
+    mod test_strip_tips {
+        use super::{fs, strip_tips, walk};
+
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+
+        #[proptest]
+        fn entry(entry: fs::Entry) {
+            let item: walk::Item = entry.into();
+
+            let out = strip_tips(item.clone());
+            prop_assert_eq!(out, item);
         }
-    }
 
-    /// Visit the given [`Item`] and return some [`fs::Result`] or  [`None`] if the [`Item`] is
-    /// skipped.
-    fn visit(item: Item, transformers: Transformers) -> Option<fs::Result> {
-        let item = transformers.iter().fold(item, |item, transform| transform(item));
-        if let Some(reason) = &item.skip_reason {
-            trace!("skipped {}: {reason}", item.path().display());
-            None
-        } else {
-            Some(item.inner)
+        #[proptest]
+        fn error(err: fs::Error) {
+            let kind = err.kind();
+            let path = err.path();
+
+            let out = strip_tips(err.into());
+            prop_assert!(out.inner.is_err());
+
+            let err = out.inner.expect_err("is_err() should be asserted");
+            prop_assert_eq!(err.kind(), kind);
+            prop_assert_eq!(err.path(), path);
+            prop_assert_eq!(err.tip(), None);
         }
     }
 
-    /// Tests for the [`visit`] function.
+    /// The tip for avoiding [`fs::ErrorKind::NotFound`] errors.
+    const TIP_NOT_FOUND: &str = "use '--blind' to ignore";
+
+    /// Transform [`fs::ErrorKind::NotFound`] errors into equivalent errors with an associated tip
+    /// for how to avoid it. Return all other values untouched.
+    pub fn tip_not_found(mut item: walk::Item) -> walk::Item {
+        item.inner = item.inner.map_err(|err| {
+            if err.kind() == fs::ErrorKind::NotFound { err.with_tip(TIP_NOT_FOUND) } else { err }
+        });
+
+        item
+    }
+
+    /// Tests for the [`tip_not_found`] function.
     #[cfg(test)]
-    mod test_visit {
-        use super::{Item, Transformers, fs, visit};
+    mod test_tip_not_found {
+        use super::{TIP_NOT_FOUND, fs, tip_not_found, walk};
 
         use proptest::prelude::*;
         use proptest_attr_macro::proptest;
-        use proptest_derive::Arbitrary;
 
         #[proptest]
-        fn transforms(item: Item, index: TransformersIndex) {
-            prop_assume!(item.skip_reason.is_none());
-
-            let mut transformers: Transformers = [
-                transform_identity,
-                transform_identity,
-                transform_identity,
-                transform_identity,
-                transform_identity,
-            ];
-
-            if let Some(transformer) = transformers.get_mut(index.0) {
-                *transformer = transform_fixed;
+        fn found(item: walk::Item) {
+            if let Err(err) = item.inner.as_ref() {
+                prop_assume!(err.kind() != fs::ErrorKind::NotFound);
             }
 
-            prop_assert_eq!(visit(item.clone(), transformers), Some(transform_fixed(item).inner));
+            let out = tip_not_found(item.clone());
+            prop_assert_eq!(out, item);
         }
 
         #[proptest]
-        fn skips(item: Item, index: TransformersIndex) {
-            let mut transformers: Transformers = [
-                transform_identity,
-                transform_identity,
-                transform_identity,
-                transform_identity,
-                transform_identity,
-            ];
+        fn not_found(entry: fs::Entry) {
+            let path = entry.path();
+            let err = entry.into_err(fs::ErrorKind::NotFound);
 
-            if let Some(transformer) = transformers.get_mut(index.0) {
-                *transformer = transform_skip;
+            let out = tip_not_found(err.into());
+            prop_assert!(out.inner.is_err());
+
+            let err = out.inner.expect_err("is_err() should be asserted");
+            prop_assert_eq!(err.kind(), fs::ErrorKind::NotFound);
+            prop_assert_eq!(err.path(), path);
+            prop_assert_eq!(err.tip(), Some(TIP_NOT_FOUND));
+        }
+    }
+
+    /// The explanation for when an [`walk::Item`] is skipped as a result of the user answering
+    /// "no".
+    const SKIP_REASON_ANSWER_NO: &str = "Kept by user";
+
+    /// The explanation for when an [`walk::Item`] is skipped as a result of the user answering to
+    /// keep the rest of the directory.
+    const SKIP_REASON_ANSWER_KEEP_DIR: &str = "Directory kept by user";
+
+    /// The explanation for when an [`walk::Item`] is skipped as a result of the user answering to
+    /// quit.
+    const SKIP_REASON_ANSWER_QUIT: &str = "Quit by user";
+
+    /// The explanation for when an [`walk::Item`] is skipped as a result of unrecognized user
+    /// input.
+    const SKIP_REASON_ANSWER_UNKNOWN: &str = "Unrecognized input";
+
+    /// The explanation for when an [`walk::Item`] is skipped as a result of an I/O error.
+    const SKIP_REASON_IO_ERROR: &str = "I/O error";
+
+    /// The explanation of the answers to an interactive prompt, shown when the user asks for help.
+    const ANSWERS_HELP: &str = "\
+        y - remove this entry (default)\n\
+        n - keep this entry\n\
+        a - remove this entry and all remaining entries\n\
+        d - keep this entry and the rest of its directory\n\
+        q - keep this entry and all remaining entries\n\
+        ? - show this help\n";
+
+    /// Struct representing the state of an interactive session, i.e. how the user is prompted and
+    /// the answers of the user that affect how later [`walk::Item`]s are treated.
+    pub struct Session {
+        /// Where the answers of the user are read from.
+        input: RefCell<Box<dyn io::BufRead>>,
+
+        /// Where the prompts for the user are written to.
+        output: RefCell<Box<dyn io::Write>>,
+
+        /// Whether the user answered to remove all remaining entries.
+        all: Cell<bool>,
+
+        /// Whether the user answered to quit.
+        quit: Cell<bool>,
+
+        /// The directories of which the user answered to keep the rest.
+        kept_dirs: RefCell<Vec<PathBuf>>,
+
+        /// The directories of which the user confirmed the removal by typing their name.
+        confirmed_dirs: RefCell<Vec<PathBuf>>,
+
+        /// Why the user cannot be prompted, if so, as a tip for entries that are refused because
+        /// of it.
+        unavailable: Option<&'static str>,
+
+        /// Whether the user interrupted the session, which is treated as answering to quit.
+        interrupted: Arc<AtomicBool>,
+    }
+
+    impl Session {
+        /// Create a [`Session`] that writes prompts to `output` and reads answers from `input`.
+        pub fn new(input: Box<dyn io::BufRead>, output: Box<dyn io::Write>) -> Self {
+            Self {
+                input: RefCell::new(input),
+                output: RefCell::new(output),
+                all: Cell::new(false),
+                quit: Cell::new(false),
+                kept_dirs: RefCell::new(vec![]),
+                confirmed_dirs: RefCell::new(vec![]),
+                unavailable: None,
+                interrupted: Arc::default(),
             }
+        }
 
-            prop_assert_eq!(visit(item, transformers), None);
+        /// Make the [`Session`] stop waiting for answers once `interrupted` is set, treating it as
+        /// if the user answered to quit.
+        #[must_use]
+        pub fn interruptible(self, interrupted: Arc<AtomicBool>) -> Self {
+            let input = Interruptible {
+                inner: self.input.into_inner(),
+                interrupted: Arc::clone(&interrupted),
+            };
+            Self { input: RefCell::new(Box::new(input)), interrupted, ..self }
         }
 
-        /// A [`super::transform::Transformer`] that does not transform the given value.
-        fn transform_identity(item: Item) -> Item {
-            item
+        /// Create a [`Session`] in which the user cannot be prompted, with the given `tip` for
+        /// entries that are refused because of it.
+        pub fn unavailable(tip: &'static str) -> Self {
+            Self { unavailable: Some(tip), ..Self::default() }
         }
 
-        /// A [`super::transform::Transformer`] that transforms all values into the same value.
-        fn transform_fixed(mut item: Item) -> Item {
-            item.inner = Err(fs::test_helpers::new_file("file").into_err(fs::ErrorKind::Unknown));
-            item
+        /// Create a [`Session`] that prompts the user through the controlling terminal.
+        ///
+        /// # Errors
+        ///
+        /// If the terminal cannot be opened, for example because there is none.
+        pub fn terminal() -> io::Result<Self> {
+            #[cfg(unix)]
+            let (input, output) = {
+                let terminal = File::options().read(true).write(true).open("/dev/tty")?;
+                (terminal.try_clone()?, terminal)
+            };
+            #[cfg(not(unix))]
+            let (input, output) =
+                (File::open("CONIN$")?, File::options().write(true).open("CONOUT$")?);
+
+            Ok(Self::new(Box::new(io::BufReader::new(input)), Box::new(AutoStream::auto(output))))
         }
 
-        /// A [`super::transform::Transformer`] that transforms all values into the skipped item.
-        fn transform_skip(item: Item) -> Item {
-            item.into_skipped("some reason")
+        /// Whether the user answered to quit or interrupted the session.
+        fn has_quit(&self) -> bool {
+            self.quit.get() || self.is_interrupted()
         }
 
-        /// Struct wrapping a [`usize`] that implements [`Arbitrary`] to generate a valid index for
-        /// a [`Transformers`] instance.
-        #[derive(Arbitrary, Debug)]
-        struct TransformersIndex(#[proptest(strategy = "0usize..=4")] usize);
+        /// Whether the user interrupted the session.
+        fn is_interrupted(&self) -> bool {
+            self.interrupted.load(Ordering::Relaxed)
+        }
+
+        /// Get the reason to skip the given path without asking, if any.
+        fn skip_reason_for(&self, path: &Path) -> Option<&'static str> {
+            if self.has_quit() {
+                Some(SKIP_REASON_ANSWER_QUIT)
+            } else if self.kept_dirs.borrow().iter().any(|dir| path.starts_with(dir)) {
+                Some(SKIP_REASON_ANSWER_KEEP_DIR)
+            } else {
+                None
+            }
+        }
     }
 
-    /// Helpers for writing unit tests in or using this module.
-    #[cfg(test)]
-    mod test_helpers {
-        use super::{Item, fs};
+    impl Default for Session {
+        /// Create a [`Session`] in which the user cannot be prompted.
+        fn default() -> Self {
+            Self::new(Box::new(io::empty()), Box::new(io::sink()))
+        }
+    }
 
-        impl Item {
-            /// Returns the reason why the [`Item`] should *not* be removed, if any.
-            pub fn skip_reason(&self) -> Option<String> {
-                self.skip_reason.clone()
+    /// Struct wrapping an [`io::BufRead`] so that reading fails, instead of being retried, when it
+    /// is interrupted by a signal after which `interrupted` is set.
+    struct Interruptible {
+        /// The reader to read from.
+        inner: Box<dyn io::BufRead>,
+
+        /// Whether reading should stop.
+        interrupted: Arc<AtomicBool>,
+    }
+
+    impl Interruptible {
+        /// Check whether reading may be retried after it was interrupted.
+        ///
+        /// # Errors
+        ///
+        /// If `interrupted` is set.
+        fn check(&self) -> io::Result<()> {
+            if self.interrupted.load(Ordering::Relaxed) {
+                Err(io::Error::other("interrupted"))
+            } else {
+                Ok(())
             }
         }
+    }
 
-        impl From<fs::Entry> for Item {
-            fn from(entry: fs::Entry) -> Self {
-                Item { inner: Ok(entry), skip_reason: None, visited: false }
+    impl io::Read for Interruptible {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            loop {
+                match self.inner.read(buf) {
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => self.check()?,
+                    result => return result,
+                }
             }
         }
+    }
 
-        impl From<fs::Error> for Item {
-            fn from(err: fs::Error) -> Self {
-                Item { inner: Err(err), skip_reason: None, visited: false }
+    impl io::BufRead for Interruptible {
+        fn fill_buf(&mut self) -> io::Result<&[u8]> {
+            loop {
+                match self.inner.fill_buf() {
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => self.check()?,
+                    Err(err) => return Err(err),
+                    Ok(_) => break,
+                }
             }
+
+            self.inner.fill_buf()
+        }
+
+        fn consume(&mut self, amount: usize) {
+            self.inner.consume(amount);
         }
     }
-}
 
-/// File system removal strategies.
-mod rm {
-    use super::fs;
+    /// Tests for the [`Interruptible`] struct.
+    #[cfg(test)]
+    mod test_interruptible {
+        use super::{Interruptible, Session, prompt};
 
-    use std::result;
+        use std::io::{self, BufRead as _, Read as _};
+        use std::sync::Arc;
+        use std::sync::atomic::AtomicBool;
 
-    use log::trace;
-    use owo_colors::OwoColorize as _;
+        /// An [`io::BufRead`] that is interrupted once before reading `inner`.
+        struct InterruptedOnce {
+            /// Whether the reader was interrupted already.
+            interrupted: bool,
 
-    /// The `Result` type for removing an [`fs::Entry`].
-    pub type Result = result::Result<String, fs::Error>;
+            /// The input to read after the interrupt.
+            inner: &'static [u8],
+        }
 
-    /// Dispose of the [`fs::Entry`]; move it to the trash.
-    ///
-    /// # Errors
-    ///
-    /// If the [`fs::Entry`] can't be moved to the trash.
-    #[cfg(feature = "trash")]
-    pub fn dispose(entry: fs::Entry) -> Result {
-        trace!("dispose of {entry}");
+        impl InterruptedOnce {
+            /// Create an [`InterruptedOnce`] reading the given `input`.
+            fn new(input: &'static str) -> Self {
+                Self { interrupted: false, inner: input.as_bytes() }
+            }
 
-        match trash::delete(entry.path()) {
-            Ok(()) => Ok(format!("Moved {} to trash", entry.bold())),
-            Err(err) => Err(entry.into_err(err.into())),
+            /// Be interrupted if not interrupted already.
+            ///
+            /// # Errors
+            ///
+            /// The first time this is called.
+            fn interrupt(&mut self) -> io::Result<()> {
+                if self.interrupted {
+                    Ok(())
+                } else {
+                    self.interrupted = true;
+                    Err(io::Error::from(io::ErrorKind::Interrupted))
+                }
+            }
         }
-    }
 
-    /// Tests for the [`dispose`] function.
-    #[cfg(test)]
-    #[cfg(feature = "trash")]
-    mod test_dispose {
-        use crate::test_helpers::{TestResult, with_test_dir};
+        impl io::Read for InterruptedOnce {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.interrupt()?;
+                self.inner.read(buf)
+            }
+        }
 
-        use super::{dispose, fs};
+        impl io::BufRead for InterruptedOnce {
+            fn fill_buf(&mut self) -> io::Result<&[u8]> {
+                self.interrupt()?;
+                self.inner.fill_buf()
+            }
+
+            fn consume(&mut self, amount: usize) {
+                self.inner.consume(amount);
+            }
+        }
+
+        #[test]
+        fn read_not_interrupted() {
+            let mut reader = Interruptible {
+                inner: Box::new(InterruptedOnce::new("y\n")),
+                interrupted: Arc::new(AtomicBool::new(false)),
+            };
 
-        use assert_fs::prelude::*;
-        use owo_colors::OwoColorize as _;
-        use predicates::prelude::*;
+            let mut out = String::new();
+            assert!(reader.read_to_string(&mut out).is_ok());
+            assert_eq!(out, "y\n");
+        }
 
         #[test]
-        #[cfg_attr(not(feature = "test-trash"), ignore = "Only run with the test-trash feature")]
-        fn file() -> TestResult {
-            with_test_dir(|test_dir| {
-                let file = test_dir.child("file");
-                file.touch()?;
+        fn read_interrupted() {
+            let mut reader = Interruptible {
+                inner: Box::new(InterruptedOnce::new("y\n")),
+                interrupted: Arc::new(AtomicBool::new(true)),
+            };
 
-                let path = file.path();
-                let entry = fs::test_helpers::new_file(path);
+            let mut out = String::new();
+            assert!(reader.read_to_string(&mut out).is_err());
+            assert_eq!(out, "");
+        }
 
-                let out = dispose(entry);
-                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+        #[test]
+        fn read_line_not_interrupted() {
+            let mut reader = Interruptible {
+                inner: Box::new(InterruptedOnce::new("y\n")),
+                interrupted: Arc::new(AtomicBool::new(false)),
+            };
 
-                file.assert(predicate::path::missing());
+            let mut out = String::new();
+            assert!(reader.read_line(&mut out).is_ok());
+            assert_eq!(out, "y\n");
+        }
 
-                Ok(())
-            })
+        #[test]
+        fn read_line_interrupted() {
+            let mut reader = Interruptible {
+                inner: Box::new(InterruptedOnce::new("y\n")),
+                interrupted: Arc::new(AtomicBool::new(true)),
+            };
+
+            let mut out = String::new();
+            assert!(reader.read_line(&mut out).is_err());
+            assert_eq!(out, "");
         }
 
         #[test]
-        #[cfg_attr(not(feature = "test-trash"), ignore = "Only run with the test-trash feature")]
-        #[cfg(all(unix, not(target_os = "macos")))]
-        fn file_not_found_toctou() -> TestResult {
-            with_test_dir(|test_dir| {
-                let file = test_dir.child("missing");
+        fn session_interrupted() {
+            let interrupted = Arc::new(AtomicBool::new(true));
+            let session = Session::new(Box::new(InterruptedOnce::new("y\n")), Box::new(io::sink()))
+                .interruptible(Arc::clone(&interrupted));
 
-                let path = file.path();
-                let entry = fs::test_helpers::new_file(path);
+            let answer =
+                prompt("", &mut *session.input.borrow_mut(), &mut *session.output.borrow_mut());
+            assert!(answer.is_err());
+            assert!(session.is_interrupted());
+        }
+    }
 
-                let out = dispose(entry);
-                assert!(out.is_err());
+    /// Create a [`Transformer`] that transforms (not skipped) [`walk::Item`]s based on user input,
+    /// remembering answers in the given [`Session`]. Return all other values untouched.
+    ///
+    /// If `details` is set, the size, number of entries and modification time of entries are
+    /// included in the prompts.
+    pub fn interactive(session: Rc<Session>, details: bool) -> Transformer {
+        Box::new(move |item| {
+            let Ok(entry) = item.inner.as_ref() else {
+                return item;
+            };
 
-                let err = out.expect_err("is_err() should be asserted");
-                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
-                assert_eq!(err.path(), path);
+            if session.has_quit() {
+                item.into_stopped(SKIP_REASON_ANSWER_QUIT)
+            } else if let Some(reason) = session.skip_reason_for(&entry.path()) {
+                item.into_skipped(reason)
+            } else if session.all.get() {
+                item
+            } else {
+                let details =
+                    if details { details_of(entry, SystemTime::now()) } else { String::new() };
+                let prompt_text = new_prompt_for(entry, item.is_visited(), &details);
+                let answer = ask(
+                    &prompt_text,
+                    &mut *session.input.borrow_mut(),
+                    &mut *session.output.borrow_mut(),
+                );
+                interact_transform(&answer, item, &session)
+            }
+        })
+    }
 
-                Ok(())
-            })
-        }
+    /// Enum representing the possible answers to an interactive prompt.
+    #[derive(Debug, Eq, PartialEq)]
+    enum Answer {
+        /// Remove this and all remaining entries.
+        All,
 
-        #[test]
-        #[cfg_attr(not(feature = "test-trash"), ignore = "Only run with the test-trash feature")]
-        fn dir_empty() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
+        /// Show an explanation of the possible answers.
+        Help,
 
-                let path = dir.path();
-                let entry = fs::test_helpers::new_dir(path);
+        /// Keep this entry and the rest of its directory.
+        KeepDir,
 
-                let out = dispose(entry);
-                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+        /// Keep this entry.
+        No,
 
-                dir.assert(predicate::path::missing());
+        /// Keep this and all remaining entries.
+        Quit,
 
-                Ok(())
-            })
-        }
+        /// An answer that is not recognized.
+        Unknown,
 
-        #[test]
-        #[cfg_attr(not(feature = "test-trash"), ignore = "Only run with the test-trash feature")]
-        fn dir_filled() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                dir.child("file").touch()?;
+        /// Remove this entry.
+        Yes,
+    }
 
-                let path = dir.path();
-                let entry = fs::test_helpers::new_dir(path);
+    impl Answer {
+        /// Parse the [`Answer`] from the user input, where no input means [`Answer::Yes`].
+        fn parse(input: &str) -> Self {
+            match input.to_lowercase().as_str() {
+                "" | "y" | "yes" => Self::Yes,
+                "n" | "no" => Self::No,
+                "a" | "all" => Self::All,
+                "d" | "dir" => Self::KeepDir,
+                "q" | "quit" => Self::Quit,
+                "?" | "help" => Self::Help,
+                _ => Self::Unknown,
+            }
+        }
+    }
 
-                let out = dispose(entry);
-                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+    /// Create a user prompt for what to do with the given [`walk::Item`], including the given
+    /// `details` about it, see [`details_of`].
+    fn new_prompt_for(entry: &fs::Entry, visited: bool, details: &str) -> String {
+        let question = match entry.kind() {
+            fs::EntryKind::Dir => {
+                if fs::is_empty(entry) {
+                    "Remove empty directory"
+                } else if visited {
+                    "Remove directory"
+                } else {
+                    "Descend into directory"
+                }
+            },
+            fs::EntryKind::Fifo => "Remove named pipe",
+            fs::EntryKind::File => "Remove regular file",
+            fs::EntryKind::Socket => "Remove socket",
+            fs::EntryKind::Symlink => "Remove symbolic link",
+        };
 
-                dir.assert(predicate::path::missing());
+        format!("{question} {}{details}? [Y/n/a/d/q/?] ", entry.bold())
+    }
 
-                Ok(())
-            })
+    /// The maximum number of entries to look at when summarizing the contents of a directory for a
+    /// prompt, see [`details_of`].
+    const DETAILS_LIMIT: usize = 10_000;
+
+    /// Describe the size, number of entries and modification time, relative to `now`, of the given
+    /// [`fs::Entry`] for use in a prompt. Returns an empty string if none of these can be determined.
+    ///
+    /// The contents of directories are counted up to [`DETAILS_LIMIT`] entries.
+    fn details_of(entry: &fs::Entry, now: SystemTime) -> String {
+        let mut details = Vec::with_capacity(3);
+        match entry.kind() {
+            fs::EntryKind::Dir if !fs::is_empty(entry) => {
+                let contents = fs::contents_of(entry, DETAILS_LIMIT);
+                let noun = if contents.entries == 1 { "entry" } else { "entries" };
+                if contents.capped {
+                    details.push(format!("{}+ {noun}", contents.entries));
+                    details.push(format!("at least {}", format_size(contents.size)));
+                } else {
+                    details.push(format!("{} {noun}", contents.entries));
+                    details.push(format_size(contents.size));
+                }
+            },
+            fs::EntryKind::File => {
+                if let Some(size) = fs::size_of(entry) {
+                    details.push(format_size(size));
+                }
+            },
+            _ => {},
+        }
+        if let Some(modified) = fs::timestamp_of(entry, fs::Timestamp::Modified) {
+            details.push(format!("modified {}", format_age(modified, now)));
         }
 
-        #[test]
-        #[cfg_attr(not(feature = "test-trash"), ignore = "Only run with the test-trash feature")]
-        #[cfg(all(unix, not(target_os = "macos")))]
-        fn dir_not_found_toctou() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("missing");
+        if details.is_empty() { String::new() } else { format!(" ({})", details.join(", ")) }
+    }
 
-                let path = dir.path();
-                let entry = fs::test_helpers::new_dir(path);
+    /// Tests for the [`details_of`] function.
+    #[cfg(test)]
+    mod test_details_of {
+        use crate::test_helpers::{TestResult, unix_time, with_test_dir};
 
-                let out = dispose(entry);
-                assert!(out.is_err());
+        use super::{details_of, fs};
 
-                let err = out.expect_err("is_err() should be asserted");
-                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
-                assert_eq!(err.path(), path);
+        use std::fs::File;
 
-                Ok(())
-            })
-        }
+        use assert_fs::prelude::*;
 
         #[test]
-        #[cfg_attr(
-            any(not(feature = "test-trash"), all(windows, not(feature = "test-symlink"))),
-            ignore = "Only run with the test-trash (and test-symlink on Windows) feature"
-        )]
-        fn symlink_to_file() -> TestResult {
+        fn file() -> TestResult {
             with_test_dir(|test_dir| {
                 let file = test_dir.child("file");
-                file.touch()?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(&file)?;
-
-                let path = link.path();
-                let entry = fs::test_helpers::new_symlink(path);
-
-                let out = dispose(entry);
-                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+                file.write_str("Hello world!")?;
+                let modified = unix_time(1_000_000);
+                File::options().write(true).open(&file)?.set_modified(modified)?;
 
-                file.assert(predicate::path::exists());
-                link.assert(predicate::path::missing());
+                let entry = fs::test_helpers::new_file(file.path());
+                assert_eq!(
+                    details_of(&entry, unix_time(1_000_180)),
+                    " (12 B, modified 3 minutes ago)"
+                );
 
                 Ok(())
             })
         }
 
         #[test]
-        #[cfg_attr(
-            any(not(feature = "test-trash"), all(windows, not(feature = "test-symlink"))),
-            ignore = "Only run with the test-trash (and test-symlink on Windows) feature"
-        )]
-        fn symlink_to_empty_dir() -> TestResult {
+        #[cfg(unix)]
+        fn dir_empty() -> TestResult {
             with_test_dir(|test_dir| {
                 let dir = test_dir.child("dir");
                 dir.create_dir_all()?;
-                let link = test_dir.child("link");
-                link.symlink_to_dir(&dir)?;
-
-                let path = link.path();
-                let entry = fs::test_helpers::new_symlink(path);
-
-                let out = dispose(entry);
-                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+                File::open(&dir)?.set_modified(unix_time(1_000_000))?;
 
-                dir.assert(predicate::path::exists());
-                link.assert(predicate::path::missing());
+                let entry = fs::test_helpers::new_dir(dir.path());
+                assert_eq!(details_of(&entry, unix_time(1_000_000)), " (modified just now)");
 
                 Ok(())
             })
         }
 
         #[test]
-        #[cfg_attr(
-            any(not(feature = "test-trash"), all(windows, not(feature = "test-symlink"))),
-            ignore = "Only run with the test-trash feature"
-        )]
-        fn symlink_to_filled_dir() -> TestResult {
+        #[cfg(unix)]
+        fn dir_filled() -> TestResult {
             with_test_dir(|test_dir| {
                 let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                let nested_file = dir.child("file");
-                nested_file.touch()?;
-                let link = test_dir.child("link");
-                link.symlink_to_dir(&dir)?;
-
-                let path = link.path();
-                let entry = fs::test_helpers::new_symlink(path);
-
-                let out = dispose(entry);
-                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+                dir.child("file").write_str("Hello world!")?;
+                dir.child("nested_dir").child("file").write_str("Hello")?;
+                File::open(&dir)?.set_modified(unix_time(1_000_000))?;
 
-                dir.assert(predicate::path::exists());
-                nested_file.assert(predicate::path::exists());
-                link.assert(predicate::path::missing());
+                let entry = fs::test_helpers::new_dir(dir.path());
+                assert_eq!(
+                    details_of(&entry, unix_time(1_007_200)),
+                    " (3 entries, 17 B, modified 2 hours ago)"
+                );
 
                 Ok(())
             })
         }
 
         #[test]
-        #[cfg_attr(not(feature = "test-trash"), ignore = "Only run with the test-trash feature")]
-        #[cfg(all(unix, not(target_os = "macos")))]
-        fn symlink_not_found_toctou() -> TestResult {
+        fn missing() -> TestResult {
             with_test_dir(|test_dir| {
-                let link = test_dir.child("missing");
-
-                let path = link.path();
-                let entry = fs::test_helpers::new_symlink(path);
-
-                let out = dispose(entry);
-                assert!(out.is_err());
+                let file = test_dir.child("missing");
 
-                let err = out.expect_err("is_err() should be asserted");
-                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
-                assert_eq!(err.path(), path);
+                let entry = fs::test_helpers::new_file(file.path());
+                assert_eq!(details_of(&entry, unix_time(0)), "");
 
                 Ok(())
             })
         }
+    }
 
-        #[test]
-        #[cfg_attr(
-            any(not(feature = "test-trash"), all(windows, not(feature = "test-symlink"))),
-            ignore = "Only run with the test-trash (and test-symlink on Windows) feature"
-        )]
-        fn symlink_to_file_at_location_of_a_file_toctou() -> TestResult {
-            with_test_dir(|test_dir| {
-                let file = test_dir.child("file");
-                file.touch()?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(&file)?;
-
-                let path = link.path();
-                let entry = fs::test_helpers::new_file(path);
+    /// Format the given number of bytes for humans, using binary units.
+    pub fn format_size(bytes: u64) -> String {
+        const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+        let mut unit_size: u64 = 1;
+        let mut unit = None;
+        for candidate in UNITS {
+            match unit_size.checked_mul(1024) {
+                Some(size) if size <= bytes => {
+                    unit_size = size;
+                    unit = Some(candidate);
+                },
+                _ => break,
+            }
+        }
 
-                let out = dispose(entry);
-                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+        let Some(unit) = unit else {
+            return format!("{bytes} B");
+        };
+        let tenths = u128::from(bytes)
+            .saturating_mul(10)
+            .checked_div(u128::from(unit_size))
+            .unwrap_or_default();
+        format!(
+            "{}.{} {unit}",
+            tenths.checked_div(10).unwrap_or_default(),
+            tenths.checked_rem(10).unwrap_or_default()
+        )
+    }
 
-                file.assert(predicate::path::exists());
-                link.assert(predicate::path::missing());
+    /// Tests for the [`format_size`] function.
+    #[cfg(test)]
+    mod test_format_size {
+        use super::format_size;
 
-                Ok(())
-            })
+        #[test]
+        fn bytes() {
+            assert_eq!(format_size(0), "0 B");
+            assert_eq!(format_size(1023), "1023 B");
         }
 
         #[test]
-        #[cfg_attr(
-            any(not(feature = "test-trash"), all(windows, not(feature = "test-symlink"))),
-            ignore = "Only run with the test-trash (and test-symlink on Windows) feature"
-        )]
-        fn symlink_to_dir_at_location_of_a_dir_toctou() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                let link = test_dir.child("link");
-                link.symlink_to_dir(&dir)?;
+        fn units() {
+            assert_eq!(format_size(1024), "1.0 KiB");
+            assert_eq!(format_size(1536), "1.5 KiB");
+            assert_eq!(format_size(10 << 20), "10.0 MiB");
+            assert_eq!(format_size(3 << 30), "3.0 GiB");
+            assert_eq!(format_size(u64::MAX), "15.9 EiB");
+        }
+    }
 
-                let path = link.path();
-                let entry = fs::test_helpers::new_dir(path);
+    /// Format the time elapsed between the given `time` and `now` for humans, e.g. "3 minutes ago".
+    fn format_age(time: SystemTime, now: SystemTime) -> String {
+        let Ok(age) = now.duration_since(time) else {
+            return "in the future".to_owned();
+        };
 
-                let out = dispose(entry);
-                assert_eq!(out, Ok(format!("Moved {} to trash", path.display().bold())));
+        let secs = age.as_secs();
+        let (count, unit) = match secs {
+            0..60 => return "just now".to_owned(),
+            60..3_600 => (secs / 60, "minute"),
+            3_600..86_400 => (secs / 3_600, "hour"),
+            _ => (secs / 86_400, "day"),
+        };
+        format!("{count} {unit}{} ago", if count == 1 { "" } else { "s" })
+    }
 
-                dir.assert(predicate::path::exists());
-                link.assert(predicate::path::missing());
+    /// Tests for the [`format_age`] function.
+    #[cfg(test)]
+    mod test_format_age {
+        use crate::test_helpers::unix_time;
+
+        use super::format_age;
+
+        #[test]
+        fn recent() {
+            assert_eq!(format_age(unix_time(100), unix_time(100)), "just now");
+            assert_eq!(format_age(unix_time(100), unix_time(159)), "just now");
+        }
+
+        #[test]
+        fn units() {
+            assert_eq!(format_age(unix_time(0), unix_time(60)), "1 minute ago");
+            assert_eq!(format_age(unix_time(0), unix_time(3_599)), "59 minutes ago");
+            assert_eq!(format_age(unix_time(0), unix_time(3_600)), "1 hour ago");
+            assert_eq!(format_age(unix_time(0), unix_time(86_399)), "23 hours ago");
+            assert_eq!(format_age(unix_time(0), unix_time(86_400)), "1 day ago");
+            assert_eq!(format_age(unix_time(0), unix_time(864_000)), "10 days ago");
+        }
+
+        #[test]
+        fn future() {
+            assert_eq!(format_age(unix_time(100), unix_time(0)), "in the future");
+        }
+    }
 
-                Ok(())
-            })
+    /// Ask the user the given question until they give an answer other than [`Answer::Help`].
+    ///
+    /// # Errors
+    ///
+    /// If any error is returned by either the reader or the writer.
+    fn ask<R, W>(question: &str, reader: &mut R, writer: &mut W) -> io::Result<Answer>
+    where
+        R: io::BufRead,
+        W: io::Write,
+    {
+        loop {
+            match Answer::parse(&prompt(question, reader, writer)?) {
+                Answer::Help => writer.write_all(ANSWERS_HELP.as_bytes())?,
+                answer => return Ok(answer),
+            }
         }
     }
 
-    /// Remove the [`fs::Entry`] from the file system.
+    /// Print the given string to the user, wait for user input, and return the user input.
     ///
     /// # Errors
     ///
-    /// If the [`fs::Entry`] can't be removed.
-    pub fn remove(entry: fs::Entry) -> Result {
-        use std::fs::{remove_dir, remove_file};
+    /// If any error is returned by either the reader or the writer, or there is no more input.
+    fn prompt<R, W>(prompt: &str, reader: &mut R, writer: &mut W) -> io::Result<String>
+    where
+        R: io::BufRead,
+        W: io::Write,
+    {
+        const ANSWER_BUFFER_SIZE: usize = "yes".len();
+        const CLEARLINE: &[u8] = "\u{1b}[1A\u{1b}[2K".as_bytes();
 
-        trace!("remove {entry}");
-        let path = entry.path();
-        let result = match entry.kind() {
-            fs::EntryKind::Dir => remove_dir(path),
-            fs::EntryKind::File => remove_file(path),
-            #[cfg(not(windows))]
-            fs::EntryKind::Symlink => remove_file(path),
-            #[cfg(windows)]
-            fs::EntryKind::Symlink => match std::fs::metadata(&path) {
-                Ok(metadata) if metadata.is_dir() => remove_dir(path),
-                Ok(metadata) if metadata.is_file() => remove_file(path),
-                Ok(_) => unreachable!(),
-                Err(err) => Err(err),
-            },
-        };
+        writer.write_all(prompt.as_bytes())?;
+        writer.flush()?;
 
-        match result {
-            Ok(()) => Ok(format!("Removed {}", entry.bold())),
-            Err(err) => Err(entry.into_err(err.kind().into())),
+        let mut answer = String::with_capacity(ANSWER_BUFFER_SIZE);
+        if reader.read_line(&mut answer)? == 0 {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+        }
+
+        writer.write_all(CLEARLINE)?;
+        writer.flush()?;
+
+        Ok(answer.trim().to_owned())
+    }
+
+    /// Transform the given [`walk::Item`] based on the given user answer, remembering answers that
+    /// affect later [`walk::Item`]s in the given [`Session`].
+    fn interact_transform(
+        answer: &io::Result<Answer>,
+        item: walk::Item,
+        session: &Session,
+    ) -> walk::Item {
+        match answer {
+            Ok(Answer::Yes) => item,
+            Ok(Answer::All) => {
+                session.all.set(true);
+                item
+            },
+            Ok(Answer::No) => item.into_skipped(SKIP_REASON_ANSWER_NO),
+            Ok(Answer::KeepDir) => {
+                let dir = item.inner.as_ref().ok().and_then(|entry| {
+                    entry
+                        .path()
+                        .parent()
+                        .filter(|dir| !dir.as_os_str().is_empty())
+                        .map(Path::to_owned)
+                });
+                if let Some(dir) = dir {
+                    session.kept_dirs.borrow_mut().push(dir);
+                }
+                item.into_skipped(SKIP_REASON_ANSWER_KEEP_DIR)
+            },
+            Ok(Answer::Quit) => {
+                session.quit.set(true);
+                item.into_stopped(SKIP_REASON_ANSWER_QUIT)
+            },
+            Ok(Answer::Help | Answer::Unknown) => item.into_skipped(SKIP_REASON_ANSWER_UNKNOWN),
+            Err(_) if session.is_interrupted() => {
+                session.quit.set(true);
+                item.into_stopped(SKIP_REASON_ANSWER_QUIT)
+            },
+            Err(_) => item.into_skipped(SKIP_REASON_IO_ERROR),
         }
     }
 
-    /// Tests for the [`remove`] function.
+    /// Ask the user the given question through the given [`Session`]. Returns `true` if the user
+    /// answered "yes" and `false` otherwise, including when no answer is given.
+    #[cfg(feature = "bsd-mode")]
+    pub fn confirm(session: &Session, question: &str) -> bool {
+        prompt(question, &mut *session.input.borrow_mut(), &mut *session.output.borrow_mut())
+            .is_ok_and(|answer| matches!(answer.to_lowercase().as_str(), "y" | "yes"))
+    }
+
+    /// Tests for the [`interactive`] and related functions.
     #[cfg(test)]
-    mod test_remove {
+    mod test_interactive {
         use crate::test_helpers::{TestResult, with_test_dir};
 
-        use super::{fs, remove};
+        use super::{
+            ANSWERS_HELP, Answer, Session, ask, fs, interact_transform, new_prompt_for, prompt,
+            walk,
+        };
+
+        use std::io;
+        use std::path::Path;
+        use std::sync::Arc;
+        use std::sync::atomic::AtomicBool;
 
         use assert_fs::prelude::*;
         use owo_colors::OwoColorize as _;
-        use predicates::prelude::*;
+        use proptest::prelude::*;
+        use proptest_attr_macro::proptest;
+        use proptest_derive::Arbitrary;
 
         #[test]
-        fn file() -> TestResult {
+        fn new_prompt_for_file_filled() -> TestResult {
             with_test_dir(|test_dir| {
                 let file = test_dir.child("file");
-                file.touch()?;
+                file.write_str("Hello world!")?;
 
                 let path = file.path();
                 let entry = fs::test_helpers::new_file(path);
 
-                let out = remove(entry);
-                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+                let out = new_prompt_for(&entry, false, "");
+                assert_eq!(
+                    out,
+                    format!("Remove regular file {}? [Y/n/a/d/q/?] ", path.display().bold())
+                );
 
-                file.assert(predicate::path::missing());
+                let out = new_prompt_for(&entry, true, "");
+                assert_eq!(
+                    out,
+                    format!("Remove regular file {}? [Y/n/a/d/q/?] ", path.display().bold())
+                );
 
                 Ok(())
             })
         }
 
         #[test]
-        fn file_not_found_toctou() -> TestResult {
+        fn new_prompt_for_file_details() -> TestResult {
             with_test_dir(|test_dir| {
-                let file = test_dir.child("missing");
+                let file = test_dir.child("file");
+                file.touch()?;
 
                 let path = file.path();
                 let entry = fs::test_helpers::new_file(path);
 
-                let out = remove(entry);
-                assert!(out.is_err());
-
-                let err = out.expect_err("is_err() should be asserted");
-                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
-                assert_eq!(err.path(), path);
+                let out = new_prompt_for(&entry, false, " (0 B)");
+                assert_eq!(
+                    out,
+                    format!("Remove regular file {} (0 B)? [Y/n/a/d/q/?] ", path.display().bold())
+                );
 
                 Ok(())
             })
         }
 
         #[test]
-        fn dir_empty() -> TestResult {
+        fn new_prompt_for_dir_empty() -> TestResult {
             with_test_dir(|test_dir| {
                 let dir = test_dir.child("dir");
                 dir.create_dir_all()?;
@@ -2831,52 +14401,70 @@ mod rm {
                 let path = dir.path();
                 let entry = fs::test_helpers::new_dir(path);
 
-                let out = remove(entry);
-                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
-
-                dir.assert(predicate::path::missing());
+                let out = new_prompt_for(&entry, false, "");
+                assert_eq!(
+                    out,
+                    format!("Remove empty directory {}? [Y/n/a/d/q/?] ", path.display().bold())
+                );
 
                 Ok(())
             })
         }
 
         #[test]
-        fn dir_filled_toctou() -> TestResult {
+        fn new_prompt_for_visited_dir_empty() -> TestResult {
             with_test_dir(|test_dir| {
                 let dir = test_dir.child("dir");
                 dir.create_dir_all()?;
-                dir.child("file").touch()?;
 
                 let path = dir.path();
                 let entry = fs::test_helpers::new_dir(path);
 
-                let out = remove(entry);
-                assert!(out.is_err());
+                let out = new_prompt_for(&entry, true, "");
+                assert_eq!(
+                    out,
+                    format!("Remove empty directory {}? [Y/n/a/d/q/?] ", path.display().bold())
+                );
 
-                let err = out.expect_err("is_err() should be asserted");
-                assert_eq!(err.kind(), fs::ErrorKind::DirectoryNotEmpty);
-                assert_eq!(err.path(), path);
+                Ok(())
+            })
+        }
 
-                dir.assert(predicate::path::exists());
+        #[test]
+        fn new_prompt_for_dir_filled() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                dir.child("file").touch()?;
+
+                let path = dir.path();
+                let entry = fs::test_helpers::new_dir(path);
+
+                let out = new_prompt_for(&entry, false, "");
+                assert_eq!(
+                    out,
+                    format!("Descend into directory {}? [Y/n/a/d/q/?] ", path.display().bold())
+                );
 
                 Ok(())
             })
         }
 
         #[test]
-        fn dir_not_found_toctou() -> TestResult {
+        fn new_prompt_for_visited_dir_filled() -> TestResult {
             with_test_dir(|test_dir| {
-                let dir = test_dir.child("missing");
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                dir.child("file").touch()?;
 
                 let path = dir.path();
                 let entry = fs::test_helpers::new_dir(path);
 
-                let out = remove(entry);
-                assert!(out.is_err());
-
-                let err = out.expect_err("is_err() should be asserted");
-                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
-                assert_eq!(err.path(), path);
+                let out = new_prompt_for(&entry, true, "");
+                assert_eq!(
+                    out,
+                    format!("Remove directory {}? [Y/n/a/d/q/?] ", path.display().bold())
+                );
 
                 Ok(())
             })
@@ -2887,21 +14475,27 @@ mod rm {
             all(windows, not(feature = "test-symlink")),
             ignore = "Only run with the test-symlink feature"
         )]
-        fn symlink_to_file() -> TestResult {
+        fn new_prompt_for_symlink_to_file() -> TestResult {
             with_test_dir(|test_dir| {
                 let file = test_dir.child("file");
                 file.touch()?;
                 let link = test_dir.child("link");
-                link.symlink_to_file(&file)?;
+                link.symlink_to_file(file)?;
 
                 let path = link.path();
                 let entry = fs::test_helpers::new_symlink(path);
 
-                let out = remove(entry);
-                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+                let out = new_prompt_for(&entry, false, "");
+                assert_eq!(
+                    out,
+                    format!("Remove symbolic link {}? [Y/n/a/d/q/?] ", path.display().bold())
+                );
 
-                file.assert(predicate::path::exists());
-                link.assert(predicate::path::missing());
+                let out = new_prompt_for(&entry, true, "");
+                assert_eq!(
+                    out,
+                    format!("Remove symbolic link {}? [Y/n/a/d/q/?] ", path.display().bold())
+                );
 
                 Ok(())
             })
@@ -2912,992 +14506,1695 @@ mod rm {
             all(windows, not(feature = "test-symlink")),
             ignore = "Only run with the test-symlink feature"
         )]
-        fn symlink_to_empty_dir() -> TestResult {
+        fn new_prompt_for_symlink_to_empty_dir() -> TestResult {
             with_test_dir(|test_dir| {
                 let dir = test_dir.child("dir");
                 dir.create_dir_all()?;
                 let link = test_dir.child("link");
-                link.symlink_to_dir(&dir)?;
+                link.symlink_to_dir(dir)?;
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_symlink(path);
+
+                let out = new_prompt_for(&entry, false, "");
+                assert_eq!(
+                    out,
+                    format!("Remove symbolic link {}? [Y/n/a/d/q/?] ", path.display().bold())
+                );
+
+                let out = new_prompt_for(&entry, true, "");
+                assert_eq!(
+                    out,
+                    format!("Remove symbolic link {}? [Y/n/a/d/q/?] ", path.display().bold())
+                );
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        #[cfg_attr(
+            all(windows, not(feature = "test-symlink")),
+            ignore = "Only run with the test-symlink feature"
+        )]
+        fn new_prompt_for_symlink_to_filled_dir() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.create_dir_all()?;
+                dir.child("file").touch()?;
+                let link = test_dir.child("link");
+                link.symlink_to_dir(dir)?;
+
+                let path = link.path();
+                let entry = fs::test_helpers::new_symlink(path);
+
+                let out = new_prompt_for(&entry, false, "");
+                assert_eq!(
+                    out,
+                    format!("Remove symbolic link {}? [Y/n/a/d/q/?] ", path.display().bold())
+                );
+
+                let out = new_prompt_for(&entry, true, "");
+                assert_eq!(
+                    out,
+                    format!("Remove symbolic link {}? [Y/n/a/d/q/?] ", path.display().bold())
+                );
+
+                Ok(())
+            })
+        }
+
+        #[proptest]
+        fn prompt_input(question: String, answer: String) {
+            prop_assume!(!answer.is_empty());
+
+            let mut reader = answer.as_bytes();
+            let mut writer = io::sink();
+
+            let out = prompt(&question, &mut reader, &mut writer);
+            prop_assert!(out.is_ok());
+
+            let user_input = out.expect("is_ok() should be asserted");
+            prop_assert_eq!(user_input, answer.trim());
+        }
+
+        #[proptest]
+        fn prompt_input_eof(question: String) {
+            let mut reader = io::empty();
+            let mut writer = io::sink();
+
+            let out = prompt(&question, &mut reader, &mut writer);
+            prop_assert!(out.is_err());
+        }
+
+        #[proptest]
+        fn prompt_input_err(question: String) {
+            let mut reader = FaultyReader;
+            let mut writer = io::sink();
+
+            let out = prompt(&question, &mut reader, &mut writer);
+            prop_assert!(out.is_err());
+        }
+
+        #[proptest]
+        fn prompt_output(question: String) {
+            let mut reader = "\n".as_bytes();
+            let mut writer = io::BufWriter::new(Vec::new());
+
+            prompt(&question, &mut reader, &mut writer)?;
+            prop_assert_eq!(
+                String::from_utf8(writer.into_inner()?)?,
+                format!("{question}\u{1b}[1A\u{1b}[2K")
+            );
+        }
+
+        #[proptest]
+        fn prompt_output_err(question: String) {
+            let mut reader = io::empty();
+            let mut writer = FaultyWriter;
 
-                let path = link.path();
-                let entry = fs::test_helpers::new_symlink(path);
+            let out = prompt(&question, &mut reader, &mut writer);
+            prop_assert!(out.is_err());
+        }
 
-                let out = remove(entry);
-                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+        #[proptest]
+        fn ask_answer(question: String, answer: AnswerYes) {
+            let input = format!("{}\n", answer.0);
+            let mut reader = input.as_bytes();
+            let mut writer = io::sink();
 
-                dir.assert(predicate::path::exists());
-                link.assert(predicate::path::missing());
+            let out = ask(&question, &mut reader, &mut writer)?;
+            prop_assert_eq!(out, Answer::Yes);
+        }
 
-                Ok(())
-            })
+        #[proptest]
+        fn ask_answer_no(question: String, answer: AnswerNo) {
+            let input = format!("{}\n", answer.0);
+            let mut reader = input.as_bytes();
+            let mut writer = io::sink();
+
+            let out = ask(&question, &mut reader, &mut writer)?;
+            prop_assert_eq!(out, Answer::No);
         }
 
-        #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn symlink_to_filled_dir() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                let nested_file = dir.child("file");
-                nested_file.touch()?;
-                let link = test_dir.child("link");
-                link.symlink_to_dir(&dir)?;
+        #[proptest]
+        fn ask_help(question: String) {
+            let mut reader = "?\nn\n".as_bytes();
+            let mut writer = io::BufWriter::new(Vec::new());
 
-                let path = link.path();
-                let entry = fs::test_helpers::new_symlink(path);
+            let out = ask(&question, &mut reader, &mut writer)?;
+            prop_assert_eq!(out, Answer::No);
+            prop_assert!(String::from_utf8(writer.into_inner()?)?.contains(ANSWERS_HELP));
+        }
 
-                let out = remove(entry);
-                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+        #[test]
+        fn parse_answer() {
+            assert_eq!(Answer::parse(""), Answer::Yes);
+            assert_eq!(Answer::parse("Y"), Answer::Yes);
+            assert_eq!(Answer::parse("no"), Answer::No);
+            assert_eq!(Answer::parse("a"), Answer::All);
+            assert_eq!(Answer::parse("ALL"), Answer::All);
+            assert_eq!(Answer::parse("d"), Answer::KeepDir);
+            assert_eq!(Answer::parse("dir"), Answer::KeepDir);
+            assert_eq!(Answer::parse("q"), Answer::Quit);
+            assert_eq!(Answer::parse("quit"), Answer::Quit);
+            assert_eq!(Answer::parse("?"), Answer::Help);
+            assert_eq!(Answer::parse("help"), Answer::Help);
+            assert_eq!(Answer::parse("maybe"), Answer::Unknown);
+        }
 
-                dir.assert(predicate::path::exists());
-                nested_file.assert(predicate::path::exists());
-                link.assert(predicate::path::missing());
+        #[proptest]
+        fn transform_answer_yes(item: walk::Item) {
+            let session = Session::default();
 
-                Ok(())
-            })
+            let out = interact_transform(&Ok(Answer::Yes), item.clone(), &session);
+            prop_assert_eq!(out, item);
+            prop_assert!(!session.all.get());
         }
 
-        #[test]
-        fn symlink_not_found_toctou() -> TestResult {
-            with_test_dir(|test_dir| {
-                let link = test_dir.child("missing");
+        #[proptest]
+        fn transform_answer_all(item: walk::Item) {
+            let session = Session::default();
 
-                let path = link.path();
-                let entry = fs::test_helpers::new_symlink(path);
+            let out = interact_transform(&Ok(Answer::All), item.clone(), &session);
+            prop_assert_eq!(out, item);
+            prop_assert!(session.all.get());
+        }
 
-                let out = remove(entry);
-                assert!(out.is_err());
+        #[proptest]
+        fn transform_answer_no(item: walk::Item) {
+            let session = Session::default();
 
-                let err = out.expect_err("is_err() should be asserted");
-                assert_eq!(err.kind(), fs::ErrorKind::NotFound);
-                assert_eq!(err.path(), path);
+            let out = interact_transform(&Ok(Answer::No), item.clone(), &session);
+            prop_assert_eq!(out, item.into_skipped(super::SKIP_REASON_ANSWER_NO));
+        }
 
-                Ok(())
-            })
+        #[test]
+        fn transform_answer_keep_dir() {
+            let session = Session::default();
+            let item: walk::Item = fs::test_helpers::new_file(Path::new("dir").join("file")).into();
+
+            let out = interact_transform(&Ok(Answer::KeepDir), item.clone(), &session);
+            assert_eq!(out, item.into_skipped(super::SKIP_REASON_ANSWER_KEEP_DIR));
+            assert_eq!(
+                session.skip_reason_for(&Path::new("dir").join("other")),
+                Some(super::SKIP_REASON_ANSWER_KEEP_DIR)
+            );
+            assert_eq!(
+                session.skip_reason_for(Path::new("dir")),
+                Some(super::SKIP_REASON_ANSWER_KEEP_DIR)
+            );
+            assert_eq!(session.skip_reason_for(Path::new("other")), None);
         }
 
         #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn symlink_to_file_at_location_of_a_file_toctou() -> TestResult {
-            with_test_dir(|test_dir| {
-                let file = test_dir.child("file");
-                file.touch()?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(&file)?;
+        fn transform_answer_keep_dir_operand() {
+            let session = Session::default();
+            let item: walk::Item = fs::test_helpers::new_file("file").into();
 
-                let path = link.path();
-                let entry = fs::test_helpers::new_file(path);
+            let out = interact_transform(&Ok(Answer::KeepDir), item.clone(), &session);
+            assert_eq!(out, item.into_skipped(super::SKIP_REASON_ANSWER_KEEP_DIR));
+            assert_eq!(session.skip_reason_for(Path::new("other")), None);
+        }
 
-                let out = remove(entry);
-                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+        #[proptest]
+        fn transform_answer_quit(item: walk::Item, path: String) {
+            let session = Session::default();
 
-                file.assert(predicate::path::exists());
-                link.assert(predicate::path::missing());
+            let out = interact_transform(&Ok(Answer::Quit), item.clone(), &session);
+            prop_assert_eq!(out, item.into_stopped(super::SKIP_REASON_ANSWER_QUIT));
+            prop_assert_eq!(
+                session.skip_reason_for(Path::new(&path)),
+                Some(super::SKIP_REASON_ANSWER_QUIT)
+            );
+        }
 
-                Ok(())
-            })
+        #[proptest]
+        fn transform_answer_unknown(item: walk::Item) {
+            let session = Session::default();
+
+            let out = interact_transform(&Ok(Answer::Unknown), item.clone(), &session);
+            prop_assert_eq!(out, item.into_skipped(super::SKIP_REASON_ANSWER_UNKNOWN));
         }
 
-        #[test]
-        #[cfg(not(windows))]
-        fn symlink_to_dir_at_location_of_a_dir_toctou() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                let link = test_dir.child("link");
-                link.symlink_to_dir(&dir)?;
+        #[proptest]
+        fn transform_io_error(item: walk::Item, err: io::Error) {
+            let session = Session::default();
 
-                let path = link.path();
-                let entry = fs::test_helpers::new_dir(path);
+            let out = interact_transform(&Err(err), item.clone(), &session);
+            prop_assert_eq!(out, item.into_skipped(super::SKIP_REASON_IO_ERROR));
+        }
 
-                let out = remove(entry.clone());
-                assert_eq!(out, Err(entry.into_err(fs::ErrorKind::Unknown)));
+        #[proptest]
+        fn transform_interrupted(item: walk::Item, err: io::Error, path: String) {
+            let session = Session::default().interruptible(Arc::new(AtomicBool::new(true)));
 
-                dir.assert(predicate::path::exists());
-                link.assert(predicate::path::exists());
+            let out = interact_transform(&Err(err), item.clone(), &session);
+            prop_assert_eq!(out, item.into_stopped(super::SKIP_REASON_ANSWER_QUIT));
+            prop_assert_eq!(
+                session.skip_reason_for(Path::new(&path)),
+                Some(super::SKIP_REASON_ANSWER_QUIT)
+            );
+        }
 
-                Ok(())
-            })
+        /// Struct wrapping a [`String`] that implements [`Arbitrary`] to generate a "no" answer
+        /// accepted by the --interactive mode of the CLI.
+        #[derive(Arbitrary, Debug)]
+        struct AnswerNo(#[proptest(regex = "(?i-u)(n|no)")] String);
+
+        /// Struct wrapping a [`String`] that implements [`Arbitrary`] to generate a "yes" answer
+        /// accepted by the --interactive mode of the CLI.
+        #[derive(Arbitrary, Debug)]
+        struct AnswerYes(#[proptest(regex = "(?i-u)(y|yes)")] String);
+
+        /// Struct providing an erroring implementation of [`io::Read`] and [`io::BufRead`] for
+        /// testing purposes.
+        struct FaultyReader;
+
+        impl io::Read for FaultyReader {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::from(io::ErrorKind::InvalidData))
+            }
         }
 
-        #[test]
-        #[cfg(windows)]
-        #[cfg_attr(
-            not(feature = "test-symlink"),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn symlink_to_dir_at_location_of_a_dir_toctou() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                let link = test_dir.child("link");
-                link.symlink_to_dir(&dir)?;
+        impl io::BufRead for FaultyReader {
+            fn consume(&mut self, _: usize) {
+                // don't need to do anything in a faulty reader
+            }
 
-                let path = link.path();
-                let entry = fs::test_helpers::new_dir(path);
+            fn fill_buf(&mut self) -> io::Result<&[u8]> {
+                Err(io::Error::from(io::ErrorKind::InvalidData))
+            }
+        }
 
-                let out = remove(entry);
-                assert_eq!(out, Ok(format!("Removed {}", path.display().bold())));
+        /// Struct providing a erroring implementation of [`io::Write`] for testing purposes.
+        struct FaultyWriter;
 
-                dir.assert(predicate::path::exists());
-                link.assert(predicate::path::missing());
+        impl io::Write for FaultyWriter {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::from(io::ErrorKind::InvalidData))
+            }
 
-                Ok(())
-            })
+            fn flush(&mut self) -> io::Result<()> {
+                Err(io::Error::from(io::ErrorKind::InvalidData))
+            }
         }
     }
 
-    /// Pretend to dispose of the [`fs::Entry`].
-    ///
-    /// See also [`dispose`].
-    ///
-    /// # Errors
-    ///
-    /// This function will never return an error.
-    #[cfg(feature = "trash")]
-    #[allow(clippy::needless_pass_by_value, reason = "Should consume since file is removed")]
-    #[allow(clippy::unnecessary_wraps, reason = "Wrap for consistent function signature")]
-    pub fn show_dispose(entry: fs::Entry) -> Result {
-        Ok(format!("Would move {} to trash", entry.bold()))
+    /// Struct representing which directories must be confirmed by typing their name before they
+    /// are removed, see [`confirm_by_name`].
+    #[derive(Clone, Debug, Default, Eq, PartialEq)]
+    pub struct Thresholds {
+        /// Confirm directories containing more than this number of entries.
+        pub count: Option<usize>,
+
+        /// Confirm directories containing more than this number of bytes.
+        pub size: Option<u64>,
+
+        /// Confirm this (home) directory and the directories directly inside it.
+        pub home: Option<PathBuf>,
     }
 
-    /// Tests for the [`show_dispose`] function.
-    #[cfg(test)]
-    #[cfg(feature = "trash")]
-    mod test_show_dispose {
-        use super::{fs, show_dispose};
+    /// The maximum number of entries to look at when checking the contents of a directory against
+    /// the [`Thresholds`]. Directories containing more entries always exceed the thresholds.
+    const CONFIRM_LIMIT: usize = 100_000;
 
-        use owo_colors::OwoColorize as _;
-        use proptest::prelude::*;
-        use proptest_attr_macro::proptest;
+    /// The tip for when the user did not type the name of a directory to confirm its removal.
+    const TIP_NAME_NOT_CONFIRMED: &str = "its name was not typed to confirm";
+
+    /// Create a [`Transformer`] that asks the user to type the name of (not skipped) directories
+    /// exceeding the given [`Thresholds`] before descending into them, using the given [`Session`].
+    /// Directories for which anything else is typed are transformed into a
+    /// [`fs::ErrorKind::Refused`] error. Return all other values untouched.
+    ///
+    /// Directories inside a directory that was already confirmed are not asked about again. If the
+    /// user cannot be prompted, directories exceeding the [`Thresholds`] are refused. Once the
+    /// [`Session`] is interrupted, all directories are skipped.
+    pub fn confirm_by_name(session: Rc<Session>, thresholds: Thresholds) -> Transformer {
+        let within = RefCell::new(HashSet::new());
+
+        Box::new(move |mut item| {
+            let Ok(entry) = item.inner.as_ref() else {
+                return item;
+            };
+            if item.is_skipped() || item.is_visited() || !entry.is_dir() {
+                return item;
+            }
 
-        #[proptest]
-        fn anything(entry: fs::Entry) {
             let path = entry.path();
-            let out = show_dispose(entry);
-            prop_assert_eq!(out, Ok(format!("Would move {} to trash", path.display().bold())));
-        }
+            if session.is_interrupted() {
+                return item.into_skipped(SKIP_REASON_ANSWER_QUIT);
+            }
+            if session.confirmed_dirs.borrow().iter().any(|dir| path.starts_with(dir)) {
+                return item;
+            }
+            let Some(reason) = reason_to_confirm(entry, &thresholds, &mut within.borrow_mut())
+            else {
+                return item;
+            };
+            if let Some(tip) = session.unavailable {
+                item.inner = item
+                    .inner
+                    .and_then(|entry| Err(entry.into_err(fs::ErrorKind::Refused).with_tip(tip)));
+                return item;
+            }
+
+            let name = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy();
+            let answer = prompt(
+                &format!("Directory {} {reason}, type its name to confirm: ", entry.bold()),
+                &mut *session.input.borrow_mut(),
+                &mut *session.output.borrow_mut(),
+            );
+            if answer.is_ok_and(|answer| answer == name) {
+                session.confirmed_dirs.borrow_mut().push(path);
+            } else if session.is_interrupted() {
+                return item.into_skipped(SKIP_REASON_ANSWER_QUIT);
+            } else {
+                item.inner = item.inner.and_then(|entry| {
+                    Err(entry.into_err(fs::ErrorKind::Refused).with_tip(TIP_NAME_NOT_CONFIRMED))
+                });
+            }
+
+            item
+        })
     }
 
-    /// Pretend to remove the [`fs::Entry`].
-    ///
-    /// See also [`remove`].
+    /// Get the reason why the removal of the given directory [`fs::Entry`] must be confirmed
+    /// according to the given [`Thresholds`], if any.
     ///
-    /// # Errors
-    ///
-    /// This function will never return an error.
-    #[allow(clippy::needless_pass_by_value, reason = "Should consume since file is removed")]
-    #[allow(clippy::unnecessary_wraps, reason = "Wrap for consistent function signature")]
-    pub fn show_remove(entry: fs::Entry) -> Result {
-        Ok(format!("Would remove {}", entry.bold()))
+    /// Directories found `within` the count and size thresholds are remembered, so that the
+    /// contents of the directories inside them, which are within the thresholds too, are not
+    /// counted again.
+    fn reason_to_confirm(
+        entry: &fs::Entry,
+        thresholds: &Thresholds,
+        within: &mut HashSet<PathBuf>,
+    ) -> Option<String> {
+        if let Some(home) = &thresholds.home
+            && let Ok(path) = canonicalize(entry.path())
+            && (path == *home || path.parent() == Some(home))
+        {
+            return Some("is in your home directory".to_owned());
+        }
+
+        let path = entry.path();
+        if (thresholds.count.is_none() && thresholds.size.is_none())
+            || path.ancestors().any(|dir| within.contains(dir))
+        {
+            return None;
+        }
+
+        let limit = thresholds.count.map_or(CONFIRM_LIMIT, |count| count.min(CONFIRM_LIMIT));
+        let contents = fs::contents_of(entry, limit);
+        if contents.capped {
+            let noun = if contents.entries == 1 { "entry" } else { "entries" };
+            Some(format!("contains more than {} {noun}", contents.entries))
+        } else if thresholds.size.is_some_and(|size| contents.size > size) {
+            Some(format!("contains {}", format_size(contents.size)))
+        } else {
+            within.insert(path);
+            None
+        }
     }
 
-    /// Tests for the [`show_remove`] function.
+    /// Tests for the [`confirm_by_name`] and related functions.
     #[cfg(test)]
-    mod test_show_remove {
-        use super::{fs, show_remove};
+    mod test_confirm_by_name {
+        use crate::test_helpers::{TestResult, with_test_dir};
 
-        use owo_colors::OwoColorize as _;
+        use super::{
+            Session, TIP_NAME_NOT_CONFIRMED, Thresholds, confirm_by_name, fs, reason_to_confirm,
+            walk,
+        };
+
+        use std::collections::HashSet;
+        use std::fs::canonicalize;
+        use std::io;
+        use std::rc::Rc;
+        use std::sync::Arc;
+        use std::sync::atomic::AtomicBool;
+
+        use assert_fs::prelude::*;
         use proptest::prelude::*;
         use proptest_attr_macro::proptest;
 
-        #[proptest]
-        fn anything(entry: fs::Entry) {
-            let path = entry.path();
-            let out = show_remove(entry);
-            prop_assert_eq!(out, Ok(format!("Would remove {}", path.display().bold())));
+        /// Create a [`Session`] in which the user answers with the given `input`.
+        fn session_answering(input: &'static str) -> Rc<Session> {
+            Rc::new(Session::new(Box::new(input.as_bytes()), Box::new(io::sink())))
+        }
+
+        #[test]
+        fn confirmed() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file1").touch()?;
+                dir.child("file2").touch()?;
+
+                let thresholds = Thresholds { count: Some(1), ..Thresholds::default() };
+                let transform = confirm_by_name(session_answering("dir\n"), thresholds);
+
+                let item: walk::Item = fs::test_helpers::new_dir(dir.path()).into();
+                assert_eq!(transform(item.clone()), item);
+
+                Ok(())
+            })
         }
-    }
-}
 
-/// Transformers for [`walk::Item`]s.
-mod transform {
-    use super::{fs, walk};
+        #[test]
+        fn not_confirmed() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file1").touch()?;
+                dir.child("file2").touch()?;
 
-    use std::io;
-    use std::path::Path;
+                let thresholds = Thresholds { count: Some(1), ..Thresholds::default() };
+                let transform = confirm_by_name(session_answering("y\n"), thresholds);
 
-    use owo_colors::OwoColorize as _;
+                let item = fs::test_helpers::new_dir(dir.path()).into();
+                let Err(err) = transform(item).inner else {
+                    panic!("expected an error");
+                };
+                assert_eq!(err.kind(), fs::ErrorKind::Refused);
+                assert_eq!(err.tip(), Some(TIP_NAME_NOT_CONFIRMED));
 
-    /// A function that may change a [`walk::Item`] into a different-but-related [`walk::Item`].
-    pub type Transformer = fn(walk::Item) -> walk::Item;
+                Ok(())
+            })
+        }
 
-    /// Does nothing, returns any value untouched.
-    pub fn identity(item: walk::Item) -> walk::Item {
-        item
-    }
+        #[test]
+        fn unavailable() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file1").touch()?;
+                dir.child("file2").touch()?;
 
-    /// Tests for the [`identity`] function.
-    #[cfg(test)]
-    mod test_identity {
-        use super::{identity, walk};
+                let thresholds = Thresholds { count: Some(1), ..Thresholds::default() };
+                let transform = confirm_by_name(Rc::new(Session::unavailable("tip")), thresholds);
 
-        use proptest::prelude::*;
-        use proptest_attr_macro::proptest;
+                let item = fs::test_helpers::new_dir(dir.path()).into();
+                let Err(err) = transform(item).inner else {
+                    panic!("expected an error");
+                };
+                assert_eq!(err.kind(), fs::ErrorKind::Refused);
+                assert_eq!(err.tip(), Some("tip"));
 
-        #[proptest]
-        fn any_item(item: walk::Item) {
-            let out = identity(item.clone());
-            prop_assert_eq!(out, item);
+                Ok(())
+            })
         }
-    }
 
-    /// The tip for avoiding [`fs::ErrorKind::IsADirectory`] errors.
-    const TIP_IS_DIR: &str = "use '--dir' to remove";
+        #[test]
+        fn interrupted() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file1").touch()?;
+                dir.child("file2").touch()?;
 
-    /// Transform all directories into a [`fs::ErrorKind::IsADirectory`] error. Return all other
-    /// values untouched.
-    pub fn disallow_all_dirs(mut item: walk::Item) -> walk::Item {
-        item.inner = item.inner.and_then(|entry| {
-            if entry.is_dir() {
-                Err(entry.into_err(fs::ErrorKind::IsADirectory).with_tip(TIP_IS_DIR))
-            } else {
-                Ok(entry)
-            }
-        });
+                let session = Session::new(Box::new("dir\n".as_bytes()), Box::new(io::sink()))
+                    .interruptible(Arc::new(AtomicBool::new(true)));
+                let thresholds = Thresholds { count: Some(1), ..Thresholds::default() };
+                let transform = confirm_by_name(Rc::new(session), thresholds);
 
-        item
-    }
+                let item: walk::Item = fs::test_helpers::new_dir(dir.path()).into();
+                assert_eq!(
+                    transform(item.clone()),
+                    item.into_skipped(super::SKIP_REASON_ANSWER_QUIT)
+                );
 
-    /// Tests for the [`disallow_all_dirs`] function.
-    #[cfg(test)]
-    mod test_disallow_all_dirs {
-        use super::{TIP_IS_DIR, disallow_all_dirs, fs, walk};
+                Ok(())
+            })
+        }
 
-        use proptest::prelude::*;
-        use proptest_attr_macro::proptest;
+        #[test]
+        fn no_input() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file").write_str("Hello world!")?;
 
-        #[proptest]
-        fn not_a_directory(item: walk::Item) {
-            if let Ok(entry) = item.inner.as_ref() {
-                prop_assume!(!entry.is_dir());
-            }
+                let thresholds = Thresholds { size: Some(1), ..Thresholds::default() };
+                let transform = confirm_by_name(session_answering(""), thresholds);
 
-            let out = disallow_all_dirs(item.clone());
-            prop_assert_eq!(out, item);
+                let item = fs::test_helpers::new_dir(dir.path()).into();
+                assert!(transform(item).inner.is_err());
+
+                Ok(())
+            })
         }
 
-        #[proptest]
-        fn a_directory(entry: fs::Entry) {
-            prop_assume!(entry.is_dir());
+        #[test]
+        fn nested_confirmed_once() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("nested").child("file1").touch()?;
+                dir.child("nested").child("file2").touch()?;
 
-            let path = entry.path();
+                let thresholds = Thresholds { count: Some(1), ..Thresholds::default() };
+                let transform = confirm_by_name(session_answering("dir\n"), thresholds);
 
-            let out = disallow_all_dirs(entry.into());
-            prop_assert!(out.inner.is_err());
+                let item: walk::Item = fs::test_helpers::new_dir(dir.path()).into();
+                assert_eq!(transform(item.clone()), item);
 
-            let err = out.inner.expect_err("is_err() should be asserted");
-            prop_assert_eq!(err.kind(), fs::ErrorKind::IsADirectory);
-            prop_assert_eq!(err.path(), path);
-            prop_assert_eq!(err.tip(), Some(TIP_IS_DIR));
+                let item: walk::Item = fs::test_helpers::new_dir(dir.child("nested").path()).into();
+                assert_eq!(transform(item.clone()), item);
+
+                Ok(())
+            })
         }
-    }
 
-    /// Transform current directory and parent directory into a [`fs::ErrorKind::Refused`] error.
-    /// Return all other values untouched.
-    pub fn disallow_current_and_parent_dir(mut item: walk::Item) -> walk::Item {
-        item.inner = item.inner.and_then(|entry| {
-            if is_current_or_parent_dir(entry.path()) {
-                Err(entry.into_err(fs::ErrorKind::Refused))
-            } else {
-                Ok(entry)
-            }
-        });
+        #[test]
+        fn within_thresholds() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file").write_str("Hello world!")?;
 
-        item
-    }
+                let thresholds = Thresholds { count: Some(1), size: Some(12), home: None };
+                let transform = confirm_by_name(session_answering(""), thresholds);
 
-    /// Check if the given [`Path`] is the current directory or parent directory.
-    fn is_current_or_parent_dir<P: AsRef<Path>>(path: P) -> bool {
-        path.as_ref().ends_with(".") || path.as_ref().ends_with("..")
-    }
+                let item: walk::Item = fs::test_helpers::new_dir(dir.path()).into();
+                assert_eq!(transform(item.clone()), item);
 
-    /// Tests for the [`disallow_current_and_parent_dir`] function.
-    #[cfg(test)]
-    mod test_disallow_current_and_parent_dir {
-        use super::{disallow_current_and_parent_dir, fs, walk};
+                Ok(())
+            })
+        }
 
-        use std::path::{MAIN_SEPARATOR_STR, Path};
+        #[test]
+        fn file() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.write_str("Hello world!")?;
 
-        use proptest::prelude::*;
-        use proptest_attr_macro::proptest;
+                let thresholds = Thresholds { size: Some(1), ..Thresholds::default() };
+                let transform = confirm_by_name(session_answering(""), thresholds);
 
-        #[proptest]
-        fn not_current_nor_parent_dir(item: walk::Item) {
-            if let Ok(entry) = item.inner.as_ref() {
-                prop_assume!(!entry.path().ends_with("."));
-                prop_assume!(!entry.path().ends_with(".."));
-            }
+                let item: walk::Item = fs::test_helpers::new_file(file.path()).into();
+                assert_eq!(transform(item.clone()), item);
 
-            let out = disallow_current_and_parent_dir(item.clone());
-            prop_assert_eq!(out, item);
+                Ok(())
+            })
         }
 
         #[proptest]
-        fn entry_current_directory(path: CurrentDirPath) {
-            let entry = fs::test_helpers::new_dir(&path.0);
-
-            let out = disallow_current_and_parent_dir(entry.into());
-            prop_assert!(out.inner.is_err());
+        fn skipped(item: walk::Item, reason: String) {
+            let thresholds = Thresholds { count: Some(0), size: Some(0), home: None };
+            let transform = confirm_by_name(session_answering(""), thresholds);
 
-            let err = out.inner.expect_err("is_err() should be asserted");
-            prop_assert_eq!(err.kind(), fs::ErrorKind::Refused);
-            prop_assert_eq!(err.path(), Path::new(&path.0));
+            let item = item.into_skipped(&reason);
+            prop_assert_eq!(transform(item.clone()), item);
         }
 
-        #[proptest]
-        fn entry_parent_directory(path: ParentDirPath) {
-            let entry = fs::test_helpers::new_dir(&path.0);
+        #[test]
+        fn reason_count() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file1").touch()?;
+                dir.child("file2").touch()?;
+                let entry = fs::test_helpers::new_dir(dir.path());
 
-            let out = disallow_current_and_parent_dir(entry.into());
-            prop_assert!(out.inner.is_err());
+                let thresholds = Thresholds { count: Some(1), ..Thresholds::default() };
+                assert_eq!(
+                    reason_to_confirm(&entry, &thresholds, &mut HashSet::new()),
+                    Some("contains more than 1 entry".to_owned())
+                );
 
-            let err = out.inner.expect_err("is_err() should be asserted");
-            prop_assert_eq!(err.kind(), fs::ErrorKind::Refused);
-            prop_assert_eq!(err.path(), Path::new(&path.0));
+                let thresholds = Thresholds { count: Some(2), ..Thresholds::default() };
+                assert_eq!(reason_to_confirm(&entry, &thresholds, &mut HashSet::new()), None);
+
+                Ok(())
+            })
         }
 
-        /// Struct wrapping a [`String`] that implements [`Arbitrary`] to generate a current
-        /// directory path.
-        #[derive(Debug)]
-        struct CurrentDirPath(String);
+        #[test]
+        fn reason_size() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file").write_str(&"x".repeat(2048))?;
+                let entry = fs::test_helpers::new_dir(dir.path());
 
-        impl Arbitrary for CurrentDirPath {
-            type Parameters = ();
-            type Strategy = BoxedStrategy<Self>;
+                let thresholds = Thresholds { size: Some(1024), ..Thresholds::default() };
+                assert_eq!(
+                    reason_to_confirm(&entry, &thresholds, &mut HashSet::new()),
+                    Some("contains 2.0 KiB".to_owned())
+                );
 
-            fn arbitrary_with((): ()) -> Self::Strategy {
-                "(\\.\\.?SEPARATOR)*\\."
-                    .prop_map(|v| CurrentDirPath(v.replace("SEPARATOR", MAIN_SEPARATOR_STR)))
-                    .boxed()
-            }
+                let thresholds = Thresholds { size: Some(2048), ..Thresholds::default() };
+                assert_eq!(reason_to_confirm(&entry, &thresholds, &mut HashSet::new()), None);
+
+                Ok(())
+            })
         }
 
-        /// Struct wrapping a [`String`] that implements [`Arbitrary`] to generate a parent
-        /// directory path.
-        #[derive(Debug)]
+        #[test]
+        fn reason_within() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let nested_dir = dir.child("nested_dir");
+                nested_dir.create_dir_all()?;
 
-        struct ParentDirPath(String);
+                let thresholds = Thresholds { count: Some(2), ..Thresholds::default() };
+                let mut within = HashSet::new();
+                let entry = fs::test_helpers::new_dir(dir.path());
+                assert_eq!(reason_to_confirm(&entry, &thresholds, &mut within), None);
 
-        impl Arbitrary for ParentDirPath {
-            type Parameters = ();
-            type Strategy = BoxedStrategy<Self>;
+                nested_dir.child("file1").touch()?;
+                nested_dir.child("file2").touch()?;
+                nested_dir.child("file3").touch()?;
 
-            fn arbitrary_with((): ()) -> Self::Strategy {
-                "(\\.\\.?SEPARATOR)*\\.\\."
-                    .prop_map(|v| ParentDirPath(v.replace("SEPARATOR", MAIN_SEPARATOR_STR)))
-                    .boxed()
-            }
+                let entry = fs::test_helpers::new_dir(nested_dir.path());
+                assert_eq!(reason_to_confirm(&entry, &thresholds, &mut within), None);
+                assert_eq!(
+                    reason_to_confirm(&entry, &thresholds, &mut HashSet::new()),
+                    Some("contains more than 2 entries".to_owned())
+                );
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn reason_home() -> TestResult {
+            with_test_dir(|test_dir| {
+                let home = test_dir.child("home");
+                home.child("dir").child("nested").create_dir_all()?;
+                test_dir.child("other").create_dir_all()?;
+
+                let thresholds =
+                    Thresholds { home: Some(canonicalize(&home)?), ..Thresholds::default() };
+                let reason = Some("is in your home directory".to_owned());
+                for path in [home.path(), home.child("dir").path()] {
+                    let entry = fs::test_helpers::new_dir(path);
+                    assert_eq!(reason_to_confirm(&entry, &thresholds, &mut HashSet::new()), reason);
+                }
+                for path in
+                    [home.child("dir").child("nested").path(), test_dir.child("other").path()]
+                {
+                    let entry = fs::test_helpers::new_dir(path);
+                    assert_eq!(reason_to_confirm(&entry, &thresholds, &mut HashSet::new()), None);
+                }
+
+                Ok(())
+            })
         }
     }
+}
 
-    /// The tip for avoiding [`fs::ErrorKind::DirectoryNotEmpty`] errors.
-    const TIP_DIR_NOT_EMPTY: &str = "use '--recursive' to remove";
+/// A full-screen checklist to pick the entries to remove, see `--pick`.
+#[cfg(feature = "pick")]
+mod pick {
+    use super::{fs, transform, walk};
+
+    use std::io::{self, IsTerminal as _};
+    use std::path::PathBuf;
+
+    use ratatui::backend::{Backend, CrosstermBackend};
+    use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+    use ratatui::crossterm::execute;
+    use ratatui::crossterm::terminal::{
+        EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+    };
+    use ratatui::layout::{Constraint, Layout};
+    use ratatui::style::{Modifier, Style};
+    use ratatui::text::Line;
+    use ratatui::widgets::{HighlightSpacing, List, ListState, Paragraph};
+    use ratatui::{Frame, Terminal};
+
+    /// The maximum number of entries to look at when determining the size of a directory.
+    const SIZE_LIMIT: usize = 10_000;
+
+    /// The explanation of the keys shown at the bottom of the screen.
+    const KEYS_HELP: &str = "space: toggle  a: toggle all  enter: remove picked  q: cancel";
+
+    /// Let the user pick which of the entries in the given [`walk::Plan`] to remove in a
+    /// full-screen checklist on the terminal, and return the paths of the entries they did not
+    /// pick. Only the paths of the [`walk::Plan`] and their direct contents are listed.
+    ///
+    /// # Errors
+    ///
+    /// If there is no terminal or the terminal cannot be used.
+    pub fn unpicked(plan: &walk::Plan) -> Result<Vec<PathBuf>, String> {
+        let mut picker = Picker::new(plan);
+        if picker.choices.is_empty() {
+            return Ok(vec![]);
+        }
+        if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+            return Err("not a terminal".to_owned());
+        }
+
+        enable_raw_mode().map_err(|err| err.to_string())?;
+        let result = execute!(io::stderr(), EnterAlternateScreen)
+            .and_then(|()| Terminal::new(CrosstermBackend::new(io::stderr())))
+            .and_then(|mut terminal| run(&mut terminal, &mut picker, event::read));
+        _ = execute!(io::stderr(), LeaveAlternateScreen);
+        _ = disable_raw_mode();
+
+        match result.map_err(|err| err.to_string())? {
+            Outcome::Confirmed => Ok(picker.unpicked()),
+            Outcome::Cancelled => {
+                Ok(picker.choices.into_iter().map(|choice| choice.path).collect())
+            },
+        }
+    }
 
-    /// Transform filled directories into a [`fs::ErrorKind::DirectoryNotEmpty`] error. Return all
-    /// other values untouched.
-    pub fn disallow_filled_dirs(mut item: walk::Item) -> walk::Item {
-        item.inner = item.inner.and_then(|entry| {
-            if entry.is_dir() && !fs::is_empty(&entry) {
-                Err(entry.into_err(fs::ErrorKind::DirectoryNotEmpty).with_tip(TIP_DIR_NOT_EMPTY))
-            } else {
-                Ok(entry)
+    /// Show the [`Picker`] on the given [`Terminal`] and handle the events produced by `next_event`
+    /// until the user is done.
+    ///
+    /// # Errors
+    ///
+    /// If the [`Terminal`] cannot be drawn on or `next_event` fails.
+    fn run<B, E>(
+        terminal: &mut Terminal<B>,
+        picker: &mut Picker,
+        mut next_event: E,
+    ) -> io::Result<Outcome>
+    where
+        B: Backend,
+        E: FnMut() -> io::Result<Event>,
+    {
+        loop {
+            terminal.draw(|frame| picker.render(frame))?;
+            if let Event::Key(key) = next_event()?
+                && let Some(outcome) = picker.handle(key)
+            {
+                return Ok(outcome);
             }
-        });
-
-        item
+        }
     }
 
-    /// Tests for the [`disallow_filled_dirs`] function.
+    /// Tests for the [`run`] function.
     #[cfg(test)]
-    mod test_disallow_filled_dirs {
+    mod test_run {
         use crate::test_helpers::{TestResult, with_test_dir};
 
-        use super::{TIP_DIR_NOT_EMPTY, disallow_filled_dirs, fs, walk};
+        use super::{Outcome, Picker, fs, run};
+
+        use std::io;
+        use std::path::MAIN_SEPARATOR;
 
         use assert_fs::prelude::*;
-        use proptest::prelude::*;
-        use proptest_attr_macro::proptest;
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+        use ratatui::buffer::Cell;
+        use ratatui::crossterm::event::{Event, KeyCode, KeyEvent};
 
-        #[proptest]
-        fn non_dir(item: walk::Item) {
-            if let Ok(entry) = item.inner.as_ref() {
-                prop_assume!(!entry.is_dir());
-            }
+        #[test]
+        fn render() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                dir.child("file").write_str("Hello world!")?;
+                dir.child("nested_dir").child("file").write_str("Hello")?;
+
+                let mut picker = Picker::new(&vec![(
+                    dir.to_path_buf(),
+                    vec![
+                        fs::open(dir.child("file")),
+                        fs::open(dir.child("nested_dir").child("file")),
+                        fs::open(dir.child("nested_dir")),
+                        fs::open(&dir),
+                    ],
+                )]);
+                picker.choices.iter_mut().for_each(|choice| {
+                    choice.label =
+                        choice.label.replace(&test_dir.path().display().to_string(), "~");
+                });
+
+                let mut keys = [KeyCode::Down, KeyCode::Char(' '), KeyCode::Enter].into_iter();
+                let mut terminal = Terminal::new(TestBackend::new(70, 6))?;
+                let outcome = run(&mut terminal, &mut picker, || {
+                    keys.next()
+                        .map(|key| Event::Key(KeyEvent::from(key)))
+                        .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))
+                })?;
+                assert_eq!(outcome, Outcome::Confirmed);
+
+                let sep = MAIN_SEPARATOR;
+                assert_eq!(
+                    screen(&terminal),
+                    vec![
+                        "Pick entries to remove (2 of 3 picked)".to_owned(),
+                        format!("  [x] file        12 B  ~{sep}dir{sep}file"),
+                        format!("> [ ] dir          5 B  ~{sep}dir{sep}nested_dir"),
+                        format!("  [x] dir         17 B  ~{sep}dir"),
+                        String::new(),
+                        super::KEYS_HELP.to_owned(),
+                    ]
+                );
 
-            let out = disallow_filled_dirs(item.clone());
-            prop_assert_eq!(out, item);
+                Ok(())
+            })
         }
 
         #[test]
-        fn entry_empty_dir() -> TestResult {
+        fn event_error() -> TestResult {
             with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-
-                let path = dir.path();
-                let entry = fs::test_helpers::new_dir(path);
+                let file = test_dir.child("file");
+                file.touch()?;
 
-                let out = disallow_filled_dirs(entry.clone().into());
-                assert_eq!(out, entry.into());
+                let mut picker = Picker::new(&vec![(file.to_path_buf(), vec![fs::open(&file)])]);
+                let mut terminal = Terminal::new(TestBackend::new(70, 6))?;
+                let outcome = run(&mut terminal, &mut picker, || Err(io::Error::other("test")));
+                assert!(outcome.is_err());
 
                 Ok(())
             })
         }
 
-        #[test]
-        fn entry_filled_dir() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                dir.child("file").touch()?;
+        /// Get the text on the screen of the given [`Terminal`], without trailing whitespace.
+        fn screen(terminal: &Terminal<TestBackend>) -> Vec<String> {
+            let buffer = terminal.backend().buffer();
+            buffer
+                .content()
+                .chunks(usize::from(buffer.area.width))
+                .map(|row| row.iter().map(Cell::symbol).collect::<String>().trim_end().to_owned())
+                .collect()
+        }
+    }
 
-                let path = dir.path();
-                let entry = fs::test_helpers::new_dir(path);
+    /// Enum representing how the user finished picking.
+    #[derive(Debug, Eq, PartialEq)]
+    enum Outcome {
+        /// The user wants to remove the picked entries.
+        Confirmed,
 
-                let out = disallow_filled_dirs(entry.into());
-                assert!(out.inner.is_err());
+        /// The user wants to remove nothing.
+        Cancelled,
+    }
 
-                let err = out.inner.expect_err("is_err() should be asserted");
-                assert_eq!(err.kind(), fs::ErrorKind::DirectoryNotEmpty);
-                assert_eq!(err.path(), path);
-                assert_eq!(err.tip(), Some(TIP_DIR_NOT_EMPTY));
+    /// Struct representing an entry that can be picked.
+    struct Choice {
+        /// The path of the entry that can be picked.
+        path: PathBuf,
 
-                Ok(())
-            })
-        }
+        /// The text that describes the entry on screen.
+        label: String,
+
+        /// Whether the entry is picked to be removed.
+        picked: bool,
     }
 
-    /// Transform root directories into a [`fs::ErrorKind::Refused`] error. Return all other values
-    /// untouched.
-    pub fn disallow_root(mut item: walk::Item) -> walk::Item {
-        item.inner = item.inner.and_then(|entry| {
-            if is_root(entry.path()) {
-                Err(entry.into_err(fs::ErrorKind::Refused))
-            } else {
-                Ok(entry)
-            }
-        });
+    impl Choice {
+        /// Create a new, picked, [`Choice`] for the given [`fs::Entry`].
+        fn new(entry: &fs::Entry) -> Self {
+            let (kind, size) = match entry.kind() {
+                fs::EntryKind::Dir => ("dir", Some(fs::contents_of(entry, SIZE_LIMIT).size)),
+                fs::EntryKind::Fifo => ("fifo", None),
+                fs::EntryKind::File => ("file", fs::size_of(entry)),
+                fs::EntryKind::Socket => ("socket", None),
+                fs::EntryKind::Symlink => ("link", None),
+            };
+            let size = size.map(transform::format_size).unwrap_or_default();
+            let label = format!("{kind:<6} {size:>9}  {entry}");
 
-        item
+            Self { path: entry.path(), label, picked: true }
+        }
     }
 
-    /// Check if the given [`Path`] is the file system root.
-    fn is_root<P: AsRef<Path>>(path: P) -> bool {
-        path.as_ref().parent().is_none()
+    /// Struct representing the state of the checklist.
+    struct Picker {
+        /// The entries that can be picked, in the order they are listed.
+        choices: Vec<Choice>,
+
+        /// The index of the [`Choice`] the cursor is on.
+        cursor: usize,
     }
 
-    /// Tests for the [`disallow_root`] function.
-    #[cfg(test)]
-    mod test_disallow_root {
-        use super::{disallow_root, fs, walk};
+    impl Picker {
+        /// Create a new [`Picker`] for the given paths of the [`walk::Plan`] and their direct
+        /// contents, with all entries picked.
+        fn new(plan: &walk::Plan) -> Self {
+            let choices = plan
+                .iter()
+                .flat_map(|(path, results)| {
+                    results.iter().flatten().filter(move |entry| {
+                        let entry_path = entry.path();
+                        entry_path == *path || entry_path.parent() == Some(path.as_path())
+                    })
+                })
+                .map(Choice::new)
+                .collect();
 
-        use std::path::Path;
+            Self { choices, cursor: 0 }
+        }
 
-        use proptest::prelude::*;
-        use proptest_attr_macro::proptest;
+        /// Update the [`Picker`] for the given key press. Returns an [`Outcome`] if the user is
+        /// done.
+        fn handle(&mut self, key: KeyEvent) -> Option<Outcome> {
+            if key.kind == KeyEventKind::Release {
+                return None;
+            }
 
-        #[proptest]
-        fn non_root(item: walk::Item) {
-            if let Ok(entry) = item.inner.as_ref() {
-                prop_assume!(entry.path().parent().is_some());
-                prop_assume!(!entry.path().as_os_str().is_empty());
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.cursor =
+                        self.cursor.saturating_add(1).min(self.choices.len().saturating_sub(1));
+                },
+                KeyCode::Char(' ') => {
+                    if let Some(choice) = self.choices.get_mut(self.cursor) {
+                        choice.picked = !choice.picked;
+                    }
+                },
+                KeyCode::Char('a') => {
+                    let picked = !self.choices.iter().all(|choice| choice.picked);
+                    self.choices.iter_mut().for_each(|choice| choice.picked = picked);
+                },
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Some(Outcome::Cancelled);
+                },
+                KeyCode::Enter => return Some(Outcome::Confirmed),
+                KeyCode::Esc | KeyCode::Char('q') => return Some(Outcome::Cancelled),
+                _ => {},
             }
 
-            let out = disallow_root(item.clone());
-            prop_assert_eq!(out, item);
+            None
         }
 
-        #[test]
-        fn entry_root() {
-            test_is_root("/");
+        /// Draw the [`Picker`] on the given [`Frame`].
+        fn render(&self, frame: &mut Frame<'_>) {
+            let [title_area, list_area, help_area] = Layout::vertical([
+                Constraint::Length(1),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .areas(frame.area());
+
+            let picked = self.choices.iter().filter(|choice| choice.picked).count();
+            let title =
+                format!("Pick entries to remove ({picked} of {} picked)", self.choices.len());
+            frame.render_widget(
+                Paragraph::new(title).style(Style::new().add_modifier(Modifier::BOLD)),
+                title_area,
+            );
+
+            let list = List::new(self.choices.iter().map(|choice| {
+                Line::from(format!("[{}] {}", if choice.picked { 'x' } else { ' ' }, choice.label))
+            }))
+            .highlight_symbol("> ")
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_spacing(HighlightSpacing::Always);
+            let mut state = ListState::default().with_selected(Some(self.cursor));
+            frame.render_stateful_widget(list, list_area, &mut state);
+
+            frame.render_widget(
+                Paragraph::new(KEYS_HELP).style(Style::new().add_modifier(Modifier::ITALIC)),
+                help_area,
+            );
         }
 
-        #[test]
-        #[cfg(windows)]
-        fn entry_drive() {
-            test_is_root("C:\\");
+        /// Get the paths of the entries that are not picked.
+        fn unpicked(&self) -> Vec<PathBuf> {
+            self.choices.iter().filter(|choice| !choice.picked).map(|c| c.path.clone()).collect()
         }
+    }
 
-        fn test_is_root<P: AsRef<Path>>(path: P) {
-            let entry = fs::test_helpers::new_dir(&path);
+    /// Tests for the [`Picker`] struct.
+    #[cfg(test)]
+    mod test_picker {
+        use crate::test_helpers::{TestResult, with_test_dir};
 
-            let out = disallow_root(entry.into());
-            assert!(out.inner.is_err());
+        use super::{Outcome, Picker, fs};
 
-            let err = out.inner.expect_err("is_err() should be asserted");
-            assert_eq!(err.kind(), fs::ErrorKind::Refused);
-            assert_eq!(err.path(), path.as_ref().to_owned());
-        }
-    }
+        use std::path::PathBuf;
 
-    /// The explanation for why a missing [`walk::Item`] is skipped.
-    const SKIP_REASON_NOT_FOUND: &str = "Not found";
+        use assert_fs::prelude::*;
+        use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-    /// Transform [`fs::ErrorKind::NotFound`] errors into skipped [`walk::Item`]s. Return all other
-    /// values untouched.
-    pub fn skip_not_found(item: walk::Item) -> walk::Item {
-        if item.inner.as_ref().is_err_and(|err| err.kind() == fs::ErrorKind::NotFound) {
-            item.into_skipped(SKIP_REASON_NOT_FOUND)
-        } else {
-            item
+        #[test]
+        fn direct_contents_only() -> TestResult {
+            with_test_dir(|test_dir| {
+                let dir = test_dir.child("dir");
+                let nested_file = dir.child("nested_dir").child("file");
+                nested_file.touch()?;
+                let file = test_dir.child("file");
+                file.touch()?;
+
+                let picker = Picker::new(&vec![
+                    (
+                        dir.to_path_buf(),
+                        vec![
+                            fs::open(&nested_file),
+                            fs::open(dir.child("nested_dir")),
+                            fs::open(&dir),
+                        ],
+                    ),
+                    (file.to_path_buf(), vec![fs::open(&file)]),
+                    (
+                        test_dir.child("missing").to_path_buf(),
+                        vec![fs::open(test_dir.child("missing"))],
+                    ),
+                ]);
+
+                let paths: Vec<_> =
+                    picker.choices.iter().map(|choice| choice.path.clone()).collect();
+                assert_eq!(
+                    paths,
+                    vec![
+                        dir.child("nested_dir").to_path_buf(),
+                        dir.to_path_buf(),
+                        file.to_path_buf()
+                    ]
+                );
+                assert!(picker.choices.iter().all(|choice| choice.picked));
+
+                Ok(())
+            })
         }
-    }
 
-    /// Tests for the [`skip_not_found`] function.
-    #[cfg(test)]
-    mod test_skip_not_found {
-        use super::{fs, skip_not_found, walk};
+        #[test]
+        fn toggle() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file1 = test_dir.child("file1");
+                file1.touch()?;
+                let file2 = test_dir.child("file2");
+                file2.touch()?;
 
-        use proptest::prelude::*;
-        use proptest_attr_macro::proptest;
+                let mut picker = Picker::new(&vec![
+                    (file1.to_path_buf(), vec![fs::open(&file1)]),
+                    (file2.to_path_buf(), vec![fs::open(&file2)]),
+                ]);
 
-        #[proptest]
-        fn found_or_error(item: walk::Item) {
-            if let Err(err) = item.inner.as_ref() {
-                prop_assume!(err.kind() != fs::ErrorKind::NotFound);
-            }
+                assert_eq!(picker.handle(KeyEvent::from(KeyCode::Char(' '))), None);
+                assert_eq!(picker.unpicked(), vec![file1.to_path_buf()]);
 
-            let out = skip_not_found(item.clone());
-            prop_assert_eq!(out, item);
+                assert_eq!(picker.handle(KeyEvent::from(KeyCode::Char('j'))), None);
+                assert_eq!(picker.handle(KeyEvent::from(KeyCode::Down)), None);
+                assert_eq!(picker.handle(KeyEvent::from(KeyCode::Char(' '))), None);
+                assert_eq!(picker.unpicked(), vec![file1.to_path_buf(), file2.to_path_buf()]);
+
+                assert_eq!(picker.handle(KeyEvent::from(KeyCode::Up)), None);
+                assert_eq!(picker.handle(KeyEvent::from(KeyCode::Char(' '))), None);
+                assert_eq!(picker.unpicked(), vec![file2.to_path_buf()]);
+
+                Ok(())
+            })
         }
 
-        #[proptest]
-        fn not_found(entry: fs::Entry) {
-            let err = entry.into_err(fs::ErrorKind::NotFound);
+        #[test]
+        fn toggle_all() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file1 = test_dir.child("file1");
+                file1.touch()?;
+                let file2 = test_dir.child("file2");
+                file2.touch()?;
 
-            let out = skip_not_found(err.into());
-            prop_assert_eq!(out.skip_reason(), Some(super::SKIP_REASON_NOT_FOUND.to_owned()));
+                let mut picker = Picker::new(&vec![
+                    (file1.to_path_buf(), vec![fs::open(&file1)]),
+                    (file2.to_path_buf(), vec![fs::open(&file2)]),
+                ]);
+
+                assert_eq!(picker.handle(KeyEvent::from(KeyCode::Char('a'))), None);
+                assert_eq!(picker.unpicked(), vec![file1.to_path_buf(), file2.to_path_buf()]);
+
+                assert_eq!(picker.handle(KeyEvent::from(KeyCode::Char(' '))), None);
+                assert_eq!(picker.handle(KeyEvent::from(KeyCode::Char('a'))), None);
+                assert_eq!(picker.unpicked(), Vec::<PathBuf>::new());
+
+                Ok(())
+            })
         }
-    }
 
-    /// The tip for avoiding [`fs::ErrorKind::NotFound`] errors.
-    const TIP_NOT_FOUND: &str = "use '--blind' to ignore";
+        #[test]
+        fn done() -> TestResult {
+            with_test_dir(|test_dir| {
+                let file = test_dir.child("file");
+                file.touch()?;
 
-    /// Transform [`fs::ErrorKind::NotFound`] errors into equivalent errors with an associated tip
-    /// for how to avoid it. Return all other values untouched.
-    pub fn tip_not_found(mut item: walk::Item) -> walk::Item {
-        item.inner = item.inner.map_err(|err| {
-            if err.kind() == fs::ErrorKind::NotFound { err.with_tip(TIP_NOT_FOUND) } else { err }
-        });
+                let mut picker = Picker::new(&vec![(file.to_path_buf(), vec![fs::open(&file)])]);
 
-        item
+                assert_eq!(picker.handle(KeyEvent::from(KeyCode::Enter)), Some(Outcome::Confirmed));
+                assert_eq!(
+                    picker.handle(KeyEvent::from(KeyCode::Char('q'))),
+                    Some(Outcome::Cancelled)
+                );
+                assert_eq!(picker.handle(KeyEvent::from(KeyCode::Esc)), Some(Outcome::Cancelled));
+                assert_eq!(
+                    picker.handle(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+                    Some(Outcome::Cancelled)
+                );
+
+                Ok(())
+            })
+        }
     }
+}
 
-    /// Tests for the [`tip_not_found`] function.
-    #[cfg(test)]
-    mod test_tip_not_found {
-        use super::{TIP_NOT_FOUND, fs, tip_not_found, walk};
+/// A progress line on stderr for long removals, see `--progress`.
+mod progress {
+    use super::{lang, rm, walk};
 
-        use proptest::prelude::*;
-        use proptest_attr_macro::proptest;
+    use std::cell::{Cell, RefCell};
+    use std::io;
+    use std::iter;
+    use std::path::{Path, PathBuf};
+    use std::rc::Rc;
+    use std::time::{Duration, Instant};
 
-        #[proptest]
-        fn found(item: walk::Item) {
-            if let Err(err) = item.inner.as_ref() {
-                prop_assume!(err.kind() != fs::ErrorKind::NotFound);
-            }
+    /// The minimum time between redraws of the progress line.
+    const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
 
-            let out = tip_not_found(item.clone());
-            prop_assert_eq!(out, item);
-        }
+    /// The maximum width of the progress line, in characters.
+    const MAX_WIDTH: usize = 79;
 
-        #[proptest]
-        fn not_found(entry: fs::Entry) {
-            let path = entry.path();
-            let err = entry.into_err(fs::ErrorKind::NotFound);
+    /// The escape sequence to return to the start of the line and erase it.
+    const ERASE_LINE: &str = "\r\x1b[K";
 
-            let out = tip_not_found(err.into());
-            prop_assert!(out.inner.is_err());
+    /// Struct representing the progress of a removal, shown as a single line that is redrawn as the
+    /// removal progresses.
+    pub struct Progress {
+        /// Where the progress line is written to.
+        output: RefCell<Box<dyn io::Write>>,
 
-            let err = out.inner.expect_err("is_err() should be asserted");
-            prop_assert_eq!(err.kind(), fs::ErrorKind::NotFound);
-            prop_assert_eq!(err.path(), path);
-            prop_assert_eq!(err.tip(), Some(TIP_NOT_FOUND));
-        }
-    }
+        /// The expected number of entries to process, if known.
+        total: Option<usize>,
 
-    /// The explanation for when an [`walk::Item`] is skipped as a result of the user answering
-    /// "no".
-    const SKIP_REASON_ANSWER_NO: &str = "Kept by user";
+        /// Whether the user may be prompted while walking, in which case the line is cleared for
+        /// every prompt.
+        prompts: bool,
 
-    /// The explanation for when an [`walk::Item`] is skipped as a result of unrecognized user
-    /// input.
-    const SKIP_REASON_ANSWER_UNKNOWN: &str = "Unrecognized input";
+        /// The point in time the removal started.
+        start: Instant,
 
-    /// The explanation for when an [`walk::Item`] is skipped as a result of an I/O error.
-    const SKIP_REASON_IO_ERROR: &str = "I/O error";
+        /// The number of entries processed so far.
+        processed: Cell<usize>,
 
-    /// Transform (not skipped) [`walk::Item`]s based on user input. Return all other values
-    /// untouched.
-    pub fn interactive(item: walk::Item) -> walk::Item {
-        if let Ok(entry) = item.inner.as_ref() {
-            let prompt_text = new_prompt_for(entry, item.is_visited());
-            interact_transform(
-                prompt(&prompt_text, &mut io::stdin().lock(), &mut anstream::stderr()),
-                item,
-            )
-        } else {
-            item
-        }
-    }
+        /// The number of errors so far.
+        errors: Cell<usize>,
 
-    /// Create a user prompt for what to do with the given [`walk::Item`].
-    fn new_prompt_for(entry: &fs::Entry, visited: bool) -> String {
-        let question = match entry.kind() {
-            fs::EntryKind::Dir => {
-                if fs::is_empty(entry) {
-                    "Remove empty directory"
-                } else if visited {
-                    "Remove directory"
-                } else {
-                    "Descend into directory"
-                }
-            },
-            fs::EntryKind::File => "Remove regular file",
-            fs::EntryKind::Symlink => "Remove symbolic link",
-        };
+        /// The directory currently being processed.
+        dir: RefCell<PathBuf>,
 
-        format!("{question} {}? [Y/n] ", entry.bold())
+        /// The point in time the line was last drawn, `None` if it is not currently shown.
+        drawn: Cell<Option<Instant>>,
     }
 
-    /// Print the given string to the user, wait for user input, and return the user input.
-    ///
-    /// # Errors
-    ///
-    /// If any error is returned by either the reader or the writer.
-    fn prompt<R, W>(prompt: &str, reader: &mut R, writer: &mut W) -> io::Result<String>
-    where
-        R: io::BufRead,
-        W: io::Write,
-    {
-        const ANSWER_BUFFER_SIZE: usize = "yes".len();
-        const CLEARLINE: &[u8] = "\u{1b}[1A\u{1b}[2K".as_bytes();
+    impl Progress {
+        /// Create a [`Progress`] that writes to `output`, expecting `total` entries if known.
+        pub fn new(output: Box<dyn io::Write>, total: Option<usize>, prompts: bool) -> Self {
+            Self {
+                output: RefCell::new(output),
+                total,
+                prompts,
+                start: Instant::now(),
+                processed: Cell::new(0),
+                errors: Cell::new(0),
+                dir: RefCell::new(PathBuf::new()),
+                drawn: Cell::new(None),
+            }
+        }
 
-        writer.write_all(prompt.as_bytes())?;
-        writer.flush()?;
+        /// Clear the progress line, if it is shown.
+        pub fn clear(&self) {
+            if self.drawn.take().is_some() {
+                self.write(ERASE_LINE);
+            }
+        }
 
-        let mut answer = String::with_capacity(ANSWER_BUFFER_SIZE);
-        reader.read_line(&mut answer)?;
+        /// Draw the progress line, unless it was drawn very recently.
+        fn draw(&self) {
+            let now = Instant::now();
+            if let Some(drawn) = self.drawn.get()
+                && now.saturating_duration_since(drawn) < REDRAW_INTERVAL
+            {
+                return;
+            }
 
-        writer.write_all(CLEARLINE)?;
-        writer.flush()?;
+            let line = describe(&Stats {
+                processed: self.processed.get(),
+                errors: self.errors.get(),
+                total: self.total,
+                elapsed: now.saturating_duration_since(self.start),
+                dir: &self.dir.borrow(),
+            });
+            self.write(&format!("{ERASE_LINE}{line}"));
+            self.drawn.set(Some(now));
+        }
 
-        Ok(answer.trim().to_owned())
-    }
+        /// Record that the entry at the given [`Path`] is being processed.
+        fn enter(&self, path: &Path) {
+            if let Some(dir) = path.parent()
+                && dir != *self.dir.borrow()
+            {
+                self.dir.replace(dir.to_owned());
+            }
+        }
 
-    /// Transform the given [`walk::Item`] based on the given user response.
-    fn interact_transform(response: io::Result<String>, item: walk::Item) -> walk::Item {
-        if let Ok(answer) = response {
-            match answer.to_lowercase().as_str() {
-                "y" | "yes" => item,
-                "n" | "no" => item.into_skipped(SKIP_REASON_ANSWER_NO),
-                _ => item.into_skipped(SKIP_REASON_ANSWER_UNKNOWN),
+        /// Record that an entry was processed, successfully or not.
+        fn record(&self, ok: bool) {
+            self.processed.set(self.processed.get().saturating_add(1));
+            if !ok {
+                self.errors.set(self.errors.get().saturating_add(1));
             }
-        } else {
-            item.into_skipped(SKIP_REASON_IO_ERROR)
+        }
+
+        /// Write the given `text` to the output, ignoring failures as progress is best effort.
+        fn write(&self, text: &str) {
+            let mut output = self.output.borrow_mut();
+            _ = output.write_all(text.as_bytes()).and_then(|()| output.flush());
         }
     }
 
-    /// Tests for the [`interactive`] and related functions.
+    /// Tests for the [`Progress`] struct.
     #[cfg(test)]
-    mod test_interactive {
-        use crate::test_helpers::{TestResult, with_test_dir};
+    mod test_progress {
+        use super::test_helpers::SharedOutput;
+        use super::{ERASE_LINE, Progress};
 
-        use super::{fs, interact_transform, new_prompt_for, prompt, walk};
-
-        use std::io;
-
-        use assert_fs::prelude::*;
-        use owo_colors::OwoColorize as _;
-        use proptest::prelude::*;
-        use proptest_attr_macro::proptest;
-        use proptest_derive::Arbitrary;
+        use std::path::Path;
 
         #[test]
-        fn new_prompt_for_file_filled() -> TestResult {
-            with_test_dir(|test_dir| {
-                let file = test_dir.child("file");
-                file.write_str("Hello world!")?;
-
-                let path = file.path();
-                let entry = fs::test_helpers::new_file(path);
+        fn draw() {
+            let output = SharedOutput::default();
+            let progress = Progress::new(Box::new(output.clone()), None, false);
 
-                let out = new_prompt_for(&entry, false);
-                assert_eq!(out, format!("Remove regular file {}? [Y/n] ", path.display().bold()));
+            progress.enter(Path::new("dir/file"));
+            progress.record(true);
+            progress.record(false);
+            progress.draw();
 
-                let out = new_prompt_for(&entry, true);
-                assert_eq!(out, format!("Remove regular file {}? [Y/n] ", path.display().bold()));
-
-                Ok(())
-            })
+            let out = output.contents();
+            assert!(out.starts_with(ERASE_LINE));
+            assert!(out.contains("2 entries processed, 1 error"));
+            assert!(out.ends_with("in dir"));
         }
 
         #[test]
-        fn new_prompt_for_dir_empty() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-
-                let path = dir.path();
-                let entry = fs::test_helpers::new_dir(path);
+        fn draw_rate_limited() {
+            let output = SharedOutput::default();
+            let progress = Progress::new(Box::new(output.clone()), None, false);
 
-                let out = new_prompt_for(&entry, false);
-                assert_eq!(
-                    out,
-                    format!("Remove empty directory {}? [Y/n] ", path.display().bold())
-                );
+            progress.draw();
+            progress.record(true);
+            progress.draw();
 
-                Ok(())
-            })
+            assert_eq!(output.contents().matches(ERASE_LINE).count(), 1);
         }
 
         #[test]
-        fn new_prompt_for_visited_dir_empty() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
+        fn clear() {
+            let output = SharedOutput::default();
+            let progress = Progress::new(Box::new(output.clone()), None, false);
 
-                let path = dir.path();
-                let entry = fs::test_helpers::new_dir(path);
+            progress.clear();
+            assert_eq!(output.contents(), "");
 
-                let out = new_prompt_for(&entry, true);
-                assert_eq!(
-                    out,
-                    format!("Remove empty directory {}? [Y/n] ", path.display().bold())
-                );
+            progress.draw();
+            progress.clear();
+            assert!(output.contents().ends_with(ERASE_LINE));
 
-                Ok(())
-            })
+            progress.draw();
+            assert_eq!(output.contents().matches(ERASE_LINE).count(), 3);
         }
+    }
 
-        #[test]
-        fn new_prompt_for_dir_filled() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                dir.child("file").touch()?;
+    /// Wrap the given [`walk::Walker`] to show the given [`Progress`] while walking and to record
+    /// the current directory and walking errors.
+    pub fn track_walker(walker: walk::Walker, progress: Rc<Progress>) -> walk::Walker {
+        Box::new(move |path| {
+            let progress = Rc::clone(&progress);
+            let mut results = walker(path);
+            Box::new(iter::from_fn(move || {
+                progress.draw();
+                if progress.prompts {
+                    progress.clear();
+                }
 
-                let path = dir.path();
-                let entry = fs::test_helpers::new_dir(path);
+                let result = results.next()?;
+                if let Ok(entry) = &result {
+                    progress.enter(&entry.path());
+                } else {
+                    progress.record(false);
+                    progress.clear();
+                }
 
-                let out = new_prompt_for(&entry, false);
-                assert_eq!(
-                    out,
-                    format!("Descend into directory {}? [Y/n] ", path.display().bold())
-                );
+                Some(result)
+            }))
+        })
+    }
 
-                Ok(())
-            })
-        }
+    /// Tests for the [`track_walker`] function.
+    #[cfg(test)]
+    mod test_track_walker {
+        use crate::fs;
+        use crate::walk;
 
-        #[test]
-        fn new_prompt_for_visited_dir_filled() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                dir.child("file").touch()?;
+        use super::test_helpers::SharedOutput;
+        use super::{Progress, track_walker};
 
-                let path = dir.path();
-                let entry = fs::test_helpers::new_dir(path);
+        use std::path::PathBuf;
+        use std::rc::Rc;
 
-                let out = new_prompt_for(&entry, true);
-                assert_eq!(out, format!("Remove directory {}? [Y/n] ", path.display().bold()));
+        #[test]
+        fn passes_through() {
+            let output = SharedOutput::default();
+            let progress = Rc::new(Progress::new(Box::new(output.clone()), None, false));
 
-                Ok(())
-            })
+            let walker = track_walker(walker(), Rc::clone(&progress));
+            let out: Vec<fs::Result> = walker(&"dir").collect();
+            assert_eq!(out, results());
+
+            assert_eq!(progress.processed.get(), 1);
+            assert_eq!(progress.errors.get(), 1);
+            assert_eq!(*progress.dir.borrow(), PathBuf::from("dir"));
         }
 
         #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn new_prompt_for_symlink_to_file() -> TestResult {
-            with_test_dir(|test_dir| {
-                let file = test_dir.child("file");
-                file.touch()?;
-                let link = test_dir.child("link");
-                link.symlink_to_file(file)?;
+        fn cleared_for_prompts() {
+            let output = SharedOutput::default();
+            let progress = Rc::new(Progress::new(Box::new(output.clone()), None, true));
 
-                let path = link.path();
-                let entry = fs::test_helpers::new_symlink(path);
-
-                let out = new_prompt_for(&entry, false);
-                assert_eq!(out, format!("Remove symbolic link {}? [Y/n] ", path.display().bold()));
+            let walker = track_walker(walker(), Rc::clone(&progress));
+            for _ in walker(&"dir") {
+                assert_eq!(progress.drawn.get(), None);
+            }
+        }
 
-                let out = new_prompt_for(&entry, true);
-                assert_eq!(out, format!("Remove symbolic link {}? [Y/n] ", path.display().bold()));
+        /// A [`walk::Walker`] that always yields the [`results`].
+        fn walker() -> walk::Walker {
+            Box::new(|_| Box::new(results().into_iter()))
+        }
 
-                Ok(())
-            })
+        /// The results yielded by the test [`walker`], a file followed by an error.
+        fn results() -> Vec<fs::Result> {
+            let file = fs::test_helpers::new_file("dir/file");
+            let missing = fs::test_helpers::new_file("dir/missing");
+            vec![Ok(file), Err(missing.into_err(fs::ErrorKind::NotFound))]
         }
+    }
 
-        #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn new_prompt_for_symlink_to_empty_dir() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                let link = test_dir.child("link");
-                link.symlink_to_dir(dir)?;
+    /// Wrap the given [`rm::Remover`] to record every removal in the given [`Progress`], clearing
+    /// the progress line if the outcome of the removal is going to be output.
+    pub fn track_remover(remove: rm::Remover, progress: Rc<Progress>) -> rm::Remover {
+        Box::new(move |entry| {
+            let result = remove(entry);
+            progress.record(result.is_ok());
+            if result.is_err() || log::log_enabled!(log::Level::Info) {
+                progress.clear();
+            }
 
-                let path = link.path();
-                let entry = fs::test_helpers::new_symlink(path);
+            result
+        })
+    }
 
-                let out = new_prompt_for(&entry, false);
-                assert_eq!(out, format!("Remove symbolic link {}? [Y/n] ", path.display().bold()));
+    /// Tests for the [`track_remover`] function.
+    #[cfg(test)]
+    mod test_track_remover {
+        use crate::fs;
+        use crate::rm;
 
-                let out = new_prompt_for(&entry, true);
-                assert_eq!(out, format!("Remove symbolic link {}? [Y/n] ", path.display().bold()));
+        use super::test_helpers::SharedOutput;
+        use super::{Progress, track_remover};
 
-                Ok(())
-            })
-        }
+        use std::rc::Rc;
 
         #[test]
-        #[cfg_attr(
-            all(windows, not(feature = "test-symlink")),
-            ignore = "Only run with the test-symlink feature"
-        )]
-        fn new_prompt_for_symlink_to_filled_dir() -> TestResult {
-            with_test_dir(|test_dir| {
-                let dir = test_dir.child("dir");
-                dir.create_dir_all()?;
-                dir.child("file").touch()?;
-                let link = test_dir.child("link");
-                link.symlink_to_dir(dir)?;
+        fn records() {
+            let output = SharedOutput::default();
+            let progress = Rc::new(Progress::new(Box::new(output.clone()), None, false));
+
+            let remove = track_remover(Box::new(rm::show_remove), Rc::clone(&progress));
+            progress.draw();
+            let out = remove(fs::test_helpers::new_file("file"));
+            assert!(out.is_ok());
+            assert_eq!(progress.processed.get(), 1);
+            assert_eq!(progress.errors.get(), 0);
+
+            let remove = track_remover(
+                Box::new(|entry: fs::Entry| Err(entry.into_err(fs::ErrorKind::Refused))),
+                Rc::clone(&progress),
+            );
+            progress.draw();
+            let out = remove(fs::test_helpers::new_file("file"));
+            assert!(out.is_err());
+            assert_eq!(progress.processed.get(), 2);
+            assert_eq!(progress.errors.get(), 1);
+            assert_eq!(progress.drawn.get(), None);
+        }
+    }
 
-                let path = link.path();
-                let entry = fs::test_helpers::new_symlink(path);
+    /// Struct representing the numbers shown in a progress line, see [`describe`].
+    struct Stats<'a> {
+        /// The number of entries processed so far.
+        processed: usize,
 
-                let out = new_prompt_for(&entry, false);
-                assert_eq!(out, format!("Remove symbolic link {}? [Y/n] ", path.display().bold()));
+        /// The number of errors so far.
+        errors: usize,
 
-                let out = new_prompt_for(&entry, true);
-                assert_eq!(out, format!("Remove symbolic link {}? [Y/n] ", path.display().bold()));
+        /// The expected number of entries to process, if known.
+        total: Option<usize>,
 
-                Ok(())
-            })
-        }
+        /// The time since the removal started.
+        elapsed: Duration,
 
-        #[proptest]
-        fn prompt_input(question: String, answer: String) {
-            let mut reader = answer.as_bytes();
-            let mut writer = io::sink();
+        /// The directory currently being processed.
+        dir: &'a Path,
+    }
 
-            let out = prompt(&question, &mut reader, &mut writer);
-            prop_assert!(out.is_ok());
+    /// Describe the given [`Stats`] in a single line of no more than [`MAX_WIDTH`] characters,
+    /// shortening the current directory if necessary.
+    fn describe(stats: &Stats<'_>) -> String {
+        let rate = u128::from(u64::try_from(stats.processed).unwrap_or(u64::MAX))
+            .saturating_mul(1000)
+            .checked_div(stats.elapsed.as_millis())
+            .unwrap_or_default();
+        let processed = match stats.total {
+            Some(total) => format!("{}/{total}", stats.processed),
+            None => stats.processed.to_string(),
+        };
+        let eta = match (stats.total, eta(stats)) {
+            (Some(_), Some(eta)) => format!(", about {} left", format_duration(eta)),
+            _ => String::new(),
+        };
 
-            let user_input = out.expect("is_ok() should be asserted");
-            prop_assert_eq!(user_input, answer.trim());
+        let line = format!(
+            "{processed} {} processed, {}, {rate}/s{eta}",
+            if stats.processed == 1 && stats.total.is_none() { "entry" } else { "entries" },
+            lang::pluralize("error", stats.errors),
+        );
+        if stats.dir.as_os_str().is_empty() {
+            return line;
+        }
+
+        let dir = stats.dir.display().to_string();
+        let room = MAX_WIDTH.saturating_sub(line.chars().count()).saturating_sub(", in ".len());
+        let len = dir.chars().count();
+        if len <= room {
+            format!("{line}, in {dir}")
+        } else if room > 1 {
+            let tail: String =
+                dir.chars().skip(len.saturating_sub(room).saturating_add(1)).collect();
+            format!("{line}, in …{tail}")
+        } else {
+            line
         }
+    }
 
-        #[proptest]
-        fn prompt_input_err(question: String) {
-            let mut reader = FaultyReader;
-            let mut writer = io::sink();
+    /// Tests for the [`describe`] function.
+    #[cfg(test)]
+    mod test_describe {
+        use super::{MAX_WIDTH, Stats, describe};
 
-            let out = prompt(&question, &mut reader, &mut writer);
-            prop_assert!(out.is_err());
+        use std::path::Path;
+        use std::time::Duration;
+
+        #[test]
+        fn without_total() {
+            let stats = Stats {
+                processed: 1500,
+                errors: 2,
+                total: None,
+                elapsed: Duration::from_secs(3),
+                dir: Path::new("dir/nested"),
+            };
+            assert_eq!(describe(&stats), "1500 entries processed, 2 errors, 500/s, in dir/nested");
         }
 
-        #[proptest]
-        fn prompt_output(question: String) {
-            let mut reader = io::empty();
-            let mut writer = io::BufWriter::new(Vec::new());
+        #[test]
+        fn single_entry() {
+            let stats = Stats {
+                processed: 1,
+                errors: 1,
+                total: None,
+                elapsed: Duration::from_secs(1),
+                dir: Path::new(""),
+            };
+            assert_eq!(describe(&stats), "1 entry processed, 1 error, 1/s");
+        }
 
-            prompt(&question, &mut reader, &mut writer)?;
-            prop_assert_eq!(
-                String::from_utf8(writer.into_inner()?)?,
-                format!("{question}\u{1b}[1A\u{1b}[2K")
+        #[test]
+        fn with_total() {
+            let stats = Stats {
+                processed: 100,
+                errors: 0,
+                total: Some(400),
+                elapsed: Duration::from_secs(10),
+                dir: Path::new("dir"),
+            };
+            assert_eq!(
+                describe(&stats),
+                "100/400 entries processed, 0 errors, 10/s, about 30s left, in dir"
             );
         }
 
-        #[proptest]
-        fn prompt_output_err(question: String) {
-            let mut reader = io::empty();
-            let mut writer = FaultyWriter;
+        #[test]
+        fn nothing_elapsed() {
+            let stats = Stats {
+                processed: 0,
+                errors: 0,
+                total: Some(10),
+                elapsed: Duration::ZERO,
+                dir: Path::new(""),
+            };
+            assert_eq!(describe(&stats), "0/10 entries processed, 0 errors, 0/s");
+        }
 
-            let out = prompt(&question, &mut reader, &mut writer);
-            prop_assert!(out.is_err());
+        #[test]
+        fn long_dir() {
+            let dir = format!("{}/end", "a".repeat(200));
+            let stats = Stats {
+                processed: 1,
+                errors: 0,
+                total: None,
+                elapsed: Duration::from_secs(1),
+                dir: Path::new(&dir),
+            };
+
+            let out = describe(&stats);
+            assert_eq!(out.chars().count(), MAX_WIDTH);
+            assert!(out.contains(", in …a"));
+            assert!(out.ends_with("a/end"));
         }
+    }
 
-        #[proptest]
-        fn transform_answer_yes(item: walk::Item, answer: AnswerYes) {
-            let out = interact_transform(Ok(answer.0), item.clone());
-            prop_assert_eq!(out, item);
+    /// Estimate the time left to process the remaining entries given the [`Stats`] so far, if
+    /// possible.
+    fn eta(stats: &Stats<'_>) -> Option<Duration> {
+        let total = u128::try_from(stats.total?).ok()?;
+        let processed = u128::try_from(stats.processed).ok()?;
+        let nanos = stats
+            .elapsed
+            .as_nanos()
+            .checked_mul(total.saturating_sub(processed))?
+            .checked_div(processed)?;
+
+        u64::try_from(nanos).ok().map(Duration::from_nanos)
+    }
+
+    /// Tests for the [`eta`] function.
+    #[cfg(test)]
+    mod test_eta {
+        use super::{Stats, eta};
+
+        use std::path::Path;
+        use std::time::Duration;
+
+        #[test]
+        fn halfway() {
+            let stats = Stats {
+                processed: 50,
+                errors: 0,
+                total: Some(100),
+                elapsed: Duration::from_secs(20),
+                dir: Path::new(""),
+            };
+            assert_eq!(eta(&stats), Some(Duration::from_secs(20)));
         }
 
-        #[proptest]
-        fn transform_answer_no(item: walk::Item, answer: AnswerNo) {
-            let out = interact_transform(Ok(answer.0), item.clone());
-            prop_assert_eq!(out, item.into_skipped(super::SKIP_REASON_ANSWER_NO));
+        #[test]
+        fn nothing_processed() {
+            let stats = Stats {
+                processed: 0,
+                errors: 0,
+                total: Some(100),
+                elapsed: Duration::from_secs(20),
+                dir: Path::new(""),
+            };
+            assert_eq!(eta(&stats), None);
         }
 
-        #[proptest]
-        fn transform_answer_nonsense(item: walk::Item, answer: String) {
-            prop_assume!(!matches!(answer.to_lowercase().as_ref(), "y" | "yes" | "n" | "no"));
+        #[test]
+        fn more_than_total() {
+            let stats = Stats {
+                processed: 150,
+                errors: 0,
+                total: Some(100),
+                elapsed: Duration::from_secs(20),
+                dir: Path::new(""),
+            };
+            assert_eq!(eta(&stats), Some(Duration::ZERO));
+        }
 
-            let out = interact_transform(Ok(answer), item.clone());
-            prop_assert_eq!(out, item.into_skipped(super::SKIP_REASON_ANSWER_UNKNOWN));
+        #[test]
+        fn unknown_total() {
+            let stats = Stats {
+                processed: 50,
+                errors: 0,
+                total: None,
+                elapsed: Duration::from_secs(20),
+                dir: Path::new(""),
+            };
+            assert_eq!(eta(&stats), None);
         }
+    }
 
-        #[proptest]
-        fn transform_io_error(item: walk::Item, err: io::Error) {
-            let out = interact_transform(Err(err), item.clone());
-            prop_assert_eq!(out, item.into_skipped(super::SKIP_REASON_IO_ERROR));
+    /// Format the given [`Duration`] for humans in at most two units, e.g. "1h 5m" or "42s".
+    fn format_duration(duration: Duration) -> String {
+        let secs = duration.as_secs();
+        let (hours, minutes, seconds) = (secs / 3_600, secs % 3_600 / 60, secs % 60);
+        match (hours, minutes) {
+            (0, 0) => format!("{seconds}s"),
+            (0, _) => format!("{minutes}m {seconds}s"),
+            _ => format!("{hours}h {minutes}m"),
         }
+    }
 
-        /// Struct wrapping a [`String`] that implements [`Arbitrary`] to generate a "no" answer
-        /// accepted by the --interactive mode of the CLI.
-        #[derive(Arbitrary, Debug)]
-        struct AnswerNo(#[proptest(regex = "(?i-u)(n|no)")] String);
+    /// Tests for the [`format_duration`] function.
+    #[cfg(test)]
+    mod test_format_duration {
+        use super::format_duration;
 
-        /// Struct wrapping a [`String`] that implements [`Arbitrary`] to generate a "yes" answer
-        /// accepted by the --interactive mode of the CLI.
-        #[derive(Arbitrary, Debug)]
-        struct AnswerYes(#[proptest(regex = "(?i-u)(y|yes)")] String);
+        use std::time::Duration;
 
-        /// Struct providing an erroring implementation of [`io::Read`] and [`io::BufRead`] for
-        /// testing purposes.
-        struct FaultyReader;
+        #[test]
+        fn seconds() {
+            assert_eq!(format_duration(Duration::ZERO), "0s");
+            assert_eq!(format_duration(Duration::from_millis(42_900)), "42s");
+        }
 
-        impl io::Read for FaultyReader {
-            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
-                Err(io::Error::from(io::ErrorKind::InvalidData))
-            }
+        #[test]
+        fn minutes() {
+            assert_eq!(format_duration(Duration::from_secs(60)), "1m 0s");
+            assert_eq!(format_duration(Duration::from_secs(200)), "3m 20s");
         }
 
-        impl io::BufRead for FaultyReader {
-            fn consume(&mut self, _: usize) {
-                // don't need to do anything in a faulty reader
-            }
+        #[test]
+        fn hours() {
+            assert_eq!(format_duration(Duration::from_secs(3_900)), "1h 5m");
+            assert_eq!(format_duration(Duration::from_secs(90_000)), "25h 0m");
+        }
+    }
 
-            fn fill_buf(&mut self) -> io::Result<&[u8]> {
-                Err(io::Error::from(io::ErrorKind::InvalidData))
+    /// Helpers for writing unit tests in or using this module.
+    #[cfg(test)]
+    mod test_helpers {
+        use std::cell::RefCell;
+        use std::io;
+        use std::rc::Rc;
+
+        /// An [`io::Write`] that keeps what is written to it, shared between its clones.
+        #[derive(Clone, Default)]
+        pub struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+        impl SharedOutput {
+            /// Get everything written so far.
+            pub fn contents(&self) -> String {
+                String::from_utf8_lossy(&self.0.borrow()).into_owned()
             }
         }
 
-        /// Struct providing a erroring implementation of [`io::Write`] for testing purposes.
-        struct FaultyWriter;
-
-        impl io::Write for FaultyWriter {
-            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
-                Err(io::Error::from(io::ErrorKind::InvalidData))
+        impl io::Write for SharedOutput {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.borrow_mut().write(buf)
             }
 
             fn flush(&mut self) -> io::Result<()> {
-                Err(io::Error::from(io::ErrorKind::InvalidData))
+                Ok(())
             }
         }
     }
@@ -3956,13 +16253,14 @@ mod lang {
 mod logging {
     /// Enum representing the available levels of output verbosity.
     pub enum Verbosity {
-        /// The normal verbosity of the CLI: output info and error messages.
+        /// The normal verbosity of the CLI: output info, warning and error messages.
         Normal,
 
-        /// The `--quiet` mode of the CLI: output error messages only.
+        /// The `--quiet` mode of the CLI: output warning and error messages only. Warnings are
+        /// only output for options that ask for them, such as `--check-open`.
         Quiet,
 
-        /// The `--verbose` mode of the CLI: output trace, info, and error messages.
+        /// The `--verbose` mode of the CLI: output trace, info, warning and error messages.
         Verbose,
     }
 
@@ -3970,7 +16268,7 @@ mod logging {
     pub fn configure(verbosity: &Verbosity) {
         match *verbosity {
             Verbosity::Normal => log::set_max_level(log::LevelFilter::Info),
-            Verbosity::Quiet => log::set_max_level(log::LevelFilter::Error),
+            Verbosity::Quiet => log::set_max_level(log::LevelFilter::Warn),
             Verbosity::Verbose => log::set_max_level(log::LevelFilter::Trace),
         }
 
@@ -3996,10 +16294,10 @@ mod logging {
             use owo_colors::OwoColorize as _;
 
             match record.level() {
-                log::Level::Error => eprintln!("{}", record.args()),
+                log::Level::Error | log::Level::Warn => eprintln!("{}", record.args()),
                 log::Level::Info => println!("{}", record.args()),
                 log::Level::Trace => println!("{}", format!("[{}]", record.args()).italic()),
-                _ => unreachable!(),
+                log::Level::Debug => unreachable!(),
             }
         }
     }
@@ -4010,6 +16308,7 @@ mod logging {
 mod test_helpers {
     use std::env;
     use std::error;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
     use assert_fs::TempDir;
 
@@ -4052,4 +16351,9 @@ mod test_helpers {
 
         callback(&temp_dir)
     }
+
+    /// Get the point in time `secs` seconds after the Unix epoch.
+    pub fn unix_time(secs: u64) -> SystemTime {
+        UNIX_EPOCH.checked_add(Duration::from_secs(secs)).unwrap_or(UNIX_EPOCH)
+    }
 }
@@ -0,0 +1,356 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--older-than` and `--newer-than`
+//! options.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, has_lines, rm_out, with_test_dir};
+
+use std::fs::{File, FileTimes};
+use std::path::{MAIN_SEPARATOR_STR, Path};
+use std::time::{Duration, SystemTime};
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+/// The number of seconds in a day.
+const DAY: u64 = 24 * 60 * 60;
+
+#[test]
+fn older_than_dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let old_file = test_dir.child("old_file");
+        old_file.touch()?;
+        set_modified(&old_file, days_ago(2))?;
+        let new_file = test_dir.child("new_file");
+        new_file.touch()?;
+
+        cmd.args(["--older-than", "1d", "old_file", "new_file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::dry_removed("old_file");
+                rm_out::newline(),
+                rm_out::dry_conclusion(1, 0),
+            ))
+            .stderr("");
+        old_file.assert(predicate::path::exists());
+        new_file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn older_than_force() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let old_file = test_dir.child("old_file");
+        old_file.touch()?;
+        set_modified(&old_file, days_ago(2))?;
+        let new_file = test_dir.child("new_file");
+        new_file.touch()?;
+
+        cmd.args(["--older-than", "1d", "--force", "old_file", "new_file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("old_file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        old_file.assert(predicate::path::missing());
+        new_file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn older_than_date() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        set_modified(&file, SystemTime::UNIX_EPOCH)?;
+
+        cmd.args(["--older-than", "1970-01-02", "--force", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn newer_than_force() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let old_file = test_dir.child("old_file");
+        old_file.touch()?;
+        set_modified(&old_file, days_ago(2))?;
+        let new_file = test_dir.child("new_file");
+        new_file.touch()?;
+
+        cmd.args(["--newer-than", "1d", "--force", "old_file", "new_file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("new_file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        old_file.assert(predicate::path::exists());
+        new_file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn older_and_newer_than() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file1 = test_dir.child("file1");
+        file1.touch()?;
+        set_modified(&file1, days_ago(1))?;
+        let file2 = test_dir.child("file2");
+        file2.touch()?;
+        set_modified(&file2, days_ago(3))?;
+        let file3 = test_dir.child("file3");
+        file3.touch()?;
+        set_modified(&file3, days_ago(5))?;
+
+        cmd.args(["--newer-than", "4d", "--older-than", "2d", "--force"])
+            .args(["file1", "file2", "file3"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file2");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        file1.assert(predicate::path::exists());
+        file2.assert(predicate::path::missing());
+        file3.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn recursive_partially_old() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let old_file = dir.child("old_file");
+        old_file.touch()?;
+        set_modified(&old_file, days_ago(2))?;
+        let new_file = dir.child("nested_dir").child("new_file");
+        new_file.touch()?;
+
+        cmd.args(["--older-than", "1d", "--recursive", "--force", "--verbose", "dir"])
+            .assert()
+            .success()
+            .stdout(has_lines!(
+                rm_out::removed(path(&["dir", "old_file"])),
+                rm_out::skipped_too_new(path(&["dir", "nested_dir", "new_file"])),
+                rm_out::skipped_contents_kept(path(&["dir", "nested_dir"])),
+                rm_out::skipped_contents_kept("dir"),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        old_file.assert(predicate::path::missing());
+        new_file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn recursive_all_old() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let old_file = dir.child("nested_dir").child("old_file");
+        old_file.touch()?;
+        set_modified(&old_file, days_ago(2))?;
+
+        cmd.args(["--older-than", "1d", "--recursive", "--force", "dir"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(path(&["dir", "nested_dir", "old_file"])),
+                rm_out::removed(path(&["dir", "nested_dir"])),
+                rm_out::removed("dir");
+                rm_out::newline(),
+                rm_out::conclusion(3, 0),
+            ))
+            .stderr("");
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn dir_new_empty() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.create_dir_all()?;
+
+        cmd.args(["--older-than", "14d", "--dir", "--force", "--verbose", "dir"])
+            .assert()
+            .success()
+            .stdout(has_lines!(rm_out::skipped_too_new("dir"), rm_out::conclusion(0, 0)))
+            .stderr("");
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn dir_old_empty() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.create_dir_all()?;
+        set_modified(&dir, days_ago(2))?;
+
+        cmd.args(["--older-than", "1d", "--dir", "--force", "dir"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("dir");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn recursive_new_empty_dir() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let old_file = dir.child("old_file");
+        old_file.touch()?;
+        set_modified(&old_file, days_ago(2))?;
+        let empty_dir = dir.child("empty_dir");
+        empty_dir.create_dir_all()?;
+
+        cmd.args(["--older-than", "1d", "--recursive", "--force", "--verbose", "dir"])
+            .assert()
+            .success()
+            .stdout(has_lines!(
+                rm_out::skipped_too_new(path(&["dir", "empty_dir"])),
+                rm_out::skipped_contents_kept("dir"),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        old_file.assert(predicate::path::missing());
+        empty_dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn time_atime() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        let times = FileTimes::new().set_accessed(days_ago(2)).set_modified(SystemTime::now());
+        File::options().write(true).open(&file)?.set_times(times)?;
+
+        cmd.args(["--older-than", "1d", "--time", "atime", "--force", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn time_ctime() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        set_modified(&file, days_ago(2))?;
+
+        cmd.args(["--older-than", "1d", "--time", "ctime", "--force", "--verbose", "file"])
+            .assert()
+            .success()
+            .stdout(has_lines!(rm_out::skipped_too_new("file"), rm_out::conclusion(0, 0)))
+            .stderr("");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn verbose_too_old() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        set_modified(&file, days_ago(2))?;
+
+        cmd.args(["--newer-than", "1d", "--force", "--verbose", "file"])
+            .assert()
+            .success()
+            .stdout(has_lines!(rm_out::skipped_too_old("file"), rm_out::conclusion(0, 0)))
+            .stderr("");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn invalid_age() -> TestResult {
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.args(["--older-than", "soon", "file"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(predicate::str::contains("invalid value 'soon' for '--older-than <AGE>'"));
+
+        Ok(())
+    })
+}
+
+/// Get the point in time the given number of `days` ago.
+fn days_ago(days: u64) -> SystemTime {
+    let duration = Duration::from_secs(days.saturating_mul(DAY));
+    SystemTime::now().checked_sub(duration).unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Set the modification time of the file or directory at the given `path`.
+fn set_modified<P: AsRef<Path>>(path: P, time: SystemTime) -> TestResult {
+    let path = path.as_ref();
+    let file =
+        if path.is_dir() { File::open(path)? } else { File::options().write(true).open(path)? };
+    file.set_modified(time)?;
+    Ok(())
+}
+
+/// Join the given path components using the platform's path separator.
+fn path(components: &[&str]) -> String {
+    components.join(MAIN_SEPARATOR_STR)
+}
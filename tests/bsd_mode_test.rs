@@ -0,0 +1,466 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of BSD mode.
+
+pub mod common;
+
+use crate::common::{TestResult, rm_ask, rm_out};
+
+#[cfg(all(feature = "bsd-mode", unix))]
+use std::fs::hard_link;
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn remove_empty_dir() -> TestResult {
+    let dirname = "dir";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child(dirname);
+        dir.create_dir_all()?;
+
+        cmd.arg(dirname).assert().failure().stdout("").stderr(rm_out::is_a_dir(dirname));
+        dir.assert(predicate::path::exists());
+
+        cmd.arg("-d").assert().success();
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn remove_filled_dir_recursively() -> TestResult {
+    let dirname = "dir";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child(dirname);
+        dir.create_dir_all()?;
+        let file = dir.child("file");
+        file.touch()?;
+
+        cmd.arg(dirname).assert().failure().stdout("").stderr(rm_out::is_a_dir(dirname));
+        dir.assert(predicate::path::exists());
+        file.assert(predicate::path::exists());
+
+        cmd.arg("-r").assert().success();
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn remove_filled_dir_recursively_on_one_file_system() -> TestResult {
+    let dirname = "dir";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child(dirname);
+        dir.create_dir_all()?;
+        let file = dir.child("file");
+        file.touch()?;
+
+        cmd.args(["-r", "-x", dirname]).assert().success();
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn interactive_once_few_files() -> TestResult {
+    let filenames = ["file1", "file2", "file3"];
+
+    with_test_dir(|mut cmd, test_dir| {
+        for filename in filenames {
+            test_dir.child(filename).touch()?;
+        }
+
+        cmd.arg("-I").args(filenames).assert().success().stdout("").stderr("");
+        for filename in filenames {
+            test_dir.child(filename).assert(predicate::path::missing());
+        }
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn interactive_once_many_files_no() -> TestResult {
+    let filenames = ["file1", "file2", "file3", "file4"];
+
+    with_test_dir(|mut cmd, test_dir| {
+        for filename in filenames {
+            test_dir.child(filename).touch()?;
+        }
+
+        cmd.arg("-I")
+            .args(filenames)
+            .write_stdin("n")
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(rm_ask::arguments(4));
+        for filename in filenames {
+            test_dir.child(filename).assert(predicate::path::exists());
+        }
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn interactive_once_many_files_no_answer() -> TestResult {
+    let filenames = ["file1", "file2", "file3", "file4"];
+
+    with_test_dir(|mut cmd, test_dir| {
+        for filename in filenames {
+            test_dir.child(filename).touch()?;
+        }
+
+        cmd.arg("-I")
+            .args(filenames)
+            .write_stdin("\n")
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(rm_ask::arguments(4));
+        for filename in filenames {
+            test_dir.child(filename).assert(predicate::path::exists());
+        }
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn interactive_once_many_files_yes() -> TestResult {
+    let filenames = ["file1", "file2", "file3", "file4"];
+
+    with_test_dir(|mut cmd, test_dir| {
+        for filename in filenames {
+            test_dir.child(filename).touch()?;
+        }
+
+        cmd.arg("-I")
+            .args(filenames)
+            .write_stdin("y")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr(rm_ask::arguments(4));
+        for filename in filenames {
+            test_dir.child(filename).assert(predicate::path::missing());
+        }
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn interactive_once_recursive() -> TestResult {
+    let dirname = "dir";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child(dirname);
+        dir.create_dir_all()?;
+        let file = dir.child("file");
+        file.touch()?;
+
+        cmd.args(["-I", "-r", dirname])
+            .write_stdin("n")
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(rm_ask::arguments_recursively(1));
+        file.assert(predicate::path::exists());
+
+        cmd.write_stdin("y").assert().success().stdout("").stderr(rm_ask::arguments_recursively(1));
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn overwrite() -> TestResult {
+    let filename = "file";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child(filename);
+        file.write_str("Hello world!")?;
+
+        cmd.args(["-P", filename]).assert().success().stdout("").stderr("");
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(all(feature = "bsd-mode", unix))]
+fn overwrite_contents() -> TestResult {
+    use std::fs::File;
+    use std::io::Read as _;
+
+    let filename = "file";
+    let contents = "Hello world!";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child(filename);
+        file.write_str(contents)?;
+        let mut handle = File::open(&file)?;
+
+        cmd.args(["-P", filename]).assert().success().stdout("").stderr("");
+        file.assert(predicate::path::missing());
+
+        let mut data = Vec::new();
+        handle.read_to_end(&mut data)?;
+        assert!(!data.windows(contents.len()).any(|window| window == contents.as_bytes()));
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(all(feature = "bsd-mode", unix))]
+fn overwrite_hard_linked() -> TestResult {
+    let filename = "file";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child(filename);
+        file.write_str("Hello world!")?;
+        let link = test_dir.child("link");
+        hard_link(&file, &link)?;
+
+        cmd.args(["-P", filename]).assert().failure().stdout("");
+        file.assert("Hello world!");
+        link.assert("Hello world!");
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn invalid_flag_blind() -> TestResult {
+    unsupported_flag("--blind")?;
+    unsupported_flag_with_force("--blind")?;
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn invalid_flag_quiet() -> TestResult {
+    unsupported_flag("--quiet")?;
+    unsupported_flag_with_force("--quiet")?;
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn invalid_flag_detach() -> TestResult {
+    let dirname = "dir";
+
+    for args in [&["-r", "--detach", dirname][..], &["-f", "-r", "--detach", dirname][..]] {
+        with_test_dir(|mut cmd, test_dir| {
+            let dir = test_dir.child(dirname);
+            dir.child("file").touch()?;
+
+            cmd.args(args)
+                .assert()
+                .failure()
+                .stdout("")
+                .stderr("error: option --detach not supported in BSD mode\n");
+            dir.assert(predicate::path::exists());
+
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn invalid_flag_gc() -> TestResult {
+    let dirname = "dir";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child(dirname);
+        dir.child("file").touch()?;
+
+        cmd.args(["-r", "--gc", dirname])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr("error: option --gc not supported in BSD mode\n");
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })?;
+
+    unsupported_flag("--gc")?;
+    unsupported_flag_even_with_force("--gc")?;
+
+    Ok(())
+}
+
+#[test]
+#[cfg(all(feature = "bsd-mode", feature = "trash"))]
+fn invalid_flag_trash() -> TestResult {
+    unsupported_flag("--trash")?;
+    unsupported_flag_with_force("--trash")?;
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn invalid_flag_undelete() -> TestResult {
+    for args in [&["-W"][..], &["-f", "-W"][..]] {
+        with_test_dir(|mut cmd, _test_dir| {
+            cmd.args(args)
+                .assert()
+                .failure()
+                .stdout("")
+                .stderr("error: option -W not supported in BSD mode\n");
+
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "bsd-mode")]
+fn flags_only_in_bsd_mode() -> TestResult {
+    for flag in ["-I", "-P", "-W"] {
+        common::with_test_dir(|mut cmd, _test_dir| {
+            cmd.arg(flag)
+                .assert()
+                .failure()
+                .stdout("")
+                .stderr(format!("error: option {flag} only supported in BSD mode\n"));
+
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(all(feature = "bsd-mode", feature = "gnu-mode"))]
+fn not_with_gnu_mode() -> TestResult {
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.env("RUST_RM_GNU_MODE", "1")
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr("error: BSD mode and GNU mode cannot be used together\n");
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(not(feature = "bsd-mode"))]
+fn ignored_without_the_build_feature() -> TestResult {
+    let filename = "file";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child(filename);
+        file.touch()?;
+
+        cmd.arg(filename).assert().success();
+        file.assert(predicate::path::exists());
+
+        cmd.arg("--force").assert().success();
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+/// Test the behavior of using an unsupported flag in BSD mode (without `-f`).
+///
+/// # Example
+///
+/// ```no_run
+/// unsupported_flag("--flag");
+/// ```
+#[cfg(feature = "bsd-mode")]
+fn unsupported_flag(flag: &str) -> TestResult {
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.arg(flag)
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(format!("error: option {flag} not supported in BSD mode\n"));
+
+        Ok(())
+    })
+}
+
+/// Test the behavior of using an unsupported flag in BSD mode with `-f`.
+///
+/// # Example
+///
+/// ```no_run
+/// unsupported_flag_with_force("--flag");
+/// ```
+#[cfg(feature = "bsd-mode")]
+fn unsupported_flag_with_force(flag: &str) -> TestResult {
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.args(["-f", flag]).assert().success();
+
+        Ok(())
+    })
+}
+
+/// Test the behavior of using an unsupported flag in BSD mode that is rejected even with `-f`.
+///
+/// # Example
+///
+/// ```no_run
+/// unsupported_flag_even_with_force("--flag");
+/// ```
+#[cfg(feature = "bsd-mode")]
+fn unsupported_flag_even_with_force(flag: &str) -> TestResult {
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.args(["-f", flag])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(format!("error: option {flag} not supported in BSD mode\n"));
+
+        Ok(())
+    })
+}
+
+/// Run a test with BSD mode enabled.
+///
+/// See also [`common::with_test_dir`].
+fn with_test_dir<C>(callback: C) -> TestResult
+where
+    C: FnOnce(assert_cmd::Command, &assert_fs::TempDir) -> TestResult,
+{
+    common::with_test_dir(|mut cmd, test_dir| {
+        cmd.env("RUST_RM_BSD_MODE", "1");
+        callback(cmd, test_dir)
+    })
+}
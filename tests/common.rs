@@ -4,6 +4,7 @@
 
 use std::env;
 use std::error;
+use std::fs;
 
 use assert_cmd::{Command, cargo};
 use assert_fs::TempDir;
@@ -143,6 +144,8 @@ pub(crate) use {has_exactly_lines, has_lines};
 
 /// Test helpers to generate strings used by the CLI to prompt the user.
 ///
+/// The prompts include the details of entries that are empty and were modified just now.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -157,24 +160,47 @@ pub(crate) use {has_exactly_lines, has_lines};
 /// }
 /// ```
 pub mod rm_ask {
-    pub fn descend<S: Into<String>>(subject: S) -> String {
-        format!("Descend into directory {}? [Y/n] ", subject.into())
+    #[must_use]
+    pub fn arguments(count: usize) -> String {
+        format!("Remove {count} argument{}? [y/N] ", if count == 1 { "" } else { "s" })
+    }
+
+    #[must_use]
+    pub fn arguments_recursively(count: usize) -> String {
+        format!("Remove {count} argument{} recursively? [y/N] ", if count == 1 { "" } else { "s" })
+    }
+
+    pub fn descend<S: Into<String>>(subject: S, entries: usize) -> String {
+        format!("Descend into directory {} ({})? [Y/n/a/d/q/?] ", subject.into(), contents(entries))
     }
 
-    pub fn dir<S: Into<String>>(subject: S) -> String {
-        format!("Remove directory {}? [Y/n] ", subject.into())
+    pub fn dir<S: Into<String>>(subject: S, entries: usize) -> String {
+        format!("Remove directory {} ({})? [Y/n/a/d/q/?] ", subject.into(), contents(entries))
     }
 
     pub fn empty_dir<S: Into<String>>(subject: S) -> String {
-        format!("Remove empty directory {}? [Y/n] ", subject.into())
+        format!("Remove empty directory {} (modified just now)? [Y/n/a/d/q/?] ", subject.into())
     }
 
     pub fn file<S: Into<String>>(subject: S) -> String {
-        format!("Remove regular file {}? [Y/n] ", subject.into())
+        format!("Remove regular file {} (0 B, modified just now)? [Y/n/a/d/q/?] ", subject.into())
     }
 
     pub fn link<S: Into<String>>(subject: S) -> String {
-        format!("Remove symbolic link {}? [Y/n] ", subject.into())
+        format!("Remove symbolic link {} (modified just now)? [Y/n/a/d/q/?] ", subject.into())
+    }
+
+    pub fn type_name<S: Into<String>>(subject: S, reason: &str) -> String {
+        format!("Directory {} {reason}, type its name to confirm: ", subject.into())
+    }
+
+    /// The details of a directory with the given number of `entries`, all of which are empty and
+    /// were modified just now.
+    fn contents(entries: usize) -> String {
+        format!(
+            "{entries} {}, 0 B, modified just now",
+            if entries == 1 { "entry" } else { "entries" }
+        )
     }
 }
 
@@ -250,11 +276,19 @@ pub mod rm_out {
         format!("Cannot remove {}: Is a directory (use '--dir' to remove)\n", subject.into())
     }
 
+    pub fn is_a_dir_no_tip<S: Into<String>>(subject: S) -> String {
+        format!("Cannot remove {}: Is a directory\n", subject.into())
+    }
+
     #[must_use]
     pub fn newline() -> String {
         "\n".to_owned()
     }
 
+    pub fn not_a_dir<S: Into<String>>(subject: S) -> String {
+        format!("Cannot remove {}: Not a directory\n", subject.into())
+    }
+
     pub fn not_found<S: Into<String>>(subject: S) -> String {
         format!("Cannot remove {}: Not found (use '--blind' to ignore)\n", subject.into())
     }
@@ -267,10 +301,46 @@ pub mod rm_out {
         format!("Cannot remove {}: Refused to remove\n", subject.into())
     }
 
+    pub fn refused_changed<S: Into<String>>(subject: S) -> String {
+        format!(
+            "Cannot remove {}: Refused to remove (changed since the plan was made)\n",
+            subject.into()
+        )
+    }
+
+    pub fn refused_no_terminal<S: Into<String>>(subject: S) -> String {
+        format!(
+            "Cannot remove {}: Refused to remove (no terminal to type its name in; use '--answers-from' to read answers from a file)\n",
+            subject.into()
+        )
+    }
+
+    pub fn refused_not_confirmed<S: Into<String>>(subject: S) -> String {
+        format!(
+            "Cannot remove {}: Refused to remove (its name was not typed to confirm)\n",
+            subject.into()
+        )
+    }
+
+    pub fn refused_recent<S: Into<String>>(subject: S) -> String {
+        format!(
+            "Cannot remove {}: Refused to remove (modified just now; use '--force-recent' to remove)\n",
+            subject.into()
+        )
+    }
+
     pub fn removed<S: Into<String>>(subject: S) -> String {
         format!("Removed {}\n", subject.into())
     }
 
+    pub fn skipped_contents_kept<S: Into<String>>(subject: S) -> String {
+        format!("[skipped {}: Not all contents would be removed]\n", subject.into())
+    }
+
+    pub fn skipped_dir_kept<S: Into<String>>(subject: S) -> String {
+        format!("[skipped {}: Directory kept by user]\n", subject.into())
+    }
+
     pub fn skipped_empty<S: Into<String>>(subject: S) -> String {
         format!("[skipped {}: Directory is empty]\n", subject.into())
     }
@@ -279,14 +349,62 @@ pub mod rm_out {
         format!("[skipped {}: Unrecognized input]\n", subject.into())
     }
 
+    pub fn skipped_io_error<S: Into<String>>(subject: S) -> String {
+        format!("[skipped {}: I/O error]\n", subject.into())
+    }
+
     pub fn skipped_kept<S: Into<String>>(subject: S) -> String {
         format!("[skipped {}: Kept by user]\n", subject.into())
     }
 
+    pub fn skipped_not_a_dir<S: Into<String>>(subject: S) -> String {
+        format!("[skipped {}: Not a directory]\n", subject.into())
+    }
+
+    pub fn skipped_not_an_empty_tree<S: Into<String>>(subject: S) -> String {
+        format!("[skipped {}: Contains more than empty directories]\n", subject.into())
+    }
+
+    pub fn skipped_not_an_empty_file<S: Into<String>>(subject: S) -> String {
+        format!("[skipped {}: Not an empty file]\n", subject.into())
+    }
+
     pub fn skipped_not_found<S: Into<String>>(subject: S) -> String {
         format!("[skipped {}: Not found]\n", subject.into())
     }
 
+    pub fn skipped_other_type<S: Into<String>>(subject: S) -> String {
+        format!("[skipped {}: Not of the selected type]\n", subject.into())
+    }
+
+    pub fn skipped_other_user<S: Into<String>>(subject: S) -> String {
+        format!("[skipped {}: Owned by a different user]\n", subject.into())
+    }
+
+    pub fn skipped_quit<S: Into<String>>(subject: S) -> String {
+        format!("[skipped {}: Quit by user]\n", subject.into())
+    }
+
+    pub fn skipped_retained<S: Into<String>>(subject: S) -> String {
+        format!("[skipped {}: Retained]\n", subject.into())
+    }
+
+    pub fn skipped_too_large<S: Into<String>>(subject: S) -> String {
+        format!("[skipped {}: Too large]\n", subject.into())
+    }
+
+    pub fn skipped_too_new<S: Into<String>>(subject: S) -> String {
+        format!("[skipped {}: Too new]\n", subject.into())
+    }
+
+    pub fn skipped_too_old<S: Into<String>>(subject: S) -> String {
+        format!("[skipped {}: Too old]\n", subject.into())
+    }
+
+    pub fn skipped_too_small<S: Into<String>>(subject: S) -> String {
+        format!("[skipped {}: Too small]\n", subject.into())
+    }
+
     #[must_use]
     pub fn start() -> String {
         "[start processing]\n".to_owned()
@@ -341,3 +459,49 @@ where
 
     callback(cmd, &temp_dir)
 }
+
+/// Run a test with access to a (temporary) testing directory, with the binary invoked as `name`.
+///
+/// # Errors
+///
+/// Any error returned by the test callback is returned by this function.
+///
+/// An error may also occur if the test could not be set up.
+///
+/// # Examples
+///
+/// ```no_run
+/// pub mod common;
+///
+/// use crate::common::{with_test_dir_as, TestResult};
+///
+/// use assert_fs::prelude::*;
+///
+/// #[test]
+/// fn example_test() -> TestResult {
+///     with_test_dir_as("rmdir", |mut cmd, test_dir| {
+///         // Test something using `test_dir` ...
+///
+///         Ok(())
+///     })
+/// }
+/// ```
+pub fn with_test_dir_as<C>(name: &str, callback: C) -> TestResult
+where
+    C: FnOnce(Command, &TempDir) -> TestResult,
+{
+    let debug = env::var_os(TEST_DEBUG_MODE).is_some();
+    let temp_dir = TempDir::new()?.into_persistent_if(debug);
+    let bin_dir = TempDir::new()?.into_persistent_if(debug);
+
+    let bin = bin_dir.join(format!("{name}{}", env::consts::EXE_SUFFIX));
+    let original = cargo::cargo_bin!("rust-rm");
+    if fs::hard_link(original, &bin).is_err() {
+        fs::copy(original, &bin)?;
+    }
+
+    let mut cmd = Command::new(&bin);
+    cmd.current_dir(&temp_dir);
+
+    callback(cmd, &temp_dir)
+}
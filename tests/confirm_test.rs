@@ -0,0 +1,249 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--confirm-count`, `--confirm-size` and
+//! `--confirm-home` options.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, rm_ask, rm_out, with_test_dir};
+
+use std::fs::canonicalize;
+
+use assert_cmd::cargo;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn count_confirmed() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file1").touch()?;
+        dir.child("file2").touch()?;
+
+        cmd.args(["--force", "--recursive", "--confirm-count", "1", "--answers-from", "-", "dir"])
+            .write_stdin("dir\n")
+            .assert()
+            .success()
+            .stdout(predicate::str::ends_with(rm_out::conclusion(3, 0)))
+            .stderr(rm_ask::type_name("dir", "contains more than 1 entry"));
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn count_not_confirmed() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file1").touch()?;
+        dir.child("file2").touch()?;
+
+        cmd.args(["--force", "--recursive", "--confirm-count", "1", "--answers-from", "-", "dir"])
+            .write_stdin("y\n")
+            .assert()
+            .failure()
+            .stdout(has_exactly_lines!(rm_out::newline(), rm_out::conclusion(0, 1)))
+            .stderr(format!(
+                "{}{}",
+                rm_ask::type_name("dir", "contains more than 1 entry"),
+                rm_out::refused_not_confirmed("dir"),
+            ));
+        dir.child("file1").assert(predicate::path::exists());
+        dir.child("file2").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn count_within_threshold() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file1").touch()?;
+        dir.child("file2").touch()?;
+
+        cmd.args(["--force", "--recursive", "--confirm-count", "2", "dir"])
+            .assert()
+            .success()
+            .stdout(predicate::str::ends_with(rm_out::conclusion(3, 0)))
+            .stderr("");
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn count_from_env() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file1").touch()?;
+        dir.child("file2").touch()?;
+
+        cmd.env("RUST_RM_CONFIRM_COUNT", "1")
+            .args(["--force", "--recursive", "--answers-from", "-", "dir"])
+            .write_stdin("\n")
+            .assert()
+            .failure()
+            .stderr(predicate::str::ends_with(rm_out::refused_not_confirmed("dir")));
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn size_confirmed() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file").write_str(&"x".repeat(2048))?;
+
+        cmd.args(["--force", "--recursive", "--confirm-size", "1K", "--answers-from", "-", "dir"])
+            .write_stdin("dir\n")
+            .assert()
+            .success()
+            .stdout(predicate::str::ends_with(rm_out::conclusion(2, 0)))
+            .stderr(rm_ask::type_name("dir", "contains 2.0 KiB"));
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn home_confirmed() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file").touch()?;
+
+        cmd.env("HOME", canonicalize(test_dir)?)
+            .args(["--force", "--recursive", "--confirm-home", "--answers-from", "-", "dir"])
+            .write_stdin("dir\n")
+            .assert()
+            .success()
+            .stdout(predicate::str::ends_with(rm_out::conclusion(2, 0)))
+            .stderr(rm_ask::type_name("dir", "is in your home directory"));
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn nested_confirmed_once() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("nested").child("file1").touch()?;
+        dir.child("nested").child("file2").touch()?;
+
+        cmd.args(["--force", "--recursive", "--confirm-count", "1", "--answers-from", "-", "dir"])
+            .write_stdin("dir\n")
+            .assert()
+            .success()
+            .stderr(rm_ask::type_name("dir", "contains more than 1 entry"));
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn with_interactive() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file1").touch()?;
+        dir.child("file2").touch()?;
+
+        cmd.args(["--interactive", "--recursive", "--no-details", "--confirm-count", "1"])
+            .args(["--answers-from", "-", "dir"])
+            .write_stdin("y\nfoo\n")
+            .assert()
+            .failure()
+            .stderr(predicate::str::ends_with(rm_out::refused_not_confirmed("dir")));
+        dir.child("file1").assert(predicate::path::exists());
+        dir.child("file2").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn no_terminal() -> TestResult {
+    with_test_dir(|_, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file1").touch()?;
+        dir.child("file2").touch()?;
+
+        assert_cmd::Command::new("setsid")
+            .current_dir(test_dir)
+            .arg("--wait")
+            .arg(cargo::cargo_bin!("rust-rm"))
+            .args(["--force", "--recursive", "--confirm-count", "1", "dir"])
+            .assert()
+            .failure()
+            .stdout(has_exactly_lines!(rm_out::newline(), rm_out::conclusion(0, 1)))
+            .stderr(rm_out::refused_no_terminal("dir"));
+        dir.child("file1").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn no_terminal_within_threshold() -> TestResult {
+    with_test_dir(|_, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file").touch()?;
+
+        assert_cmd::Command::new("setsid")
+            .current_dir(test_dir)
+            .arg("--wait")
+            .arg(cargo::cargo_bin!("rust-rm"))
+            .args(["--force", "--recursive", "--confirm-count", "1", "dir"])
+            .assert()
+            .success()
+            .stderr("");
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file1").touch()?;
+        dir.child("file2").touch()?;
+
+        cmd.args(["--recursive", "--confirm-count", "1", "dir"])
+            .assert()
+            .success()
+            .stdout(predicate::str::ends_with(rm_out::dry_conclusion(3, 0)))
+            .stderr("");
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn answers_from_without_confirm() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file1").touch()?;
+        dir.child("file2").touch()?;
+
+        cmd.args(["--force", "--recursive", "--answers-from", "-", "dir"])
+            .assert()
+            .failure()
+            .code(2)
+            .stdout("");
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
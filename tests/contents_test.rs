@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--contents` option.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, rm_ask, rm_out, with_test_dir};
+
+use std::path::MAIN_SEPARATOR_STR;
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let hidden_file = dir.child(".hidden");
+        hidden_file.touch()?;
+
+        cmd.args(["--contents", "dir"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::dry_removed(path(&["dir", ".hidden"]));
+                rm_out::newline(),
+                rm_out::dry_conclusion(1, 0),
+            ))
+            .stderr("");
+        hidden_file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn force() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let hidden_file = dir.child(".hidden");
+        hidden_file.touch()?;
+        let nested_dir = dir.child("nested_dir");
+        nested_dir.child("file").touch()?;
+
+        cmd.args(["--contents", "--force", "dir"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(path(&["dir", ".hidden"])),
+                rm_out::removed(path(&["dir", "nested_dir", "file"])),
+                rm_out::removed(path(&["dir", "nested_dir"]));
+                rm_out::newline(),
+                rm_out::conclusion(3, 0),
+            ))
+            .stderr("");
+        dir.assert(predicate::path::exists());
+        hidden_file.assert(predicate::path::missing());
+        nested_dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn empty_dir() -> TestResult {
+    let dirname = "dir";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child(dirname);
+        dir.create_dir_all()?;
+
+        cmd.args(["--contents", "--interactive", "--answers-from", "-", dirname])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(rm_out::conclusion(0, 0)))
+            .stderr("");
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn file() -> TestResult {
+    let filename = "file";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child(filename);
+        file.touch()?;
+
+        cmd.args(["--contents", "--force", filename])
+            .assert()
+            .failure()
+            .stdout(has_exactly_lines!(rm_out::newline(), rm_out::conclusion(0, 1),))
+            .stderr(rm_out::not_a_dir(filename));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn interactive() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let file = dir.child("file");
+        file.touch()?;
+
+        let file_path = path(&["dir", "file"]);
+
+        cmd.args(["--contents", "--interactive", "--answers-from", "-", "dir"])
+            .write_stdin("y\ny\n")
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(&file_path);
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr(format!("{}{}", rm_ask::descend("dir", 1), rm_ask::file(&file_path)));
+        dir.assert(predicate::path::exists());
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn interactive_no_descend() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let file = dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--contents", "--interactive", "--answers-from", "-", "dir"])
+            .write_stdin("n\n")
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(rm_out::conclusion(0, 0)))
+            .stderr(rm_ask::descend("dir", 1));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn root() -> TestResult {
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.args(["--contents", "/"])
+            .assert()
+            .failure()
+            .stdout(has_exactly_lines!(rm_out::newline(), rm_out::dry_conclusion(0, 1),))
+            .stderr(rm_out::refused("/"));
+
+        Ok(())
+    })
+}
+
+/// Join the given path components using the platform's path separator.
+fn path(components: &[&str]) -> String {
+    components.join(MAIN_SEPARATOR_STR)
+}
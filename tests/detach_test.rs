@@ -0,0 +1,285 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--detach` and `--gc` options.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, has_lines, rm_out, with_test_dir};
+
+use std::error;
+use std::fs::{read_to_string, remove_file};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use assert_fs::TempDir;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn detach() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("nested").child("file").touch()?;
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        let output = cmd.args(["--force", "--recursive", "--detach", "dir", "file"]).output()?;
+        assert!(output.status.success());
+        dir.assert(predicate::path::missing());
+        file.assert(predicate::path::missing());
+
+        let stdout = String::from_utf8(output.stdout)?;
+        assert!(stdout.starts_with("Detached dir\nDetached file\n"), "{stdout}");
+        let conclusion = stdout.lines().last().unwrap_or_default();
+        assert!(
+            conclusion.starts_with("0 removed, 2 detached (removing in the background, see "),
+            "{stdout}"
+        );
+        assert!(conclusion.ends_with("), 0 errors occurred"), "{stdout}");
+
+        let log = wait_for_log(&stdout)?;
+        assert!(log.ends_with(&rm_out::conclusion(4, 0)), "{log}");
+        assert_eq!(tombstones_in(test_dir)?, Vec::<PathBuf>::new());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file").touch()?;
+
+        cmd.args(["--recursive", "--detach", "dir"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::dry_removed("dir");
+                rm_out::newline(),
+                rm_out::dry_conclusion(1, 0),
+            ))
+            .stderr("");
+        dir.assert(predicate::path::exists());
+        assert_eq!(tombstones_in(test_dir)?, Vec::<PathBuf>::new());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn not_found() -> TestResult {
+    with_test_dir(|mut cmd, _| {
+        cmd.args(["--force", "--recursive", "--detach", "missing"])
+            .assert()
+            .failure()
+            .stdout(has_lines!(rm_out::conclusion(0, 1)))
+            .stderr(has_lines!(rm_out::not_found("missing")));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn requires_recursive() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--force", "--detach", "file"]).assert().failure();
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn max_count_env() -> TestResult {
+    test_refused_env("RUST_RM_MAX_COUNT", "5")
+}
+
+#[test]
+fn protect_recent_env() -> TestResult {
+    test_refused_env("RUST_RM_PROTECT_RECENT", "10m")
+}
+
+#[test]
+fn max_count_option() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file").touch()?;
+
+        cmd.args(["--force", "--recursive", "--detach", "--max-count", "5", "dir"])
+            .assert()
+            .failure();
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn conflicting_options() -> TestResult {
+    let mut options = vec![
+        &["--edit"][..],
+        &["--force", "--keep-newest", "1"],
+        &["--force", "--keep-oldest", "1"],
+    ];
+    if cfg!(feature = "pick") {
+        options.push(&["--pick"]);
+    }
+    if cfg!(unix) {
+        options.push(&["--force", "--all-links", "."]);
+    }
+
+    for option in options {
+        with_test_dir(|mut cmd, test_dir| {
+            let dir = test_dir.child("dir");
+            dir.child("file").touch()?;
+
+            cmd.args(["--recursive", "--detach", "dir"])
+                .args(option)
+                .assert()
+                .failure()
+                .stderr(predicate::str::contains("cannot be used with"));
+            dir.assert(predicate::path::exists());
+            assert!(tombstones_in(test_dir)?.is_empty());
+
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn gc() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let tombstone1 = test_dir.child(".rust-rm-tombstone-abc");
+        tombstone1.child("file").touch()?;
+        let tombstone2 = test_dir.child(".rust-rm-tombstone-def");
+        tombstone2.touch()?;
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--force", "--gc"])
+            .assert()
+            .success()
+            .stdout(has_lines!(rm_out::conclusion(3, 0)))
+            .stderr("");
+        tombstone1.assert(predicate::path::missing());
+        tombstone2.assert(predicate::path::missing());
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn gc_in_dir() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let tombstone = dir.child(".rust-rm-tombstone-abc");
+        tombstone.touch()?;
+        let other = test_dir.child(".rust-rm-tombstone-def");
+        other.touch()?;
+
+        cmd.args(["--force", "--gc", "dir"])
+            .assert()
+            .success()
+            .stdout(has_lines!(rm_out::conclusion(1, 0)))
+            .stderr("");
+        tombstone.assert(predicate::path::missing());
+        other.assert(predicate::path::exists());
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn gc_dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let tombstone = test_dir.child(".rust-rm-tombstone-abc");
+        tombstone.touch()?;
+
+        cmd.arg("--gc")
+            .assert()
+            .success()
+            .stdout(has_lines!(rm_out::dry_conclusion(1, 0)))
+            .stderr("");
+        tombstone.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn gc_missing_dir() -> TestResult {
+    with_test_dir(|mut cmd, _| {
+        cmd.args(["--force", "--gc", "missing"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::starts_with("Cannot read missing: "));
+
+        Ok(())
+    })
+}
+
+/// Test that --detach is refused, leaving everything in place, if the given environment variable
+/// is set.
+fn test_refused_env(var: &str, val: &str) -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        for i in 0..20 {
+            dir.child(format!("file{i}")).touch()?;
+        }
+
+        cmd.env(var, val)
+            .args(["--force", "--recursive", "--detach", "dir"])
+            .assert()
+            .failure()
+            .code(2)
+            .stdout("")
+            .stderr(predicate::str::contains(format!("cannot be used with {var} set")));
+        dir.assert(predicate::path::exists());
+        assert_eq!(tombstones_in(test_dir)?, Vec::<PathBuf>::new());
+
+        Ok(())
+    })
+}
+
+/// Wait for the background removal that logs to the file named in the given `stdout` to finish
+/// and return (and remove) its log.
+fn wait_for_log(stdout: &str) -> Result<String, Box<dyn error::Error>> {
+    let (_, rest) = stdout
+        .lines()
+        .find_map(|line| line.split_once("(removing in the background, see "))
+        .ok_or("no log in stdout")?;
+    let (path, _) = rest.split_once(')').ok_or("unexpected conclusion")?;
+
+    let start = Instant::now();
+    loop {
+        let log = read_to_string(Path::new(path))?;
+        if log.contains("occurred") || start.elapsed() > Duration::from_secs(10) {
+            remove_file(path)?;
+            return Ok(log);
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Get the tombstones in the given test directory.
+fn tombstones_in(test_dir: &TempDir) -> io::Result<Vec<PathBuf>> {
+    let mut tombstones = Vec::new();
+    for entry in test_dir.read_dir()? {
+        let path = entry?.path();
+        if path.file_name().is_some_and(|name| name.to_string_lossy().starts_with(".rust-rm-")) {
+            tombstones.push(path);
+        }
+    }
+
+    Ok(tombstones)
+}
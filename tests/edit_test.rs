@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--edit` option.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, has_lines, rm_out, with_test_dir};
+
+#[cfg(unix)]
+use std::error;
+use std::path::MAIN_SEPARATOR_STR;
+
+use assert_fs::TempDir;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+#[cfg(unix)]
+fn unchanged() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let file = dir.child("file");
+        file.touch()?;
+
+        cmd.env("VISUAL", "true")
+            .args(["--edit", "--recursive", "dir"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(path(&["dir", "file"])),
+                rm_out::removed("dir");
+                rm_out::newline(),
+                rm_out::conclusion(2, 0),
+            ))
+            .stderr("");
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(unix)]
+fn entry_kept() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let file1 = dir.child("file1");
+        file1.touch()?;
+        let file2 = dir.child("file2");
+        file2.touch()?;
+        let editor = editor(test_dir, "file2")?;
+
+        cmd.env("VISUAL", editor)
+            .args(["--edit", "--recursive", "--verbose", "dir"])
+            .assert()
+            .success()
+            .stdout(has_lines!(rm_out::removed(path(&["dir", "file1"])), rm_out::conclusion(1, 0),))
+            .stderr("");
+        file1.assert(predicate::path::missing());
+        file2.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(unix)]
+fn editor_from_env() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file1 = test_dir.child("file1");
+        file1.touch()?;
+        let file2 = test_dir.child("file2");
+        file2.touch()?;
+        let editor = editor(test_dir, "file1")?;
+
+        cmd.env_remove("VISUAL")
+            .env("EDITOR", editor)
+            .args(["--edit", "file1", "file2"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file2");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        file1.assert(predicate::path::exists());
+        file2.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(unix)]
+fn editor_fails() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.env("VISUAL", "false")
+            .args(["--edit", "file"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(predicate::str::starts_with("Cannot edit the entries to remove: false failed"));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn edit_and_force() -> TestResult {
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.args(["--edit", "--force", "file"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(predicate::str::contains("cannot be used with"));
+
+        Ok(())
+    })
+}
+
+/// Create an editor script in the `test_dir` that removes all lines containing `keep`.
+#[cfg(unix)]
+fn editor(test_dir: &TempDir, keep: &str) -> Result<String, Box<dyn error::Error>> {
+    use std::fs::{Permissions, set_permissions};
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let script = test_dir.child("editor.sh");
+    script.write_str(&format!(
+        "#!/bin/sh\nsed '/{keep}/d' \"$1\" > \"$1.new\"\nmv \"$1.new\" \"$1\"\n"
+    ))?;
+    set_permissions(&script, Permissions::from_mode(0o755))?;
+
+    Ok(script.path().display().to_string())
+}
+
+/// Join the given path components using the platform's path separator.
+fn path(components: &[&str]) -> String {
+    components.join(MAIN_SEPARATOR_STR)
+}
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--empty-dirs` option.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, has_lines, rm_out, with_test_dir};
+
+use std::path::MAIN_SEPARATOR_STR;
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("empty_dir").create_dir_all()?;
+        dir.child("nested_dir").child("empty_dir").create_dir_all()?;
+        dir.child("filled_dir").child("file").touch()?;
+        dir.child("file").touch()?;
+
+        cmd.args(["--empty-dirs", "--recursive", "dir"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::dry_removed(path(&["dir", "empty_dir"])),
+                rm_out::dry_removed(path(&["dir", "nested_dir", "empty_dir"])),
+                rm_out::dry_removed(path(&["dir", "nested_dir"]));
+                rm_out::newline(),
+                rm_out::dry_conclusion(3, 0),
+            ))
+            .stderr("");
+        dir.child("empty_dir").assert(predicate::path::exists());
+        dir.child("nested_dir").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn force() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("empty_dir").create_dir_all()?;
+        dir.child("nested_dir").child("empty_dir").create_dir_all()?;
+        let nested_file = dir.child("filled_dir").child("file");
+        nested_file.touch()?;
+        let file = dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--empty-dirs", "--recursive", "--force", "dir"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(path(&["dir", "empty_dir"])),
+                rm_out::removed(path(&["dir", "nested_dir", "empty_dir"])),
+                rm_out::removed(path(&["dir", "nested_dir"]));
+                rm_out::newline(),
+                rm_out::conclusion(3, 0),
+            ))
+            .stderr("");
+        dir.child("empty_dir").assert(predicate::path::missing());
+        dir.child("nested_dir").assert(predicate::path::missing());
+        nested_file.assert(predicate::path::exists());
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn empty_tree() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("nested_dir").child("empty_dir").create_dir_all()?;
+
+        cmd.args(["--empty-dirs", "--recursive", "--force", "dir"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(path(&["dir", "nested_dir", "empty_dir"])),
+                rm_out::removed(path(&["dir", "nested_dir"])),
+                rm_out::removed("dir");
+                rm_out::newline(),
+                rm_out::conclusion(3, 0),
+            ))
+            .stderr("");
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn file() -> TestResult {
+    let filename = "file";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child(filename);
+        file.touch()?;
+
+        cmd.args(["--empty-dirs", "--recursive", "--force", filename])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(rm_out::conclusion(0, 0)))
+            .stderr("");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn verbose() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file").touch()?;
+
+        cmd.args(["--empty-dirs", "--recursive", "--force", "--verbose", "dir"])
+            .assert()
+            .success()
+            .stdout(has_lines!(
+                rm_out::skipped_not_a_dir(path(&["dir", "file"])),
+                rm_out::skipped_not_an_empty_tree("dir"),
+                rm_out::conclusion(0, 0),
+            ))
+            .stderr("");
+
+        Ok(())
+    })
+}
+
+#[test]
+fn requires_recursive() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.create_dir_all()?;
+
+        cmd.args(["--empty-dirs", "--force", "dir"]).assert().failure().code(2).stdout("");
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn conflicts_with_dir() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.create_dir_all()?;
+
+        cmd.args(["--empty-dirs", "--dir", "--force", "dir"]).assert().failure().code(2).stdout("");
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+/// Join the given path components using the platform's path separator.
+fn path(components: &[&str]) -> String {
+    components.join(MAIN_SEPARATOR_STR)
+}
@@ -0,0 +1,365 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--type`, `--larger-than`,
+//! `--smaller-than`, `--empty-files`, `--user` and `--group` options.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, has_lines, rm_out, with_test_dir};
+
+use std::path::MAIN_SEPARATOR_STR;
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn type_file() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let file = dir.child("file");
+        file.touch()?;
+        let nested_file = dir.child("nested_dir").child("file");
+        nested_file.touch()?;
+
+        cmd.args(["--type", "f", "--recursive", "--force", "--verbose", "dir"])
+            .assert()
+            .success()
+            .stdout(has_lines!(
+                rm_out::removed(path(&["dir", "file"])),
+                rm_out::removed(path(&["dir", "nested_dir", "file"])),
+                rm_out::skipped_other_type(path(&["dir", "nested_dir"])),
+                rm_out::skipped_other_type("dir"),
+                rm_out::conclusion(2, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::missing());
+        nested_file.assert(predicate::path::missing());
+        dir.child("nested_dir").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn type_dir() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let file = dir.child("filled_dir").child("file");
+        file.touch()?;
+        dir.child("empty_dir").create_dir_all()?;
+
+        cmd.args(["--type", "d", "--recursive", "--force", "--verbose", "dir"])
+            .assert()
+            .success()
+            .stdout(has_lines!(
+                rm_out::removed(path(&["dir", "empty_dir"])),
+                rm_out::skipped_other_type(path(&["dir", "filled_dir", "file"])),
+                rm_out::skipped_contents_kept(path(&["dir", "filled_dir"])),
+                rm_out::skipped_contents_kept("dir"),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        dir.child("empty_dir").assert(predicate::path::missing());
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg_attr(
+    all(windows, not(feature = "test-symlink")),
+    ignore = "Only run with the test-symlink feature"
+)]
+fn type_multiple() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        let link = test_dir.child("link");
+        link.symlink_to_file(&file)?;
+        let dir = test_dir.child("dir");
+        dir.create_dir_all()?;
+
+        cmd.args(["--type", "l,d", "--dir", "--force", "file", "link", "dir"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("link"),
+                rm_out::removed("dir");
+                rm_out::newline(),
+                rm_out::conclusion(2, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(unix)]
+fn type_socket() -> TestResult {
+    use std::os::unix::net::UnixListener;
+
+    with_test_dir(|mut cmd, test_dir| {
+        let socket = test_dir.child("socket");
+        let _listener = UnixListener::bind(&socket)?;
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--type", "s", "--force", "socket", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("socket");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        socket.assert(predicate::path::missing());
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn larger_than() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let small_file = test_dir.child("small_file");
+        small_file.write_str("Hello")?;
+        let large_file = test_dir.child("large_file");
+        large_file.write_str(&"a".repeat(2048))?;
+
+        cmd.args(["--larger-than", "1K", "--force", "small_file", "large_file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("large_file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        small_file.assert(predicate::path::exists());
+        large_file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn smaller_than() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let small_file = test_dir.child("small_file");
+        small_file.write_str("Hello")?;
+        let large_file = test_dir.child("large_file");
+        large_file.write_str(&"a".repeat(2048))?;
+
+        cmd.args(["--smaller-than", "1K", "--force", "--verbose", "small_file", "large_file"])
+            .assert()
+            .success()
+            .stdout(has_lines!(
+                rm_out::removed("small_file"),
+                rm_out::skipped_too_large("large_file"),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        small_file.assert(predicate::path::missing());
+        large_file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn larger_than_empty_dirs() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let empty_dir = dir.child("empty_dir");
+        empty_dir.create_dir_all()?;
+
+        cmd.args(["--larger-than", "1M", "--recursive", "--force", "--verbose", "dir"])
+            .assert()
+            .success()
+            .stdout(has_lines!(
+                rm_out::skipped_too_small(path(&["dir", "empty_dir"])),
+                rm_out::skipped_contents_kept("dir"),
+                rm_out::conclusion(0, 0),
+            ))
+            .stderr("");
+        empty_dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn type_file_empty_dirs() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let empty_dir = dir.child("empty_dir");
+        empty_dir.create_dir_all()?;
+
+        cmd.args(["--type", "f", "--recursive", "--force", "--verbose", "dir"])
+            .assert()
+            .success()
+            .stdout(has_lines!(
+                rm_out::skipped_other_type(path(&["dir", "empty_dir"])),
+                rm_out::skipped_other_type("dir"),
+                rm_out::conclusion(0, 0),
+            ))
+            .stderr("");
+        empty_dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(unix)]
+fn user_by_name() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--user", "root", "file"]).assert().success().stderr("");
+
+        Ok(())
+    })
+}
+
+#[test]
+fn combined() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let small_file = dir.child("small_file");
+        small_file.write_str("Hello")?;
+        let large_file = dir.child("large_file");
+        large_file.write_str(&"a".repeat(2048))?;
+
+        cmd.args(["--type", "f", "--smaller-than", "1K", "--recursive", "--force", "dir"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(path(&["dir", "small_file"]));
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        small_file.assert(predicate::path::missing());
+        large_file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn empty_files() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let empty_file = dir.child("empty_file");
+        empty_file.touch()?;
+        let filled_file = dir.child("filled_file");
+        filled_file.write_str("Hello world!")?;
+        let nested_dir = dir.child("nested_dir");
+        nested_dir.child("empty_file").touch()?;
+
+        cmd.args(["--empty-files", "--recursive", "--force", "--verbose", "dir"])
+            .assert()
+            .success()
+            .stdout(has_lines!(
+                rm_out::removed(path(&["dir", "empty_file"])),
+                rm_out::removed(path(&["dir", "nested_dir", "empty_file"])),
+                rm_out::removed(path(&["dir", "nested_dir"])),
+                rm_out::skipped_not_an_empty_file(path(&["dir", "filled_file"])),
+                rm_out::skipped_contents_kept("dir"),
+                rm_out::conclusion(3, 0),
+            ))
+            .stderr("");
+        empty_file.assert(predicate::path::missing());
+        filled_file.assert(predicate::path::exists());
+        nested_dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(unix)]
+fn user_and_group() -> TestResult {
+    use std::os::unix::fs::MetadataExt as _;
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        let metadata = file.path().metadata()?;
+        let uid = metadata.uid().to_string();
+        let gid = metadata.gid().to_string();
+
+        cmd.args(["--user", &uid, "--group", &gid, "--force", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(unix)]
+fn other_user() -> TestResult {
+    use std::os::unix::fs::MetadataExt as _;
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        let uid = file.path().metadata()?.uid().wrapping_add(1).to_string();
+
+        cmd.args(["--user", &uid, "--force", "--verbose", "file"])
+            .assert()
+            .success()
+            .stdout(has_lines!(rm_out::skipped_other_user("file"), rm_out::conclusion(0, 0)))
+            .stderr("");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(unix)]
+fn unknown_user() -> TestResult {
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.args(["--user", "no-such-user-exists", "file"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(predicate::str::contains("no such user"));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn invalid_size() -> TestResult {
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.args(["--larger-than", "big", "file"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(predicate::str::contains("invalid value 'big' for '--larger-than <SIZE>'"));
+
+        Ok(())
+    })
+}
+
+/// Join the given path components using the platform's path separator.
+fn path(components: &[&str]) -> String {
+    components.join(MAIN_SEPARATOR_STR)
+}
@@ -205,6 +205,55 @@ fn invalid_flag_quiet() -> TestResult {
     Ok(())
 }
 
+#[test]
+#[cfg(feature = "gnu-mode")]
+fn invalid_flag_detach() -> TestResult {
+    let dirname = "dir";
+
+    for args in [&["-r", "--detach", dirname][..], &["--force", "-r", "--detach", dirname][..]] {
+        with_test_dir(|mut cmd, test_dir| {
+            let dir = test_dir.child(dirname);
+            dir.child("file").touch()?;
+
+            cmd.args(args)
+                .assert()
+                .failure()
+                .stdout("")
+                .stderr("error: option --detach not supported in GNU mode\n");
+            dir.assert(predicate::path::exists());
+
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "gnu-mode")]
+fn invalid_flag_gc() -> TestResult {
+    let dirname = "dir";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child(dirname);
+        dir.child("file").touch()?;
+
+        cmd.args(["-r", "--gc", dirname])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr("error: option --gc not supported in GNU mode\n");
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })?;
+
+    unsupported_flag("--gc")?;
+    unsupported_flag_even_with_force("--gc")?;
+
+    Ok(())
+}
+
 #[test]
 #[cfg(all(feature = "gnu-mode", feature = "trash"))]
 fn invalid_flag_trash() -> TestResult {
@@ -269,6 +318,26 @@ fn unsupported_flag_with_force(flag: &str) -> TestResult {
     })
 }
 
+/// Test the behavior of using an unsupported flag in GNU mode that is rejected even with `--force`.
+///
+/// # Example
+///
+/// ```no_run
+/// unsupported_flag_even_with_force("--flag");
+/// ```
+#[cfg(feature = "gnu-mode")]
+fn unsupported_flag_even_with_force(flag: &str) -> TestResult {
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.args(["--force", flag])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(format!("error: option {flag} not supported in GNU mode\n"));
+
+        Ok(())
+    })
+}
+
 /// Run a test with GNU mode enabled.
 ///
 /// See also [`common::with_test_dir`].
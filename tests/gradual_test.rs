@@ -0,0 +1,129 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--gradual` and `--gradual-pause`
+//! options.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, rm_out, with_test_dir};
+
+use std::fs::File;
+use std::time::{Duration, Instant};
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn sparse_file() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        File::create(&file)?.set_len(10 << 20)?;
+
+        cmd.args(["--force", "--gradual=1M", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn sparse_file_with_pause() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        File::create(&file)?.set_len(4 << 20)?;
+
+        let start = Instant::now();
+        cmd.args(["--force", "--gradual=1M", "--gradual-pause", "50ms", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        assert!(start.elapsed() >= Duration::from_millis(150));
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn default_chunk() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.write_str("Hello world!")?;
+
+        cmd.args(["--force", "--gradual", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn zero_chunk() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.write_str("Hello world!")?;
+
+        cmd.args(["--force", "--gradual=0", "file"]).assert().failure();
+        file.assert("Hello world!");
+
+        Ok(())
+    })
+}
+
+#[test]
+fn dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        File::create(&file)?.set_len(10 << 20)?;
+
+        cmd.args(["--gradual=1M", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::dry_removed("file");
+                rm_out::newline(),
+                rm_out::dry_conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn pause_without_gradual() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--force", "--gradual-pause", "50ms", "file"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--gradual"));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
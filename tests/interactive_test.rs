@@ -8,6 +8,7 @@ use crate::common::{TestResult, has_exactly_lines, has_lines, rm_ask, rm_out};
 
 use std::path::MAIN_SEPARATOR;
 
+use assert_cmd::cargo;
 use assert_fs::prelude::*;
 use predicates::prelude::*;
 
@@ -161,7 +162,7 @@ fn remove_filled_dir_recursive_no() -> TestResult {
             .assert()
             .success()
             .stdout(rm_out::conclusion(0, 0))
-            .stderr(rm_ask::descend(dirname));
+            .stderr(rm_ask::descend(dirname, 1));
         dir.assert(predicate::path::exists());
 
         cmd.arg("--verbose")
@@ -173,7 +174,7 @@ fn remove_filled_dir_recursive_no() -> TestResult {
                 rm_out::newline(),
                 rm_out::conclusion(0, 0),
             ))
-            .stderr(rm_ask::descend(dirname));
+            .stderr(rm_ask::descend(dirname, 1));
         dir.assert(predicate::path::exists());
 
         Ok(())
@@ -310,7 +311,7 @@ fn remove_filled_dir_recursive_yes_to_all() -> TestResult {
                 rm_out::conclusion(2, 0)
             ))
             .stderr(has_exactly_lines!(
-                rm_ask::descend(dirname),
+                rm_ask::descend(dirname, 1),
                 rm_ask::file(format!("{dirname}{MAIN_SEPARATOR}{filename}")),
                 rm_ask::empty_dir(dirname),
             ));
@@ -347,7 +348,7 @@ fn remove_filled_dir_recursive_descend_but_keep_dir() -> TestResult {
                 rm_out::conclusion(1, 0),
             ))
             .stderr(has_exactly_lines!(
-                rm_ask::descend(dirname),
+                rm_ask::descend(dirname, 1),
                 rm_ask::file(format!("{dirname}{MAIN_SEPARATOR}{filename}")),
                 rm_ask::empty_dir(dirname),
             ));
@@ -373,7 +374,7 @@ fn remove_filled_dir_recursive_descend_but_keep_dir() -> TestResult {
                 rm_out::conclusion(1, 0),
             ))
             .stderr(has_exactly_lines!(
-                rm_ask::descend(dirname),
+                rm_ask::descend(dirname, 1),
                 rm_ask::file(format!("{dirname}{MAIN_SEPARATOR}{filename}")),
                 rm_ask::empty_dir(dirname),
             ));
@@ -411,10 +412,10 @@ fn remove_filled_dir_recursive_descend_but_keep_all() -> TestResult {
             .success()
             .stdout(rm_out::conclusion(0, 0))
             .stderr(has_exactly_lines!(
-                rm_ask::descend(dirname),
+                rm_ask::descend(dirname, 2),
                 rm_ask::file(format!("{dirname}{MAIN_SEPARATOR}{filename1}")),
                 rm_ask::file(format!("{dirname}{MAIN_SEPARATOR}{filename2}")),
-                rm_ask::dir(dirname),
+                rm_ask::dir(dirname, 2),
             ));
         dir.assert(predicate::path::exists());
         file1.assert(predicate::path::exists());
@@ -439,10 +440,10 @@ fn remove_filled_dir_recursive_descend_but_keep_all() -> TestResult {
                 rm_out::conclusion(0, 0),
             ))
             .stderr(has_exactly_lines!(
-                rm_ask::descend(dirname),
+                rm_ask::descend(dirname, 2),
                 rm_ask::file(format!("{dirname}{MAIN_SEPARATOR}{filename1}")),
                 rm_ask::file(format!("{dirname}{MAIN_SEPARATOR}{filename2}")),
-                rm_ask::dir(dirname),
+                rm_ask::dir(dirname, 2),
             ));
         dir.assert(predicate::path::exists());
         file1.assert(predicate::path::exists());
@@ -479,9 +480,9 @@ fn remove_filled_dir_recursive_descend_but_keep_content_and_remove_dir() -> Test
                 rm_out::conclusion(0, 1),
             ))
             .stderr(has_exactly_lines!(
-                rm_ask::descend(dirname),
+                rm_ask::descend(dirname, 1),
                 rm_ask::file(format!("{dirname}{MAIN_SEPARATOR}{filename}")),
-                rm_ask::dir(dirname),
+                rm_ask::dir(dirname, 1),
                 rm_out::dir_not_empty_no_tip(dirname),
             ));
         dir.assert(predicate::path::exists());
@@ -503,9 +504,9 @@ fn remove_filled_dir_recursive_descend_but_keep_content_and_remove_dir() -> Test
                 rm_out::conclusion(0, 1),
             ))
             .stderr(has_exactly_lines!(
-                rm_ask::descend(dirname),
+                rm_ask::descend(dirname, 1),
                 rm_ask::file(format!("{dirname}{MAIN_SEPARATOR}{filename}")),
-                rm_ask::dir(dirname),
+                rm_ask::dir(dirname, 1),
                 rm_out::dir_not_empty_no_tip(dirname),
             ));
         dir.assert(predicate::path::exists());
@@ -535,7 +536,7 @@ fn remove_filled_dir_recursive_trash() -> TestResult {
                 rm_out::newline(),
                 rm_out::conclusion(1, 0)
             ))
-            .stderr(has_exactly_lines!(rm_ask::dir(dirname)));
+            .stderr(has_exactly_lines!(rm_ask::dir(dirname, 1)));
         dir.assert(predicate::path::missing());
 
         Ok(())
@@ -605,6 +606,224 @@ fn answer_invalid() -> TestResult {
     })
 }
 
+#[test]
+fn answer_empty() -> TestResult {
+    test_answer_yes("")
+}
+
+#[test]
+fn answer_all() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file").touch()?;
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--recursive", "dir", "file"])
+            .write_stdin(format!("a{ENTER}"))
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(format!("dir{MAIN_SEPARATOR}file")),
+                rm_out::removed("dir"),
+                rm_out::removed("file");
+                rm_out::newline(),
+                rm_out::conclusion(3, 0)
+            ))
+            .stderr(rm_ask::descend("dir", 1));
+        dir.assert(predicate::path::missing());
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn answer_keep_dir() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let nested_file = dir.child("file");
+        nested_file.touch()?;
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--recursive", "--verbose", "dir", "file"])
+            .write_stdin(format!("{YES}{ENTER}d{ENTER}{YES}{ENTER}"))
+            .assert()
+            .success()
+            .stdout(has_lines!(
+                rm_out::skipped_dir_kept(format!("dir{MAIN_SEPARATOR}file")),
+                rm_out::skipped_dir_kept("dir"),
+                rm_out::removed("file"),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr(has_exactly_lines!(
+                rm_ask::descend("dir", 1),
+                rm_ask::file(format!("dir{MAIN_SEPARATOR}file")),
+                rm_ask::file("file"),
+            ));
+        nested_file.assert(predicate::path::exists());
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn answer_quit() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file1 = test_dir.child("file1");
+        file1.touch()?;
+        let file2 = test_dir.child("file2");
+        file2.touch()?;
+        let file3 = test_dir.child("file3");
+        file3.touch()?;
+
+        cmd.args(["--verbose", "file1", "file2", "file3"])
+            .write_stdin(format!("{YES}{ENTER}q{ENTER}"))
+            .assert()
+            .success()
+            .stdout(has_lines!(
+                rm_out::removed("file1"),
+                rm_out::skipped_quit("file2"),
+                rm_out::skipped_quit("file3"),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr(has_exactly_lines!(rm_ask::file("file1"), rm_ask::file("file2")));
+        file1.assert(predicate::path::missing());
+        file2.assert(predicate::path::exists());
+        file3.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn answer_quit_in_dir() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file1").touch()?;
+        dir.child("file2").touch()?;
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--recursive", "--verbose", "dir", "file"])
+            .write_stdin(format!("{YES}{ENTER}q{ENTER}"))
+            .assert()
+            .success()
+            .stdout(
+                predicate::str::contains(rm_out::skipped_quit("dir"))
+                    .and(predicate::str::contains(rm_out::skipped_quit("file")))
+                    .and(predicate::str::contains("Quit by user").count(3)),
+            )
+            .stderr(predicate::str::contains("Remove regular file").count(1));
+        dir.child("file1").assert(predicate::path::exists());
+        dir.child("file2").assert(predicate::path::exists());
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn answer_help() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.arg("file")
+            .write_stdin(format!("?{ENTER}{NO}{ENTER}"))
+            .assert()
+            .success()
+            .stdout(rm_out::conclusion(0, 0))
+            .stderr(
+                predicate::str::contains("q - keep this entry and all remaining entries")
+                    .and(predicate::str::ends_with(rm_ask::file("file"))),
+            );
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn no_more_answers() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--verbose", "file"])
+            .write_stdin("")
+            .assert()
+            .success()
+            .stdout(has_lines!(rm_out::skipped_io_error("file"), rm_out::conclusion(0, 0)))
+            .stderr(rm_ask::file("file"));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn answers_from_file() -> TestResult {
+    common::with_test_dir(|mut cmd, test_dir| {
+        test_dir.child("answers").write_str(&format!("{NO}{ENTER}"))?;
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--interactive", "--answers-from", "answers", "file"])
+            .assert()
+            .success()
+            .stdout(rm_out::conclusion(0, 0))
+            .stderr(rm_ask::file("file"));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn answers_from_missing_file() -> TestResult {
+    common::with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--interactive", "--answers-from", "missing", "file"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(predicate::str::starts_with("Cannot read answers missing: "));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn no_terminal() -> TestResult {
+    common::with_test_dir(|_, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        assert_cmd::Command::new("setsid")
+            .current_dir(test_dir)
+            .arg("--wait")
+            .arg(cargo::cargo_bin!("rust-rm"))
+            .args(["--interactive", "file"])
+            .write_stdin(format!("{YES}{ENTER}"))
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(predicate::str::starts_with("Cannot prompt: no terminal available").and(
+                predicate::str::contains("(use '--answers-from' to read answers from a file)"),
+            ));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
 #[test]
 fn not_found() -> TestResult {
     let missing_path = "missing";
@@ -624,6 +843,65 @@ fn not_found() -> TestResult {
     })
 }
 
+#[test]
+fn details_file_size() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.write_str("Hello world!")?;
+
+        cmd.arg("file")
+            .write_stdin(format!("{NO}{ENTER}"))
+            .assert()
+            .success()
+            .stdout(rm_out::conclusion(0, 0))
+            .stderr("Remove regular file file (12 B, modified just now)? [Y/n/a/d/q/?] ");
+
+        Ok(())
+    })
+}
+
+#[test]
+fn details_dir_contents() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file").write_str("Hello world!")?;
+        dir.child("nested_dir").child("file").write_str("Hello")?;
+
+        cmd.args(["--recursive", "dir"])
+            .write_stdin(format!("{NO}{ENTER}"))
+            .assert()
+            .success()
+            .stdout(rm_out::conclusion(0, 0))
+            .stderr(
+                "Descend into directory dir (3 entries, 17 B, modified just now)? [Y/n/a/d/q/?] ",
+            );
+
+        Ok(())
+    })
+}
+
+#[test]
+fn no_details() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file").touch()?;
+        let file = test_dir.child("file");
+        file.write_str("Hello world!")?;
+
+        cmd.args(["--no-details", "--recursive", "dir", "file"])
+            .write_stdin(format!("{NO}{ENTER}{NO}{ENTER}"))
+            .assert()
+            .success()
+            .stdout(rm_out::conclusion(0, 0))
+            .stderr(has_exactly_lines!(
+                "Descend into directory dir? [Y/n/a/d/q/?] ",
+                "Remove regular file file? [Y/n/a/d/q/?] ",
+            ));
+
+        Ok(())
+    })
+}
+
 /// Re-usable test for validating the behavior of a correct negative answer.
 fn test_answer_no(answer: &str) -> TestResult {
     let filename = "file";
@@ -680,7 +958,7 @@ fn test_answer_yes(answer: &str) -> TestResult {
     })
 }
 
-/// Run a test with `--interactive` enabled.
+/// Run a test with `--interactive` enabled, reading answers from stdin.
 ///
 /// See also [`common::with_test_dir`].
 fn with_test_dir<C>(callback: C) -> TestResult
@@ -688,7 +966,7 @@ where
     C: FnOnce(assert_cmd::Command, &assert_fs::TempDir) -> TestResult,
 {
     common::with_test_dir(|mut cmd, test_dir| {
-        cmd.arg("--interactive");
+        cmd.args(["--interactive", "--answers-from", "-"]);
         callback(cmd, test_dir)
     })
 }
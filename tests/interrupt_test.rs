@@ -0,0 +1,171 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the behavior when the removal is interrupted by a signal.
+
+#![cfg(unix)]
+
+pub mod common;
+
+use crate::common::{TestResult, with_test_dir};
+
+use std::io::{self, Read as _};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use assert_cmd::cargo;
+use assert_fs::TempDir;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+/// The exit code expected when the removal is interrupted by SIGINT.
+const EXIT_INTERRUPTED: i32 = 130;
+
+/// The exit code expected when the removal is interrupted by SIGTERM.
+const EXIT_TERMINATED: i32 = 143;
+
+#[test]
+fn interrupt() -> TestResult {
+    test_signal("INT", EXIT_INTERRUPTED)
+}
+
+#[test]
+fn terminate() -> TestResult {
+    test_signal("TERM", EXIT_TERMINATED)
+}
+
+#[test]
+fn interrupt_at_prompt() -> TestResult {
+    test_prompt(&["--interactive", "--recursive", "dir"])
+}
+
+#[test]
+fn interrupt_at_confirm() -> TestResult {
+    test_prompt(&["--force", "--recursive", "--confirm-count", "1", "dir"])
+}
+
+#[test]
+fn interrupt_while_planning() -> TestResult {
+    test_prompt(&["--interactive", "--recursive", "--max-count", "10", "dir"])
+}
+
+#[test]
+fn interrupt_while_throttled() -> TestResult {
+    with_test_dir(|_, test_dir| {
+        test_dir.child("file1").write_binary(&vec![0; 1 << 20])?;
+        test_dir.child("file2").touch()?;
+        test_dir.child("file3").touch()?;
+
+        let args = ["--force", "--throttle-size", "1K", "file1", "file2", "file3"];
+        let mut child = spawn(test_dir, &args)?;
+        thread::sleep(Duration::from_millis(500));
+
+        send(&child, "INT")?;
+        for _ in 0..50 {
+            if child.try_wait()?.is_some() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        let Some(status) = child.try_wait()? else {
+            child.kill()?;
+            return Err("still waiting for the throttle after SIGINT".into());
+        };
+        assert_eq!(status.code(), Some(EXIT_INTERRUPTED));
+
+        let conclusion = conclusion_of(&mut child)?;
+        assert!(
+            conclusion.ends_with(" removed, 0 errors occurred, interrupted"),
+            "unexpected conclusion: {conclusion:?}"
+        );
+        test_dir.child("file1").assert(predicate::path::missing());
+        test_dir.child("file3").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+/// Test that sending the given signal stops a (slow) removal with a summary and the given exit
+/// code.
+fn test_signal(signal: &str, code: i32) -> TestResult {
+    with_test_dir(|_, test_dir| {
+        let names: Vec<String> = (0..20).map(|i| format!("file{i}")).collect();
+        for name in &names {
+            test_dir.child(name).touch()?;
+        }
+
+        let mut args = vec!["--force", "--throttle-count", "10"];
+        args.extend(names.iter().map(String::as_str));
+        let mut child = spawn(test_dir, &args)?;
+        thread::sleep(Duration::from_millis(500));
+
+        send(&child, signal)?;
+        let status = child.wait()?;
+        assert_eq!(status.code(), Some(code));
+
+        let conclusion = conclusion_of(&mut child)?;
+        assert!(
+            conclusion.ends_with(" removed, 0 errors occurred, interrupted"),
+            "unexpected conclusion: {conclusion:?}"
+        );
+
+        let remaining = names.iter().filter(|name| test_dir.child(name).exists()).count();
+        assert!(remaining > 0, "nothing remains");
+        assert!(remaining < names.len(), "nothing was removed");
+
+        Ok(())
+    })
+}
+
+/// Test that a single SIGINT stops a removal waiting for an answer to a prompt, with the given
+/// arguments, keeping the directory it asks about.
+fn test_prompt(args: &[&str]) -> TestResult {
+    with_test_dir(|_, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file1").touch()?;
+        dir.child("file2").touch()?;
+
+        let mut args = args.to_vec();
+        args.extend(["--answers-from", "-"]);
+        let mut child = spawn(test_dir, &args)?;
+        thread::sleep(Duration::from_millis(500));
+
+        send(&child, "INT")?;
+        let status = child.wait()?;
+        assert_eq!(status.code(), Some(EXIT_INTERRUPTED));
+
+        let conclusion = conclusion_of(&mut child)?;
+        assert_eq!(conclusion, "0 removed, 0 errors occurred, interrupted");
+        dir.child("file1").assert(predicate::path::exists());
+        dir.child("file2").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+/// Start the binary under test with the given arguments in `test_dir`.
+fn spawn(test_dir: &TempDir, args: &[&str]) -> io::Result<Child> {
+    Command::new(cargo::cargo_bin!("rust-rm"))
+        .args(args)
+        .current_dir(test_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+}
+
+/// Get the last line written to stdout by the given (exited) child process.
+fn conclusion_of(child: &mut Child) -> io::Result<String> {
+    let mut stdout = String::new();
+    child.stdout.take().ok_or(io::ErrorKind::BrokenPipe)?.read_to_string(&mut stdout)?;
+    Ok(stdout.lines().last().unwrap_or_default().to_owned())
+}
+
+/// Send the named signal to the given child process.
+fn send(child: &Child, signal: &str) -> TestResult {
+    let status =
+        Command::new("kill").args([&format!("-{signal}"), &child.id().to_string()]).status()?;
+    assert!(status.success(), "could not send SIG{signal}");
+
+    Ok(())
+}
@@ -239,7 +239,7 @@ fn hard_link_to_a_file_remove_link() -> TestResult {
             .assert()
             .success()
             .stdout(has_exactly_lines!(
-                rm_out::dry_removed(linkname);
+                rm_out::dry_removed(format!("{linkname} (1 other link remains)"));
                 rm_out::newline(),
                 rm_out::dry_conclusion(1, 0)
             ))
@@ -251,7 +251,7 @@ fn hard_link_to_a_file_remove_link() -> TestResult {
             .assert()
             .success()
             .stdout(has_exactly_lines!(
-                rm_out::removed(linkname);
+                rm_out::removed(format!("{linkname} (1 other link remains)"));
                 rm_out::newline(),
                 rm_out::conclusion(1, 0)
             ))
@@ -278,7 +278,7 @@ fn hard_link_to_a_file_remove_file() -> TestResult {
             .assert()
             .success()
             .stdout(has_exactly_lines!(
-                rm_out::dry_removed(filename);
+                rm_out::dry_removed(format!("{filename} (1 other link remains)"));
                 rm_out::newline(),
                 rm_out::dry_conclusion(1, 0)
             ))
@@ -290,7 +290,7 @@ fn hard_link_to_a_file_remove_file() -> TestResult {
             .assert()
             .success()
             .stdout(has_exactly_lines!(
-                rm_out::removed(filename);
+                rm_out::removed(format!("{filename} (1 other link remains)"));
                 rm_out::newline(),
                 rm_out::conclusion(1, 0)
             ))
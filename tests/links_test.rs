@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing how hard links are handled, including the `--all-links` option.
+
+#![cfg(unix)]
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, has_lines, rm_out, with_test_dir};
+
+use std::fs::hard_link;
+use std::path::MAIN_SEPARATOR_STR;
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn other_links_remain() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        hard_link(&file, test_dir.child("link"))?;
+
+        cmd.args(["--force", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                "Removed file (1 other link remains)\n".to_owned();
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::missing());
+        test_dir.child("link").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn other_links_remain_dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        hard_link(&file, test_dir.child("link1"))?;
+        hard_link(&file, test_dir.child("link2"))?;
+
+        cmd.arg("file")
+            .assert()
+            .success()
+            .stdout(has_lines!("Would remove file (2 other links remain)\n"))
+            .stderr("");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn all_links() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        let links = test_dir.child("links");
+        links.child("unrelated").touch()?;
+        hard_link(&file, links.child("link1"))?;
+        links.child("nested").create_dir_all()?;
+        hard_link(&file, links.child("nested").child("link2"))?;
+
+        cmd.args(["--force", "--all-links", "links", "file"])
+            .assert()
+            .success()
+            .stdout(has_lines!("Removed file (2 other links remain)\n", rm_out::conclusion(3, 0),))
+            .stdout(predicate::str::contains(format!("Removed {}", path(&["links", "link1"]))))
+            .stdout(predicate::str::contains(format!(
+                "Removed {}",
+                path(&["links", "nested", "link2"])
+            )))
+            .stderr("");
+        file.assert(predicate::path::missing());
+        links.child("link1").assert(predicate::path::missing());
+        links.child("nested").child("link2").assert(predicate::path::missing());
+        links.child("unrelated").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn all_links_already_removed() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file").touch()?;
+        hard_link(dir.child("file"), dir.child("link"))?;
+
+        cmd.args(["--force", "--recursive", "--all-links", ".", "dir"])
+            .assert()
+            .success()
+            .stdout(predicate::str::ends_with(rm_out::conclusion(3, 0)))
+            .stderr("");
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn all_links_not_a_dir() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--force", "--all-links", "missing", "file"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(predicate::str::contains("expected a directory"));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+/// Join the given path components using the platform's path separator.
+fn path(components: &[&str]) -> String {
+    components.join(MAIN_SEPARATOR_STR)
+}
@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--max-count` option.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, rm_out, with_test_dir};
+
+use std::path::MAIN_SEPARATOR_STR;
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn within_limit() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        test_dir.child("file1").touch()?;
+        test_dir.child("file2").touch()?;
+
+        cmd.args(["--max-count", "2", "--force", "file1", "file2"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file1"),
+                rm_out::removed("file2");
+                rm_out::newline(),
+                rm_out::conclusion(2, 0),
+            ))
+            .stderr("");
+        test_dir.child("file1").assert(predicate::path::missing());
+        test_dir.child("file2").assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn over_limit() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        test_dir.child("file1").touch()?;
+        test_dir.child("file2").touch()?;
+
+        cmd.args(["--max-count", "1", "--force", "file1", "file2"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(
+                predicate::str::contains("Refused to remove anything")
+                    .and(predicate::str::contains("2 would be removed"))
+                    .and(predicate::str::contains("use '--max-count' to raise the limit")),
+            );
+        test_dir.child("file1").assert(predicate::path::exists());
+        test_dir.child("file2").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn over_limit_dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        test_dir.child("file1").touch()?;
+        test_dir.child("file2").touch()?;
+
+        cmd.args(["--max-count", "1", "file1", "file2"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(predicate::str::contains("Refused to remove anything"));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn recursive() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file1").touch()?;
+        dir.child("file2").touch()?;
+
+        cmd.args(["--max-count", "2", "--recursive", "--force", "dir"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(predicate::str::contains("3 would be removed"));
+        dir.child("file1").assert(predicate::path::exists());
+        dir.child("file2").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn recursive_within_limit() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file").touch()?;
+
+        cmd.args(["--max-count", "2", "--recursive", "--force", "dir"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(path(&["dir", "file"])),
+                rm_out::removed("dir");
+                rm_out::newline(),
+                rm_out::conclusion(2, 0),
+            ))
+            .stderr("");
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn errors_not_counted() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        test_dir.child("file").touch()?;
+
+        cmd.args(["--max-count", "1", "--force", "file", "missing"])
+            .assert()
+            .failure()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 1),
+            ))
+            .stderr(rm_out::not_found("missing"));
+        test_dir.child("file").assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn default_from_env() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        test_dir.child("file1").touch()?;
+        test_dir.child("file2").touch()?;
+
+        cmd.env("RUST_RM_MAX_COUNT", "1")
+            .args(["--force", "file1", "file2"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(predicate::str::contains("Refused to remove anything"));
+        test_dir.child("file1").assert(predicate::path::exists());
+        test_dir.child("file2").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn option_overrides_env() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        test_dir.child("file1").touch()?;
+        test_dir.child("file2").touch()?;
+
+        cmd.env("RUST_RM_MAX_COUNT", "1")
+            .args(["--max-count", "2", "--force", "file1", "file2"])
+            .assert()
+            .success()
+            .stderr("");
+        test_dir.child("file1").assert(predicate::path::missing());
+        test_dir.child("file2").assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+/// Join the given path components using the platform's path separator.
+fn path(components: &[&str]) -> String {
+    components.join(MAIN_SEPARATOR_STR)
+}
@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--one-file-system`/`-x` option.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, rm_out, with_test_dir};
+
+use std::path::MAIN_SEPARATOR;
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn file() -> TestResult {
+    let filename = "file";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child(filename);
+        file.touch()?;
+
+        cmd.args(["--one-file-system", "--force", filename])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(filename);
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn filled_dir_recursively() -> TestResult {
+    let dirname = "dir";
+    let filename = "file";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child(dirname);
+        dir.create_dir_all()?;
+        let file = dir.child(filename);
+        file.touch()?;
+
+        cmd.args(["--one-file-system", "--recursive", dirname])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::dry_removed(format!("{dirname}{MAIN_SEPARATOR}{filename}")),
+                rm_out::dry_removed(dirname);
+                rm_out::newline(),
+                rm_out::dry_conclusion(2, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::exists());
+
+        cmd.arg("--force")
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(format!("{dirname}{MAIN_SEPARATOR}{filename}")),
+                rm_out::removed(dirname);
+                rm_out::newline(),
+                rm_out::conclusion(2, 0),
+            ))
+            .stderr("");
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--check-open` and `--protect-open`
+//! options.
+
+#![cfg(target_os = "linux")]
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, rm_out, with_test_dir};
+
+use std::fs::{File, read_to_string};
+use std::io;
+use std::path::Path;
+use std::process::{Child, Command};
+use std::thread;
+use std::time::Duration;
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn protect_open_held() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        let mut holder = hold_open(&file)?;
+
+        let assert = cmd.args(["--force", "--protect-open", "file"]).assert();
+        holder.kill()?;
+        holder.wait()?;
+
+        assert
+            .failure()
+            .stdout(has_exactly_lines!(rm_out::newline(), rm_out::conclusion(0, 1)))
+            .stderr(format!(
+                "Cannot remove file: Refused to remove (held open by sleep[{}])\n",
+                holder.id()
+            ));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn protect_open_not_held() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--force", "--protect-open", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn check_open_held() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        let mut holder = hold_open(&file)?;
+
+        let assert = cmd.args(["--force", "--check-open", "file"]).assert();
+        holder.kill()?;
+        holder.wait()?;
+
+        assert
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr(format!(
+                "Removing file frees no space until it is closed (held open by sleep[{}])\n",
+                holder.id()
+            ));
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn check_open_held_quiet() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        let mut holder = hold_open(&file)?;
+
+        let assert = cmd.args(["--force", "--quiet", "--check-open", "file"]).assert();
+        holder.kill()?;
+        holder.wait()?;
+
+        assert.success().stdout("").stderr(format!(
+            "Removing file frees no space until it is closed (held open by sleep[{}])\n",
+            holder.id()
+        ));
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(feature = "gnu-mode")]
+fn check_open_held_gnu_mode() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        let mut holder = hold_open(&file)?;
+
+        let assert = cmd.env("RUST_RM_GNU_MODE", "1").args(["--check-open", "file"]).assert();
+        holder.kill()?;
+        holder.wait()?;
+
+        assert.success().stdout("").stderr(format!(
+            "Removing file frees no space until it is closed (held open by sleep[{}])\n",
+            holder.id()
+        ));
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+/// Spawn a `sleep` process that holds the file at the given `path` open until it is killed. Waits
+/// for the process to run `sleep`, so that it is named accordingly.
+fn hold_open<P: AsRef<Path>>(path: P) -> io::Result<Child> {
+    let child = Command::new("sleep").arg("60").stdin(File::open(path)?).spawn()?;
+    let comm = format!("/proc/{}/comm", child.id());
+    for _ in 0..100 {
+        if read_to_string(&comm)?.trim() == "sleep" {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    Ok(child)
+}
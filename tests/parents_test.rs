@@ -0,0 +1,213 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--parents`/`-p` option.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, rm_out, with_test_dir};
+
+use std::path::MAIN_SEPARATOR_STR;
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn file_in_nested_dirs() -> TestResult {
+    let file_path = ["dir", "nested_dir", "file"].join(MAIN_SEPARATOR_STR);
+    let nested_dir_path = ["dir", "nested_dir"].join(MAIN_SEPARATOR_STR);
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let file = test_dir.child(&file_path);
+        file.touch()?;
+
+        cmd.args(["--parents", "--force", &file_path])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(&file_path),
+                rm_out::removed(&nested_dir_path),
+                rm_out::removed("dir");
+                rm_out::newline(),
+                rm_out::conclusion(3, 0),
+            ))
+            .stderr("");
+        dir.assert(predicate::path::missing());
+        test_dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn short_name() -> TestResult {
+    let file_path = ["dir", "file"].join(MAIN_SEPARATOR_STR);
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        test_dir.child(&file_path).touch()?;
+
+        cmd.args(["-p", "--force", &file_path]).assert().success();
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn stops_at_filled_dir() -> TestResult {
+    let file_path = ["dir", "nested_dir", "file"].join(MAIN_SEPARATOR_STR);
+    let nested_dir_path = ["dir", "nested_dir"].join(MAIN_SEPARATOR_STR);
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let other_file = dir.child("other_file");
+        other_file.touch()?;
+        test_dir.child(&file_path).touch()?;
+
+        cmd.args(["--parents", "--force", &file_path])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(&file_path),
+                rm_out::removed(&nested_dir_path);
+                rm_out::newline(),
+                rm_out::conclusion(2, 0),
+            ))
+            .stderr("");
+        test_dir.child(&nested_dir_path).assert(predicate::path::missing());
+        other_file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn stops_at_working_dir() -> TestResult {
+    let filename = "file";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let file = dir.child(filename);
+        file.touch()?;
+
+        let dir_path = dir.path().display().to_string();
+        let file_path = file.path().display().to_string();
+
+        cmd.args(["--parents", "--force", &file_path])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(&file_path),
+                rm_out::removed(&dir_path);
+                rm_out::newline(),
+                rm_out::conclusion(2, 0),
+            ))
+            .stderr("");
+        dir.assert(predicate::path::missing());
+        test_dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn filled_dir_recursively() -> TestResult {
+    let nested_dir_path = ["dir", "nested_dir"].join(MAIN_SEPARATOR_STR);
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        test_dir.child(&nested_dir_path).child("file").touch()?;
+
+        cmd.args(["--parents", "--recursive", "--force", &nested_dir_path]).assert().success();
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn not_removed() -> TestResult {
+    let file_path = ["dir", "file"].join(MAIN_SEPARATOR_STR);
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.create_dir_all()?;
+
+        cmd.args(["--parents", "--force", &file_path])
+            .assert()
+            .failure()
+            .stdout(has_exactly_lines!(rm_out::newline(), rm_out::conclusion(0, 1),))
+            .stderr(rm_out::not_found(&file_path));
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn dry_run() -> TestResult {
+    let file_path = ["dir", "nested_dir", "file"].join(MAIN_SEPARATOR_STR);
+    let nested_dir_path = ["dir", "nested_dir"].join(MAIN_SEPARATOR_STR);
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child(&file_path);
+        file.touch()?;
+
+        cmd.args(["--parents", &file_path])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::dry_removed(&file_path),
+                rm_out::dry_removed(&nested_dir_path),
+                rm_out::dry_removed("dir");
+                rm_out::newline(),
+                rm_out::dry_conclusion(3, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn dry_run_stops_at_filled_dir() -> TestResult {
+    let file_path = ["dir", "nested_dir", "file"].join(MAIN_SEPARATOR_STR);
+    let nested_dir_path = ["dir", "nested_dir"].join(MAIN_SEPARATOR_STR);
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child(&file_path);
+        file.touch()?;
+        test_dir.child("dir").child("other_file").touch()?;
+
+        cmd.args(["--parents", &file_path])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::dry_removed(&file_path),
+                rm_out::dry_removed(&nested_dir_path);
+                rm_out::newline(),
+                rm_out::dry_conclusion(2, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn without_parents() -> TestResult {
+    let file_path = ["dir", "file"].join(MAIN_SEPARATOR_STR);
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        test_dir.child(&file_path).touch()?;
+
+        cmd.args(["--force", &file_path]).assert().success();
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--pick` option.
+
+#![cfg(feature = "pick")]
+
+pub mod common;
+
+use crate::common::{TestResult, with_test_dir};
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn not_a_terminal() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--pick", "file"])
+            .write_stdin("\n")
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr("Cannot pick the entries to remove: not a terminal\n");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn nothing_to_pick() -> TestResult {
+    with_test_dir(|mut cmd, _| {
+        cmd.args(["--pick", "--blind", "missing"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("0 removed, 0 errors occurred"))
+            .stderr("");
+
+        Ok(())
+    })
+}
+
+#[test]
+fn conflicts_with_force() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--pick", "--force", "file"]).assert().failure().stdout("");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
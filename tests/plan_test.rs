@@ -0,0 +1,259 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--plan-out` and `--apply-plan`
+//! options.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, rm_out, with_test_dir};
+
+use std::fs::remove_file;
+use std::path::MAIN_SEPARATOR_STR;
+
+use assert_cmd::cargo;
+use assert_fs::TempDir;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn plan_out_dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--plan-out", "plan", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::dry_removed("file");
+                rm_out::newline(),
+                rm_out::dry_conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::exists());
+        test_dir.child("plan").assert(predicate::str::ends_with("\tfile\n"));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn apply_plan_dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        plan(test_dir, &["file"]);
+
+        cmd.args(["--apply-plan", "plan"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::dry_removed("file");
+                rm_out::newline(),
+                rm_out::dry_conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn apply_plan_force() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file1 = test_dir.child("file1");
+        file1.touch()?;
+        let file2 = test_dir.child("file2");
+        file2.touch()?;
+        plan(test_dir, &["file1", "file2"]);
+
+        cmd.args(["--apply-plan", "plan", "--force"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file1"),
+                rm_out::removed("file2");
+                rm_out::newline(),
+                rm_out::conclusion(2, 0),
+            ))
+            .stderr("");
+        file1.assert(predicate::path::missing());
+        file2.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn apply_plan_recursive() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file").touch()?;
+        plan(test_dir, &["--recursive", "dir"]);
+
+        cmd.args(["--apply-plan", "plan", "--force"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(["dir", "file"].join(MAIN_SEPARATOR_STR)),
+                rm_out::removed("dir");
+                rm_out::newline(),
+                rm_out::conclusion(2, 0),
+            ))
+            .stderr("");
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn apply_plan_only_planned() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let file = dir.child("file");
+        file.touch()?;
+        plan(test_dir, &["--recursive", "dir"]);
+        let new_file = dir.child("new_file");
+        new_file.touch()?;
+
+        cmd.args(["--apply-plan", "plan", "--force"])
+            .assert()
+            .failure()
+            .stdout(has_exactly_lines!(
+                rm_out::removed(["dir", "file"].join(MAIN_SEPARATOR_STR));
+                rm_out::newline(),
+                rm_out::conclusion(1, 1),
+            ))
+            .stderr(has_exactly_lines!(rm_out::refused_changed("dir")));
+        file.assert(predicate::path::missing());
+        new_file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn apply_plan_changed() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file1 = test_dir.child("file1");
+        file1.touch()?;
+        let file2 = test_dir.child("file2");
+        file2.touch()?;
+        plan(test_dir, &["file1", "file2"]);
+        file1.write_str("Hello world!")?;
+
+        cmd.args(["--apply-plan", "plan", "--force"])
+            .assert()
+            .failure()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file2");
+                rm_out::newline(),
+                rm_out::conclusion(1, 1),
+            ))
+            .stderr(rm_out::refused_changed("file1"));
+        file1.assert(predicate::path::exists());
+        file2.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn apply_plan_protected() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        plan(test_dir, &["file"]);
+
+        cmd.args(["--apply-plan", "plan", "--force", "--protect-recent", "1h"])
+            .assert()
+            .failure()
+            .stdout(has_exactly_lines!(rm_out::newline(), rm_out::conclusion(0, 1),))
+            .stderr(rm_out::refused_recent("file"));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn apply_plan_confirm_by_name() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file1").touch()?;
+        dir.child("file2").touch()?;
+        plan(test_dir, &["--recursive", "dir"]);
+
+        cmd.args(["--apply-plan", "plan", "--force", "--confirm-count", "1"])
+            .args(["--answers-from", "-"])
+            .write_stdin("nope\n")
+            .assert()
+            .failure()
+            .stderr(predicate::str::ends_with(rm_out::refused_not_confirmed("dir")));
+        dir.child("file1").assert(predicate::path::exists());
+        dir.child("file2").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn apply_plan_missing() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        plan(test_dir, &["file"]);
+        remove_file(&file)?;
+
+        cmd.args(["--apply-plan", "plan", "--force"])
+            .assert()
+            .failure()
+            .stdout(has_exactly_lines!(; rm_out::newline(), rm_out::conclusion(0, 1)))
+            .stderr(rm_out::not_found_no_tip("file"));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn apply_plan_invalid() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        test_dir.child("plan").write_str("file\n")?;
+
+        cmd.args(["--apply-plan", "plan", "--force"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr("Cannot read plan plan: not a plan file\n");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn apply_plan_with_paths() -> TestResult {
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.args(["--apply-plan", "plan", "file"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(predicate::str::contains("cannot be used with"));
+
+        Ok(())
+    })
+}
+
+/// Write a plan named "plan" in the `test_dir` by running the CLI with the given `args`.
+fn plan(test_dir: &TempDir, args: &[&str]) {
+    cargo::cargo_bin_cmd!("rust-rm")
+        .current_dir(test_dir)
+        .args(["--plan-out", "plan"])
+        .args(args)
+        .assert()
+        .success();
+}
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--progress` and `--progress-eta`
+//! options.
+
+pub mod common;
+
+use crate::common::{TestResult, has_lines, rm_out, with_test_dir};
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn not_a_terminal() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file1").touch()?;
+        dir.child("file2").touch()?;
+
+        cmd.args(["--force", "--recursive", "--progress", "--progress-eta", "dir"])
+            .assert()
+            .success()
+            .stdout(has_lines!(rm_out::removed("dir"), rm_out::conclusion(3, 0)))
+            .stderr("");
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn eta_without_progress() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--force", "--progress-eta", "file"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--progress"));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(feature = "gnu-mode")]
+fn gnu_mode() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.env("RUST_RM_GNU_MODE", "1")
+            .args(["--progress", "file"])
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
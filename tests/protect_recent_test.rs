@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--protect-recent` and
+//! `--force-recent` options.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, has_lines, rm_out, with_test_dir};
+
+use std::fs::File;
+use std::path::{MAIN_SEPARATOR_STR, Path};
+use std::time::{Duration, SystemTime};
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn recent_file() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--force", "--protect-recent", "10m", "file"])
+            .assert()
+            .failure()
+            .stdout(has_exactly_lines!(rm_out::newline(), rm_out::conclusion(0, 1)))
+            .stderr(rm_out::refused_recent("file"));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn old_file() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+        set_modified(&file, hours_ago(1))?;
+
+        cmd.args(["--force", "--protect-recent", "10m", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn force_recent() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--force", "--protect-recent", "10m", "--force-recent", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn from_env() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.env("RUST_RM_PROTECT_RECENT", "10m")
+            .args(["--force", "file"])
+            .assert()
+            .failure()
+            .stderr(rm_out::refused_recent("file"));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--protect-recent", "10m", "file"])
+            .assert()
+            .failure()
+            .stdout(has_exactly_lines!(rm_out::newline(), rm_out::dry_conclusion(0, 1)))
+            .stderr(rm_out::refused_recent("file"));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn recursive_partially_recent() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let old_file = dir.child("old_file");
+        old_file.touch()?;
+        set_modified(&old_file, hours_ago(1))?;
+        let new_file = dir.child("nested_dir").child("new_file");
+        new_file.touch()?;
+
+        cmd.args(["--protect-recent", "10m", "--recursive", "--force", "--verbose", "dir"])
+            .assert()
+            .failure()
+            .stdout(has_lines!(
+                rm_out::removed(path(&["dir", "old_file"])),
+                rm_out::skipped_contents_kept(path(&["dir", "nested_dir"])),
+                rm_out::skipped_contents_kept("dir"),
+                rm_out::conclusion(1, 1),
+            ))
+            .stderr(rm_out::refused_recent(path(&["dir", "nested_dir", "new_file"])));
+        old_file.assert(predicate::path::missing());
+        new_file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+/// Get the point in time the given number of `hours` ago.
+fn hours_ago(hours: u64) -> SystemTime {
+    let duration = Duration::from_secs(hours.saturating_mul(60 * 60));
+    SystemTime::now().checked_sub(duration).unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Set the modification time of the file at the given `path`.
+fn set_modified<P: AsRef<Path>>(path: P, time: SystemTime) -> TestResult {
+    File::options().write(true).open(path)?.set_modified(time)?;
+    Ok(())
+}
+
+/// Join the given path components using the platform's path separator.
+fn path(components: &[&str]) -> String {
+    components.join(MAIN_SEPARATOR_STR)
+}
@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--keep-newest`, `--keep-oldest` and
+//! `--keep-group` options.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, has_lines, rm_out, with_test_dir};
+
+use std::fs::File;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use assert_fs::TempDir;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn keep_newest_dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        files(test_dir, &["backup1", "backup2", "backup3"])?;
+
+        cmd.args(["--keep-newest", "2", "backup1", "backup2", "backup3"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::dry_removed("backup1");
+                rm_out::newline(),
+                rm_out::dry_conclusion(1, 0),
+            ))
+            .stderr("");
+        test_dir.child("backup1").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn keep_newest_force() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        files(test_dir, &["backup1", "backup2", "backup3"])?;
+
+        cmd.args(["--keep-newest", "1", "--force", "backup3", "backup1", "backup2"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("backup1"),
+                rm_out::removed("backup2");
+                rm_out::newline(),
+                rm_out::conclusion(2, 0),
+            ))
+            .stderr("");
+        test_dir.child("backup1").assert(predicate::path::missing());
+        test_dir.child("backup2").assert(predicate::path::missing());
+        test_dir.child("backup3").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn keep_oldest_force() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        files(test_dir, &["backup1", "backup2", "backup3"])?;
+
+        cmd.args(["--keep-oldest", "2", "--force", "--verbose", "backup1", "backup2", "backup3"])
+            .assert()
+            .success()
+            .stdout(has_lines!(
+                rm_out::skipped_retained("backup1"),
+                rm_out::skipped_retained("backup2"),
+                rm_out::removed("backup3"),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        test_dir.child("backup1").assert(predicate::path::exists());
+        test_dir.child("backup2").assert(predicate::path::exists());
+        test_dir.child("backup3").assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn keep_newest_recursive() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let old_dir = test_dir.child("old_dir");
+        old_dir.child("file").touch()?;
+        set_modified(&old_dir, 1)?;
+        let new_dir = test_dir.child("new_dir");
+        new_dir.child("file").touch()?;
+        set_modified(&new_dir, 2)?;
+
+        cmd.args(["--keep-newest", "1", "--recursive", "--force", "old_dir", "new_dir"])
+            .assert()
+            .success()
+            .stdout(has_lines!(rm_out::removed("old_dir"), rm_out::conclusion(2, 0)))
+            .stderr("");
+        old_dir.assert(predicate::path::missing());
+        new_dir.child("file").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn keep_group() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        files(test_dir, &["api-1", "web-1", "api-2", "web-2", "api-3"])?;
+
+        cmd.args(["--keep-newest", "1", "--keep-group", "^([a-z]+)-", "--force"])
+            .args(["api-1", "api-2", "api-3", "web-1", "web-2"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("api-1"),
+                rm_out::removed("api-2"),
+                rm_out::removed("web-1");
+                rm_out::newline(),
+                rm_out::conclusion(3, 0),
+            ))
+            .stderr("");
+        test_dir.child("api-3").assert(predicate::path::exists());
+        test_dir.child("web-2").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn missing_operand() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        files(test_dir, &["backup1"])?;
+
+        cmd.args(["--keep-newest", "1", "--force", "backup1", "missing"])
+            .assert()
+            .failure()
+            .stdout(has_exactly_lines!(; rm_out::newline(), rm_out::conclusion(0, 1)))
+            .stderr(rm_out::not_found("missing"));
+        test_dir.child("backup1").assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn keep_group_without_retention() -> TestResult {
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.args(["--keep-group", "^([a-z]+)-", "file"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(predicate::str::contains("required arguments were not provided"));
+
+        Ok(())
+    })
+}
+
+/// Create files with the given `names` in the `test_dir` with increasing modification times, in
+/// the order given.
+fn files(test_dir: &TempDir, names: &[&str]) -> TestResult {
+    for (name, secs) in names.iter().zip(1..) {
+        let file = test_dir.child(name);
+        file.touch()?;
+        set_modified(&file, secs)?;
+    }
+
+    Ok(())
+}
+
+/// Set the modification time of the file at the given `path` to `secs` after the Unix epoch.
+fn set_modified<P: AsRef<Path>>(path: P, secs: u64) -> TestResult {
+    let time = SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(secs)).ok_or("overflow")?;
+    File::open(path)?.set_modified(time)?;
+    Ok(())
+}
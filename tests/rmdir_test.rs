@@ -0,0 +1,311 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the binary invoked as `rmdir`.
+
+pub mod common;
+
+use crate::common::{TestResult, rm_out};
+
+use std::path::MAIN_SEPARATOR_STR;
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn remove_empty_dir() -> TestResult {
+    let dirname = "dir";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child(dirname);
+        dir.create_dir_all()?;
+
+        cmd.arg(dirname).assert().success().stdout("").stderr("");
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn remove_multiple_empty_dirs() -> TestResult {
+    let dirname1 = "dir1";
+    let dirname2 = "dir2";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir1 = test_dir.child(dirname1);
+        dir1.create_dir_all()?;
+        let dir2 = test_dir.child(dirname2);
+        dir2.create_dir_all()?;
+
+        cmd.args([dirname1, dirname2]).assert().success().stdout("").stderr("");
+        dir1.assert(predicate::path::missing());
+        dir2.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn remove_filled_dir() -> TestResult {
+    let dirname = "dir";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child(dirname);
+        dir.create_dir_all()?;
+        let file = dir.child("file");
+        file.touch()?;
+
+        cmd.arg(dirname)
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(rm_out::dir_not_empty_no_tip(dirname));
+        dir.assert(predicate::path::exists());
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn remove_file() -> TestResult {
+    let filename = "file";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child(filename);
+        file.touch()?;
+
+        cmd.arg(filename).assert().failure().stdout("").stderr(rm_out::not_a_dir(filename));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn remove_missing() -> TestResult {
+    let dirname = "dir";
+
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.arg(dirname).assert().failure().stdout("").stderr(rm_out::not_found_no_tip(dirname));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn dry_run() -> TestResult {
+    let dirname = "dir";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child(dirname);
+        dir.create_dir_all()?;
+
+        cmd.args(["--dry-run", dirname])
+            .assert()
+            .success()
+            .stdout(rm_out::dry_removed(dirname))
+            .stderr("");
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn ignore_fail_on_non_empty() -> TestResult {
+    let filled_dirname = "filled_dir";
+    let empty_dirname = "empty_dir";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let filled_dir = test_dir.child(filled_dirname);
+        filled_dir.create_dir_all()?;
+        filled_dir.child("file").touch()?;
+        let empty_dir = test_dir.child(empty_dirname);
+        empty_dir.create_dir_all()?;
+
+        cmd.args(["--ignore-fail-on-non-empty", filled_dirname, empty_dirname])
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+        filled_dir.assert(predicate::path::exists());
+        empty_dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn ignore_fail_on_non_empty_other_errors() -> TestResult {
+    let filename = "file";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child(filename);
+        file.touch()?;
+
+        cmd.args(["--ignore-fail-on-non-empty", filename])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(rm_out::not_a_dir(filename));
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn parents() -> TestResult {
+    let path = ["dir", "nested_dir", "nested_nested_dir"].join(MAIN_SEPARATOR_STR);
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let nested_dir = test_dir.child(&path);
+        nested_dir.create_dir_all()?;
+
+        cmd.args(["--parents", &path]).assert().success().stdout("").stderr("");
+        dir.assert(predicate::path::missing());
+        test_dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn parents_missing() -> TestResult {
+    let path = ["dir", "missing"].join(MAIN_SEPARATOR_STR);
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.create_dir_all()?;
+
+        cmd.args(["--parents", &path])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(rm_out::not_found_no_tip(&path));
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn parents_stops_at_filled_dir() -> TestResult {
+    let path = ["dir", "nested_dir", "nested_nested_dir"].join(MAIN_SEPARATOR_STR);
+    let parent = ["dir", "nested_dir"].join(MAIN_SEPARATOR_STR);
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let nested_dir = test_dir.child(&path);
+        nested_dir.create_dir_all()?;
+        let file = dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--parents", &path])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(rm_out::dir_not_empty_no_tip("dir"));
+        nested_dir.assert(predicate::path::missing());
+        test_dir.child(&parent).assert(predicate::path::missing());
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn parents_ignore_fail_on_non_empty() -> TestResult {
+    let path = ["dir", "nested_dir"].join(MAIN_SEPARATOR_STR);
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        let nested_dir = test_dir.child(&path);
+        nested_dir.create_dir_all()?;
+        let file = dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--parents", "--ignore-fail-on-non-empty", &path])
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+        nested_dir.assert(predicate::path::missing());
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn verbose() -> TestResult {
+    let path = ["dir", "nested_dir"].join(MAIN_SEPARATOR_STR);
+
+    with_test_dir(|mut cmd, test_dir| {
+        test_dir.child(&path).create_dir_all()?;
+
+        cmd.args(["--verbose", "--parents", &path])
+            .assert()
+            .success()
+            .stdout(format!("{}{}", rm_out::removed(&path), rm_out::removed("dir")))
+            .stderr("");
+
+        Ok(())
+    })
+}
+
+#[test]
+fn parents_dry_run() -> TestResult {
+    let path = ["dir", "nested_dir"].join(MAIN_SEPARATOR_STR);
+
+    with_test_dir(|mut cmd, test_dir| {
+        let nested_dir = test_dir.child(&path);
+        nested_dir.create_dir_all()?;
+
+        cmd.args(["--dry-run", "--parents", &path])
+            .assert()
+            .success()
+            .stdout(format!("{}{}", rm_out::dry_removed(&path), rm_out::dry_removed("dir")))
+            .stderr("");
+        nested_dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn no_operands() -> TestResult {
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.assert().failure().stdout("").stderr(predicate::str::contains("rmdir"));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn rm_flags() -> TestResult {
+    let dirname = "dir";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child(dirname);
+        dir.create_dir_all()?;
+        dir.child("file").touch()?;
+
+        cmd.args(["--recursive", "--force", dirname]).assert().failure().stdout("");
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+/// Run a test with the binary invoked as `rmdir`.
+///
+/// See also [`common::with_test_dir_as`].
+fn with_test_dir<C>(callback: C) -> TestResult
+where
+    C: FnOnce(assert_cmd::Command, &assert_fs::TempDir) -> TestResult,
+{
+    common::with_test_dir_as("rmdir", callback)
+}
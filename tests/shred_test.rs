@@ -0,0 +1,203 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--shred` option.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, rm_out, with_test_dir};
+
+#[cfg(unix)]
+use std::fs::hard_link;
+use std::path::MAIN_SEPARATOR;
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn file() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.write_str("Hello world!")?;
+
+        cmd.args(["--force", "--shred", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                shredded("file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr(warning());
+        file.assert(predicate::path::missing());
+        assert_eq!(test_dir.read_dir()?.count(), 0);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn quiet() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.write_str("Hello world!")?;
+
+        cmd.args(["--force", "--quiet", "--shred", "file"])
+            .assert()
+            .success()
+            .stdout("")
+            .stderr(warning());
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(feature = "gnu-mode")]
+fn gnu_mode() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.write_str("Hello world!")?;
+
+        cmd.env("RUST_RM_GNU_MODE", "1")
+            .args(["--shred", "file"])
+            .assert()
+            .success()
+            .stdout("")
+            .stderr(warning());
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn passes() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.write_str("Hello world!")?;
+
+        cmd.args(["--force", "--shred=1", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                shredded("file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr(warning());
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn zero_passes() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.write_str("Hello world!")?;
+
+        cmd.args(["--force", "--shred=0", "file"]).assert().failure();
+        file.assert("Hello world!");
+
+        Ok(())
+    })
+}
+
+#[test]
+fn recursive() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file").write_str("Hello world!")?;
+
+        cmd.args(["--force", "--recursive", "--shred", "dir"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                shredded(format!("dir{MAIN_SEPARATOR}file")),
+                rm_out::removed("dir");
+                rm_out::newline(),
+                rm_out::conclusion(2, 0),
+            ))
+            .stderr(warning());
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(unix)]
+fn hard_linked() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.write_str("Hello world!")?;
+        let link = test_dir.child("link");
+        hard_link(&file, &link)?;
+
+        cmd.args(["--force", "--shred", "file"])
+            .assert()
+            .failure()
+            .stdout(has_exactly_lines!(rm_out::newline(), rm_out::conclusion(0, 1)))
+            .stderr(format!(
+                "{}Cannot remove file: Refused to remove (shredding would overwrite its other links \
+                too)\n",
+                warning(),
+            ));
+        file.assert("Hello world!");
+        link.assert("Hello world!");
+
+        Ok(())
+    })
+}
+
+#[test]
+fn dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.write_str("Hello world!")?;
+
+        cmd.args(["--shred", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::dry_removed("file");
+                rm_out::newline(),
+                rm_out::dry_conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert("Hello world!");
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(feature = "trash")]
+fn with_trash() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.write_str("Hello world!")?;
+
+        cmd.args(["--force", "--shred", "--trash", "file"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("cannot be used with"));
+        file.assert("Hello world!");
+
+        Ok(())
+    })
+}
+
+/// The expected output for shredding the given `subject`.
+fn shredded<S: Into<String>>(subject: S) -> String {
+    format!("Shredded {}\n", subject.into())
+}
+
+/// The expected caveat output when shredding.
+fn warning() -> String {
+    "Shredding cannot guarantee the contents are unrecoverable on copy-on-write or journaling file \
+    systems (such as Btrfs, ZFS or ext4), nor on flash storage\n"
+        .to_owned()
+}
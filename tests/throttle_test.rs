@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the `--throttle-count`, `--throttle-size`
+//! and `--throttle-idle` options.
+
+pub mod common;
+
+use crate::common::{TestResult, has_exactly_lines, has_lines, rm_out, with_test_dir};
+
+use std::time::{Duration, Instant};
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn count() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        for name in ["file1", "file2", "file3"] {
+            test_dir.child(name).touch()?;
+        }
+
+        let start = Instant::now();
+        cmd.args(["--force", "--throttle-count", "20", "file1", "file2", "file3"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file1"),
+                rm_out::removed("file2"),
+                rm_out::removed("file3");
+                rm_out::newline(),
+                rm_out::conclusion(3, 0),
+            ))
+            .stderr("");
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn size() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child("dir");
+        dir.child("file1").write_str(&"x".repeat(1024))?;
+        dir.child("file2").write_str(&"x".repeat(1024))?;
+
+        let start = Instant::now();
+        cmd.args(["--force", "--recursive", "--throttle-size", "10K", "dir"])
+            .assert()
+            .success()
+            .stdout(has_lines!(rm_out::removed("dir"), rm_out::conclusion(3, 0)))
+            .stderr("");
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        dir.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn zero_count() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--force", "--throttle-count", "0", "file"]).assert().failure();
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn idle() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child("file");
+        file.touch()?;
+
+        cmd.args(["--force", "--throttle-idle", "file"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::removed("file");
+                rm_out::newline(),
+                rm_out::conclusion(1, 0),
+            ))
+            .stderr("");
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn dry_run() -> TestResult {
+    with_test_dir(|mut cmd, test_dir| {
+        test_dir.child("file1").touch()?;
+        test_dir.child("file2").touch()?;
+
+        cmd.args(["--throttle-count", "1", "file1", "file2"])
+            .assert()
+            .success()
+            .stdout(has_exactly_lines!(
+                rm_out::dry_removed("file1"),
+                rm_out::dry_removed("file2");
+                rm_out::newline(),
+                rm_out::dry_conclusion(2, 0),
+            ))
+            .stderr("");
+
+        Ok(())
+    })
+}
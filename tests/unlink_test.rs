@@ -0,0 +1,145 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test suite focussed on testing the functionality of the binary invoked as `unlink`.
+
+pub mod common;
+
+use crate::common::{TestResult, rm_out};
+
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn remove_file() -> TestResult {
+    let filename = "file";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child(filename);
+        file.touch()?;
+
+        cmd.arg(filename).assert().success().stdout("").stderr("");
+        file.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+#[cfg_attr(
+    all(windows, not(feature = "test-symlink")),
+    ignore = "Only run with the test-symlink feature"
+)]
+fn remove_symlink() -> TestResult {
+    let linkname = "link";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let linked_file = test_dir.child("linked_file");
+        linked_file.touch()?;
+        let link = test_dir.child(linkname);
+        link.symlink_to_file(&linked_file)?;
+
+        cmd.arg(linkname).assert().success().stdout("").stderr("");
+        linked_file.assert(predicate::path::exists());
+        link.assert(predicate::path::missing());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn remove_dir() -> TestResult {
+    let dirname = "dir";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let dir = test_dir.child(dirname);
+        dir.create_dir_all()?;
+
+        cmd.arg(dirname).assert().failure().stdout("").stderr(rm_out::is_a_dir_no_tip(dirname));
+        dir.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn remove_missing() -> TestResult {
+    let filename = "file";
+
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.arg(filename).assert().failure().stdout("").stderr(rm_out::not_found_no_tip(filename));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn dry_run() -> TestResult {
+    let filename = "file";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child(filename);
+        file.touch()?;
+
+        cmd.args(["--dry-run", filename])
+            .assert()
+            .success()
+            .stdout(rm_out::dry_removed(filename))
+            .stderr("");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn single_operand() -> TestResult {
+    let filename1 = "file1";
+    let filename2 = "file2";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file1 = test_dir.child(filename1);
+        file1.touch()?;
+        let file2 = test_dir.child(filename2);
+        file2.touch()?;
+
+        cmd.args([filename1, filename2]).assert().failure().stdout("");
+        file1.assert(predicate::path::exists());
+        file2.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+#[test]
+fn no_operands() -> TestResult {
+    with_test_dir(|mut cmd, _test_dir| {
+        cmd.assert().failure().stdout("").stderr(predicate::str::contains("unlink"));
+
+        Ok(())
+    })
+}
+
+#[test]
+fn rm_flags() -> TestResult {
+    let filename = "file";
+
+    with_test_dir(|mut cmd, test_dir| {
+        let file = test_dir.child(filename);
+        file.touch()?;
+
+        cmd.args(["--force", filename]).assert().failure().stdout("");
+        file.assert(predicate::path::exists());
+
+        Ok(())
+    })
+}
+
+/// Run a test with the binary invoked as `unlink`.
+///
+/// See also [`common::with_test_dir_as`].
+fn with_test_dir<C>(callback: C) -> TestResult
+where
+    C: FnOnce(assert_cmd::Command, &assert_fs::TempDir) -> TestResult,
+{
+    common::with_test_dir_as("unlink", callback)
+}